@@ -0,0 +1,443 @@
+//! Probability calibration
+//!
+//! Contains models which map the raw scores of a binary classifier
+//! to calibrated probabilities of the positive class.
+//!
+//! The calibrators are trained on the raw scores of a classifier,
+//! typically on held-out data, together with the true `0`/`1` labels.
+//!
+//! - `PlattScaling` fits a sigmoid to the scores.
+//! - `IsotonicRegression` fits a non-decreasing step function to the
+//!   scores using the pool-adjacent-violators algorithm.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::analysis::calibration::PlattScaling;
+//! use rusty_machine::learning::SupModel;
+//! use rusty_machine::linalg::Vector;
+//!
+//! let scores = Vector::new(vec![-3.0, -2.0, -1.0, -0.5, 0.5, 1.0, 2.0, 3.0]);
+//! let labels = Vector::new(vec![0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0]);
+//!
+//! let mut platt = PlattScaling::default();
+//! platt.train(&scores, &labels).unwrap();
+//!
+//! let probs = platt.predict(&Vector::new(vec![-2.5, 2.5])).unwrap();
+//! assert!(probs[0] < 0.5 && probs[1] > 0.5);
+//! ```
+
+use linalg::Vector;
+
+use learning::{LearningResult, SupModel};
+use learning::error::{Error, ErrorKind};
+
+/// Platt scaling calibrator.
+///
+/// Fits `P(y = 1 | f) = 1 / (1 + exp(A f + B))` to the raw scores `f`
+/// by regularized maximum likelihood, using the Newton method of
+/// Lin, Lin and Weng (2007).
+#[derive(Debug)]
+pub struct PlattScaling {
+    /// Max iterations of Newton's method.
+    iters: usize,
+    /// The fitted sigmoid parameters `(A, B)`.
+    parameters: Option<(f64, f64)>,
+}
+
+/// The default Platt scaling calibrator.
+///
+/// The defaults are:
+///
+/// - `iters` = `100`
+impl Default for PlattScaling {
+    fn default() -> PlattScaling {
+        PlattScaling {
+            iters: 100,
+            parameters: None,
+        }
+    }
+}
+
+impl PlattScaling {
+    /// Constructs an untrained Platt scaling calibrator with
+    /// the given maximum number of Newton iterations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::analysis::calibration::PlattScaling;
+    ///
+    /// let platt = PlattScaling::new(50);
+    /// ```
+    pub fn new(iters: usize) -> PlattScaling {
+        PlattScaling {
+            iters,
+            parameters: None,
+        }
+    }
+
+    /// The fitted sigmoid parameters `(A, B)`.
+    ///
+    /// Returns `None` if the calibrator has not been trained.
+    pub fn parameters(&self) -> Option<(f64, f64)> {
+        self.parameters
+    }
+}
+
+impl SupModel<Vector<f64>, Vector<f64>> for PlattScaling {
+    /// Fit the sigmoid to the raw scores and `0`/`1` labels.
+    fn train(&mut self, scores: &Vector<f64>, labels: &Vector<f64>) -> LearningResult<()> {
+        check_labels(scores, labels)?;
+
+        let n_pos = labels.data().iter().filter(|&&y| y == 1f64).count() as f64;
+        let n_neg = labels.size() as f64 - n_pos;
+
+        // Regularized targets avoid overfitting to separable scores
+        let hi = (n_pos + 1f64) / (n_pos + 2f64);
+        let lo = 1f64 / (n_neg + 2f64);
+        let targets = labels.data()
+            .iter()
+            .map(|&y| if y == 1f64 { hi } else { lo })
+            .collect::<Vec<_>>();
+        let scores = scores.data();
+
+        let objective = |a: f64, b: f64| {
+            scores.iter().zip(targets.iter()).fold(0f64, |acc, (&f, &t)| {
+                let z = a * f + b;
+                if z >= 0f64 {
+                    acc + t * z + (-z).exp().ln_1p()
+                } else {
+                    acc + (t - 1f64) * z + z.exp().ln_1p()
+                }
+            })
+        };
+
+        let mut a = 0f64;
+        let mut b = ((n_neg + 1f64) / (n_pos + 1f64)).ln();
+        let mut fval = objective(a, b);
+
+        for _ in 0..self.iters {
+            // Gradient and Hessian of the objective
+            let (mut h11, mut h22, mut h21) = (1e-12, 1e-12, 0f64);
+            let (mut g1, mut g2) = (0f64, 0f64);
+
+            for (&f, &t) in scores.iter().zip(targets.iter()) {
+                let p = sigmoid_neg(a * f + b);
+                let d2 = p * (1f64 - p);
+                h11 += f * f * d2;
+                h22 += d2;
+                h21 += f * d2;
+
+                let d1 = t - p;
+                g1 += f * d1;
+                g2 += d1;
+            }
+
+            if g1.abs() < 1e-5 && g2.abs() < 1e-5 {
+                break;
+            }
+
+            let det = h11 * h22 - h21 * h21;
+            let d_a = -(h22 * g1 - h21 * g2) / det;
+            let d_b = -(-h21 * g1 + h11 * g2) / det;
+            let gd = g1 * d_a + g2 * d_b;
+
+            // Backtracking line search
+            let mut step = 1f64;
+            while step >= 1e-10 {
+                let (new_a, new_b) = (a + step * d_a, b + step * d_b);
+                let new_f = objective(new_a, new_b);
+
+                if new_f < fval + 1e-4 * step * gd {
+                    a = new_a;
+                    b = new_b;
+                    fval = new_f;
+                    break;
+                }
+                step /= 2f64;
+            }
+
+            if step < 1e-10 {
+                break;
+            }
+        }
+
+        self.parameters = Some((a, b));
+        Ok(())
+    }
+
+    /// Predict the calibrated probabilities of the raw scores.
+    fn predict(&self, scores: &Vector<f64>) -> LearningResult<Vector<f64>> {
+        if let Some((a, b)) = self.parameters {
+            Ok(scores.clone().apply(&|f| sigmoid_neg(a * f + b)))
+        } else {
+            Err(Error::new_untrained())
+        }
+    }
+}
+
+/// Isotonic regression calibrator.
+///
+/// Fits the non-decreasing step function of the scores which minimizes
+/// the squared error to the labels, using the pool-adjacent-violators
+/// algorithm. Predictions interpolate linearly between the fitted steps
+/// and are clamped to the fitted range outside of the training scores.
+#[derive(Debug, Default)]
+pub struct IsotonicRegression {
+    /// The scores at the ends of each fitted step.
+    thresholds: Option<Vec<f64>>,
+    /// The fitted probabilities at each threshold.
+    values: Option<Vec<f64>>,
+}
+
+impl IsotonicRegression {
+    /// Constructs an untrained isotonic regression calibrator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::analysis::calibration::IsotonicRegression;
+    ///
+    /// let iso = IsotonicRegression::new();
+    /// ```
+    pub fn new() -> IsotonicRegression {
+        IsotonicRegression::default()
+    }
+}
+
+impl SupModel<Vector<f64>, Vector<f64>> for IsotonicRegression {
+    /// Fit the step function to the raw scores and `0`/`1` labels.
+    fn train(&mut self, scores: &Vector<f64>, labels: &Vector<f64>) -> LearningResult<()> {
+        check_labels(scores, labels)?;
+
+        let mut pairs = scores.data()
+            .iter()
+            .cloned()
+            .zip(labels.data().iter().cloned())
+            .collect::<Vec<_>>();
+        pairs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        // Each block holds (min score, max score, label sum, count)
+        let mut blocks: Vec<(f64, f64, f64, f64)> = Vec::with_capacity(pairs.len());
+        for (x, y) in pairs {
+            // Equal scores must share a value, so they are always pooled
+            let tied = blocks.last().is_some_and(|last| last.1 == x);
+            if tied {
+                let last = blocks.last_mut().unwrap();
+                last.2 += y;
+                last.3 += 1f64;
+            } else {
+                blocks.push((x, x, y, 1f64));
+            }
+
+            // Pool adjacent violators
+            while blocks.len() > 1 {
+                let n = blocks.len();
+                let (prev, last) = (blocks[n - 2], blocks[n - 1]);
+                if prev.2 / prev.3 < last.2 / last.3 {
+                    break;
+                }
+
+                blocks.pop();
+                blocks[n - 2] = (prev.0, last.1, prev.2 + last.2, prev.3 + last.3);
+            }
+        }
+
+        let mut thresholds = Vec::with_capacity(2 * blocks.len());
+        let mut values = Vec::with_capacity(2 * blocks.len());
+        for (min, max, sum, count) in blocks {
+            let value = sum / count;
+            thresholds.push(min);
+            values.push(value);
+            if max > min {
+                thresholds.push(max);
+                values.push(value);
+            }
+        }
+
+        self.thresholds = Some(thresholds);
+        self.values = Some(values);
+        Ok(())
+    }
+
+    /// Predict the calibrated probabilities of the raw scores.
+    fn predict(&self, scores: &Vector<f64>) -> LearningResult<Vector<f64>> {
+        if let (Some(thresholds), Some(values)) = (self.thresholds.as_ref(),
+                                                   self.values.as_ref()) {
+            Ok(scores.clone().apply(&|f| interpolate(thresholds, values, f)))
+        } else {
+            Err(Error::new_untrained())
+        }
+    }
+}
+
+/// Computes `1 / (1 + exp(z))` without overflow.
+fn sigmoid_neg(z: f64) -> f64 {
+    if z >= 0f64 {
+        let e = (-z).exp();
+        e / (1f64 + e)
+    } else {
+        1f64 / (1f64 + z.exp())
+    }
+}
+
+/// Linearly interpolates the points `(xs, ys)` at `x`.
+///
+/// The `xs` must be sorted. Values outside the range of `xs` are clamped.
+fn interpolate(xs: &[f64], ys: &[f64], x: f64) -> f64 {
+    let n = xs.len();
+    if x <= xs[0] {
+        return ys[0];
+    }
+    if x >= xs[n - 1] {
+        return ys[n - 1];
+    }
+
+    // Find the first threshold above x
+    let upper = xs.iter().position(|&t| t > x).unwrap();
+    let (x0, x1) = (xs[upper - 1], xs[upper]);
+    let (y0, y1) = (ys[upper - 1], ys[upper]);
+
+    y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+}
+
+/// Checks that the scores and labels are valid for calibration.
+fn check_labels(scores: &Vector<f64>, labels: &Vector<f64>) -> LearningResult<()> {
+    if scores.size() == 0 {
+        return Err(Error::new(ErrorKind::InvalidData, "Cannot calibrate with no scores."));
+    }
+
+    if scores.size() != labels.size() {
+        return Err(Error::new(ErrorKind::InvalidData,
+                              "Scores and labels have different sizes."));
+    }
+
+    if scores.data().iter().any(|f| f.is_nan()) {
+        return Err(Error::new(ErrorKind::InvalidData, "Scores cannot be NaN."));
+    }
+
+    if labels.data().iter().any(|&y| y != 0f64 && y != 1f64) {
+        return Err(Error::new(ErrorKind::InvalidData, "Labels must be 0 or 1."));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlattScaling, IsotonicRegression, interpolate};
+
+    use learning::SupModel;
+    use learning::toolkit::activ_fn::{ActivationFunc, Sigmoid};
+    use learning::toolkit::cost_fn::{CostFunc, CrossEntropyError};
+    use linalg::Vector;
+
+    use rand::{Rng, SeedableRng, StdRng};
+
+    /// Overconfident raw scores with labels drawn from the true probabilities.
+    ///
+    /// The true probability of the positive class is `sigmoid(x)`,
+    /// while the raw score is `4x`.
+    fn scored_data(seed: usize, n: usize) -> (Vector<f64>, Vector<f64>) {
+        let seed: &[_] = &[seed];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+
+        let mut scores = Vec::with_capacity(n);
+        let mut labels = Vec::with_capacity(n);
+        for _ in 0..n {
+            let x = rng.gen_range(-4f64, 4f64);
+            let label = if rng.gen::<f64>() < Sigmoid::func(x) { 1f64 } else { 0f64 };
+
+            scores.push(4f64 * x);
+            labels.push(label);
+        }
+
+        (Vector::new(scores), Vector::new(labels))
+    }
+
+    fn raw_probabilities(scores: &Vector<f64>) -> Vector<f64> {
+        scores.clone().apply(&Sigmoid::func)
+    }
+
+    #[test]
+    fn test_platt_improves_log_loss() {
+        let (train_scores, train_labels) = scored_data(1, 500);
+        let (test_scores, test_labels) = scored_data(2, 500);
+
+        let mut platt = PlattScaling::default();
+        platt.train(&train_scores, &train_labels).unwrap();
+
+        // The sigmoid should undo the overconfident scaling
+        let (a, _) = platt.parameters().unwrap();
+        assert!((a + 0.25).abs() < 0.1);
+
+        let calibrated = platt.predict(&test_scores).unwrap();
+        let raw = raw_probabilities(&test_scores);
+
+        let calibrated_loss = CrossEntropyError::cost(&calibrated, &test_labels);
+        let raw_loss = CrossEntropyError::cost(&raw, &test_labels);
+        assert!(calibrated_loss < raw_loss);
+    }
+
+    #[test]
+    fn test_isotonic_improves_log_loss() {
+        let (train_scores, train_labels) = scored_data(3, 500);
+        let (test_scores, test_labels) = scored_data(4, 500);
+
+        let mut iso = IsotonicRegression::new();
+        iso.train(&train_scores, &train_labels).unwrap();
+
+        // Clip to avoid infinite loss from the extreme steps
+        let calibrated = iso.predict(&test_scores)
+            .unwrap()
+            .apply(&|p| p.max(1e-3).min(1f64 - 1e-3));
+        let raw = raw_probabilities(&test_scores);
+
+        let calibrated_loss = CrossEntropyError::cost(&calibrated, &test_labels);
+        let raw_loss = CrossEntropyError::cost(&raw, &test_labels);
+        assert!(calibrated_loss < raw_loss);
+    }
+
+    #[test]
+    fn test_isotonic_is_monotonic() {
+        let scores = Vector::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let labels = Vector::new(vec![0.0, 1.0, 0.0, 0.0, 1.0, 1.0]);
+
+        let mut iso = IsotonicRegression::new();
+        iso.train(&scores, &labels).unwrap();
+
+        let outputs = iso.predict(&scores).unwrap();
+        assert_eq!(outputs, Vector::new(vec![0.0, 1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0, 1.0, 1.0]));
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let xs = [0.0, 1.0, 3.0];
+        let ys = [0.0, 0.5, 1.0];
+
+        assert_eq!(interpolate(&xs, &ys, -1.0), 0.0);
+        assert_eq!(interpolate(&xs, &ys, 0.5), 0.25);
+        assert_eq!(interpolate(&xs, &ys, 2.0), 0.75);
+        assert_eq!(interpolate(&xs, &ys, 5.0), 1.0);
+    }
+
+    #[test]
+    fn test_invalid_labels() {
+        let scores = Vector::new(vec![1.0, 2.0]);
+        let labels = Vector::new(vec![0.0, 2.0]);
+
+        let mut platt = PlattScaling::default();
+        assert!(platt.train(&scores, &labels).is_err());
+
+        let mut iso = IsotonicRegression::new();
+        assert!(iso.train(&scores, &labels).is_err());
+    }
+
+    #[test]
+    fn test_untrained() {
+        let scores = Vector::new(vec![1.0]);
+        assert!(PlattScaling::default().predict(&scores).is_err());
+        assert!(IsotonicRegression::new().predict(&scores).is_err());
+    }
+}
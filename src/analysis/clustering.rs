@@ -0,0 +1,331 @@
+//! Internal measures of clustering quality
+//!
+//! Contains functions which judge a clustering from the data and the
+//! cluster labels alone, without any ground truth labels.
+//!
+//! The silhouette of a sample compares the mean distance `a` to the other
+//! members of its cluster with the mean distance `b` to the members of the
+//! nearest other cluster, as `(b - a) / max(a, b)`. It lies in `[-1, 1]` and
+//! higher values mean the sample sits well inside its cluster.
+//!
+//! The `davies_bouldin` and `calinski_harabasz` indices instead compare the
+//! spread of each cluster around its centroid with the separation of the
+//! centroids. They are cheaper than the silhouette and do not favour the
+//! same shapes of cluster, so they are useful as a second opinion.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::analysis::clustering::{silhouette_samples, silhouette_score};
+//! use rusty_machine::linalg::{Matrix, Vector};
+//!
+//! let data = Matrix::new(4, 1, vec![0.0f64, 1.0, 10.0, 11.0]);
+//! let labels = Vector::new(vec![0, 0, 1, 1]);
+//!
+//! let silhouettes = silhouette_samples(&data, &labels);
+//! assert!(silhouettes.iter().all(|&s| s > 0.8));
+//!
+//! // The score is the mean silhouette
+//! let score = silhouette_score(&data, &labels);
+//! assert!((score - silhouettes.mean()).abs() < 1e-12);
+//! ```
+
+use linalg::{Matrix, BaseMatrix, Vector};
+
+use libnum::{Float, FromPrimitive};
+
+use neighbours::{distance, pairwise_distances, squared_distance};
+
+/// The silhouette of each sample.
+///
+/// Samples in a cluster of their own have a silhouette of zero.
+///
+/// # Panics
+///
+/// - There is not one label per row of the data.
+/// - There are fewer than two clusters, or as many clusters as samples.
+pub fn silhouette_samples<T>(data: &Matrix<T>, labels: &Vector<usize>) -> Vector<T>
+    where T: Float + FromPrimitive
+{
+    let (clusters, counts) = cluster_indices(data, labels);
+    let n_clusters = counts.len();
+    assert!(n_clusters >= 2 && n_clusters < data.rows(),
+            "The silhouette needs between 2 and n_samples - 1 clusters.");
+
+    let distances = pairwise_distances(data);
+    let n = data.rows();
+
+    let silhouettes = (0..n)
+        .map(|i| {
+            let own = clusters[i];
+            if counts[own] == 1 {
+                return T::zero();
+            }
+
+            // The total distance from sample i to each cluster
+            let mut totals = vec![T::zero(); n_clusters];
+            for j in 0..n {
+                totals[clusters[j]] = totals[clusters[j]] + distances[[i, j]];
+            }
+
+            let a = totals[own] / T::from_usize(counts[own] - 1).unwrap();
+            let b = (0..n_clusters)
+                .filter(|&c| c != own)
+                .map(|c| totals[c] / T::from_usize(counts[c]).unwrap())
+                .fold(T::infinity(), T::min);
+
+            let scale = a.max(b);
+            if scale == T::zero() { T::zero() } else { (b - a) / scale }
+        })
+        .collect::<Vec<_>>();
+
+    Vector::new(silhouettes)
+}
+
+/// The mean silhouette over all samples.
+///
+/// See `silhouette_samples`.
+pub fn silhouette_score<T>(data: &Matrix<T>, labels: &Vector<usize>) -> T
+    where T: Float + FromPrimitive
+{
+    let silhouettes = silhouette_samples(data, labels);
+    silhouettes.sum() / T::from_usize(silhouettes.size()).unwrap()
+}
+
+/// The Davies-Bouldin index of the clustering.
+///
+/// For each cluster this finds the most similar other cluster, where the
+/// similarity of clusters `i` and `j` is `(s_i + s_j) / d_ij`. Here `s_i` is
+/// the mean distance of the members of cluster `i` to its centroid and
+/// `d_ij` is the distance between the centroids. The index is the mean of
+/// these worst-case similarities.
+///
+/// Lower values are better, and zero is the minimum.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::analysis::clustering::davies_bouldin;
+/// use rusty_machine::linalg::{Matrix, Vector};
+///
+/// let data = Matrix::new(4, 1, vec![0.0f64, 1.0, 10.0, 11.0]);
+///
+/// let good = davies_bouldin(&data, &Vector::new(vec![0, 0, 1, 1]));
+/// let bad = davies_bouldin(&data, &Vector::new(vec![0, 1, 0, 1]));
+/// assert!(good < bad);
+/// ```
+///
+/// # Panics
+///
+/// - There is not one label per row of the data.
+/// - There are fewer than two clusters.
+pub fn davies_bouldin<T>(data: &Matrix<T>, labels: &Vector<usize>) -> T
+    where T: Float + FromPrimitive
+{
+    let (clusters, counts) = cluster_indices(data, labels);
+    let n_clusters = counts.len();
+    assert!(n_clusters >= 2, "The Davies-Bouldin index needs at least 2 clusters.");
+
+    let centroids = centroids(data, &clusters, &counts);
+    let mut spreads = vec![T::zero(); n_clusters];
+    for (row, &c) in data.iter_rows().zip(clusters.iter()) {
+        spreads[c] = spreads[c] + distance(row, centroids.get_row(c).unwrap());
+    }
+    for (s, &count) in spreads.iter_mut().zip(counts.iter()) {
+        *s = *s / T::from_usize(count).unwrap();
+    }
+
+    let total = (0..n_clusters)
+        .map(|i| {
+            (0..n_clusters)
+                .filter(|&j| j != i)
+                .map(|j| {
+                    let d = distance(centroids.get_row(i).unwrap(), centroids.get_row(j).unwrap());
+                    (spreads[i] + spreads[j]) / d
+                })
+                .fold(T::zero(), T::max)
+        })
+        .fold(T::zero(), |acc, r| acc + r);
+    total / T::from_usize(n_clusters).unwrap()
+}
+
+/// The Calinski-Harabasz index of the clustering.
+///
+/// This is the ratio of the between-cluster dispersion to the
+/// within-cluster dispersion, each divided by its degrees of freedom:
+///
+/// <pre>[B / (k - 1)] / [W / (n - k)]</pre>
+///
+/// where `B` is the size-weighted sum of squared distances of the
+/// centroids to the overall mean and `W` is the sum of squared distances
+/// of the samples to their centroids.
+///
+/// Higher values are better.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::analysis::clustering::calinski_harabasz;
+/// use rusty_machine::linalg::{Matrix, Vector};
+///
+/// let data = Matrix::new(4, 1, vec![0.0f64, 1.0, 10.0, 11.0]);
+///
+/// let good = calinski_harabasz(&data, &Vector::new(vec![0, 0, 1, 1]));
+/// let bad = calinski_harabasz(&data, &Vector::new(vec![0, 1, 0, 1]));
+/// assert!(good > bad);
+/// ```
+///
+/// # Panics
+///
+/// - There is not one label per row of the data.
+/// - There are fewer than two clusters, or as many clusters as samples.
+pub fn calinski_harabasz<T>(data: &Matrix<T>, labels: &Vector<usize>) -> T
+    where T: Float + FromPrimitive
+{
+    let (clusters, counts) = cluster_indices(data, labels);
+    let n_clusters = counts.len();
+    let n = data.rows();
+    assert!(n_clusters >= 2 && n_clusters < n,
+            "The Calinski-Harabasz index needs between 2 and n_samples - 1 clusters.");
+
+    let overall = centroids(data, &vec![0; n], &[n]);
+    let mean = overall.get_row(0).unwrap();
+    let centroids = centroids(data, &clusters, &counts);
+
+    let between = centroids.iter_rows()
+        .zip(counts.iter())
+        .fold(T::zero(), |acc, (c, &count)| {
+            acc + squared_distance(c, mean) * T::from_usize(count).unwrap()
+        });
+    let within = data.iter_rows()
+        .zip(clusters.iter())
+        .fold(T::zero(), |acc, (row, &c)| {
+            acc + squared_distance(row, centroids.get_row(c).unwrap())
+        });
+
+    if within == T::zero() {
+        return T::infinity();
+    }
+    (between / T::from_usize(n_clusters - 1).unwrap()) /
+    (within / T::from_usize(n - n_clusters).unwrap())
+}
+
+/// Maps the labels onto `0..n_clusters` in order of first appearance.
+///
+/// Returns the cluster index of each sample and the size of each cluster.
+fn cluster_indices<T>(data: &Matrix<T>, labels: &Vector<usize>) -> (Vec<usize>, Vec<usize>) {
+    assert!(data.rows() == labels.size(),
+            "There must be one label per sample.");
+
+    let mut distinct: Vec<usize> = Vec::new();
+    let mut counts: Vec<usize> = Vec::new();
+    let clusters = labels.iter()
+        .map(|label| {
+            match distinct.iter().position(|l| l == label) {
+                Some(c) => {
+                    counts[c] += 1;
+                    c
+                }
+                None => {
+                    distinct.push(*label);
+                    counts.push(1);
+                    distinct.len() - 1
+                }
+            }
+        })
+        .collect();
+    (clusters, counts)
+}
+
+/// The mean of the members of each cluster, one row per cluster.
+fn centroids<T>(data: &Matrix<T>, clusters: &[usize], counts: &[usize]) -> Matrix<T>
+    where T: Float + FromPrimitive
+{
+    let mut sums = Matrix::zeros(counts.len(), data.cols());
+    for (row, &c) in data.iter_rows().zip(clusters.iter()) {
+        for (k, &x) in row.iter().enumerate() {
+            sums[[c, k]] = sums[[c, k]] + x;
+        }
+    }
+    for (c, &count) in counts.iter().enumerate() {
+        let count = T::from_usize(count).unwrap();
+        for k in 0..data.cols() {
+            sums[[c, k]] = sums[[c, k]] / count;
+        }
+    }
+    sums
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{silhouette_samples, silhouette_score, davies_bouldin, calinski_harabasz};
+    use linalg::{Matrix, Vector};
+
+    fn blobs() -> Matrix<f64> {
+        Matrix::new(9, 2, vec![0.0, 0.0, 0.3, 0.1, 0.1, 0.4,
+                               5.0, 5.0, 5.2, 4.7, 4.8, 5.3,
+                               0.0, 6.0, 0.4, 5.8, 2.5, 3.0])
+    }
+
+    #[test]
+    fn test_silhouette_by_hand() {
+        let data = Matrix::new(4, 1, vec![0.0, 1.0, 10.0, 11.0]);
+        let labels = Vector::new(vec![3, 3, 1, 1]);
+
+        let silhouettes = silhouette_samples(&data, &labels);
+        let expected = [1.0f64 - 1.0 / 10.5, 1.0 - 1.0 / 9.5, 1.0 - 1.0 / 9.5, 1.0 - 1.0 / 10.5];
+        for (s, e) in silhouettes.iter().zip(expected.iter()) {
+            assert!((s - e).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_mean_of_samples_is_score() {
+        let data = blobs();
+        let labels = Vector::new(vec![0, 0, 0, 1, 1, 1, 2, 2, 0]);
+
+        let silhouettes = silhouette_samples(&data, &labels);
+        assert!(silhouettes.iter().all(|&s| s >= -1.0 && s <= 1.0));
+        assert!((silhouettes.mean() - silhouette_score(&data, &labels)).abs() < 1e-12);
+
+        // The ambiguous last point fits its cluster worst
+        let worst = silhouettes.argmin().0;
+        assert_eq!(worst, 8);
+    }
+
+    #[test]
+    fn test_singleton_cluster_is_zero() {
+        let data = blobs();
+        let labels = Vector::new(vec![0, 0, 0, 1, 1, 1, 0, 0, 2]);
+
+        assert_eq!(silhouette_samples(&data, &labels)[8], 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_single_cluster() {
+        let _ = silhouette_samples(&blobs(), &Vector::new(vec![0; 9]));
+    }
+
+    #[test]
+    fn test_indices_rank_clusterings() {
+        let data = blobs();
+        let good = Vector::new(vec![0, 0, 0, 1, 1, 1, 2, 2, 0]);
+        let poor = Vector::new(vec![0, 1, 2, 0, 1, 2, 0, 1, 2]);
+
+        assert!(davies_bouldin(&data, &good) < davies_bouldin(&data, &poor));
+        assert!(calinski_harabasz(&data, &good) > calinski_harabasz(&data, &poor));
+    }
+
+    #[test]
+    fn test_indices_by_hand() {
+        let data = Matrix::new(4, 1, vec![0.0, 1.0, 10.0, 11.0]);
+        let labels = Vector::new(vec![0, 0, 1, 1]);
+
+        // Spreads are 0.5, the centroids are 10 apart
+        assert!((davies_bouldin(&data, &labels) - 0.1f64).abs() < 1e-12);
+
+        // B = 4 * 5^2 = 100 and W = 4 * 0.5^2 = 1
+        assert!((calinski_harabasz(&data, &labels) - 200.0f64).abs() < 1e-10);
+    }
+}
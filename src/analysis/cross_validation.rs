@@ -1,11 +1,12 @@
 //! Module for performing cross-validation of models.
+//!
+//! Also contains diagnostics built on cross-validation, such
+//! as the `learning_curve` and `validation_curve`.
 
 use std::cmp;
-use std::iter::Chain;
-use std::slice::Iter;
+use folds::{Folds, create_shuffled_indices};
 use linalg::{BaseMatrix, Matrix};
 use learning::{LearningResult, SupModel};
-use learning::toolkit::rand_utils::in_place_fisher_yates;
 
 /// Randomly splits the inputs into k 'folds'. For each fold a model
 /// is trained using all inputs except for that fold, and tested on the
@@ -52,10 +53,45 @@ pub fn k_fold_validate<M, S>(model: &mut M,
                              score: S) -> LearningResult<Vec<f64>>
     where S: Fn(&Matrix<f64>, &Matrix<f64>) -> f64,
           M: SupModel<Matrix<f64>, Matrix<f64>>,
+{
+    k_fold_validate_seeded(model, inputs, targets, k, score, None)
+}
+
+/// Performs k-fold cross validation with a reproducible split.
+///
+/// This is the same as `k_fold_validate`, except that the shuffle which
+/// assigns the samples to folds is seeded with `seed`. The same seed
+/// always gives the same folds, so together with a seeded model the
+/// scores are reproducible. The shuffle is random if `seed` is `None`.
+///
+/// # Examples
+/// ```
+/// use rusty_machine::analysis::cross_validation::k_fold_validate_seeded;
+/// use rusty_machine::analysis::score::row_accuracy;
+/// use rusty_machine::learning::naive_bayes::{NaiveBayes, Bernoulli};
+/// use rusty_machine::linalg::Matrix;
+///
+/// let inputs = Matrix::new(4, 2, vec![1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0]);
+/// let targets = Matrix::new(4, 2, vec![1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0]);
+///
+/// let mut model = NaiveBayes::<Bernoulli>::new();
+/// let a = k_fold_validate_seeded(&mut model, &inputs, &targets, 2, row_accuracy, Some(3));
+/// let b = k_fold_validate_seeded(&mut model, &inputs, &targets, 2, row_accuracy, Some(3));
+///
+/// assert_eq!(a.unwrap(), b.unwrap());
+/// ```
+pub fn k_fold_validate_seeded<M, S>(model: &mut M,
+                                    inputs: &Matrix<f64>,
+                                    targets: &Matrix<f64>,
+                                    k: usize,
+                                    score: S,
+                                    seed: Option<u64>) -> LearningResult<Vec<f64>>
+    where S: Fn(&Matrix<f64>, &Matrix<f64>) -> f64,
+          M: SupModel<Matrix<f64>, Matrix<f64>>,
 {
     assert_eq!(inputs.rows(), targets.rows());
     let num_samples = inputs.rows();
-    let shuffled_indices = create_shuffled_indices(num_samples);
+    let shuffled_indices = create_shuffled_indices(num_samples, seed);
     let folds = Folds::new(&shuffled_indices, k);
 
     let mut costs: Vec<f64> = Vec::new();
@@ -75,211 +111,366 @@ pub fn k_fold_validate<M, S>(model: &mut M,
     Ok(costs)
 }
 
-/// A permutation of 0..n.
-struct ShuffledIndices(Vec<usize>);
-
-/// Permute the indices of the inputs samples.
-fn create_shuffled_indices(num_samples: usize) -> ShuffledIndices {
-    let mut indices: Vec<usize> = (0..num_samples).collect();
-    in_place_fisher_yates(&mut indices);
-    ShuffledIndices(indices)
+/// Cross-validated scores of a model trained on increasing amounts of data.
+///
+/// Returned by `learning_curve`.
+#[derive(Debug)]
+pub struct LearningCurve {
+    /// The number of training samples used at each point of the curve.
+    pub train_sizes: Vec<usize>,
+    /// The score on the training data of each fold, for each point of the curve.
+    pub train_scores: Vec<Vec<f64>>,
+    /// The score on the held-out data of each fold, for each point of the curve.
+    pub validation_scores: Vec<Vec<f64>>,
 }
 
-/// A partition of indices of all available samples into
-/// a training set and a test set.
-struct Partition<'a> {
-    train_indices_iter: TrainingIndices<'a>,
-    test_indices_iter: TestIndices<'a>
+impl LearningCurve {
+    /// The training score at each point of the curve, averaged over the folds.
+    pub fn mean_train_scores(&self) -> Vec<f64> {
+        self.train_scores.iter().map(|s| mean(s)).collect()
+    }
+
+    /// The validation score at each point of the curve, averaged over the folds.
+    pub fn mean_validation_scores(&self) -> Vec<f64> {
+        self.validation_scores.iter().map(|s| mean(s)).collect()
+    }
 }
 
-#[derive(Clone)]
-struct TestIndices<'a>(Iter<'a, usize>);
+/// Computes the learning curve of a model.
+///
+/// The inputs are randomly split into k folds. For each fold, and for each
+/// fraction in `train_sizes`, a new model is trained on that fraction of the
+/// data outside of the fold. The model is scored on the data it was trained
+/// on and on the data in the fold.
+///
+/// Comparing the training and validation scores as the amount of training
+/// data increases helps to diagnose whether a model suffers from high bias
+/// or high variance.
+///
+/// # Arguments
+/// * `model_builder` - Creates a new untrained model for each point of the curve.
+/// * `inputs` - All input samples.
+/// * `targets` - All targets.
+/// * `train_sizes` - Fractions in `(0, 1]` of the available training data to use.
+/// * `k` - Number of folds to use.
+/// * `score` - Used to compare the outputs to the targets. Higher scores are better.
+/// * `seed` - Seeds the shuffle which assigns the samples to folds, so that the
+///   curve is reproducible. The shuffle is random if `None`.
+///
+/// # Examples
+/// ```
+/// use rusty_machine::analysis::cross_validation::learning_curve;
+/// use rusty_machine::analysis::score::row_accuracy;
+/// use rusty_machine::learning::naive_bayes::{NaiveBayes, Bernoulli};
+/// use rusty_machine::linalg::Matrix;
+///
+/// let inputs = Matrix::new(6, 2, vec![1.0, 0.0, 1.0, 0.0, 1.0, 0.0,
+///                                     0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+///
+/// let targets = Matrix::new(6, 2, vec![1.0, 0.0, 1.0, 0.0, 1.0, 0.0,
+///                                      0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+///
+/// let curve = learning_curve(|| NaiveBayes::<Bernoulli>::new(),
+///                            &inputs,
+///                            &targets,
+///                            &[0.5, 1.0],
+///                            3,
+///                            row_accuracy,
+///                            Some(42)).unwrap();
+///
+/// assert_eq!(curve.train_sizes, vec![2, 4]);
+/// ```
+pub fn learning_curve<M, B, S>(model_builder: B,
+                               inputs: &Matrix<f64>,
+                               targets: &Matrix<f64>,
+                               train_sizes: &[f64],
+                               k: usize,
+                               score: S,
+                               seed: Option<u64>) -> LearningResult<LearningCurve>
+    where B: Fn() -> M,
+          S: Fn(&Matrix<f64>, &Matrix<f64>) -> f64,
+          M: SupModel<Matrix<f64>, Matrix<f64>>,
+{
+    assert_eq!(inputs.rows(), targets.rows());
+    assert!(train_sizes.iter().all(|&f| f > 0f64 && f <= 1f64),
+            "Training sizes must be fractions in (0, 1].");
 
-#[derive(Clone)]
-struct TrainingIndices<'a> {
-    chain: Chain<Iter<'a, usize>, Iter<'a, usize>>,
-    size: usize
-}
+    let num_samples = inputs.rows();
+    let shuffled_indices = create_shuffled_indices(num_samples, seed);
 
-impl<'a> TestIndices<'a> {
-    fn new(indices: &'a [usize]) -> TestIndices<'a> {
-        TestIndices(indices.iter())
-    }
-}
+    // Use the same number of samples for every fold
+    let min_train = num_samples - num_samples.div_ceil(k);
+    let sizes = train_sizes.iter()
+        .map(|&f| cmp::max(1, (f * min_train as f64).round() as usize))
+        .collect::<Vec<_>>();
 
-impl<'a> Iterator for TestIndices<'a> {
-    type Item = &'a usize;
+    let mut train_scores = vec![Vec::with_capacity(k); sizes.len()];
+    let mut validation_scores = vec![Vec::with_capacity(k); sizes.len()];
 
-    fn next(&mut self) -> Option<&'a usize> {
-        self.0.next()
-    }
-}
+    for p in Folds::new(&shuffled_indices, k) {
+        let fold_train = p.train_indices_iter.cloned().collect::<Vec<_>>();
+        let test_inputs = inputs.select_rows(p.test_indices_iter.clone());
+        let test_targets = targets.select_rows(p.test_indices_iter.clone());
 
-impl <'a> ExactSizeIterator for TestIndices<'a> {
-    fn len(&self) -> usize {
-        self.0.len()
-    }
-}
+        for (i, &size) in sizes.iter().enumerate() {
+            let train_inputs = inputs.select_rows(&fold_train[..size]);
+            let train_targets = targets.select_rows(&fold_train[..size]);
+
+            let mut model = model_builder();
+            model.train(&train_inputs, &train_targets)?;
+
+            let train_outputs = model.predict(&train_inputs)?;
+            train_scores[i].push(score(&train_outputs, &train_targets));
 
-impl<'a> TrainingIndices<'a> {
-    fn new(left: &'a [usize], right: &'a [usize]) -> TrainingIndices<'a> {
-        let chain = left.iter().chain(right.iter());
-        TrainingIndices {
-            chain: chain,
-            size: left.len() + right.len()
+            let test_outputs = model.predict(&test_inputs)?;
+            validation_scores[i].push(score(&test_outputs, &test_targets));
         }
     }
+
+    Ok(LearningCurve {
+        train_sizes: sizes,
+        train_scores,
+        validation_scores,
+    })
 }
 
-impl<'a> Iterator for TrainingIndices<'a> {
-    type Item = &'a usize;
+/// Cross-validated scores of a model for a range of hyperparameter values.
+///
+/// Returned by `validation_curve`.
+#[derive(Debug)]
+pub struct ValidationCurve {
+    /// The score on the training data of each fold, for each parameter value.
+    pub train_scores: Vec<Vec<f64>>,
+    /// The score on the held-out data of each fold, for each parameter value.
+    pub validation_scores: Vec<Vec<f64>>,
+}
 
-    fn next(&mut self) -> Option<&'a usize> {
-        self.chain.next()
+impl ValidationCurve {
+    /// The training score for each parameter value, averaged over the folds.
+    pub fn mean_train_scores(&self) -> Vec<f64> {
+        self.train_scores.iter().map(|s| mean(s)).collect()
     }
-}
 
-impl <'a> ExactSizeIterator for TrainingIndices<'a> {
-    fn len(&self) -> usize {
-        self.size
+    /// The validation score for each parameter value, averaged over the folds.
+    pub fn mean_validation_scores(&self) -> Vec<f64> {
+        self.validation_scores.iter().map(|s| mean(s)).collect()
     }
 }
 
-/// An iterator over the sets of indices required for k-fold cross validation.
-struct Folds<'a> {
-    num_folds: usize,
-    indices: &'a[usize],
-    count: usize
-}
+/// Computes the validation curve of a model over a single hyperparameter.
+///
+/// The inputs are randomly split into k folds. For each fold, and for each
+/// value in `param_values`, a new model is built from the value and trained
+/// on the data outside of the fold. The model is scored on the data it was
+/// trained on and on the data in the fold. The same folds are used for
+/// every parameter value.
+///
+/// # Arguments
+/// * `model_builder` - Creates a new untrained model from a parameter value.
+/// * `inputs` - All input samples.
+/// * `targets` - All targets.
+/// * `param_values` - The hyperparameter values to evaluate.
+/// * `k` - Number of folds to use.
+/// * `score` - Used to compare the outputs to the targets. Higher scores are better.
+/// * `seed` - Seeds the shuffle which assigns the samples to folds, so that the
+///   curve is reproducible. The shuffle is random if `None`.
+///
+/// # Examples
+/// ```
+/// use rusty_machine::analysis::cross_validation::validation_curve;
+/// use rusty_machine::analysis::score::row_accuracy;
+/// use rusty_machine::learning::naive_bayes::{NaiveBayes, Bernoulli};
+/// use rusty_machine::linalg::Matrix;
+///
+/// let inputs = Matrix::new(6, 2, vec![1.0, 0.0, 1.0, 0.0, 1.0, 0.0,
+///                                     0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+///
+/// let targets = Matrix::new(6, 2, vec![1.0, 0.0, 1.0, 0.0, 1.0, 0.0,
+///                                      0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+///
+/// // NaiveBayes has no hyperparameters, so the builder ignores the value
+/// let curve = validation_curve(|_: &f64| NaiveBayes::<Bernoulli>::new(),
+///                              &inputs,
+///                              &targets,
+///                              &[0.1, 1.0],
+///                              3,
+///                              row_accuracy,
+///                              Some(42)).unwrap();
+///
+/// assert_eq!(curve.validation_scores.len(), 2);
+/// ```
+pub fn validation_curve<M, P, B, S>(model_builder: B,
+                                    inputs: &Matrix<f64>,
+                                    targets: &Matrix<f64>,
+                                    param_values: &[P],
+                                    k: usize,
+                                    score: S,
+                                    seed: Option<u64>) -> LearningResult<ValidationCurve>
+    where B: Fn(&P) -> M,
+          S: Fn(&Matrix<f64>, &Matrix<f64>) -> f64,
+          M: SupModel<Matrix<f64>, Matrix<f64>>,
+{
+    assert_eq!(inputs.rows(), targets.rows());
+    let num_samples = inputs.rows();
+    let shuffled_indices = create_shuffled_indices(num_samples, seed);
 
-impl<'a> Folds<'a> {
-    /// Let n = indices.len(), and k = num_folds.
-    /// The first n % k folds have size n / k + 1 and the
-    /// rest have size n / k. (In particular, if n % k == 0 then all
-    /// folds are the same size.)
-    fn new(indices: &'a ShuffledIndices, num_folds: usize) -> Folds<'a> {
-        let num_samples = indices.0.len();
-        assert!(num_folds > 1 && num_samples >= num_folds,
-            "Require num_folds > 1 && num_samples >= num_folds");
-
-        Folds {
-            num_folds: num_folds,
-            indices: &indices.0,
-            count: 0
-        }
-    }
-}
+    let mut train_scores = vec![Vec::with_capacity(k); param_values.len()];
+    let mut validation_scores = vec![Vec::with_capacity(k); param_values.len()];
 
-impl<'a> Iterator for Folds<'a> {
-    type Item = Partition<'a>;
+    for p in Folds::new(&shuffled_indices, k) {
+        let train_inputs = inputs.select_rows(p.train_indices_iter.clone());
+        let train_targets = targets.select_rows(p.train_indices_iter.clone());
+        let test_inputs = inputs.select_rows(p.test_indices_iter.clone());
+        let test_targets = targets.select_rows(p.test_indices_iter.clone());
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.count >= self.num_folds {
-            return None;
-        }
+        for (i, param) in param_values.iter().enumerate() {
+            let mut model = model_builder(param);
+            model.train(&train_inputs, &train_targets)?;
+
+            let train_outputs = model.predict(&train_inputs)?;
+            train_scores[i].push(score(&train_outputs, &train_targets));
 
-        let num_samples = self.indices.len();
-        let q = num_samples / self.num_folds;
-        let r = num_samples % self.num_folds;
-        let fold_start = self.count * q + cmp::min(self.count, r);
-        let fold_size = if self.count >= r {q} else {q + 1};
-        let fold_end = fold_start + fold_size;
-
-        self.count += 1;
-
-        let prefix = &self.indices[..fold_start];
-        let suffix = &self.indices[fold_end..];
-        let infix = &self.indices[fold_start..fold_end];
-        Some(Partition {
-            train_indices_iter: TrainingIndices::new(prefix, suffix),
-            test_indices_iter: TestIndices::new(infix)
-        })
+            let test_outputs = model.predict(&test_inputs)?;
+            validation_scores[i].push(score(&test_outputs, &test_targets));
+        }
     }
+
+    Ok(ValidationCurve {
+        train_scores,
+        validation_scores,
+    })
+}
+
+/// The mean of a non-empty slice.
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / (values.len() as f64)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ShuffledIndices, Folds};
-
-    // k % n == 0
-    #[test]
-    fn test_folds_n6_k3() {
-        let idxs = ShuffledIndices(vec![0, 1, 2, 3, 4, 5]);
-        let folds = collect_folds(Folds::new(&idxs, 3));
-
-        assert_eq!(folds, vec![
-            (vec![2, 3, 4, 5], vec![0, 1]),
-            (vec![0, 1, 4, 5], vec![2, 3]),
-            (vec![0, 1, 2, 3], vec![4, 5])
-            ]);
+    use super::{learning_curve, validation_curve};
+    use analysis::score::neg_mean_squared_error;
+    use learning::{LearningResult, SupModel};
+    use learning::error::{Error, ErrorKind};
+    use linalg::{BaseMatrix, Matrix, MatrixExt};
+
+    use rand::{SeedableRng, StdRng};
+    use rand::distributions::{IndependentSample, Normal};
+
+    /// Ridge regression with matrix targets.
+    ///
+    /// A `lambda` of zero gives ordinary least squares.
+    struct LeastSquares {
+        lambda: f64,
+        parameters: Option<Matrix<f64>>,
     }
 
-    // k % n == 1
-    #[test]
-    fn test_folds_n5_k2() {
-        let idxs = ShuffledIndices(vec![0, 1, 2, 3, 4]);
-        let folds = collect_folds(Folds::new(&idxs, 2));
-
-        assert_eq!(folds, vec![
-            (vec![3, 4], vec![0, 1, 2]),
-            (vec![0, 1, 2], vec![3, 4])
-            ]);
+    impl LeastSquares {
+        fn new(lambda: f64) -> LeastSquares {
+            LeastSquares {
+                lambda,
+                parameters: None,
+            }
+        }
     }
 
-    // k % n == 2
-    #[test]
-    fn test_folds_n6_k4() {
-        let idxs = ShuffledIndices(vec![0, 1, 2, 3, 4, 5]);
-        let folds = collect_folds(Folds::new(&idxs, 4));
-
-        assert_eq!(folds, vec![
-            (vec![2, 3, 4, 5], vec![0, 1]),
-            (vec![0, 1, 4, 5], vec![2, 3]),
-            (vec![0, 1, 2, 3, 5], vec![4]),
-            (vec![0, 1, 2, 3, 4], vec![5])
-            ]);
-    }
+    impl SupModel<Matrix<f64>, Matrix<f64>> for LeastSquares {
+        fn train(&mut self, inputs: &Matrix<f64>, targets: &Matrix<f64>) -> LearningResult<()> {
+            let penalty = Matrix::<f64>::identity(inputs.cols()) * self.lambda;
+            let inv = (inputs.gram() + penalty)
+                .inverse()
+                .map_err(|_| Error::new(ErrorKind::LinearAlgebra, "Singular gram matrix."))?;
+            self.parameters = Some(inv * inputs.transpose() * targets);
+            Ok(())
+        }
 
-    // k == n
-    #[test]
-    fn test_folds_n4_k4() {
-        let idxs = ShuffledIndices(vec![0, 1, 2, 3]);
-        let folds = collect_folds(Folds::new(&idxs, 4));
-
-        assert_eq!(folds, vec![
-            (vec![1, 2, 3], vec![0]),
-            (vec![0, 2, 3], vec![1]),
-            (vec![0, 1, 3], vec![2]),
-            (vec![0, 1, 2], vec![3])
-            ]);
+        fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+            self.parameters.as_ref().map(|p| inputs * p).ok_or_else(Error::new_untrained)
+        }
     }
 
-    #[test]
-    #[should_panic]
-    fn test_folds_rejects_large_k() {
-        let idxs = ShuffledIndices(vec![0, 1, 2]);
-        let _ = collect_folds(Folds::new(&idxs, 4));
+    /// Noisy samples of `y = 2x + 1` with an explicit bias column.
+    fn linear_data(n: usize) -> (Matrix<f64>, Matrix<f64>) {
+        let mut inputs = Vec::with_capacity(2 * n);
+        let mut targets = Vec::with_capacity(n);
+        for i in 0..n {
+            let x = i as f64 / n as f64;
+            inputs.push(1.0);
+            inputs.push(x);
+            targets.push(2.0 * x + 1.0 + 0.1 * (7.3 * i as f64).sin());
+        }
+        (Matrix::new(n, 2, inputs), Matrix::new(n, 1, targets))
     }
 
-    // Check we're really returning iterators into the shuffled
-    // indices rather than into (0..n).
     #[test]
-    fn test_folds_unordered_indices() {
-        let idxs = ShuffledIndices(vec![5, 4, 3, 2, 1, 0]);
-        let folds = collect_folds(Folds::new(&idxs, 3));
-
-        assert_eq!(folds, vec![
-            (vec![3, 2, 1, 0], vec![5, 4]),
-            (vec![5, 4, 1, 0], vec![3, 2]),
-            (vec![5, 4, 3, 2], vec![1, 0])
-            ]);
+    fn test_learning_curve_shape() {
+        let (inputs, targets) = linear_data(40);
+        let curve = learning_curve(|| LeastSquares::new(0.0),
+                                   &inputs,
+                                   &targets,
+                                   &[0.25, 0.5, 1.0],
+                                   4,
+                                   neg_mean_squared_error,
+                                   Some(3))
+            .unwrap();
+
+        assert_eq!(curve.train_sizes, vec![8, 15, 30]);
+        assert_eq!(curve.train_scores.len(), 3);
+        assert_eq!(curve.validation_scores.len(), 3);
+
+        for (train, validation) in curve.train_scores.iter().zip(curve.validation_scores.iter()) {
+            assert_eq!(train.len(), 4);
+            assert_eq!(validation.len(), 4);
+        }
+
+        // The training error is reported and small for a good fit
+        for s in curve.mean_train_scores() {
+            assert!(s <= 0.0 && s > -0.05);
+        }
     }
 
-    fn collect_folds<'a>(folds: Folds<'a>) -> Vec<(Vec<usize>, Vec<usize>)> {
-        folds
-            .map(|p|
-                (p.train_indices_iter.map(|x| *x).collect::<Vec<_>>(),
-                 p.test_indices_iter.map(|x| *x).collect::<Vec<_>>()))
-            .collect::<Vec<(Vec<usize>, Vec<usize>)>>()
+    #[test]
+    fn test_validation_curve_ridge() {
+        // Few samples relative to features, so least squares overfits
+        let seed: &[_] = &[7, 11];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let normal = Normal::new(0.0, 1.0);
+
+        let (n, d) = (38, 30);
+        let mut sample = |size: usize| {
+            (0..size).map(|_| normal.ind_sample(&mut rng)).collect::<Vec<_>>()
+        };
+
+        let inputs = Matrix::new(n, d, sample(n * d));
+        let coefs = Matrix::new(d, 1, sample(d));
+        let noise = Matrix::new(n, 1, sample(n));
+        let targets = &inputs * coefs + noise;
+
+        // Fix the folds so the curve does not depend on the shuffle
+        let lambdas = [0.0, 3.0, 1e5];
+        let curve = |seed| {
+            validation_curve(|&lambda: &f64| LeastSquares::new(lambda),
+                             &inputs,
+                             &targets,
+                             &lambdas,
+                             5,
+                             neg_mean_squared_error,
+                             Some(seed))
+                .unwrap()
+        };
+        let (curve, repeat) = (curve(5), curve(5));
+        assert_eq!(curve.validation_scores, repeat.validation_scores);
+
+        assert_eq!(curve.train_scores.len(), 3);
+        assert!(curve.validation_scores.iter().all(|s| s.len() == 5));
+
+        // The training score always improves with less regularization
+        let train = curve.mean_train_scores();
+        assert!(train[0] > train[1] && train[1] > train[2]);
+
+        // The validation score peaks at the intermediate value
+        let validation = curve.mean_validation_scores();
+        assert!(validation[1] > validation[0]);
+        assert!(validation[1] > validation[2]);
     }
 }
@@ -1,8 +1,12 @@
 //! Functions for scoring a set of predictions, i.e. evaluating
 //! how close predictions and truth are. All functions in this
 //! module obey the convention that higher is better.
+//!
+//! The `weighted_*` variants take a weight for each sample, so that
+//! samples contribute to the score in proportion to their weight. With
+//! equal weights they agree with the unweighted functions.
 
-use linalg::{BaseMatrix, Matrix};
+use linalg::{BaseMatrix, Matrix, Vector};
 use learning::toolkit::cost_fn::{CostFunc, MeanSqError};
 
 /// Returns the fraction of outputs which match their target.
@@ -19,11 +23,66 @@ pub fn accuracy<I>(outputs: I, targets: I) -> f64
     correct as f64 / len
 }
 
+/// Returns the weighted fraction of outputs which match their target.
+///
+/// This is the total weight of the matching outputs divided by the
+/// total weight.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::analysis::score::weighted_accuracy;
+/// use rusty_machine::linalg::Vector;
+///
+/// let outputs = [1, 2, 3];
+/// let targets = [1, 2, 0];
+/// let weights = Vector::new(vec![1.0, 2.0, 1.0]);
+///
+/// assert_eq!(weighted_accuracy(outputs.iter(), targets.iter(), &weights), 0.75);
+/// ```
+///
+/// # Panics
+///
+/// - The outputs, targets and weights have different lengths.
+/// - The weights are invalid, see `check_weights`.
+pub fn weighted_accuracy<I>(outputs: I, targets: I, weights: &Vector<f64>) -> f64
+    where I: ExactSizeIterator,
+          I::Item: PartialEq
+{
+    assert!(outputs.len() == targets.len());
+    let total = check_weights(weights, outputs.len());
+    let correct = outputs
+        .zip(targets)
+        .zip(weights.iter())
+        .filter(|&((ref x, ref y), _)| x == y)
+        .map(|(_, w)| *w)
+        .sum::<f64>();
+    correct / total
+}
+
+/// Returns the fraction of predicted labels which match their target label.
+///
+/// This is the score of the classifiers, whose outputs are a `Vector`
+/// of class labels.
+pub fn label_accuracy(outputs: &Vector<f64>, targets: &Vector<f64>) -> f64 {
+    accuracy(outputs.data().iter(), targets.data().iter())
+}
+
 /// Returns the fraction of outputs rows which match their target.
 pub fn row_accuracy(outputs: &Matrix<f64>, targets: &Matrix<f64>) -> f64 {
     accuracy(outputs.iter_rows(), targets.iter_rows())
 }
 
+/// Returns the weighted fraction of outputs rows which match their target.
+///
+/// See `weighted_accuracy`.
+pub fn weighted_row_accuracy(outputs: &Matrix<f64>,
+                             targets: &Matrix<f64>,
+                             weights: &Vector<f64>)
+                             -> f64 {
+    weighted_accuracy(outputs.iter_rows(), targets.iter_rows(), weights)
+}
+
 // TODO: generalise to accept arbitrary iterators of diff-able things
 /// Returns the additive inverse of the mean-squared-error of the
 /// outputs. So higher is better, and the returned value is always
@@ -34,10 +93,156 @@ pub fn neg_mean_squared_error(outputs: &Matrix<f64>, targets: &Matrix<f64>) -> f
     -2f64 * MeanSqError::cost(outputs, targets)
 }
 
+/// Returns the additive inverse of the weighted mean-squared-error of
+/// the outputs.
+///
+/// Each row contributes its squared error weighted by the corresponding
+/// entry of `weights`, and the total is divided by the sum of the weights.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::analysis::score::weighted_neg_mean_squared_error;
+/// use rusty_machine::linalg::{Matrix, Vector};
+///
+/// let outputs = Matrix::new(2, 1, vec![1.0, 2.0]);
+/// let targets = Matrix::new(2, 1, vec![1.0, 4.0]);
+/// let weights = Vector::new(vec![3.0, 1.0]);
+///
+/// assert_eq!(weighted_neg_mean_squared_error(&outputs, &targets, &weights), -1.0);
+/// ```
+///
+/// # Panics
+///
+/// - The outputs and targets have different dimensions.
+/// - The weights are invalid, see `check_weights`.
+pub fn weighted_neg_mean_squared_error(outputs: &Matrix<f64>,
+                                       targets: &Matrix<f64>,
+                                       weights: &Vector<f64>)
+                                       -> f64 {
+    assert!(outputs.rows() == targets.rows() && outputs.cols() == targets.cols(),
+            "outputs and targets must have the same dimensions");
+    let total = check_weights(weights, targets.rows());
+
+    let sq_err = outputs.iter_rows()
+        .zip(targets.iter_rows())
+        .zip(weights.iter())
+        .map(|((o, t), w)| {
+            w * o.iter().zip(t.iter()).map(|(a, b)| (a - b) * (a - b)).sum::<f64>()
+        })
+        .sum::<f64>();
+
+    -sq_err / total
+}
+
+/// Returns the coefficient of determination (R<sup>2</sup>) of the outputs.
+///
+/// A perfect fit scores `1` and predicting the mean of the targets
+/// scores `0`. For outputs with multiple columns the scores of each
+/// column are averaged.
+///
+/// If a column of targets is constant the score for that column is `1`
+/// for a perfect fit and `0` otherwise.
+///
+/// # Panics
+///
+/// - The outputs and targets have different dimensions.
+pub fn r_squared(outputs: &Matrix<f64>, targets: &Matrix<f64>) -> f64 {
+    weighted_r_squared(outputs, targets, &Vector::ones(targets.rows()))
+}
+
+/// Returns the weighted coefficient of determination (R<sup>2</sup>)
+/// of the outputs.
+///
+/// The residual and total sums of squares are weighted by sample and
+/// the total is taken about the weighted mean of the targets. See
+/// `r_squared` for the handling of multiple and constant columns.
+///
+/// # Panics
+///
+/// - The outputs and targets have different dimensions.
+/// - The weights are invalid, see `check_weights`.
+pub fn weighted_r_squared(outputs: &Matrix<f64>,
+                          targets: &Matrix<f64>,
+                          weights: &Vector<f64>)
+                          -> f64 {
+    assert!(outputs.rows() == targets.rows() && outputs.cols() == targets.cols(),
+            "outputs and targets must have the same dimensions");
+
+    let total_weight = check_weights(weights, targets.rows());
+    let mut total_score = 0f64;
+
+    for j in 0..targets.cols() {
+        let target_mean = targets.iter_rows()
+            .zip(weights.iter())
+            .map(|(r, w)| w * r[j])
+            .sum::<f64>() / total_weight;
+
+        let mut ss_res = 0f64;
+        let mut ss_tot = 0f64;
+        for ((o, t), w) in outputs.iter_rows().zip(targets.iter_rows()).zip(weights.iter()) {
+            ss_res += w * (t[j] - o[j]) * (t[j] - o[j]);
+            ss_tot += w * (t[j] - target_mean) * (t[j] - target_mean);
+        }
+
+        total_score += if ss_tot > 0f64 {
+            1f64 - ss_res / ss_tot
+        } else if ss_res > 0f64 {
+            0f64
+        } else {
+            1f64
+        };
+    }
+
+    total_score / targets.cols() as f64
+}
+
+/// Checks that the sample weights are valid and returns their sum.
+///
+/// # Panics
+///
+/// - The number of weights is not `n`.
+/// - A weight is negative or not finite.
+/// - The weights sum to zero.
+pub fn check_weights(weights: &Vector<f64>, n: usize) -> f64 {
+    assert!(weights.size() == n,
+            "There must be one weight for each sample");
+    assert!(weights.iter().all(|w| w.is_finite() && *w >= 0f64),
+            "Weights must be finite and non-negative");
+
+    let total = weights.sum();
+    assert!(total > 0f64, "Weights must not all be zero");
+    total
+}
+
+/// The default score used by `SupModel::score`.
+///
+/// This is implemented for the output types of the models and
+/// returns the R<sup>2</sup> score of the outputs.
+pub trait DefaultScore {
+    /// Score the outputs against the targets, higher is better.
+    fn default_score(outputs: &Self, targets: &Self) -> f64;
+}
+
+impl DefaultScore for Matrix<f64> {
+    fn default_score(outputs: &Matrix<f64>, targets: &Matrix<f64>) -> f64 {
+        r_squared(outputs, targets)
+    }
+}
+
+impl DefaultScore for Vector<f64> {
+    fn default_score(outputs: &Vector<f64>, targets: &Vector<f64>) -> f64 {
+        r_squared(&Matrix::new(outputs.size(), 1, outputs.data().clone()),
+                  &Matrix::new(targets.size(), 1, targets.data().clone()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use linalg::Matrix;
-    use super::{accuracy, neg_mean_squared_error};
+    use linalg::{BaseMatrix, Matrix, Vector};
+    use super::{accuracy, label_accuracy, neg_mean_squared_error, r_squared};
+    use super::{weighted_accuracy, weighted_row_accuracy, weighted_neg_mean_squared_error,
+                weighted_r_squared};
 
     #[test]
     fn test_accuracy() {
@@ -46,6 +251,13 @@ mod tests {
         assert_eq!(accuracy(outputs.iter(), targets.iter()), 2f64/3f64);
     }
 
+    #[test]
+    fn test_label_accuracy() {
+        let outputs = Vector::new(vec![1.0, 0.0, 2.0, 2.0]);
+        let targets = Vector::new(vec![1.0, 1.0, 2.0, 0.0]);
+        assert_eq!(label_accuracy(&outputs, &targets), 0.5);
+    }
+
     #[test]
     fn test_neg_mean_squared_error_1d() {
         let outputs = Matrix::new(3, 1, vec![1f64, 2f64, 3f64]);
@@ -67,4 +279,108 @@ mod tests {
             ]);
         assert_eq!(neg_mean_squared_error(&outputs, &targets), -3f64);
     }
+
+    #[test]
+    fn test_r_squared_perfect_fit() {
+        let outputs = Matrix::new(3, 1, vec![1f64, 2f64, 3f64]);
+        assert_eq!(r_squared(&outputs, &outputs), 1f64);
+    }
+
+    #[test]
+    fn test_r_squared_mean_prediction() {
+        let outputs = Matrix::new(3, 1, vec![2f64, 2f64, 2f64]);
+        let targets = Matrix::new(3, 1, vec![1f64, 2f64, 3f64]);
+        assert_eq!(r_squared(&outputs, &targets), 0f64);
+    }
+
+    #[test]
+    fn test_r_squared_2d() {
+        let outputs = Matrix::new(3, 2, vec![
+            1f64, 2f64,
+            2f64, 2f64,
+            3f64, 2f64
+            ]);
+        let targets = Matrix::new(3, 2, vec![
+            1f64, 1f64,
+            2f64, 2f64,
+            3f64, 3f64
+            ]);
+        assert_eq!(r_squared(&outputs, &targets), 0.5f64);
+    }
+
+    #[test]
+    fn test_r_squared_constant_targets() {
+        let targets = Matrix::new(2, 1, vec![1f64, 1f64]);
+        let outputs = Matrix::new(2, 1, vec![1f64, 2f64]);
+        assert_eq!(r_squared(&targets, &targets), 1f64);
+        assert_eq!(r_squared(&outputs, &targets), 0f64);
+    }
+
+    #[test]
+    fn test_weighted_accuracy_equal_weights() {
+        let outputs = [1, 2, 3, 4, 5, 6];
+        let targets = [1, 2, 3, 3, 5, 1];
+        let weights = Vector::new(vec![0.5; 6]);
+        assert_eq!(weighted_accuracy(outputs.iter(), targets.iter(), &weights),
+                   accuracy(outputs.iter(), targets.iter()));
+    }
+
+    #[test]
+    fn test_weighted_accuracy_zero_weights_ignored() {
+        let outputs = [1, 2, 3, 4];
+        let targets = [1, 2, 0, 0];
+        let weights = Vector::new(vec![1.0, 1.0, 0.0, 0.0]);
+        assert_eq!(weighted_accuracy(outputs.iter(), targets.iter(), &weights), 1f64);
+
+        let outputs = Matrix::new(3, 1, vec![1f64, 2f64, 3f64]);
+        let targets = Matrix::new(3, 1, vec![1f64, 0f64, 3f64]);
+        let weights = Vector::new(vec![2.0, 0.0, 1.0]);
+        assert_eq!(weighted_row_accuracy(&outputs, &targets, &weights), 1f64);
+    }
+
+    #[test]
+    fn test_weighted_neg_mean_squared_error_equal_weights() {
+        let outputs = Matrix::new(3, 2, vec![1f64, 2f64, 3f64, 4f64, 5f64, 6f64]);
+        let targets = Matrix::new(3, 2, vec![1.5f64, 2.5f64, 5f64, 6f64, 5.5f64, 6.5f64]);
+        let weights = Vector::new(vec![2f64; 3]);
+
+        let weighted = weighted_neg_mean_squared_error(&outputs, &targets, &weights);
+        let unweighted = neg_mean_squared_error(&outputs, &targets);
+        assert!((weighted - unweighted).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_weighted_neg_mean_squared_error_zero_weights_ignored() {
+        let outputs = Matrix::new(3, 1, vec![1f64, 2f64, 100f64]);
+        let targets = Matrix::new(3, 1, vec![2f64, 4f64, 3f64]);
+        let weights = Vector::new(vec![1f64, 1f64, 0f64]);
+
+        let subset_out = Matrix::new(2, 1, vec![1f64, 2f64]);
+        let subset_tar = Matrix::new(2, 1, vec![2f64, 4f64]);
+        assert_eq!(weighted_neg_mean_squared_error(&outputs, &targets, &weights),
+                   neg_mean_squared_error(&subset_out, &subset_tar));
+    }
+
+    #[test]
+    fn test_weighted_r_squared() {
+        let outputs = Matrix::new(4, 1, vec![1f64, 2.5f64, 2f64, 50f64]);
+        let targets = Matrix::new(4, 1, vec![1f64, 2f64, 3f64, 4f64]);
+
+        let equal = weighted_r_squared(&outputs, &targets, &Vector::new(vec![3f64; 4]));
+        assert!((equal - r_squared(&outputs, &targets)).abs() < 1e-12);
+
+        let weights = Vector::new(vec![1f64, 1f64, 1f64, 0f64]);
+        let subset = r_squared(&outputs.select_rows(&[0, 1, 2]),
+                               &targets.select_rows(&[0, 1, 2]));
+        let weighted = weighted_r_squared(&outputs, &targets, &weights);
+        assert!((weighted - subset).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_weighted_accuracy_all_zero_weights() {
+        let outputs = [1, 2];
+        let weights = Vector::zeros(2);
+        let _ = weighted_accuracy(outputs.iter(), outputs.iter(), &weights);
+    }
 }
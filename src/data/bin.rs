@@ -0,0 +1,189 @@
+//! Binning for data handling
+//!
+//! This module contains tools for dividing continuous values into bins.
+//!
+//! The `histogram` function counts the values falling in equal width
+//! bins, and `digitize` finds the bin of a value given the bin edges.
+//! The `KBinsDiscretizer` transformer in `data::transforms` uses these
+//! to map continuous features into integer bins.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::data::bin::histogram;
+//! use rusty_machine::linalg::Vector;
+//!
+//! let v = Vector::new(vec![0.0, 0.5, 1.0, 1.5, 4.0]);
+//! let (counts, edges) = histogram(&v, 4);
+//!
+//! assert_eq!(counts, vec![2, 2, 0, 1]);
+//! assert_eq!(edges, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+//! ```
+
+use linalg::Vector;
+
+use libnum::{Float, FromPrimitive};
+
+/// Computes a histogram of the values with equal width bins.
+///
+/// The bins span the range of the finite values. Each bin includes
+/// its left edge, and the last bin also includes its right edge. If
+/// all values are equal the bins span one unit centered on the value.
+/// Non-finite values are not counted.
+///
+/// Returns the counts for each bin and the `bins + 1` bin edges.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::data::bin::histogram;
+/// use rusty_machine::linalg::Vector;
+///
+/// let v = Vector::new(vec![2.0, 2.0, 2.0]);
+/// let (counts, edges) = histogram(&v, 2);
+///
+/// assert_eq!(counts, vec![0, 3]);
+/// assert_eq!(edges, vec![1.5, 2.0, 2.5]);
+/// ```
+///
+/// # Panics
+///
+/// - `bins` is zero.
+pub fn histogram<T>(v: &Vector<T>, bins: usize) -> (Vec<usize>, Vec<T>)
+    where T: Float + FromPrimitive
+{
+    assert!(bins > 0, "The number of bins must be positive.");
+
+    let finite = v.data().iter().cloned().filter(|x| x.is_finite()).collect::<Vec<_>>();
+    let (min, max) = if finite.is_empty() {
+        (T::zero(), T::one())
+    } else {
+        finite.iter().fold((T::max_value(), T::min_value()),
+                           |(lo, hi), &x| (lo.min(x), hi.max(x)))
+    };
+
+    let edges = uniform_edges(min, max, bins);
+    let mut counts = vec![0; bins];
+    for x in finite {
+        counts[digitize(x, &edges)] += 1;
+    }
+
+    (counts, edges)
+}
+
+/// Finds the bin containing `x` given the bin edges.
+///
+/// Bin `i` contains the values in `[edges[i], edges[i + 1])`, and the
+/// last bin also contains the final edge. Values outside of the edges
+/// are placed in the first or last bin.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::data::bin::digitize;
+///
+/// let edges = [0.0, 1.0, 2.0, 3.0];
+///
+/// assert_eq!(digitize(1.5, &edges), 1);
+/// assert_eq!(digitize(3.0, &edges), 2);
+/// assert_eq!(digitize(-4.0, &edges), 0);
+/// ```
+///
+/// # Panics
+///
+/// - There are fewer than two edges.
+pub fn digitize<T: Float>(x: T, edges: &[T]) -> usize {
+    assert!(edges.len() > 1, "There must be at least two bin edges.");
+    edges[1..edges.len() - 1].iter().take_while(|&&e| e <= x).count()
+}
+
+/// Equally spaced edges for `bins` bins from `min` to `max`.
+///
+/// If `min` and `max` are equal the edges span one unit around them.
+pub fn uniform_edges<T>(min: T, max: T, bins: usize) -> Vec<T>
+    where T: Float + FromPrimitive
+{
+    let half = T::from_f64(0.5).unwrap();
+    let (min, max) = if min == max { (min - half, max + half) } else { (min, max) };
+
+    let width = (max - min) / T::from_usize(bins).unwrap();
+    let mut edges = (0..bins).map(|i| min + width * T::from_usize(i).unwrap()).collect::<Vec<_>>();
+    edges.push(max);
+    edges
+}
+
+/// Edges for `bins` bins at the quantiles of the values.
+///
+/// The quantiles are linearly interpolated between the sorted
+/// values, so each bin holds roughly the same number of values.
+///
+/// # Panics
+///
+/// - `values` is empty or contains NaN.
+pub fn quantile_edges<T>(values: &[T], bins: usize) -> Vec<T>
+    where T: Float + FromPrimitive
+{
+    assert!(!values.is_empty(), "Cannot compute quantiles of empty data.");
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).expect("Cannot compute quantiles of NaN."));
+
+    let last = T::from_usize(sorted.len() - 1).unwrap();
+    (0..bins + 1)
+        .map(|i| {
+            let pos = last * T::from_usize(i).unwrap() / T::from_usize(bins).unwrap();
+            let lo = pos.floor();
+            let idx = lo.to_usize().unwrap();
+            if idx + 1 < sorted.len() {
+                sorted[idx] + (sorted[idx + 1] - sorted[idx]) * (pos - lo)
+            } else {
+                sorted[idx]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{histogram, digitize, quantile_edges};
+    use linalg::Vector;
+
+    use std::f64;
+
+    #[test]
+    fn test_histogram_counts_sum_to_length() {
+        let v = Vector::new((0..97).map(|i| ((i * 37) % 101) as f64 / 7.0).collect::<Vec<_>>());
+        let (counts, edges) = histogram(&v, 10);
+
+        assert_eq!(counts.len(), 10);
+        assert_eq!(edges.len(), 11);
+        assert_eq!(counts.iter().sum::<usize>(), 97);
+    }
+
+    #[test]
+    fn test_histogram_ignores_non_finite() {
+        let v = Vector::new(vec![0.0, 1.0, f64::NAN, f64::INFINITY, 2.0]);
+        let (counts, edges) = histogram(&v, 2);
+
+        assert_eq!(counts, vec![1, 2]);
+        assert_eq!(edges, vec![0.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_digitize_edges() {
+        let edges = [0.0, 0.5, 1.0];
+
+        assert_eq!(digitize(0.0, &edges), 0);
+        assert_eq!(digitize(0.49, &edges), 0);
+        assert_eq!(digitize(0.5, &edges), 1);
+        assert_eq!(digitize(1.0, &edges), 1);
+        assert_eq!(digitize(7.0, &edges), 1);
+    }
+
+    #[test]
+    fn test_quantile_edges() {
+        let values = [4.0, 0.0, 3.0, 1.0, 2.0];
+        assert_eq!(quantile_edges(&values, 4), vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(quantile_edges(&values, 2), vec![0.0, 2.0, 4.0]);
+    }
+}
@@ -0,0 +1,735 @@
+//! The Feature Selection module
+//!
+//! This module contains transformers which reduce the inputs to a
+//! subset of their columns.
+//!
+//! Each selector implements `Transformer`, returning the reduced
+//! matrix and recording the indices of the retained columns.
+//! Supervised selectors, such as `SelectKBest`, are first fitted
+//! against the targets and then transform inputs with the chosen
+//! columns.
+//!
+//! `RecursiveFeatureElimination` selects columns using a model
+//! which implements `FeatureImportances`.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::data::feature_selection::VarianceThreshold;
+//! use rusty_machine::data::transforms::Transformer;
+//! use rusty_machine::linalg::{Matrix, BaseMatrix};
+//!
+//! // The first column is constant
+//! let inputs = Matrix::new(3, 2, vec![1.0, 2.0, 1.0, 4.0, 1.0, 6.0]);
+//!
+//! let mut selector = VarianceThreshold::default();
+//! let reduced = selector.transform(inputs).unwrap();
+//!
+//! assert_eq!(reduced.cols(), 1);
+//! assert_eq!(selector.selected().unwrap(), &[1]);
+//! ```
+
+use learning::{LearningResult, SupModel};
+use learning::error::{Error, ErrorKind};
+use linalg::{Matrix, BaseMatrix, MatrixExt, Vector};
+use data::transforms::Transformer;
+
+pub use learning::FeatureImportances;
+
+use libnum::{Float, FromPrimitive};
+
+use std::cmp::Ordering;
+
+/// The `VarianceThreshold` feature selector
+///
+/// Removes the columns whose sample variance does not exceed
+/// the threshold. The default threshold of zero removes the
+/// constant columns.
+#[derive(Debug)]
+pub struct VarianceThreshold<T: Float> {
+    /// The variance which retained columns must exceed
+    threshold: T,
+    /// The indices of the retained columns
+    selected: Option<Vec<usize>>,
+}
+
+/// Create a default `VarianceThreshold` which removes constant columns.
+impl<T: Float> Default for VarianceThreshold<T> {
+    fn default() -> VarianceThreshold<T> {
+        VarianceThreshold::new(T::zero())
+    }
+}
+
+impl<T: Float> VarianceThreshold<T> {
+    /// Constructs a new `VarianceThreshold` with the given threshold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::data::feature_selection::VarianceThreshold;
+    ///
+    /// // Remove columns with variance of at most 0.1
+    /// let selector = VarianceThreshold::new(0.1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The threshold is negative or not finite.
+    pub fn new(threshold: T) -> VarianceThreshold<T> {
+        assert!(threshold >= T::zero() && threshold.is_finite(),
+                "The variance threshold must be non-negative and finite.");
+
+        VarianceThreshold {
+            threshold,
+            selected: None,
+        }
+    }
+
+    /// The variance which retained columns must exceed.
+    pub fn threshold(&self) -> T {
+        self.threshold
+    }
+
+    /// The indices of the columns retained by the last transform.
+    ///
+    /// Returns `None` if the selector has not been used.
+    pub fn selected(&self) -> Option<&[usize]> {
+        self.selected.as_ref().map(|s| &s[..])
+    }
+}
+
+impl<T: Float + FromPrimitive> Transformer<Matrix<T>> for VarianceThreshold<T> {
+    fn transform(&mut self, inputs: Matrix<T>) -> Result<Matrix<T>, Error> {
+        if inputs.rows() < 2 {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "At least two rows are needed to compute the variance."));
+        }
+
+        let selected = inputs.variance_cols()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, v)| v > self.threshold)
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+
+        if selected.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "No column has variance above the threshold."));
+        }
+
+        let reduced = inputs.select_cols(&selected);
+        self.selected = Some(selected);
+        Ok(reduced)
+    }
+}
+
+/// The scoring functions used by `SelectKBest`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScoreFunction {
+    /// The absolute Pearson correlation with a continuous target.
+    ///
+    /// Use this score for regression.
+    Correlation,
+    /// The ANOVA F-statistic of the feature across the target classes.
+    ///
+    /// Use this score for classification, the targets are
+    /// treated as class labels.
+    FScore,
+}
+
+impl ScoreFunction {
+    /// Scores a single feature column against the targets.
+    ///
+    /// Higher scores indicate more informative features.
+    fn score(&self, feature: &[f64], targets: &[f64]) -> f64 {
+        match *self {
+            ScoreFunction::Correlation => correlation(feature, targets).abs(),
+            ScoreFunction::FScore => f_score(feature, targets),
+        }
+    }
+}
+
+/// The Pearson correlation of two samples, zero if either is constant.
+fn correlation(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len() as f64;
+    let x_mean = x.iter().sum::<f64>() / n;
+    let y_mean = y.iter().sum::<f64>() / n;
+
+    let mut xy = 0f64;
+    let mut xx = 0f64;
+    let mut yy = 0f64;
+    for (a, b) in x.iter().zip(y.iter()) {
+        xy += (a - x_mean) * (b - y_mean);
+        xx += (a - x_mean) * (a - x_mean);
+        yy += (b - y_mean) * (b - y_mean);
+    }
+
+    if xx == 0f64 || yy == 0f64 {
+        0f64
+    } else {
+        xy / (xx * yy).sqrt()
+    }
+}
+
+/// The one-way ANOVA F-statistic of `x` grouped by the labels.
+fn f_score(x: &[f64], labels: &[f64]) -> f64 {
+    let mut classes: Vec<f64> = Vec::new();
+    let mut sums: Vec<f64> = Vec::new();
+    let mut counts: Vec<usize> = Vec::new();
+    let mut group = Vec::with_capacity(x.len());
+
+    for (&a, &label) in x.iter().zip(labels.iter()) {
+        let idx = match classes.iter().position(|&c| c == label) {
+            Some(idx) => idx,
+            None => {
+                classes.push(label);
+                sums.push(0f64);
+                counts.push(0);
+                classes.len() - 1
+            }
+        };
+        sums[idx] += a;
+        counts[idx] += 1;
+        group.push(idx);
+    }
+
+    let n = x.len();
+    let n_classes = classes.len();
+    if n_classes < 2 || n <= n_classes {
+        return 0f64;
+    }
+
+    let mean = x.iter().sum::<f64>() / n as f64;
+    let means = sums.iter().zip(counts.iter()).map(|(s, &c)| s / c as f64).collect::<Vec<_>>();
+
+    let between = means.iter()
+        .zip(counts.iter())
+        .map(|(m, &c)| c as f64 * (m - mean) * (m - mean))
+        .sum::<f64>();
+    let within = x.iter()
+        .zip(group.iter())
+        .map(|(a, &g)| (a - means[g]) * (a - means[g]))
+        .sum::<f64>();
+
+    let between = between / (n_classes - 1) as f64;
+    let within = within / (n - n_classes) as f64;
+
+    if within == 0f64 {
+        if between == 0f64 { 0f64 } else { f64::INFINITY }
+    } else {
+        between / within
+    }
+}
+
+/// The `SelectKBest` feature selector
+///
+/// Scores each column against the targets and retains the `k`
+/// highest scoring columns, in their original order.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::data::feature_selection::{SelectKBest, ScoreFunction};
+/// use rusty_machine::data::transforms::Transformer;
+/// use rusty_machine::linalg::{Matrix, Vector};
+///
+/// let inputs = Matrix::new(4, 2, vec![1.0, 3.0, 2.0, 1.0, 3.0, 4.0, 4.0, 2.0]);
+/// let targets = Vector::new(vec![2.0, 4.0, 6.0, 8.0]);
+///
+/// let mut selector = SelectKBest::new(1, ScoreFunction::Correlation);
+/// selector.fit(&inputs, &targets).unwrap();
+///
+/// let reduced = selector.transform(inputs).unwrap();
+/// assert_eq!(selector.selected().unwrap(), &[0]);
+/// ```
+#[derive(Debug)]
+pub struct SelectKBest {
+    /// The number of columns to retain
+    k: usize,
+    /// The function scoring each column
+    score_fn: ScoreFunction,
+    /// The scores of each column
+    scores: Option<Vec<f64>>,
+    /// The indices of the retained columns
+    selected: Option<Vec<usize>>,
+}
+
+impl SelectKBest {
+    /// Constructs a new `SelectKBest` retaining `k` columns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::data::feature_selection::{SelectKBest, ScoreFunction};
+    ///
+    /// let selector = SelectKBest::new(5, ScoreFunction::FScore);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `k` is zero.
+    pub fn new(k: usize, score_fn: ScoreFunction) -> SelectKBest {
+        assert!(k > 0, "The number of columns to select must be positive.");
+
+        SelectKBest {
+            k,
+            score_fn,
+            scores: None,
+            selected: None,
+        }
+    }
+
+    /// The number of columns to retain.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// The function scoring each column.
+    pub fn score_fn(&self) -> ScoreFunction {
+        self.score_fn
+    }
+
+    /// The scores of each column from the last fit.
+    ///
+    /// Returns `None` if the selector has not been fitted.
+    pub fn scores(&self) -> Option<&[f64]> {
+        self.scores.as_ref().map(|s| &s[..])
+    }
+
+    /// The indices of the retained columns, in increasing order.
+    ///
+    /// Returns `None` if the selector has not been fitted.
+    pub fn selected(&self) -> Option<&[usize]> {
+        self.selected.as_ref().map(|s| &s[..])
+    }
+
+    /// Scores the columns of the inputs against the targets
+    /// and chooses the `k` best.
+    ///
+    /// Ties are broken in favour of the earlier column. Columns with a
+    /// NaN score, for example from NaN inputs, are ranked last.
+    pub fn fit(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
+        if inputs.rows() != targets.size() {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "The number of inputs must match the number of targets."));
+        }
+        if self.k > inputs.cols() {
+            return Err(Error::new(ErrorKind::InvalidParameters,
+                                  "Cannot select more columns than the inputs have."));
+        }
+        if inputs.rows() < 2 {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "At least two rows are needed to score the columns."));
+        }
+
+        let scores = (0..inputs.cols())
+            .map(|j| {
+                let column = inputs.select_cols(&[j]).into_vec();
+                self.score_fn.score(&column, targets.data())
+            })
+            .collect::<Vec<_>>();
+
+        let mut order = (0..scores.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| descending_nan_last(scores[a], scores[b]));
+
+        let mut selected = order[..self.k].to_vec();
+        selected.sort();
+
+        self.scores = Some(scores);
+        self.selected = Some(selected);
+        Ok(())
+    }
+}
+
+/// Orders scores from highest to lowest, with NaN after every number.
+fn descending_nan_last(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => b.partial_cmp(&a).unwrap_or(Ordering::Equal),
+    }
+}
+
+impl Transformer<Matrix<f64>> for SelectKBest {
+    /// Retains the columns chosen by the last fit.
+    fn transform(&mut self, inputs: Matrix<f64>) -> Result<Matrix<f64>, Error> {
+        match (self.selected.as_ref(), self.scores.as_ref()) {
+            (Some(selected), Some(scores)) => {
+                if inputs.cols() != scores.len() {
+                    return Err(Error::new(ErrorKind::InvalidData,
+                                          "Inputs have different feature count than the fitted data."));
+                }
+                Ok(inputs.select_cols(selected))
+            }
+            _ => Err(Error::new_untrained()),
+        }
+    }
+}
+
+/// Recursive feature elimination
+///
+/// Repeatedly trains the model and removes the least important of
+/// the remaining columns, until `n_features` columns are left. The
+/// model is finally trained on the retained columns.
+///
+/// Importances based on coefficients depend on the scale of each
+/// column, so the inputs should usually be standardized first.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::data::feature_selection::RecursiveFeatureElimination;
+/// use rusty_machine::data::transforms::Transformer;
+/// use rusty_machine::learning::lin_reg::LinRegressor;
+/// use rusty_machine::learning::SupModel;
+/// use rusty_machine::linalg::{Matrix, Vector};
+///
+/// // The target only depends on the first column
+/// let inputs = Matrix::new(5, 2, vec![1.0, 0.2, 2.0, -0.1, 3.0, 0.1,
+///                                     4.0, -0.2, 5.0, 0.0]);
+/// let targets = Vector::new(vec![2.0, 4.0, 6.0, 8.0, 10.0]);
+///
+/// let mut rfe = RecursiveFeatureElimination::new(LinRegressor::default(), 1);
+/// rfe.fit(&inputs, &targets).unwrap();
+/// assert_eq!(rfe.selected().unwrap(), &[0]);
+///
+/// let reduced = rfe.transform(inputs).unwrap();
+/// let outputs = rfe.model().predict(&reduced).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct RecursiveFeatureElimination<M> {
+    /// The model providing the feature importances
+    model: M,
+    /// The number of columns to retain
+    n_features: usize,
+    /// The number of columns in the fitted data
+    total_features: Option<usize>,
+    /// The indices of the retained columns
+    selected: Option<Vec<usize>>,
+    /// The elimination rank of each column
+    ranking: Option<Vec<usize>>,
+}
+
+impl<M> RecursiveFeatureElimination<M> {
+    /// Constructs a new `RecursiveFeatureElimination` retaining
+    /// `n_features` columns.
+    ///
+    /// # Panics
+    ///
+    /// - `n_features` is zero.
+    pub fn new(model: M, n_features: usize) -> RecursiveFeatureElimination<M> {
+        assert!(n_features > 0, "The number of features to select must be positive.");
+
+        RecursiveFeatureElimination {
+            model,
+            n_features,
+            total_features: None,
+            selected: None,
+            ranking: None,
+        }
+    }
+
+    /// The number of columns to retain.
+    pub fn n_features(&self) -> usize {
+        self.n_features
+    }
+
+    /// The model, trained on the retained columns after fitting.
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+
+    /// The indices of the retained columns, in increasing order.
+    ///
+    /// Returns `None` if the selector has not been fitted.
+    pub fn selected(&self) -> Option<&[usize]> {
+        self.selected.as_ref().map(|s| &s[..])
+    }
+
+    /// The rank of each column.
+    ///
+    /// Retained columns have rank `1`, the last column eliminated has
+    /// rank `2` and so on, so that the first column eliminated has the
+    /// highest rank.
+    ///
+    /// Returns `None` if the selector has not been fitted.
+    pub fn ranking(&self) -> Option<&[usize]> {
+        self.ranking.as_ref().map(|r| &r[..])
+    }
+}
+
+impl<M> RecursiveFeatureElimination<M>
+    where M: SupModel<Matrix<f64>, Vector<f64>> + FeatureImportances
+{
+    /// Eliminates columns one at a time until `n_features` remain.
+    ///
+    /// Ties are broken by eliminating the later column.
+    pub fn fit(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
+        let cols = inputs.cols();
+        if self.n_features > cols {
+            return Err(Error::new(ErrorKind::InvalidParameters,
+                                  "Cannot select more columns than the inputs have."));
+        }
+
+        let mut remaining = (0..cols).collect::<Vec<_>>();
+        let mut ranking = vec![1; cols];
+
+        loop {
+            self.model.train(&inputs.select_cols(&remaining), targets)?;
+            if remaining.len() == self.n_features {
+                break;
+            }
+
+            let importances = self.model.feature_importances().ok_or_else(|| {
+                Error::new(ErrorKind::InvalidState,
+                           "The model did not provide feature importances.")
+            })?;
+            if importances.size() != remaining.len() {
+                return Err(Error::new(ErrorKind::InvalidState,
+                                      "The model gave the wrong number of feature importances."));
+            }
+
+            let mut weakest = 0;
+            for (idx, &importance) in importances.data().iter().enumerate() {
+                if importance <= importances[weakest] {
+                    weakest = idx;
+                }
+            }
+
+            ranking[remaining[weakest]] = remaining.len() - self.n_features + 1;
+            remaining.remove(weakest);
+        }
+
+        self.total_features = Some(cols);
+        self.selected = Some(remaining);
+        self.ranking = Some(ranking);
+        Ok(())
+    }
+}
+
+impl<M> Transformer<Matrix<f64>> for RecursiveFeatureElimination<M> {
+    /// Retains the columns chosen by the last fit.
+    fn transform(&mut self, inputs: Matrix<f64>) -> Result<Matrix<f64>, Error> {
+        match (self.selected.as_ref(), self.total_features) {
+            (Some(selected), Some(total)) => {
+                if inputs.cols() != total {
+                    return Err(Error::new(ErrorKind::InvalidData,
+                                          "Inputs have different feature count than the fitted data."));
+                }
+                Ok(inputs.select_cols(selected))
+            }
+            _ => Err(Error::new_untrained()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VarianceThreshold, SelectKBest, ScoreFunction, RecursiveFeatureElimination};
+    use data::transforms::Transformer;
+    use learning::SupModel;
+    use learning::lin_reg::LinRegressor;
+    use linalg::{Matrix, BaseMatrix, Vector};
+
+    use rand::{SeedableRng, StdRng};
+    use rand::distributions::{IndependentSample, Normal};
+
+    #[test]
+    fn test_variance_threshold_drops_constant_column() {
+        let inputs = Matrix::new(4, 3, vec![1.0, 5.0, 0.0,
+                                            2.0, 5.0, 10.0,
+                                            3.0, 5.0, 0.0,
+                                            4.0, 5.0, 10.0]);
+
+        let mut selector = VarianceThreshold::default();
+        let reduced = selector.transform(inputs).unwrap();
+
+        assert_eq!(selector.selected().unwrap(), &[0, 2]);
+        assert_eq!(reduced, Matrix::new(4, 2, vec![1.0, 0.0,
+                                                   2.0, 10.0,
+                                                   3.0, 0.0,
+                                                   4.0, 10.0]));
+    }
+
+    #[test]
+    fn test_variance_threshold_above_zero() {
+        // Column variances are 1.0, 0.01 and 100.0
+        let inputs = Matrix::new(3, 3, vec![0.0, 0.0, 0.0,
+                                            1.0, 0.1, 10.0,
+                                            2.0, 0.2, 20.0]);
+
+        let mut selector = VarianceThreshold::new(0.5);
+        let reduced = selector.transform(inputs).unwrap();
+
+        assert_eq!(selector.selected().unwrap(), &[0, 2]);
+        assert_eq!(reduced.cols(), 2);
+    }
+
+    #[test]
+    fn test_variance_threshold_errors() {
+        let mut selector = VarianceThreshold::default();
+
+        let constant = Matrix::new(3, 2, vec![1.0; 6]);
+        assert!(selector.transform(constant).is_err());
+        assert!(selector.selected().is_none());
+
+        let single_row = Matrix::new(1, 2, vec![1.0, 2.0]);
+        assert!(selector.transform(single_row).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_variance_threshold_negative() {
+        let _ = VarianceThreshold::new(-1.0);
+    }
+
+    /// Columns 2 and 5 are informative, the other eight are noise.
+    fn informative_data(classification: bool) -> (Matrix<f64>, Vector<f64>) {
+        let seed: &[usize] = &[3];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let normal = Normal::new(0.0, 1.0);
+
+        let n = 100;
+        let mut inputs = Vec::with_capacity(10 * n);
+        let mut targets = Vec::with_capacity(n);
+        for i in 0..n {
+            let a = normal.ind_sample(&mut rng);
+            let b = normal.ind_sample(&mut rng);
+            let target = if classification {
+                (i % 2) as f64
+            } else {
+                a + b + 0.1 * normal.ind_sample(&mut rng)
+            };
+
+            for j in 0..10 {
+                let x = match j {
+                    2 if classification => 2.0 * target + 0.5 * a,
+                    5 if classification => -3.0 * target + 0.5 * b,
+                    2 => a,
+                    5 => b,
+                    _ => normal.ind_sample(&mut rng),
+                };
+                inputs.push(x);
+            }
+            targets.push(target);
+        }
+
+        (Matrix::new(n, 10, inputs), Vector::new(targets))
+    }
+
+    #[test]
+    fn test_select_k_best_correlation() {
+        let (inputs, targets) = informative_data(false);
+
+        let mut selector = SelectKBest::new(2, ScoreFunction::Correlation);
+        selector.fit(&inputs, &targets).unwrap();
+        assert_eq!(selector.selected().unwrap(), &[2, 5]);
+        assert_eq!(selector.scores().unwrap().len(), 10);
+
+        let reduced = selector.transform(inputs.clone()).unwrap();
+        assert_eq!(reduced, inputs.select_cols(&[2, 5]));
+    }
+
+    #[test]
+    fn test_select_k_best_f_score() {
+        let (inputs, targets) = informative_data(true);
+
+        let mut selector = SelectKBest::new(2, ScoreFunction::FScore);
+        selector.fit(&inputs, &targets).unwrap();
+        assert_eq!(selector.selected().unwrap(), &[2, 5]);
+    }
+
+    #[test]
+    fn test_f_score_separated_classes() {
+        let x = [1.0, 1.0, 3.0, 3.0];
+        let labels = [0.0, 0.0, 1.0, 1.0];
+        assert_eq!(super::f_score(&x, &labels), ::std::f64::INFINITY);
+
+        // Between-group mean square 4, within-group mean square 2
+        let x = [0.0, 2.0, 2.0, 4.0];
+        assert!((super::f_score(&x, &labels) - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_select_k_best_nan_scores_last() {
+        let inputs = Matrix::new(4, 3, vec![1.0, ::std::f64::NAN, 0.5,
+                                            2.0, 1.0, 0.1,
+                                            3.0, 2.0, 0.4,
+                                            4.0, 3.0, 0.2]);
+        let targets = Vector::new(vec![1.0, 2.0, 3.0, 4.0]);
+
+        let mut selector = SelectKBest::new(2, ScoreFunction::Correlation);
+        selector.fit(&inputs, &targets).unwrap();
+
+        assert!(selector.scores().unwrap()[1].is_nan());
+        assert_eq!(selector.selected().unwrap(), &[0, 2]);
+    }
+
+    #[test]
+    fn test_select_k_best_errors() {
+        let inputs = Matrix::new(3, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 7.0]);
+        let targets = Vector::new(vec![1.0, 2.0, 3.0]);
+
+        let mut selector = SelectKBest::new(3, ScoreFunction::Correlation);
+        assert!(selector.transform(inputs.clone()).is_err());
+        assert!(selector.fit(&inputs, &targets).is_err());
+
+        let mut selector = SelectKBest::new(1, ScoreFunction::Correlation);
+        assert!(selector.fit(&inputs, &Vector::new(vec![1.0, 2.0])).is_err());
+
+        selector.fit(&inputs, &targets).unwrap();
+        assert!(selector.transform(Matrix::new(1, 3, vec![1.0; 3])).is_err());
+    }
+
+    #[test]
+    fn test_rfe_eliminates_irrelevant_features_first() {
+        let seed: &[usize] = &[7];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let normal = Normal::new(0.0, 1.0);
+
+        // The target depends on columns 1 and 3 only
+        let n = 80;
+        let inputs = Matrix::from_fn(n, 5, |_, _| normal.ind_sample(&mut rng));
+        let targets = (0..n)
+            .map(|i| 3.0 * inputs[[i, 1]] - 1.5 * inputs[[i, 3]] + 0.1 * normal.ind_sample(&mut rng))
+            .collect::<Vec<_>>();
+        let targets = Vector::new(targets);
+
+        let mut rfe = RecursiveFeatureElimination::new(LinRegressor::default(), 1);
+        rfe.fit(&inputs, &targets).unwrap();
+
+        let ranking = rfe.ranking().unwrap();
+        assert_eq!(rfe.selected().unwrap(), &[1]);
+        assert_eq!(ranking[1], 1);
+        assert_eq!(ranking[3], 2);
+        for &j in &[0, 2, 4] {
+            assert!(ranking[j] > 2);
+        }
+
+        let mut rfe = RecursiveFeatureElimination::new(LinRegressor::default(), 2);
+        rfe.fit(&inputs, &targets).unwrap();
+        assert_eq!(rfe.selected().unwrap(), &[1, 3]);
+
+        let reduced = rfe.transform(inputs.clone()).unwrap();
+        assert_eq!(reduced.cols(), 2);
+        let params = rfe.model().parameters().unwrap();
+        assert!((params[1] - 3.0).abs() < 0.1);
+        assert!((params[2] + 1.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_rfe_errors() {
+        let inputs = Matrix::new(3, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 7.0]);
+        let targets = Vector::new(vec![1.0, 2.0, 3.0]);
+
+        let mut rfe = RecursiveFeatureElimination::new(LinRegressor::default(), 3);
+        assert!(rfe.transform(inputs.clone()).is_err());
+        assert!(rfe.fit(&inputs, &targets).is_err());
+
+        let mut rfe = RecursiveFeatureElimination::new(LinRegressor::default(), 1);
+        rfe.fit(&inputs, &targets).unwrap();
+        assert!(rfe.model().predict(&Matrix::new(1, 1, vec![2.0])).is_ok());
+        assert!(rfe.transform(Matrix::new(1, 3, vec![1.0; 3])).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Grids for data handling
+//!
+//! This module contains tools for generating regular grids of points.
+//!
+//! These are useful for evaluating a model over a region, for example
+//! to inspect the decision boundary of a classifier with two features.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::data::grid::meshgrid;
+//! use rusty_machine::linalg::{BaseMatrix, Vector};
+//!
+//! let x = Vector::new(vec![0.0, 1.0, 2.0]);
+//! let y = Vector::new(vec![-1.0, 1.0]);
+//!
+//! let (xx, yy) = meshgrid(&x, &y);
+//!
+//! assert_eq!(*xx.data(), vec![0.0, 1.0, 2.0, 0.0, 1.0, 2.0]);
+//! assert_eq!(*yy.data(), vec![-1.0, -1.0, -1.0, 1.0, 1.0, 1.0]);
+//! ```
+
+use linalg::{Matrix, Vector};
+
+use std::iter;
+
+/// Returns coordinate matrices from coordinate vectors.
+///
+/// For `x` of length `n` and `y` of length `m` both matrices are
+/// `m x n`. Entry `(i, j)` of the first matrix is `x[j]` and of the
+/// second is `y[i]`, so together they give the point `(x[j], y[i])`.
+/// This matches the default (`xy`) indexing of numpy's `meshgrid`.
+///
+/// Flattening both matrices gives the grid points in row-major order,
+/// which can be stacked into an input matrix with two columns.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::data::grid::meshgrid;
+/// use rusty_machine::linalg::{BaseMatrix, Matrix, Vector};
+///
+/// let x = Vector::new(vec![0.0, 0.5, 1.0]);
+/// let y = Vector::new(vec![0.0, 1.0]);
+/// let (xx, yy) = meshgrid(&x, &y);
+///
+/// // Stack the coordinates into a matrix of grid points
+/// let points = Matrix::new(6, 1, xx.into_vec()).hcat(&Matrix::new(6, 1, yy.into_vec()));
+/// assert_eq!(points.rows(), 6);
+/// ```
+pub fn meshgrid<T: Copy>(x: &Vector<T>, y: &Vector<T>) -> (Matrix<T>, Matrix<T>) {
+    let (n, m) = (x.size(), y.size());
+
+    let mut xx = Vec::with_capacity(n * m);
+    let mut yy = Vec::with_capacity(n * m);
+
+    for &y_i in y.data() {
+        xx.extend_from_slice(x.data());
+        yy.extend(iter::repeat_n(y_i, n));
+    }
+
+    (Matrix::new(m, n, xx), Matrix::new(m, n, yy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::meshgrid;
+    use linalg::{BaseMatrix, Vector};
+
+    #[test]
+    fn test_meshgrid_shapes_and_corners() {
+        let x = Vector::new((0..5).map(|i| -1.0 + 0.5 * i as f64).collect::<Vec<_>>());
+        let y = Vector::new(vec![10.0, 20.0, 30.0]);
+
+        let (xx, yy) = meshgrid(&x, &y);
+
+        assert_eq!((xx.rows(), xx.cols()), (3, 5));
+        assert_eq!((yy.rows(), yy.cols()), (3, 5));
+
+        assert_eq!((xx[[0, 0]], yy[[0, 0]]), (-1.0, 10.0));
+        assert_eq!((xx[[0, 4]], yy[[0, 4]]), (1.0, 10.0));
+        assert_eq!((xx[[2, 0]], yy[[2, 0]]), (-1.0, 30.0));
+        assert_eq!((xx[[2, 4]], yy[[2, 4]]), (1.0, 30.0));
+    }
+
+    #[test]
+    fn test_meshgrid_empty() {
+        let (xx, yy) = meshgrid(&Vector::<f64>::new(vec![]), &Vector::new(vec![1.0, 2.0]));
+
+        assert_eq!((xx.rows(), xx.cols()), (2, 0));
+        assert_eq!((yy.rows(), yy.cols()), (2, 0));
+    }
+}
@@ -0,0 +1,99 @@
+//! Data splitting
+//!
+//! This module contains tools for splitting data into training and
+//! test sets.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::data::split::time_series_split;
+//!
+//! for (train, test) in time_series_split(10, 3) {
+//!     // Every test sample comes after every training sample
+//!     assert!(train.last() < test.first());
+//! }
+//! ```
+
+/// Splits `n` time-ordered samples into expanding-window folds.
+///
+/// Returns `n_splits` pairs of `(train, test)` indices. The samples are
+/// divided into `n_splits + 1` consecutive chunks; fold `k` tests on
+/// chunk `k + 1` and trains on every sample before it. The test set
+/// therefore always follows the training set, avoiding the leakage of
+/// future information that a random split would cause.
+///
+/// The chunk size is `n / (n_splits + 1)` and any remainder is added to
+/// the first training window.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::data::split::time_series_split;
+///
+/// let folds = time_series_split(6, 2);
+///
+/// assert_eq!(folds[0], (vec![0, 1], vec![2, 3]));
+/// assert_eq!(folds[1], (vec![0, 1, 2, 3], vec![4, 5]));
+/// ```
+///
+/// # Panics
+///
+/// - `n_splits` is zero.
+/// - There are fewer than `n_splits + 1` samples.
+pub fn time_series_split(n: usize, n_splits: usize) -> Vec<(Vec<usize>, Vec<usize>)> {
+    assert!(n_splits > 0, "Number of splits must be positive.");
+    assert!(n > n_splits,
+            "Number of samples must be greater than the number of splits.");
+
+    let test_size = n / (n_splits + 1);
+    let first_train = n - n_splits * test_size;
+
+    (0..n_splits)
+        .map(|k| {
+            let train_end = first_train + k * test_size;
+            ((0..train_end).collect(), (train_end..train_end + test_size).collect())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::time_series_split;
+
+    #[test]
+    fn test_time_series_split_ordered_and_expanding() {
+        let folds = time_series_split(23, 4);
+        assert_eq!(folds.len(), 4);
+
+        let mut last_train = 0;
+        for (train, test) in folds {
+            assert!(!test.is_empty());
+            assert!(train.iter().all(|&i| test.iter().all(|&j| i < j)));
+
+            // Training windows start at zero, are contiguous and expand
+            assert_eq!(train, (0..train.len()).collect::<Vec<_>>());
+            assert!(train.len() > last_train);
+            last_train = train.len();
+
+            // Test set immediately follows the training set
+            assert_eq!(test[0], train.len());
+        }
+
+        // The final fold ends with the last sample
+        assert_eq!(last_train + 23 / 5, 23);
+    }
+
+    #[test]
+    fn test_time_series_split_remainder_in_first_train() {
+        let folds = time_series_split(7, 2);
+
+        assert_eq!(folds[0], (vec![0, 1, 2], vec![3, 4]));
+        assert_eq!(folds[1], (vec![0, 1, 2, 3, 4], vec![5, 6]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_time_series_split_too_few_samples() {
+        let _ = time_series_split(3, 3);
+    }
+}
@@ -0,0 +1,372 @@
+//! Statistics for data handling
+//!
+//! This module contains tools for computing statistics of data sets.
+//!
+//! The `RunningStats` struct maintains the per-feature mean and variance
+//! of data which arrives incrementally, without storing the data itself.
+//! The `covariance` and `cross_covariance` functions compute sample
+//! covariance matrices of data held in a `Matrix`. The `class_priors`
+//! function computes the empirical class frequencies of labels.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::data::stats::RunningStats;
+//! use rusty_machine::linalg::Vector;
+//!
+//! let mut stats = RunningStats::default();
+//!
+//! stats.update(&Vector::new(vec![1.0, 2.0]));
+//! stats.update(&Vector::new(vec![3.0, 6.0]));
+//!
+//! assert_eq!(*stats.mean().unwrap().data(), vec![2.0, 4.0]);
+//! assert_eq!(*stats.variance().unwrap().data(), vec![2.0, 8.0]);
+//! ```
+
+use linalg::{Axes, Matrix, BaseMatrix, Vector};
+
+use libnum::{Float, FromPrimitive};
+
+/// Running mean and variance statistics.
+///
+/// Uses Welford's algorithm to update the per-feature mean
+/// and variance one sample at a time.
+#[derive(Debug)]
+pub struct RunningStats<T: Float> {
+    /// The number of samples seen so far
+    count: usize,
+    /// The running mean of each feature
+    mean: Option<Vector<T>>,
+    /// The running sum of squared differences from the mean
+    sq_diffs: Option<Vector<T>>,
+}
+
+/// Create an empty `RunningStats` which has not seen any data.
+impl<T: Float> Default for RunningStats<T> {
+    fn default() -> RunningStats<T> {
+        RunningStats {
+            count: 0,
+            mean: None,
+            sq_diffs: None,
+        }
+    }
+}
+
+impl<T: Float + FromPrimitive> RunningStats<T> {
+    /// Update the statistics with a new sample.
+    ///
+    /// The number of features is fixed by the first sample.
+    ///
+    /// # Panics
+    ///
+    /// - The sample has a different number of features to earlier samples.
+    pub fn update(&mut self, x: &Vector<T>) {
+        if let Some(ref mean) = self.mean {
+            assert!(x.size() == mean.size(),
+                    "Sample has a different number of features than previous samples.");
+        }
+
+        self.count += 1;
+        let n = T::from_usize(self.count).unwrap();
+
+        match (&mut self.mean, &mut self.sq_diffs) {
+            (&mut Some(ref mut mean), &mut Some(ref mut sq_diffs)) => {
+                for ((m, s), &x_i) in mean.mut_data()
+                    .iter_mut()
+                    .zip(sq_diffs.mut_data().iter_mut())
+                    .zip(x.data().iter()) {
+                    let delta = x_i - *m;
+                    *m = *m + delta / n;
+                    *s = *s + delta * (x_i - *m);
+                }
+            }
+            (mean, sq_diffs) => {
+                *mean = Some(x.clone());
+                *sq_diffs = Some(Vector::zeros(x.size()));
+            }
+        }
+    }
+
+    /// The number of samples seen so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The mean of each feature.
+    ///
+    /// Returns `None` if no samples have been seen.
+    pub fn mean(&self) -> Option<&Vector<T>> {
+        self.mean.as_ref()
+    }
+
+    /// The sample variance of each feature.
+    ///
+    /// Returns `None` if fewer than two samples have been seen.
+    pub fn variance(&self) -> Option<Vector<T>> {
+        if self.count < 2 {
+            return None;
+        }
+
+        self.sq_diffs
+            .as_ref()
+            .map(|s| s / T::from_usize(self.count - 1).unwrap())
+    }
+}
+
+/// Computes the sample cross-covariance of two data sets.
+///
+/// Each row of `x` and `y` is a sample, and the two must have the same
+/// number of samples. Returns the `x.cols() x y.cols()` matrix
+/// `(X - mean(X))^T (Y - mean(Y)) / (n - 1)`, whose entry `(j, k)` is
+/// the covariance of feature `j` of `x` with feature `k` of `y`.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::data::stats::cross_covariance;
+/// use rusty_machine::linalg::Matrix;
+///
+/// let x = Matrix::new(3, 1, vec![1.0, 2.0, 3.0]);
+/// let y = Matrix::new(3, 2, vec![2.0, 1.0, 4.0, 1.0, 6.0, 1.0]);
+///
+/// let cov = cross_covariance(&x, &y);
+/// assert_eq!(*cov.data(), vec![2.0, 0.0]);
+/// ```
+///
+/// # Panics
+///
+/// - The inputs have different numbers of rows.
+/// - There are fewer than two rows.
+pub fn cross_covariance<T>(x: &Matrix<T>, y: &Matrix<T>) -> Matrix<T>
+    where T: Float + FromPrimitive
+{
+    assert!(x.rows() == y.rows(),
+            "The inputs must have the same number of rows, found {} and {}.",
+            x.rows(),
+            y.rows());
+    assert!(x.rows() > 1, "At least two rows are needed to compute a covariance.");
+
+    let x_mean = x.mean(Axes::Row);
+    let y_mean = y.mean(Axes::Row);
+
+    let mut cov = Matrix::zeros(x.cols(), y.cols());
+    for (x_row, y_row) in x.iter_rows().zip(y.iter_rows()) {
+        for (j, (&xj, &mj)) in x_row.iter().zip(x_mean.data().iter()).enumerate() {
+            let dx = xj - mj;
+            for (k, (&yk, &mk)) in y_row.iter().zip(y_mean.data().iter()).enumerate() {
+                cov[[j, k]] = cov[[j, k]] + dx * (yk - mk);
+            }
+        }
+    }
+
+    cov / T::from_usize(x.rows() - 1).unwrap()
+}
+
+/// Computes the sample covariance matrix of a data set.
+///
+/// Each row of `x` is a sample. This is the cross-covariance of `x`
+/// with itself.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::data::stats::covariance;
+/// use rusty_machine::linalg::Matrix;
+///
+/// let x = Matrix::new(3, 2, vec![1.0, 2.0, 2.0, 4.0, 3.0, 6.0]);
+///
+/// assert_eq!(*covariance(&x).data(), vec![1.0, 2.0, 2.0, 4.0]);
+/// ```
+///
+/// # Panics
+///
+/// - There are fewer than two rows.
+pub fn covariance<T>(x: &Matrix<T>) -> Matrix<T>
+    where T: Float + FromPrimitive
+{
+    cross_covariance(x, x)
+}
+
+/// Computes the empirical frequency of each class.
+///
+/// The labels are class indices in `0..num_classes`. Entry `c` of the
+/// result is the fraction of the labels equal to `c`, so classes which
+/// do not appear have a prior of zero.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::data::stats::class_priors;
+///
+/// let priors = class_priors::<f64>(&[0, 2, 2, 0, 2], 4);
+/// assert_eq!(*priors.data(), vec![0.4, 0.0, 0.6, 0.0]);
+/// ```
+///
+/// # Panics
+///
+/// - There are no labels.
+/// - A label is not less than `num_classes`.
+pub fn class_priors<T>(labels: &[usize], num_classes: usize) -> Vector<T>
+    where T: Float + FromPrimitive
+{
+    assert!(!labels.is_empty(), "At least one label is needed to compute the priors.");
+
+    let mut counts = vec![0usize; num_classes];
+    for &label in labels {
+        assert!(label < num_classes,
+                "The label {} is not less than the number of classes {}.",
+                label,
+                num_classes);
+        counts[label] += 1;
+    }
+
+    let total = T::from_usize(labels.len()).unwrap();
+    Vector::new(counts.into_iter()
+        .map(|c| T::from_usize(c).unwrap() / total)
+        .collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RunningStats, covariance, cross_covariance, class_priors};
+    use linalg::{Axes, Matrix, BaseMatrix, Vector};
+
+    use std::panic;
+
+    #[test]
+    fn test_running_stats_empty() {
+        let stats = RunningStats::<f64>::default();
+
+        assert_eq!(stats.count(), 0);
+        assert!(stats.mean().is_none());
+        assert!(stats.variance().is_none());
+    }
+
+    #[test]
+    fn test_running_stats_single_sample() {
+        let mut stats = RunningStats::default();
+        stats.update(&Vector::new(vec![1.0, 2.0]));
+
+        assert_eq!(*stats.mean().unwrap().data(), vec![1.0, 2.0]);
+        assert!(stats.variance().is_none());
+    }
+
+    #[test]
+    fn test_running_stats_matches_batch() {
+        let inputs = Matrix::new(5, 3, vec![1.0f64, -2.0, 10.0,
+                                            2.5, 0.5, 12.0,
+                                            -1.0, 3.0, 9.5,
+                                            4.0, 1.5, 11.0,
+                                            0.5, -0.5, 10.5]);
+
+        let mut stats = RunningStats::<f64>::default();
+        for row in inputs.iter_rows() {
+            stats.update(&Vector::new(row.to_vec()));
+        }
+
+        let batch_mean = inputs.mean(Axes::Row);
+        let batch_var = inputs.variance(Axes::Row).unwrap();
+
+        assert_eq!(stats.count(), 5);
+        for (x, y) in stats.mean().unwrap().data().iter().zip(batch_mean.data().iter()) {
+            assert!((x - y).abs() < 1e-12);
+        }
+        for (x, y) in stats.variance().unwrap().data().iter().zip(batch_var.data().iter()) {
+            assert!((x - y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_running_stats_wrong_size() {
+        let mut stats = RunningStats::default();
+        stats.update(&Vector::new(vec![1.0, 2.0]));
+        stats.update(&Vector::new(vec![1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn test_running_stats_wrong_size_not_counted() {
+        let mut stats = RunningStats::default();
+        stats.update(&Vector::new(vec![1.0, 2.0]));
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            stats.update(&Vector::new(vec![1.0, 2.0, 3.0]));
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(stats.count(), 1);
+        assert!(stats.variance().is_none());
+    }
+
+    fn sample_data() -> Matrix<f64> {
+        Matrix::new(5, 3, vec![1.0, -2.0, 10.0,
+                               2.5, 0.5, 12.0,
+                               -1.0, 3.0, 9.5,
+                               4.0, 1.5, 11.0,
+                               0.5, -0.5, 10.5])
+    }
+
+    #[test]
+    fn test_cross_covariance_with_self() {
+        let x = sample_data();
+        let cov = covariance(&x);
+
+        assert_eq!(cross_covariance(&x, &x), cov);
+
+        // The diagonal holds the variances and the matrix is symmetric
+        let var = x.variance(Axes::Row).unwrap();
+        for j in 0..3 {
+            assert!((cov[[j, j]] - var[j]).abs() < 1e-12);
+            for k in 0..3 {
+                assert_eq!(cov[[j, k]], cov[[k, j]]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cross_covariance() {
+        let x = sample_data();
+        let y = x.select_cols(&[2, 0]);
+        let cov = cross_covariance(&x, &y);
+        let full = covariance(&x);
+
+        assert_eq!((cov.rows(), cov.cols()), (3, 2));
+        for j in 0..3 {
+            assert!((cov[[j, 0]] - full[[j, 2]]).abs() < 1e-12);
+            assert!((cov[[j, 1]] - full[[j, 0]]).abs() < 1e-12);
+        }
+
+        // Shifting the data does not change the covariance
+        let shifted = y.clone() + 100.0;
+        let cov_shifted = cross_covariance(&x, &shifted);
+        for (a, b) in cov.data().iter().zip(cov_shifted.data().iter()) {
+            assert!((a - b).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cross_covariance_row_mismatch() {
+        let _ = cross_covariance(&Matrix::<f64>::zeros(3, 2), &Matrix::zeros(4, 2));
+    }
+
+    #[test]
+    fn test_class_priors_match_frequencies() {
+        let labels = (0..60).map(|i| (i * i + i / 7) % 5).collect::<Vec<_>>();
+        let priors = class_priors::<f64>(&labels, 6);
+
+        assert_eq!(priors.size(), 6);
+        assert!((priors.sum() - 1.0).abs() < 1e-12);
+        for c in 0..6 {
+            let count = labels.iter().filter(|&&l| l == c).count();
+            assert_eq!(priors[c], count as f64 / 60.0);
+        }
+        assert_eq!(priors[5], 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_class_priors_label_out_of_range() {
+        let _ = class_priors::<f64>(&[0, 1, 3], 3);
+    }
+}
@@ -0,0 +1,208 @@
+//! The K-Bins Discretizer
+//!
+//! This module contains the `KBinsDiscretizer` transformer.
+//!
+//! The `KBinsDiscretizer` maps each continuous column of the input
+//! data into integer bins `0, 1, .., n_bins - 1`. The bin edges are
+//! either equally spaced over the range of each column, or placed at
+//! the quantiles of each column so that the bins hold roughly equal
+//! numbers of points.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::data::transforms::{Transformer, KBinsDiscretizer, BinStrategy};
+//! use rusty_machine::linalg::Matrix;
+//!
+//! let mut transformer = KBinsDiscretizer::new(2, BinStrategy::Uniform);
+//!
+//! let inputs = Matrix::new(4, 1, vec![0.0, 1.0, 3.0, 4.0]);
+//! let transformed = transformer.transform(inputs).unwrap();
+//!
+//! assert_eq!(*transformed.data(), vec![0.0, 0.0, 1.0, 1.0]);
+//! ```
+
+use learning::error::{Error, ErrorKind};
+use linalg::{Matrix, BaseMatrix, BaseMatrixMut};
+use data::bin;
+use super::Transformer;
+
+use libnum::{Float, FromPrimitive};
+
+/// The strategy used to place the bin edges.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinStrategy {
+    /// Bins of equal width over the range of each column.
+    Uniform,
+    /// Bins at the quantiles of each column, holding roughly equal counts.
+    Quantile,
+}
+
+/// The `KBinsDiscretizer`
+///
+/// The `KBinsDiscretizer` provides an implementation of `Transformer`
+/// which replaces each entry with the index of its bin.
+///
+/// See the module description for more information.
+#[derive(Debug)]
+pub struct KBinsDiscretizer<T: Float> {
+    /// The number of bins per column
+    n_bins: usize,
+    /// How the bin edges are placed
+    strategy: BinStrategy,
+    /// The bin edges for each column
+    edges: Option<Vec<Vec<T>>>,
+}
+
+/// Create a `KBinsDiscretizer` with 5 quantile bins.
+impl<T: Float> Default for KBinsDiscretizer<T> {
+    fn default() -> KBinsDiscretizer<T> {
+        KBinsDiscretizer::new(5, BinStrategy::Quantile)
+    }
+}
+
+impl<T: Float> KBinsDiscretizer<T> {
+    /// Constructs a new `KBinsDiscretizer` with the given number of bins and strategy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::data::transforms::{KBinsDiscretizer, BinStrategy};
+    ///
+    /// let transformer = KBinsDiscretizer::<f64>::new(10, BinStrategy::Uniform);
+    /// ```
+    pub fn new(n_bins: usize, strategy: BinStrategy) -> KBinsDiscretizer<T> {
+        assert!(n_bins > 0, "The number of bins must be positive.");
+
+        KBinsDiscretizer {
+            n_bins,
+            strategy,
+            edges: None,
+        }
+    }
+
+    /// The bin edges for each column of the last transformed data.
+    ///
+    /// Returns `None` if no data has been transformed.
+    pub fn edges(&self) -> Option<&Vec<Vec<T>>> {
+        self.edges.as_ref()
+    }
+}
+
+impl<T: Float + FromPrimitive> Transformer<Matrix<T>> for KBinsDiscretizer<T> {
+    fn transform(&mut self, mut inputs: Matrix<T>) -> Result<Matrix<T>, Error> {
+        if inputs.rows() == 0 {
+            return Err(Error::new(ErrorKind::InvalidData, "Cannot discretize empty data."));
+        }
+        if inputs.data().iter().any(|x| !x.is_finite()) {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "Non-finite data cannot be discretized."));
+        }
+
+        let edges = (0..inputs.cols())
+            .map(|j| {
+                let column = inputs.iter_rows().map(|row| row[j]).collect::<Vec<_>>();
+                match self.strategy {
+                    BinStrategy::Uniform => {
+                        let (min, max) = column.iter().fold((T::max_value(), T::min_value()),
+                                                            |(lo, hi), &x| (lo.min(x), hi.max(x)));
+                        bin::uniform_edges(min, max, self.n_bins)
+                    }
+                    BinStrategy::Quantile => bin::quantile_edges(&column, self.n_bins),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for row in inputs.iter_rows_mut() {
+            for (x, col_edges) in row.iter_mut().zip(edges.iter()) {
+                *x = T::from_usize(bin::digitize(*x, col_edges)).unwrap();
+            }
+        }
+
+        self.edges = Some(edges);
+        Ok(inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KBinsDiscretizer, BinStrategy};
+    use super::super::Transformer;
+    use linalg::{Matrix, BaseMatrix};
+
+    use std::f64;
+
+    fn skewed_column(n: usize) -> Matrix<f64> {
+        Matrix::new(n, 1, (0..n).map(|i| ((i * 53) % n) as f64).map(|x| x * x).collect::<Vec<_>>())
+    }
+
+    fn bin_counts(transformed: &Matrix<f64>, n_bins: usize) -> Vec<usize> {
+        let mut counts = vec![0; n_bins];
+        for &x in transformed.data() {
+            counts[x as usize] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn test_uniform_bins() {
+        let inputs = Matrix::new(5, 2, vec![0.0, 10.0,
+                                            1.0, 20.0,
+                                            2.0, 30.0,
+                                            3.0, 40.0,
+                                            4.0, 50.0]);
+
+        let mut transformer = KBinsDiscretizer::new(4, BinStrategy::Uniform);
+        let transformed = transformer.transform(inputs).unwrap();
+
+        assert_eq!(*transformed.data(), vec![0.0, 0.0,
+                                             1.0, 1.0,
+                                             2.0, 2.0,
+                                             3.0, 3.0,
+                                             3.0, 3.0]);
+        assert_eq!(transformer.edges().unwrap()[1], vec![10.0, 20.0, 30.0, 40.0, 50.0]);
+    }
+
+    #[test]
+    fn test_quantile_bins_equal_counts() {
+        let inputs = skewed_column(100);
+
+        let mut transformer = KBinsDiscretizer::new(4, BinStrategy::Quantile);
+        let transformed = transformer.transform(inputs).unwrap();
+
+        for count in bin_counts(&transformed, 4) {
+            assert!(count >= 24 && count <= 26);
+        }
+    }
+
+    #[test]
+    fn test_uniform_bins_skewed_counts() {
+        let inputs = skewed_column(100);
+
+        let mut transformer = KBinsDiscretizer::new(4, BinStrategy::Uniform);
+        let transformed = transformer.transform(inputs).unwrap();
+
+        // Most of the squared values fall in the lowest bin
+        let counts = bin_counts(&transformed, 4);
+        assert!(counts[0] > 40);
+        assert!(counts[3] < 15);
+    }
+
+    #[test]
+    fn test_constant_column() {
+        let inputs = Matrix::new(3, 1, vec![2.0, 2.0, 2.0]);
+
+        let mut transformer = KBinsDiscretizer::new(3, BinStrategy::Uniform);
+        let transformed = transformer.transform(inputs).unwrap();
+
+        assert_eq!(*transformed.data(), vec![1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_non_finite_data() {
+        let inputs = Matrix::new(2, 1, vec![1.0, f64::NAN]);
+
+        let mut transformer = KBinsDiscretizer::default();
+        assert!(transformer.transform(inputs).is_err());
+    }
+}
@@ -12,11 +12,15 @@
 pub mod minmax;
 pub mod standardize;
 pub mod shuffle;
+pub mod kbins;
+pub mod spline;
 
 use learning::error;
 
+pub use self::kbins::{KBinsDiscretizer, BinStrategy};
 pub use self::minmax::MinMaxScaler;
 pub use self::shuffle::Shuffler;
+pub use self::spline::SplineBasis;
 pub use self::standardize::Standardizer;
 
 /// Trait for data transformers
@@ -0,0 +1,288 @@
+//! The Spline Basis transformer
+//!
+//! This module contains the `SplineBasis` transformer.
+//!
+//! The `SplineBasis` expands a single feature column into the values
+//! of the B-spline basis functions of the chosen degree. A linear model
+//! fitted on the expanded features is a piecewise polynomial in the
+//! original feature, which is more stable than raw polynomial features.
+//!
+//! The knots span the range of the data and are either equally spaced
+//! or placed at its quantiles, using the same `BinStrategy` as the
+//! `KBinsDiscretizer`. With `n_knots` knots (including the two boundary
+//! knots) and degree `d` there are `n_knots + d - 1` basis functions,
+//! which are non-negative and sum to one at every point.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::data::transforms::{Transformer, SplineBasis, BinStrategy};
+//! use rusty_machine::linalg::{Matrix, BaseMatrix};
+//!
+//! // Cubic splines with three knots
+//! let mut transformer = SplineBasis::new(3, 3, BinStrategy::Uniform);
+//!
+//! let inputs = Matrix::new(4, 1, vec![0.0, 1.0, 3.0, 4.0]);
+//! let transformed = transformer.transform(inputs).unwrap();
+//!
+//! assert_eq!(transformed.cols(), 5);
+//! ```
+
+use learning::error::{Error, ErrorKind};
+use linalg::{Matrix, BaseMatrix};
+use data::bin;
+use super::{BinStrategy, Transformer};
+
+use libnum::{Float, FromPrimitive};
+
+/// The `SplineBasis`
+///
+/// The `SplineBasis` provides an implementation of `Transformer`
+/// which expands a single column into a B-spline basis.
+///
+/// See the module description for more information.
+#[derive(Debug)]
+pub struct SplineBasis<T: Float> {
+    /// The number of knots, including the boundary knots
+    n_knots: usize,
+    /// The degree of the spline pieces
+    degree: usize,
+    /// How the knots are placed
+    strategy: BinStrategy,
+    /// The knots of the last transformed data
+    knots: Option<Vec<T>>,
+}
+
+/// Create a `SplineBasis` of cubic splines with 5 uniform knots.
+impl<T: Float> Default for SplineBasis<T> {
+    fn default() -> SplineBasis<T> {
+        SplineBasis::new(5, 3, BinStrategy::Uniform)
+    }
+}
+
+impl<T: Float> SplineBasis<T> {
+    /// Constructs a new `SplineBasis` with the given knots, degree and strategy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::data::transforms::{SplineBasis, BinStrategy};
+    ///
+    /// // Quadratic splines with knots at the quartiles
+    /// let transformer = SplineBasis::<f64>::new(5, 2, BinStrategy::Quantile);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - There are fewer than two knots.
+    pub fn new(n_knots: usize, degree: usize, strategy: BinStrategy) -> SplineBasis<T> {
+        assert!(n_knots >= 2, "There must be at least two knots.");
+
+        SplineBasis {
+            n_knots,
+            degree,
+            strategy,
+            knots: None,
+        }
+    }
+
+    /// The degree of the spline pieces.
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// The number of basis functions, the number of output columns.
+    pub fn n_basis(&self) -> usize {
+        self.n_knots + self.degree - 1
+    }
+
+    /// The knots of the last transformed data, in increasing order.
+    ///
+    /// Returns `None` if no data has been transformed.
+    pub fn knots(&self) -> Option<&[T]> {
+        self.knots.as_ref().map(|k| &k[..])
+    }
+
+    /// Evaluates the basis using the knots of the last transformed data.
+    ///
+    /// Points outside the knots are clamped to the boundary knots, so
+    /// the basis functions still sum to one.
+    pub fn evaluate(&self, inputs: &Matrix<T>) -> Result<Matrix<T>, Error> {
+        let knots = self.knots.as_ref()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidState, "Transformer has not been fitted."))?;
+        check_column(inputs)?;
+
+        let full = clamped_knots(knots, self.degree);
+        let mut data = Vec::with_capacity(inputs.rows() * self.n_basis());
+        for &x in inputs.data() {
+            data.extend(basis_row(x, &full, self.degree, self.n_basis()));
+        }
+        Ok(Matrix::new(inputs.rows(), self.n_basis(), data))
+    }
+}
+
+impl<T: Float + FromPrimitive> Transformer<Matrix<T>> for SplineBasis<T> {
+    fn transform(&mut self, inputs: Matrix<T>) -> Result<Matrix<T>, Error> {
+        check_column(&inputs)?;
+
+        let values = inputs.data();
+        let knots = match self.strategy {
+            BinStrategy::Uniform => {
+                let (min, max) = values.iter().fold((T::max_value(), T::min_value()),
+                                                    |(lo, hi), &x| (lo.min(x), hi.max(x)));
+                bin::uniform_edges(min, max, self.n_knots - 1)
+            }
+            BinStrategy::Quantile => bin::quantile_edges(values, self.n_knots - 1),
+        };
+
+        if knots[0] == knots[knots.len() - 1] {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "Cannot place quantile knots on a constant feature."));
+        }
+
+        self.knots = Some(knots);
+        self.evaluate(&inputs)
+    }
+}
+
+/// Checks that the inputs are a single non-empty, finite column.
+fn check_column<T: Float>(inputs: &Matrix<T>) -> Result<(), Error> {
+    if inputs.cols() != 1 {
+        return Err(Error::new(ErrorKind::InvalidData,
+                              "The spline basis expands a single feature column."));
+    }
+    if inputs.rows() == 0 {
+        return Err(Error::new(ErrorKind::InvalidData, "Cannot expand empty data."));
+    }
+    if inputs.data().iter().any(|x| !x.is_finite()) {
+        return Err(Error::new(ErrorKind::InvalidData, "Non-finite data cannot be expanded."));
+    }
+    Ok(())
+}
+
+/// Repeats the boundary knots so the basis is clamped at the ends.
+fn clamped_knots<T: Float>(knots: &[T], degree: usize) -> Vec<T> {
+    let mut full = vec![knots[0]; degree];
+    full.extend_from_slice(knots);
+    full.extend(vec![knots[knots.len() - 1]; degree]);
+    full
+}
+
+/// The values of all basis functions at `x`.
+///
+/// Uses the Cox-de Boor recursion on the knot span containing `x`,
+/// where only `degree + 1` basis functions are non-zero.
+fn basis_row<T: Float>(x: T, knots: &[T], degree: usize, n_basis: usize) -> Vec<T> {
+    let x = x.max(knots[0]).min(knots[knots.len() - 1]);
+
+    // The last span with a non-zero width starting at or before x
+    let mut span = degree;
+    while span + 1 < n_basis && knots[span + 1] <= x {
+        span += 1;
+    }
+    while span > degree && knots[span] == knots[span + 1] {
+        span -= 1;
+    }
+
+    let mut values = vec![T::zero(); degree + 1];
+    let mut left = vec![T::zero(); degree + 1];
+    let mut right = vec![T::zero(); degree + 1];
+    values[0] = T::one();
+
+    for j in 1..degree + 1 {
+        left[j] = x - knots[span + 1 - j];
+        right[j] = knots[span + j] - x;
+
+        let mut saved = T::zero();
+        for r in 0..j {
+            let denom = right[r + 1] + left[j - r];
+            let temp = if denom == T::zero() { T::zero() } else { values[r] / denom };
+            values[r] = saved + right[r + 1] * temp;
+            saved = left[j - r] * temp;
+        }
+        values[j] = saved;
+    }
+
+    let mut row = vec![T::zero(); n_basis];
+    for (k, v) in values.into_iter().enumerate() {
+        row[span - degree + k] = v;
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SplineBasis;
+    use super::super::{BinStrategy, Transformer};
+    use linalg::{Matrix, BaseMatrix};
+
+    use std::f64;
+
+    fn skewed_column(n: usize) -> Matrix<f64> {
+        Matrix::new(n, 1, (0..n).map(|i| ((i * 53) % n) as f64).map(|x| x * x / 10.0).collect::<Vec<_>>())
+    }
+
+    fn assert_partition_of_unity(basis: &Matrix<f64>) {
+        for row in basis.iter_rows() {
+            assert!(row.iter().all(|&b| b >= -1e-12));
+            assert!((row.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_partition_of_unity() {
+        for &strategy in &[BinStrategy::Uniform, BinStrategy::Quantile] {
+            for degree in 0..4 {
+                let mut transformer = SplineBasis::new(6, degree, strategy);
+                let transformed = transformer.transform(skewed_column(50)).unwrap();
+
+                assert_eq!(transformed.rows(), 50);
+                assert_eq!(transformed.cols(), 5 + degree);
+                assert_partition_of_unity(&transformed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_linear_basis_is_hat_functions() {
+        let inputs = Matrix::new(5, 1, vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+
+        let mut transformer = SplineBasis::new(3, 1, BinStrategy::Uniform);
+        let transformed = transformer.transform(inputs).unwrap();
+
+        assert_eq!(transformer.knots().unwrap(), &[0.0, 1.0, 2.0]);
+        assert_eq!(*transformed.data(), vec![1.0, 0.0, 0.0,
+                                             0.5, 0.5, 0.0,
+                                             0.0, 1.0, 0.0,
+                                             0.0, 0.5, 0.5,
+                                             0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_evaluate_uses_fitted_knots() {
+        let mut transformer = SplineBasis::new(4, 3, BinStrategy::Quantile);
+        assert!(transformer.evaluate(&Matrix::new(1, 1, vec![1.0])).is_err());
+
+        transformer.transform(skewed_column(40)).unwrap();
+        let knots = transformer.knots().unwrap().to_vec();
+
+        // Points outside the knots are clamped
+        let new_points = Matrix::new(3, 1, vec![-5.0, knots[1], 1e6]);
+        let basis = transformer.evaluate(&new_points).unwrap();
+        assert_partition_of_unity(&basis);
+        assert_eq!(basis[[0, 0]], 1.0);
+        assert_eq!(basis[[2, 5]], 1.0);
+        assert_eq!(transformer.knots().unwrap(), &knots[..]);
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        let mut transformer = SplineBasis::default();
+
+        assert!(transformer.transform(Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0])).is_err());
+        assert!(transformer.transform(Matrix::new(2, 1, vec![1.0, f64::NAN])).is_err());
+
+        let mut quantile = SplineBasis::new(3, 2, BinStrategy::Quantile);
+        assert!(quantile.transform(Matrix::new(3, 1, vec![2.0; 3])).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+//! Shuffled k-fold partitions of sample indices.
+//!
+//! Shared by cross-validation and the stacking ensemble.
+
+use std::cmp;
+use std::iter::Chain;
+use std::slice::Iter;
+use learning::toolkit::rand_utils::{in_place_fisher_yates_with_rng, seeded_rng};
+
+/// A permutation of 0..n.
+pub struct ShuffledIndices(Vec<usize>);
+
+/// Permute the indices of the inputs samples.
+///
+/// The permutation is reproducible if a seed is given.
+pub fn create_shuffled_indices(num_samples: usize, seed: Option<u64>) -> ShuffledIndices {
+    let mut indices: Vec<usize> = (0..num_samples).collect();
+    in_place_fisher_yates_with_rng(&mut indices, &mut seeded_rng(seed));
+    ShuffledIndices(indices)
+}
+
+/// A partition of indices of all available samples into
+/// a training set and a test set.
+pub struct Partition<'a> {
+    pub train_indices_iter: TrainingIndices<'a>,
+    pub test_indices_iter: TestIndices<'a>
+}
+
+#[derive(Clone)]
+pub struct TestIndices<'a>(Iter<'a, usize>);
+
+#[derive(Clone)]
+pub struct TrainingIndices<'a> {
+    chain: Chain<Iter<'a, usize>, Iter<'a, usize>>,
+    size: usize
+}
+
+impl<'a> TestIndices<'a> {
+    fn new(indices: &'a [usize]) -> TestIndices<'a> {
+        TestIndices(indices.iter())
+    }
+}
+
+impl<'a> Iterator for TestIndices<'a> {
+    type Item = &'a usize;
+
+    fn next(&mut self) -> Option<&'a usize> {
+        self.0.next()
+    }
+}
+
+impl <'a> ExactSizeIterator for TestIndices<'a> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a> TrainingIndices<'a> {
+    fn new(left: &'a [usize], right: &'a [usize]) -> TrainingIndices<'a> {
+        let chain = left.iter().chain(right.iter());
+        TrainingIndices {
+            chain,
+            size: left.len() + right.len()
+        }
+    }
+}
+
+impl<'a> Iterator for TrainingIndices<'a> {
+    type Item = &'a usize;
+
+    fn next(&mut self) -> Option<&'a usize> {
+        self.chain.next()
+    }
+}
+
+impl <'a> ExactSizeIterator for TrainingIndices<'a> {
+    fn len(&self) -> usize {
+        self.size
+    }
+}
+
+/// An iterator over the sets of indices required for k-fold cross validation.
+pub struct Folds<'a> {
+    num_folds: usize,
+    indices: &'a[usize],
+    count: usize
+}
+
+impl<'a> Folds<'a> {
+    /// Let n = indices.len(), and k = num_folds.
+    /// The first n % k folds have size n / k + 1 and the
+    /// rest have size n / k. (In particular, if n % k == 0 then all
+    /// folds are the same size.)
+    pub fn new(indices: &'a ShuffledIndices, num_folds: usize) -> Folds<'a> {
+        let num_samples = indices.0.len();
+        assert!(num_folds > 1 && num_samples >= num_folds,
+            "Require num_folds > 1 && num_samples >= num_folds");
+
+        Folds {
+            num_folds,
+            indices: &indices.0,
+            count: 0
+        }
+    }
+}
+
+impl<'a> Iterator for Folds<'a> {
+    type Item = Partition<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count >= self.num_folds {
+            return None;
+        }
+
+        let num_samples = self.indices.len();
+        let q = num_samples / self.num_folds;
+        let r = num_samples % self.num_folds;
+        let fold_start = self.count * q + cmp::min(self.count, r);
+        let fold_size = if self.count >= r {q} else {q + 1};
+        let fold_end = fold_start + fold_size;
+
+        self.count += 1;
+
+        let prefix = &self.indices[..fold_start];
+        let suffix = &self.indices[fold_end..];
+        let infix = &self.indices[fold_start..fold_end];
+        Some(Partition {
+            train_indices_iter: TrainingIndices::new(prefix, suffix),
+            test_indices_iter: TestIndices::new(infix)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ShuffledIndices, Folds, create_shuffled_indices};
+
+    #[test]
+    fn test_seeded_shuffle() {
+        let a = create_shuffled_indices(20, Some(9)).0;
+        assert_eq!(a, create_shuffled_indices(20, Some(9)).0);
+        assert!(a != create_shuffled_indices(20, Some(10)).0);
+
+        let mut sorted = a.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+    }
+
+    // k % n == 0
+    #[test]
+    fn test_folds_n6_k3() {
+        let idxs = ShuffledIndices(vec![0, 1, 2, 3, 4, 5]);
+        let folds = collect_folds(Folds::new(&idxs, 3));
+
+        assert_eq!(folds, vec![
+            (vec![2, 3, 4, 5], vec![0, 1]),
+            (vec![0, 1, 4, 5], vec![2, 3]),
+            (vec![0, 1, 2, 3], vec![4, 5])
+            ]);
+    }
+
+    // k % n == 1
+    #[test]
+    fn test_folds_n5_k2() {
+        let idxs = ShuffledIndices(vec![0, 1, 2, 3, 4]);
+        let folds = collect_folds(Folds::new(&idxs, 2));
+
+        assert_eq!(folds, vec![
+            (vec![3, 4], vec![0, 1, 2]),
+            (vec![0, 1, 2], vec![3, 4])
+            ]);
+    }
+
+    // k % n == 2
+    #[test]
+    fn test_folds_n6_k4() {
+        let idxs = ShuffledIndices(vec![0, 1, 2, 3, 4, 5]);
+        let folds = collect_folds(Folds::new(&idxs, 4));
+
+        assert_eq!(folds, vec![
+            (vec![2, 3, 4, 5], vec![0, 1]),
+            (vec![0, 1, 4, 5], vec![2, 3]),
+            (vec![0, 1, 2, 3, 5], vec![4]),
+            (vec![0, 1, 2, 3, 4], vec![5])
+            ]);
+    }
+
+    // k == n
+    #[test]
+    fn test_folds_n4_k4() {
+        let idxs = ShuffledIndices(vec![0, 1, 2, 3]);
+        let folds = collect_folds(Folds::new(&idxs, 4));
+
+        assert_eq!(folds, vec![
+            (vec![1, 2, 3], vec![0]),
+            (vec![0, 2, 3], vec![1]),
+            (vec![0, 1, 3], vec![2]),
+            (vec![0, 1, 2], vec![3])
+            ]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_folds_rejects_large_k() {
+        let idxs = ShuffledIndices(vec![0, 1, 2]);
+        let _ = collect_folds(Folds::new(&idxs, 4));
+    }
+
+    // Check we're really returning iterators into the shuffled
+    // indices rather than into (0..n).
+    #[test]
+    fn test_folds_unordered_indices() {
+        let idxs = ShuffledIndices(vec![5, 4, 3, 2, 1, 0]);
+        let folds = collect_folds(Folds::new(&idxs, 3));
+
+        assert_eq!(folds, vec![
+            (vec![3, 2, 1, 0], vec![5, 4]),
+            (vec![5, 4, 1, 0], vec![3, 2]),
+            (vec![5, 4, 3, 2], vec![1, 0])
+            ]);
+    }
+
+    fn collect_folds<'a>(folds: Folds<'a>) -> Vec<(Vec<usize>, Vec<usize>)> {
+        folds
+            .map(|p|
+                (p.train_indices_iter.map(|x| *x).collect::<Vec<_>>(),
+                 p.test_indices_iter.map(|x| *x).collect::<Vec<_>>()))
+            .collect::<Vec<(Vec<usize>, Vec<usize>)>>()
+    }
+}
@@ -0,0 +1,579 @@
+//! Anomaly Detection Module
+//!
+//! Contains unsupervised models for detecting outliers.
+//!
+//! The `IsolationForest` isolates points with random feature splits.
+//! Anomalous points are isolated in fewer splits than normal points,
+//! and so have shorter average path lengths through the trees.
+//!
+//! The model's `predict` function returns an anomaly score in `(0, 1]`
+//! for each input. Scores close to `1` indicate anomalies, while scores
+//! well below `0.5` indicate normal points.
+//!
+//! The `LocalOutlierFactor` compares the local density of each point
+//! to that of its `k` nearest neighbours. Points in regions of much lower
+//! density than their neighbours have a local outlier factor well above `1`.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::anomaly::IsolationForest;
+//! use rusty_machine::learning::UnSupModel;
+//! use rusty_machine::linalg::Matrix;
+//!
+//! let inputs = Matrix::new(6, 2, vec![0.0, 0.1, 0.2, 0.0, 0.1, 0.2,
+//!                                     0.0, 0.0, 0.2, 0.2, 10.0, 10.0]);
+//!
+//! let mut model = IsolationForest::new(100, 6);
+//! model.train(&inputs).unwrap();
+//!
+//! let scores = model.predict(&inputs).unwrap();
+//!
+//! // The last point is the most anomalous
+//! let max_idx = scores.clone().argmax().0;
+//! assert_eq!(max_idx, 5);
+//! ```
+
+use linalg::{Matrix, BaseMatrix};
+use linalg::Vector;
+
+use learning::{LearningResult, UnSupModel};
+use learning::error::{Error, ErrorKind};
+use learning::toolkit::rand_utils;
+use neighbours::k_nearest;
+
+use rand::Rng;
+
+/// The Euler-Mascheroni constant.
+const EULER_GAMMA: f64 = 0.5772156649015329;
+
+/// Isolation Forest
+///
+/// An ensemble of random isolation trees, each trained on a
+/// random subsample of the data.
+#[derive(Debug)]
+pub struct IsolationForest {
+    n_trees: usize,
+    sample_size: usize,
+    trees: Option<Vec<IsolationTree>>,
+    /// The subsample size actually used during training.
+    trained_sample_size: usize,
+    /// The number of features in the training data.
+    trained_cols: usize,
+    /// The seed for the subsampling and splits.
+    seed: Option<u64>,
+}
+
+/// The default Isolation Forest.
+///
+/// The defaults are:
+///
+/// - `n_trees` = `100`
+/// - `sample_size` = `256`
+impl Default for IsolationForest {
+    fn default() -> IsolationForest {
+        IsolationForest::new(100, 256)
+    }
+}
+
+impl IsolationForest {
+    /// Constructs an untrained Isolation Forest.
+    ///
+    /// Each of the `n_trees` trees is trained on `sample_size` points
+    /// drawn without replacement from the training data. If the data
+    /// contains fewer points then all of them are used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::anomaly::IsolationForest;
+    ///
+    /// let model = IsolationForest::new(50, 128);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `n_trees` is zero.
+    /// - `sample_size` is less than 2.
+    pub fn new(n_trees: usize, sample_size: usize) -> IsolationForest {
+        assert!(n_trees > 0, "The number of trees must be positive.");
+        assert!(sample_size > 1, "The sample size must be greater than 1.");
+
+        IsolationForest {
+            n_trees,
+            sample_size,
+            trees: None,
+            trained_sample_size: 0,
+            trained_cols: 0,
+            seed: None,
+        }
+    }
+
+    /// Sets the seed used to build the trees.
+    ///
+    /// Forests with the same seed produce the same trees when
+    /// trained on the same data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::anomaly::IsolationForest;
+    ///
+    /// let model = IsolationForest::default().with_seed(42);
+    /// ```
+    pub fn with_seed(mut self, seed: u64) -> IsolationForest {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// The number of trees in the forest.
+    pub fn n_trees(&self) -> usize {
+        self.n_trees
+    }
+
+    /// The number of points used to train each tree.
+    pub fn sample_size(&self) -> usize {
+        self.sample_size
+    }
+}
+
+impl UnSupModel<Matrix<f64>, Vector<f64>> for IsolationForest {
+    /// Train the forest on the inputs.
+    fn train(&mut self, inputs: &Matrix<f64>) -> LearningResult<()> {
+        if inputs.rows() < 2 {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "Isolation forest requires at least two samples."));
+        }
+
+        let sample_size = self.sample_size.min(inputs.rows());
+        let max_depth = (sample_size as f64).log2().ceil() as usize;
+        let all_rows = (0..inputs.rows()).collect::<Vec<_>>();
+
+        let mut rng = rand_utils::seeded_rng(self.seed);
+        let trees = (0..self.n_trees)
+            .map(|_| {
+                let rows = rand_utils::reservoir_sample_with_rng(&all_rows, sample_size, &mut rng);
+                IsolationTree::build(inputs, rows, 0, max_depth, &mut rng)
+            })
+            .collect();
+
+        self.trees = Some(trees);
+        self.trained_sample_size = sample_size;
+        self.trained_cols = inputs.cols();
+        Ok(())
+    }
+
+    /// Compute the anomaly score of each input.
+    ///
+    /// The score is `2^(-E[h(x)] / c(n))` where `E[h(x)]` is the average
+    /// path length of the input through the trees and `c(n)` is the
+    /// average path length of an unsuccessful search in a binary search
+    /// tree of `n` points.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        if let Some(ref trees) = self.trees {
+            if inputs.cols() != self.trained_cols {
+                return Err(Error::new(ErrorKind::InvalidData,
+                                      "Inputs have different feature count than the training data."));
+            }
+
+            let norm = average_path_length(self.trained_sample_size);
+
+            let scores = inputs.iter_rows()
+                .map(|row| {
+                    let total = trees.iter().map(|t| t.path_length(row, 0)).sum::<f64>();
+                    let mean_path = total / (trees.len() as f64);
+                    2f64.powf(-mean_path / norm)
+                })
+                .collect::<Vec<_>>();
+
+            Ok(Vector::new(scores))
+        } else {
+            Err(Error::new_untrained())
+        }
+    }
+}
+
+/// A single random isolation tree.
+#[derive(Debug)]
+enum IsolationTree {
+    /// A terminal node containing `size` training points.
+    Leaf { size: usize },
+    /// An internal node splitting on `feature` at `threshold`.
+    Split {
+        feature: usize,
+        threshold: f64,
+        left: Box<IsolationTree>,
+        right: Box<IsolationTree>,
+    },
+}
+
+impl IsolationTree {
+    /// Recursively build a tree from the given rows of the inputs.
+    fn build<R: Rng>(inputs: &Matrix<f64>,
+                     rows: Vec<usize>,
+                     depth: usize,
+                     max_depth: usize,
+                     rng: &mut R)
+                     -> IsolationTree {
+        if depth >= max_depth || rows.len() <= 1 {
+            return IsolationTree::Leaf { size: rows.len() };
+        }
+
+        // Try the features in a random order until one can be split
+        let mut features = (0..inputs.cols()).collect::<Vec<_>>();
+        rng.shuffle(&mut features);
+
+        for feature in features {
+            let (min, max) = rows.iter()
+                .map(|&r| inputs[[r, feature]])
+                .fold((f64::INFINITY, f64::NEG_INFINITY),
+                      |(min, max), x| (min.min(x), max.max(x)));
+
+            if min < max {
+                let threshold = rng.gen_range(min, max);
+                let (left, right): (Vec<usize>, Vec<usize>) =
+                    rows.into_iter().partition(|&r| inputs[[r, feature]] < threshold);
+
+                return IsolationTree::Split {
+                    feature,
+                    threshold,
+                    left: Box::new(IsolationTree::build(inputs, left, depth + 1, max_depth, rng)),
+                    right: Box::new(IsolationTree::build(inputs, right, depth + 1, max_depth, rng)),
+                };
+            }
+        }
+
+        // All of the points are identical
+        IsolationTree::Leaf { size: rows.len() }
+    }
+
+    /// The path length of the point through the tree.
+    ///
+    /// Points ending in a leaf of more than one training point have the
+    /// expected remaining path length of that leaf added.
+    fn path_length(&self, point: &[f64], depth: usize) -> f64 {
+        match *self {
+            IsolationTree::Leaf { size } => depth as f64 + average_path_length(size),
+            IsolationTree::Split { feature, threshold, ref left, ref right } => {
+                if point[feature] < threshold {
+                    left.path_length(point, depth + 1)
+                } else {
+                    right.path_length(point, depth + 1)
+                }
+            }
+        }
+    }
+}
+
+/// The average path length of an unsuccessful search in a
+/// binary search tree containing `n` points.
+fn average_path_length(n: usize) -> f64 {
+    match n {
+        0 | 1 => 0f64,
+        2 => 1f64,
+        _ => {
+            let n = n as f64;
+            2f64 * ((n - 1f64).ln() + EULER_GAMMA) - 2f64 * (n - 1f64) / n
+        }
+    }
+}
+
+/// Local Outlier Factor
+///
+/// Scores points by the ratio of the local reachability density of
+/// their `k` nearest neighbours to their own. Points with a score
+/// above the threshold are flagged as outliers.
+#[derive(Debug)]
+pub struct LocalOutlierFactor {
+    k: usize,
+    threshold: f64,
+    train_inputs: Option<Matrix<f64>>,
+    /// The distance from each training point to its `k`th nearest neighbour.
+    k_distances: Option<Vec<f64>>,
+    /// The local reachability density of each training point.
+    densities: Option<Vec<f64>>,
+    /// The local outlier factor of each training point.
+    scores: Option<Vector<f64>>,
+}
+
+/// The default Local Outlier Factor model.
+///
+/// The defaults are:
+///
+/// - `k` = `20`
+/// - `threshold` = `1.5`
+impl Default for LocalOutlierFactor {
+    fn default() -> LocalOutlierFactor {
+        LocalOutlierFactor::new(20, 1.5)
+    }
+}
+
+impl LocalOutlierFactor {
+    /// Constructs an untrained Local Outlier Factor model.
+    ///
+    /// Uses the `k` nearest neighbours of each point, and flags points
+    /// whose local outlier factor exceeds `threshold` as outliers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::anomaly::LocalOutlierFactor;
+    ///
+    /// let model = LocalOutlierFactor::new(10, 1.5);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `k` is zero.
+    pub fn new(k: usize, threshold: f64) -> LocalOutlierFactor {
+        assert!(k > 0, "The number of neighbours must be positive.");
+
+        LocalOutlierFactor {
+            k,
+            threshold,
+            train_inputs: None,
+            k_distances: None,
+            densities: None,
+            scores: None,
+        }
+    }
+
+    /// The number of neighbours used to estimate local density.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// The local outlier factor above which points are flagged as outliers.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// The local outlier factor of each training point.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn scores(&self) -> Option<&Vector<f64>> {
+        self.scores.as_ref()
+    }
+
+    /// Whether each training point is flagged as an outlier.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn outliers(&self) -> Option<Vec<bool>> {
+        self.scores.as_ref().map(|s| s.data().iter().map(|&x| x > self.threshold).collect())
+    }
+
+    /// Whether each input is flagged as an outlier relative to the training data.
+    pub fn predict_outliers(&self, inputs: &Matrix<f64>) -> LearningResult<Vec<bool>> {
+        let scores = self.predict(inputs)?;
+        Ok(scores.data().iter().map(|&x| x > self.threshold).collect())
+    }
+
+    /// The local outlier factor of a point given its nearest training neighbours.
+    fn local_outlier_factor(&self,
+                            neighbours: &[(usize, f64)],
+                            k_distances: &[f64],
+                            densities: &[f64])
+                            -> f64 {
+        let density = reachability_density(neighbours, k_distances);
+        let neighbour_density = neighbours.iter().map(|&(o, _)| densities[o]).sum::<f64>() /
+                                (neighbours.len() as f64);
+        neighbour_density / density
+    }
+}
+
+impl UnSupModel<Matrix<f64>, Vector<f64>> for LocalOutlierFactor {
+    /// Compute the neighbourhood densities of the training data.
+    ///
+    /// The local outlier factor of each training point is then
+    /// available from `scores`.
+    fn train(&mut self, inputs: &Matrix<f64>) -> LearningResult<()> {
+        if inputs.rows() <= self.k {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "Local outlier factor requires more than k samples."));
+        }
+
+        let neighbours = (0..inputs.rows())
+            .map(|i| k_nearest(inputs.get_row(i).unwrap(), inputs, self.k, Some(i)))
+            .collect::<Vec<_>>();
+
+        let k_distances = neighbours.iter()
+            .map(|n| n[n.len() - 1].1)
+            .collect::<Vec<_>>();
+
+        let densities = neighbours.iter()
+            .map(|n| reachability_density(n, &k_distances))
+            .collect::<Vec<_>>();
+
+        let scores = neighbours.iter()
+            .map(|n| self.local_outlier_factor(n, &k_distances, &densities))
+            .collect::<Vec<_>>();
+
+        self.train_inputs = Some(inputs.clone());
+        self.k_distances = Some(k_distances);
+        self.densities = Some(densities);
+        self.scores = Some(Vector::new(scores));
+        Ok(())
+    }
+
+    /// Compute the local outlier factor of each input relative to the training data.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        if let (Some(train_inputs), Some(k_distances), Some(densities)) =
+               (self.train_inputs.as_ref(), self.k_distances.as_ref(), self.densities.as_ref()) {
+            if inputs.cols() != train_inputs.cols() {
+                return Err(Error::new(ErrorKind::InvalidData,
+                                      "Inputs have different feature count than the training data."));
+            }
+
+            let scores = inputs.iter_rows()
+                .map(|row| {
+                    let neighbours = k_nearest(row, train_inputs, self.k, None);
+                    self.local_outlier_factor(&neighbours, k_distances, densities)
+                })
+                .collect::<Vec<_>>();
+
+            Ok(Vector::new(scores))
+        } else {
+            Err(Error::new_untrained())
+        }
+    }
+}
+
+/// The local reachability density of a point given its nearest neighbours.
+fn reachability_density(neighbours: &[(usize, f64)], k_distances: &[f64]) -> f64 {
+    let total_reach = neighbours.iter()
+        .map(|&(o, dist)| dist.max(k_distances[o]))
+        .sum::<f64>();
+
+    // Guard against duplicated points having zero reachability distance
+    1f64 / (total_reach / (neighbours.len() as f64) + 1e-10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IsolationForest, LocalOutlierFactor, average_path_length};
+
+    use learning::UnSupModel;
+    use linalg::Matrix;
+    use neighbours::ascending_nan_last;
+
+    /// A dense cluster of points with outliers in the last three rows.
+    fn data_with_outliers() -> Matrix<f64> {
+        let mut data = Vec::new();
+        for i in 0..100 {
+            let t = i as f64;
+            data.push((0.9 * t).sin());
+            data.push((1.7 * t).cos());
+        }
+
+        data.extend_from_slice(&[10.0, 10.0, -8.0, 9.0, 9.0, -10.0]);
+        Matrix::new(103, 2, data)
+    }
+
+    #[test]
+    fn test_average_path_length() {
+        assert_eq!(average_path_length(1), 0.0);
+        assert_eq!(average_path_length(2), 1.0);
+        assert!(average_path_length(256) > average_path_length(128));
+    }
+
+    #[test]
+    fn test_isolation_forest_outliers() {
+        let inputs = data_with_outliers();
+
+        let mut model = IsolationForest::new(100, 64).with_seed(7);
+        model.train(&inputs).unwrap();
+
+        let scores = model.predict(&inputs).unwrap();
+
+        let mut ranked = (0..103).collect::<Vec<_>>();
+        ranked.sort_by(|&a, &b| ascending_nan_last(-scores[a], -scores[b]));
+
+        let mut top = ranked[..3].to_vec();
+        top.sort();
+        assert_eq!(top, vec![100, 101, 102]);
+
+        for s in scores.data() {
+            assert!(*s > 0.0 && *s <= 1.0);
+        }
+    }
+
+    #[test]
+    fn test_isolation_forest_untrained() {
+        let model = IsolationForest::default();
+        assert!(model.predict(&Matrix::zeros(1, 2)).is_err());
+    }
+
+    #[test]
+    fn test_isolation_forest_too_few_samples() {
+        let mut model = IsolationForest::default();
+        assert!(model.train(&Matrix::zeros(1, 2)).is_err());
+    }
+
+    #[test]
+    fn test_isolation_forest_feature_mismatch() {
+        let mut model = IsolationForest::new(10, 32).with_seed(1);
+        model.train(&data_with_outliers()).unwrap();
+
+        assert!(model.predict(&Matrix::zeros(1, 1)).is_err());
+        assert!(model.predict(&Matrix::zeros(1, 3)).is_err());
+    }
+
+    #[test]
+    fn test_local_outlier_factor_outliers() {
+        let inputs = data_with_outliers();
+
+        let mut model = LocalOutlierFactor::new(10, 1.5);
+        model.train(&inputs).unwrap();
+
+        let scores = model.scores().unwrap();
+        for i in 0..100 {
+            assert!(scores[i] < 1.5);
+        }
+        for i in 100..103 {
+            assert!(scores[i] > 3.0);
+        }
+
+        let outliers = model.outliers().unwrap();
+        assert_eq!(outliers.iter().filter(|&&o| o).count(), 3);
+        assert!(outliers[100] && outliers[101] && outliers[102]);
+    }
+
+    #[test]
+    fn test_local_outlier_factor_predict() {
+        let inputs = data_with_outliers();
+
+        let mut model = LocalOutlierFactor::new(10, 1.5);
+        model.train(&inputs).unwrap();
+
+        let new_points = Matrix::new(2, 2, vec![0.1, -0.2, 20.0, -20.0]);
+        let flags = model.predict_outliers(&new_points).unwrap();
+
+        assert_eq!(flags, vec![false, true]);
+    }
+
+    #[test]
+    fn test_local_outlier_factor_too_few_samples() {
+        let mut model = LocalOutlierFactor::new(5, 1.5);
+        assert!(model.train(&Matrix::zeros(5, 2)).is_err());
+    }
+
+    #[test]
+    fn test_local_outlier_factor_untrained() {
+        let model = LocalOutlierFactor::default();
+        assert!(model.predict(&Matrix::zeros(1, 2)).is_err());
+    }
+
+    #[test]
+    fn test_isolation_forest_seeded() {
+        let inputs = data_with_outliers();
+
+        let scores = |seed| {
+            let mut model = IsolationForest::new(10, 32).with_seed(seed);
+            model.train(&inputs).unwrap();
+            model.predict(&inputs).unwrap()
+        };
+
+        assert_eq!(scores(1), scores(1));
+        assert!(scores(1) != scores(2));
+    }
+}
@@ -0,0 +1,315 @@
+//! Canonical Correlation Analysis Module
+//!
+//! Contains an implementation of canonical correlation analysis (CCA).
+//!
+//! Given two views of the same samples, CCA finds pairs of projections
+//! `(a_i, b_i)` such that the correlation of `X a_i` and `Y b_i` is
+//! maximized, with each pair uncorrelated with the previous pairs.
+//!
+//! The projections solve the generalized eigenproblem on the covariance
+//! blocks `C_xy C_yy^-1 C_yx a = r^2 C_xx a`. This is computed by
+//! whitening each view with the Cholesky factor of its covariance and
+//! taking the SVD of the whitened cross-covariance, whose singular
+//! values are the canonical correlations.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::cca::CCA;
+//! use rusty_machine::linalg::{Matrix, BaseMatrix};
+//!
+//! let x = Matrix::new(5, 2, vec![1.0, 0.3, 2.0, -0.1, 3.0, 0.4, 4.0, 0.0, 5.0, -0.2]);
+//! let y = Matrix::new(5, 1, vec![2.1, 3.9, 6.2, 7.9, 10.1]);
+//!
+//! let mut model = CCA::new(1);
+//! model.fit(&x, &y).unwrap();
+//!
+//! // The views share a strong linear relation
+//! assert!(model.correlations().unwrap()[0] > 0.99);
+//!
+//! let (x_scores, y_scores) = model.transform(&x, &y).unwrap();
+//! assert_eq!(x_scores.cols(), 1);
+//! ```
+
+use linalg::{Matrix, BaseMatrix};
+use linalg::{MatrixExt, Vector};
+
+use data::stats::{covariance, cross_covariance};
+use learning::LearningResult;
+use learning::error::{Error, ErrorKind};
+use learning::standardize::Standardization;
+
+/// Canonical Correlation Analysis
+#[derive(Debug)]
+pub struct CCA {
+    /// The number of canonical pairs to keep.
+    n_components: usize,
+    /// The constant added to the diagonals of the view covariances.
+    regularization: f64,
+    /// The centering of the two views.
+    centering: Option<(Standardization, Standardization)>,
+    /// The projections of the two views, stored in columns.
+    weights: Option<(Matrix<f64>, Matrix<f64>)>,
+    /// The canonical correlations.
+    correlations: Option<Vector<f64>>,
+}
+
+impl CCA {
+    /// Constructs an untrained CCA model which keeps
+    /// `n_components` canonical pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::cca::CCA;
+    ///
+    /// let model = CCA::new(2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `n_components` is zero.
+    pub fn new(n_components: usize) -> CCA {
+        assert!(n_components > 0, "The number of components must be positive.");
+
+        CCA {
+            n_components,
+            regularization: 0f64,
+            centering: None,
+            weights: None,
+            correlations: None,
+        }
+    }
+
+    /// The number of canonical pairs kept by the model.
+    pub fn n_components(&self) -> usize {
+        self.n_components
+    }
+
+    /// The constant added to the diagonals of the view covariances.
+    pub fn regularization(&self) -> f64 {
+        self.regularization
+    }
+
+    /// Sets the constant added to the diagonals of the view covariances.
+    ///
+    /// A positive value is needed when a view has more features than
+    /// samples, or collinear features. Defaults to `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::cca::CCA;
+    ///
+    /// let mut model = CCA::new(1);
+    /// model.set_regularization(1e-3);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The regularization is negative.
+    pub fn set_regularization(&mut self, regularization: f64) {
+        assert!(regularization >= 0f64, "The regularization must be non-negative.");
+        self.regularization = regularization;
+    }
+
+    /// The canonical correlations, in decreasing order.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn correlations(&self) -> Option<&Vector<f64>> {
+        self.correlations.as_ref()
+    }
+
+    /// The projections of the first view.
+    ///
+    /// Each column is a projection vector, scaled so that the
+    /// projected data has unit variance.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn x_weights(&self) -> Option<&Matrix<f64>> {
+        self.weights.as_ref().map(|w| &w.0)
+    }
+
+    /// The projections of the second view.
+    ///
+    /// Each column is a projection vector, scaled so that the
+    /// projected data has unit variance.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn y_weights(&self) -> Option<&Matrix<f64>> {
+        self.weights.as_ref().map(|w| &w.1)
+    }
+
+    /// Fits the canonical pairs of the two views.
+    ///
+    /// Each row of `x` and `y` is a view of the same sample.
+    ///
+    /// # Failures
+    ///
+    /// - The views have different numbers of rows, or fewer than two.
+    /// - `n_components` exceeds the number of features of either view.
+    /// - A view covariance is not positive definite.
+    pub fn fit(&mut self, x: &Matrix<f64>, y: &Matrix<f64>) -> LearningResult<()> {
+        if x.rows() != y.rows() {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "The views must have the same number of rows."));
+        }
+        if x.rows() < 2 {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "At least two samples are needed to fit CCA."));
+        }
+        if self.n_components > x.cols() || self.n_components > y.cols() {
+            return Err(Error::new(ErrorKind::InvalidParameters,
+                                  "The number of components cannot exceed the view features."));
+        }
+
+        let c_xx = covariance(x) + Matrix::identity(x.cols()) * self.regularization;
+        let c_yy = covariance(y) + Matrix::identity(y.cols()) * self.regularization;
+        let c_xy = cross_covariance(x, y);
+
+        let l_x = cholesky(&c_xx)?;
+        let l_y = cholesky(&c_yy)?;
+
+        // The whitened cross-covariance L_x^-1 C_xy L_y^-T
+        let half = l_x.solve_l_triangular_multiple(&c_xy)?;
+        let whitened = l_y.solve_l_triangular_multiple(&half.transpose())?.transpose();
+
+        let (s, u, v) = whitened.svd_jacobi();
+        let k = self.n_components;
+        let cols = (0..k).collect::<Vec<_>>();
+
+        // Map the singular vectors back to the original coordinates
+        let x_weights = l_x.transpose().solve_u_triangular_multiple(&u.select_cols(&cols))?;
+        let y_weights = l_y.transpose().solve_u_triangular_multiple(&v.select_cols(&cols))?;
+
+        self.centering = Some((Standardization::new(x, true, false),
+                               Standardization::new(y, true, false)));
+        self.weights = Some((x_weights, y_weights));
+        self.correlations = Some(Vector::new((0..k).map(|i| s[[i, i]]).collect::<Vec<_>>()));
+        Ok(())
+    }
+
+    /// Projects the two views onto the canonical pairs.
+    ///
+    /// The views are centered by the means of the training data.
+    ///
+    /// # Failures
+    ///
+    /// - The model has not been trained.
+    /// - The views do not have the number of features seen in training.
+    pub fn transform(&self,
+                     x: &Matrix<f64>,
+                     y: &Matrix<f64>)
+                     -> LearningResult<(Matrix<f64>, Matrix<f64>)> {
+        if let (Some(centering), Some(weights)) = (self.centering.as_ref(), self.weights.as_ref()) {
+            if x.cols() != centering.0.mean.size() || y.cols() != centering.1.mean.size() {
+                return Err(Error::new(ErrorKind::InvalidData,
+                                      "Views have different feature counts than the training data."));
+            }
+
+            Ok((centering.0.transform(x) * &weights.0, centering.1.transform(y) * &weights.1))
+        } else {
+            Err(Error::new_untrained())
+        }
+    }
+}
+
+/// The Cholesky factor of a covariance, checking that it is positive definite.
+fn cholesky(cov: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+    let l = cov.cholesky()?;
+    if (0..l.rows()).all(|i| l[[i, i]] > 0f64 && l[[i, i]].is_finite()) {
+        Ok(l)
+    } else {
+        Err(Error::new(ErrorKind::LinearAlgebra,
+                       "The view covariance is not positive definite."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CCA;
+    use linalg::{Matrix, BaseMatrix};
+
+    use rand::{SeedableRng, StdRng};
+    use rand::distributions::{IndependentSample, Normal};
+
+    /// Two views sharing one latent factor, each with independent noise features.
+    fn shared_latent_views(n: usize) -> (Matrix<f64>, Matrix<f64>) {
+        let seed: &[usize] = &[7, 11];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let normal = Normal::new(0.0, 1.0);
+
+        let mut x = Vec::with_capacity(3 * n);
+        let mut y = Vec::with_capacity(2 * n);
+        for _ in 0..n {
+            let z = normal.ind_sample(&mut rng);
+            let noise = (0..5).map(|_| normal.ind_sample(&mut rng)).collect::<Vec<_>>();
+
+            x.extend_from_slice(&[z + 0.05 * noise[0], noise[1], noise[2] - z]);
+            y.extend_from_slice(&[noise[3], 2.0 * z + 0.05 * noise[4]]);
+        }
+
+        (Matrix::new(n, 3, x), Matrix::new(n, 2, y))
+    }
+
+    fn correlation(a: &[f64], b: &[f64]) -> f64 {
+        let n = a.len() as f64;
+        let (ma, mb) = (a.iter().sum::<f64>() / n, b.iter().sum::<f64>() / n);
+        let cov = a.iter().zip(b.iter()).map(|(x, y)| (x - ma) * (y - mb)).sum::<f64>();
+        let va = a.iter().map(|x| (x - ma).powi(2)).sum::<f64>();
+        let vb = b.iter().map(|y| (y - mb).powi(2)).sum::<f64>();
+        cov / (va * vb).sqrt()
+    }
+
+    #[test]
+    fn test_cca_shared_latent_factor() {
+        let (x, y) = shared_latent_views(500);
+
+        let mut model = CCA::new(2);
+        model.fit(&x, &y).unwrap();
+
+        let corrs = model.correlations().unwrap();
+        assert!(corrs[0] > 0.99);
+        // The remaining features are independent noise
+        assert!(corrs[1] < 0.2);
+
+        // The projected views have the reported correlation
+        let (x_scores, y_scores) = model.transform(&x, &y).unwrap();
+        let xs = x_scores.select_cols(&[0]).into_vec();
+        let ys = y_scores.select_cols(&[0]).into_vec();
+        assert!((correlation(&xs, &ys).abs() - corrs[0]).abs() < 1e-8);
+
+        // and unit variance
+        let var = xs.iter().map(|v| v * v).sum::<f64>() / 499.0;
+        assert!((var - 1.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_cca_errors() {
+        let (x, y) = shared_latent_views(20);
+
+        let mut model = CCA::new(3);
+        assert!(model.transform(&x, &y).is_err());
+        assert!(model.fit(&x, &y).is_err());
+
+        let mut model = CCA::new(1);
+        assert!(model.fit(&x, &y.select_rows(&[0, 1, 2])).is_err());
+
+        model.fit(&x, &y).unwrap();
+        assert!(model.transform(&y, &y).is_err());
+    }
+
+    #[test]
+    fn test_cca_regularization() {
+        // The last feature of x is constant
+        let x = Matrix::new(4, 2, vec![1.0, 5.0, 0.0, 5.0, 2.0, 5.0, 3.0, 5.0]);
+        let y = Matrix::new(4, 1, vec![1.0, 2.0, 4.0, 3.0]);
+
+        let mut model = CCA::new(1);
+        assert!(model.fit(&x, &y).is_err());
+
+        model.set_regularization(0.1);
+        model.fit(&x, &y).unwrap();
+        assert!(model.correlations().unwrap()[0] < 1.0);
+    }
+}
@@ -0,0 +1,591 @@
+//! Ensemble module
+//!
+//! Contains models which combine the predictions of several
+//! other models.
+//!
+//! The `VotingEnsemble` blends a heterogeneous set of models by a
+//! (weighted) majority vote of their predicted class labels, or by
+//! the (weighted) mean of their predicted values.
+//!
+//! The `StackingEnsemble` instead trains a meta-model on the
+//! predictions of its base models. The meta-model is trained on
+//! out-of-fold predictions, so it learns how the base models behave
+//! on data they have not seen.
+//!
+//! Any model implementing `SupModel<Matrix<f64>, Vector<f64>>` can be
+//! a member. The members are stored as boxed trait objects, see
+//! `BoxedModel`, so models of different types can be mixed.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::ensemble::{VotingEnsemble, Voting};
+//! use rusty_machine::learning::knn::KNNRegressor;
+//! use rusty_machine::learning::lin_reg::LinRegressor;
+//! use rusty_machine::learning::SupModel;
+//! use rusty_machine::linalg::{Matrix, Vector};
+//!
+//! let inputs = Matrix::new(4, 1, vec![1.0, 2.0, 3.0, 4.0]);
+//! let targets = Vector::new(vec![2.0, 4.0, 6.0, 8.0]);
+//!
+//! let mut ensemble = VotingEnsemble::new(vec![Box::new(LinRegressor::default()),
+//!                                             Box::new(KNNRegressor::new(1))],
+//!                                        Voting::Mean);
+//! ensemble.train(&inputs, &targets).unwrap();
+//!
+//! let output = ensemble.predict(&Matrix::new(1, 1, vec![2.0])).unwrap();
+//! assert!((output[0] - 4.0).abs() < 1e-8);
+//! ```
+
+use std::fmt;
+
+use linalg::{Matrix, BaseMatrix};
+use linalg::Vector;
+
+use analysis::score::{self, DefaultScore};
+use learning::{LearningResult, SupModel};
+use learning::error::{Error, ErrorKind};
+use folds::{Folds, create_shuffled_indices};
+
+/// A boxed supervised model with matrix inputs and vector targets.
+pub type BoxedModel = Box<dyn SupModel<Matrix<f64>, Vector<f64>>>;
+
+/// How the predictions of the members are combined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Voting {
+    /// The class label with the greatest total weight.
+    ///
+    /// Ties are broken in favour of the label predicted by the
+    /// earliest member. Use this for classification.
+    Majority,
+    /// The weighted mean of the predictions.
+    ///
+    /// Use this for regression.
+    Mean,
+}
+
+/// An ensemble combining the predictions of its members by voting.
+pub struct VotingEnsemble {
+    models: Vec<BoxedModel>,
+    weights: Vec<f64>,
+    voting: Voting,
+}
+
+impl fmt::Debug for VotingEnsemble {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VotingEnsemble")
+            .field("n_models", &self.models.len())
+            .field("weights", &self.weights)
+            .field("voting", &self.voting)
+            .finish()
+    }
+}
+
+impl VotingEnsemble {
+    /// Constructs an ensemble of the given models with equal weights.
+    ///
+    /// The models may already be trained, in which case the ensemble
+    /// can predict straight away. Training the ensemble trains every
+    /// member on the same data.
+    ///
+    /// # Panics
+    ///
+    /// - `models` is empty.
+    pub fn new(models: Vec<BoxedModel>, voting: Voting) -> VotingEnsemble {
+        assert!(!models.is_empty(), "An ensemble needs at least one model.");
+
+        let weights = vec![1f64; models.len()];
+        VotingEnsemble {
+            models,
+            weights,
+            voting,
+        }
+    }
+
+    /// Sets the weight given to each member's vote.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::ensemble::{VotingEnsemble, Voting};
+    /// use rusty_machine::learning::knn::KNNClassifier;
+    ///
+    /// let ensemble = VotingEnsemble::new(vec![Box::new(KNNClassifier::new(1)),
+    ///                                         Box::new(KNNClassifier::new(5))],
+    ///                                    Voting::Majority)
+    ///     .with_weights(vec![2.0, 1.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - There is not one weight per model.
+    /// - A weight is negative or not finite, or all weights are zero.
+    pub fn with_weights(mut self, weights: Vec<f64>) -> VotingEnsemble {
+        assert!(weights.len() == self.models.len(),
+                "There must be one weight per model.");
+        assert!(weights.iter().all(|&w| w >= 0f64 && w.is_finite()),
+                "Model weights must be non-negative and finite.");
+        assert!(weights.iter().any(|&w| w > 0f64),
+                "At least one model weight must be positive.");
+
+        self.weights = weights;
+        self
+    }
+
+    /// The number of members.
+    pub fn n_models(&self) -> usize {
+        self.models.len()
+    }
+
+    /// The weight given to each member's vote.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// How the predictions of the members are combined.
+    pub fn voting(&self) -> Voting {
+        self.voting
+    }
+}
+
+impl SupModel<Matrix<f64>, Vector<f64>> for VotingEnsemble {
+    /// Train every member on the inputs and targets.
+    fn train(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
+        for model in &mut self.models {
+            model.train(inputs, targets)?;
+        }
+        Ok(())
+    }
+
+    /// Combine the predictions of the members.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        let mut predictions = Vec::with_capacity(self.models.len());
+        for model in &self.models {
+            let outputs = model.predict(inputs)?;
+            if outputs.size() != inputs.rows() {
+                return Err(Error::new(ErrorKind::InvalidState,
+                                      "A member predicted the wrong number of outputs."));
+            }
+            predictions.push(outputs);
+        }
+
+        let combined = (0..inputs.rows())
+            .map(|i| {
+                let votes = predictions.iter().map(|p| p[i]);
+                match self.voting {
+                    Voting::Majority => majority_vote(votes, &self.weights),
+                    Voting::Mean => weighted_mean(votes, &self.weights),
+                }
+            })
+            .collect::<Vec<_>>();
+        Ok(Vector::new(combined))
+    }
+
+    /// Score the ensemble.
+    ///
+    /// Majority voting is scored by accuracy, and mean voting by
+    /// the R<sup>2</sup> score.
+    fn score(&self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<f64> {
+        let outputs = self.predict(inputs)?;
+        match self.voting {
+            Voting::Majority => Ok(score::label_accuracy(&outputs, targets)),
+            Voting::Mean => Ok(Vector::default_score(&outputs, targets)),
+        }
+    }
+}
+
+/// An ensemble training a meta-model on the predictions of base models.
+///
+/// During training the data is split into folds. Each base model is
+/// trained on all but one fold and predicts the held-out fold, giving
+/// leak-free out-of-fold predictions for every sample. These form the
+/// columns of the meta-features on which the meta-model is trained.
+/// Finally the base models are retrained on all of the data.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::learning::ensemble::StackingEnsemble;
+/// use rusty_machine::learning::knn::KNNRegressor;
+/// use rusty_machine::learning::lin_reg::LinRegressor;
+/// use rusty_machine::learning::SupModel;
+/// use rusty_machine::linalg::{Matrix, Vector};
+///
+/// let inputs = Matrix::new(6, 1, vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+/// let targets = Vector::new(vec![0.0, 1.0, 4.0, 9.0, 16.0, 25.0]);
+///
+/// let mut ensemble = StackingEnsemble::new(vec![Box::new(LinRegressor::default()),
+///                                               Box::new(KNNRegressor::new(2))],
+///                                          Box::new(LinRegressor::default()))
+///     .with_folds(3)
+///     .with_seed(1);
+/// ensemble.train(&inputs, &targets).unwrap();
+///
+/// let output = ensemble.predict(&Matrix::new(1, 1, vec![2.5])).unwrap();
+/// ```
+pub struct StackingEnsemble {
+    base_models: Vec<BoxedModel>,
+    meta_model: BoxedModel,
+    n_folds: usize,
+    seed: Option<u64>,
+}
+
+impl fmt::Debug for StackingEnsemble {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StackingEnsemble")
+            .field("n_models", &self.base_models.len())
+            .field("n_folds", &self.n_folds)
+            .field("seed", &self.seed)
+            .finish()
+    }
+}
+
+impl StackingEnsemble {
+    /// Constructs a stacking ensemble using 5 folds.
+    ///
+    /// # Panics
+    ///
+    /// - `base_models` is empty.
+    pub fn new(base_models: Vec<BoxedModel>, meta_model: BoxedModel) -> StackingEnsemble {
+        assert!(!base_models.is_empty(), "An ensemble needs at least one model.");
+
+        StackingEnsemble {
+            base_models,
+            meta_model,
+            n_folds: 5,
+            seed: None,
+        }
+    }
+
+    /// Sets the number of folds used to create the meta-features.
+    ///
+    /// # Panics
+    ///
+    /// - `n_folds` is less than 2.
+    pub fn with_folds(mut self, n_folds: usize) -> StackingEnsemble {
+        assert!(n_folds > 1, "There must be at least two folds.");
+        self.n_folds = n_folds;
+        self
+    }
+
+    /// Sets the seed used to shuffle the samples into folds.
+    pub fn with_seed(mut self, seed: u64) -> StackingEnsemble {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// The number of base models.
+    pub fn n_models(&self) -> usize {
+        self.base_models.len()
+    }
+
+    /// The number of folds used to create the meta-features.
+    pub fn n_folds(&self) -> usize {
+        self.n_folds
+    }
+
+    /// The meta-model, trained on the base model predictions.
+    pub fn meta_model(&self) -> &BoxedModel {
+        &self.meta_model
+    }
+
+    /// The out-of-fold predictions of the base models.
+    ///
+    /// Row `i` holds the predictions for sample `i` made by base models
+    /// which were not trained on it. This trains the base models on each
+    /// fold in turn, so they must be retrained afterwards.
+    fn out_of_fold_predictions(&mut self,
+                               inputs: &Matrix<f64>,
+                               targets: &Vector<f64>)
+                               -> LearningResult<Matrix<f64>> {
+        if inputs.rows() != targets.size() {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "The number of inputs and targets must match."));
+        }
+        if inputs.rows() < self.n_folds {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "There must be at least as many samples as folds."));
+        }
+
+        let n = inputs.rows();
+        let indices = create_shuffled_indices(n, self.seed);
+
+        let n_models = self.base_models.len();
+        let mut meta_features = Matrix::zeros(n, n_models);
+        for p in Folds::new(&indices, self.n_folds) {
+            let train_idx = p.train_indices_iter.cloned().collect::<Vec<_>>();
+            let test_idx = p.test_indices_iter.cloned().collect::<Vec<_>>();
+            let train_inputs = inputs.select_rows(&train_idx);
+            let train_targets = Vector::new(train_idx.iter()
+                .map(|&i| targets[i])
+                .collect::<Vec<_>>());
+            let test_inputs = inputs.select_rows(&test_idx);
+
+            for (j, model) in self.base_models.iter_mut().enumerate() {
+                model.train(&train_inputs, &train_targets)?;
+                let outputs = model.predict(&test_inputs)?;
+                if outputs.size() != test_idx.len() {
+                    return Err(Error::new(ErrorKind::InvalidState,
+                                          "A member predicted the wrong number of outputs."));
+                }
+                for (&i, &y) in test_idx.iter().zip(outputs.iter()) {
+                    meta_features[[i, j]] = y;
+                }
+            }
+        }
+        Ok(meta_features)
+    }
+
+    /// The predictions of the trained base models, one column per model.
+    fn base_predictions(&self, inputs: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        let mut meta_features = Matrix::zeros(inputs.rows(), self.base_models.len());
+        for (j, model) in self.base_models.iter().enumerate() {
+            let outputs = model.predict(inputs)?;
+            if outputs.size() != inputs.rows() {
+                return Err(Error::new(ErrorKind::InvalidState,
+                                      "A member predicted the wrong number of outputs."));
+            }
+            for (i, &y) in outputs.iter().enumerate() {
+                meta_features[[i, j]] = y;
+            }
+        }
+        Ok(meta_features)
+    }
+}
+
+impl SupModel<Matrix<f64>, Vector<f64>> for StackingEnsemble {
+    /// Train the meta-model on out-of-fold predictions, then
+    /// retrain the base models on all of the data.
+    fn train(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
+        let meta_features = self.out_of_fold_predictions(inputs, targets)?;
+        self.meta_model.train(&meta_features, targets)?;
+
+        for model in &mut self.base_models {
+            model.train(inputs, targets)?;
+        }
+        Ok(())
+    }
+
+    /// Predict with the meta-model from the base model predictions.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        let meta_features = self.base_predictions(inputs)?;
+        self.meta_model.predict(&meta_features)
+    }
+
+    /// Score the ensemble using the meta-model's score.
+    fn score(&self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<f64> {
+        let meta_features = self.base_predictions(inputs)?;
+        self.meta_model.score(&meta_features, targets)
+    }
+}
+
+/// The label with the greatest total weight, earliest first on ties.
+fn majority_vote<I: Iterator<Item = f64>>(votes: I, weights: &[f64]) -> f64 {
+    let mut tally: Vec<(f64, f64)> = Vec::new();
+    for (label, &w) in votes.zip(weights.iter()) {
+        match tally.iter_mut().find(|t| t.0 == label) {
+            Some(t) => t.1 += w,
+            None => tally.push((label, w)),
+        }
+    }
+
+    let mut best = tally[0];
+    for &t in &tally[1..] {
+        if t.1 > best.1 {
+            best = t;
+        }
+    }
+    best.0
+}
+
+/// The weighted mean of the votes.
+fn weighted_mean<I: Iterator<Item = f64>>(votes: I, weights: &[f64]) -> f64 {
+    let total = weights.iter().sum::<f64>();
+    votes.zip(weights.iter()).map(|(v, w)| v * w).sum::<f64>() / total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VotingEnsemble, StackingEnsemble, Voting, BoxedModel};
+    use learning::SupModel;
+    use learning::knn::{KNNClassifier, KNNRegressor};
+    use learning::lin_reg::LinRegressor;
+    use learning::ridge::RidgeRegressor;
+    use linalg::{Matrix, BaseMatrix, Vector};
+
+    fn classification_data() -> (Matrix<f64>, Vector<f64>) {
+        let inputs = Matrix::new(8, 2, vec![0.0, 0.0, 0.5, 0.5, 1.0, 0.0, 0.0, 1.0,
+                                            3.0, 3.0, 3.5, 2.5, 2.0, 3.0, 1.6, 1.6]);
+        let targets = Vector::new(vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0]);
+        (inputs, targets)
+    }
+
+    fn classifiers() -> Vec<BoxedModel> {
+        vec![Box::new(KNNClassifier::new(1)),
+             Box::new(KNNClassifier::new(3)),
+             Box::new(KNNClassifier::new(5))]
+    }
+
+    #[test]
+    fn test_majority_vote_of_members() {
+        let (inputs, targets) = classification_data();
+        let test_inputs = Matrix::new(4, 2, vec![1.8, 1.8, 0.2, 0.1, 2.5, 2.5, 1.2, 1.5]);
+
+        let mut ensemble = VotingEnsemble::new(classifiers(), Voting::Majority);
+        ensemble.train(&inputs, &targets).unwrap();
+        let output = ensemble.predict(&test_inputs).unwrap();
+
+        let mut members = classifiers();
+        let mut predictions = Vec::new();
+        for model in &mut members {
+            model.train(&inputs, &targets).unwrap();
+            predictions.push(model.predict(&test_inputs).unwrap());
+        }
+
+        for i in 0..4 {
+            let ones = predictions.iter().filter(|p| p[i] == 1.0).count();
+            let expected = if ones >= 2 { 1.0 } else { 0.0 };
+            assert_eq!(output[i], expected);
+        }
+        assert_eq!(ensemble.score(&inputs, &targets).unwrap(),
+                   ::analysis::score::accuracy(ensemble.predict(&inputs).unwrap().data().iter(),
+                                               targets.data().iter()));
+    }
+
+    #[test]
+    fn test_weighted_majority_vote() {
+        let (inputs, targets) = classification_data();
+
+        // The nearest neighbour alone outweighs the other two
+        let mut ensemble = VotingEnsemble::new(classifiers(), Voting::Majority)
+            .with_weights(vec![3.0, 1.0, 1.0]);
+        ensemble.train(&inputs, &targets).unwrap();
+
+        let mut nearest = KNNClassifier::new(1);
+        nearest.train(&inputs, &targets).unwrap();
+
+        let test_inputs = Matrix::new(3, 2, vec![1.7, 1.7, 2.9, 2.9, 0.4, 0.6]);
+        assert_eq!(ensemble.predict(&test_inputs).unwrap(),
+                   nearest.predict(&test_inputs).unwrap());
+    }
+
+    #[test]
+    fn test_mean_of_members() {
+        let inputs = Matrix::new(5, 1, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+        let targets = Vector::new(vec![1.0, 2.5, 2.0, 4.5, 5.0]);
+        let test_inputs = Matrix::new(3, 1, vec![0.5, 2.2, 5.0]);
+
+        let members = || -> Vec<BoxedModel> {
+            vec![Box::new(LinRegressor::default()),
+                 Box::new(RidgeRegressor::new(2.0)),
+                 Box::new(KNNRegressor::new(2))]
+        };
+
+        let mut ensemble = VotingEnsemble::new(members(), Voting::Mean)
+            .with_weights(vec![1.0, 2.0, 0.5]);
+        ensemble.train(&inputs, &targets).unwrap();
+        let output = ensemble.predict(&test_inputs).unwrap();
+
+        let mut expected = Vector::zeros(3);
+        for (mut model, &w) in members().into_iter().zip([1.0, 2.0, 0.5].iter()) {
+            model.train(&inputs, &targets).unwrap();
+            expected = expected + model.predict(&test_inputs).unwrap() * (w / 3.5);
+        }
+
+        for i in 0..3 {
+            assert!((output[i] - expected[i]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_untrained_member() {
+        let ensemble = VotingEnsemble::new(classifiers(), Voting::Majority);
+        assert!(ensemble.predict(&Matrix::new(1, 2, vec![0.0, 0.0])).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_number_of_weights() {
+        let _ = VotingEnsemble::new(classifiers(), Voting::Majority).with_weights(vec![1.0]);
+    }
+
+    fn curved_data() -> (Matrix<f64>, Vector<f64>) {
+        let x = (0..60).map(|i| i as f64 / 10.0).collect::<Vec<_>>();
+        let y = x.iter().map(|&x| 2.0 * x.sin() + 0.5 * x).collect::<Vec<_>>();
+        (Matrix::new(60, 1, x), Vector::new(y))
+    }
+
+    fn stacking_members() -> Vec<BoxedModel> {
+        vec![Box::new(LinRegressor::default()),
+             Box::new(KNNRegressor::new(8))]
+    }
+
+    /// The mean R^2 score over interleaved folds.
+    fn cv_score<F>(make_model: F, inputs: &Matrix<f64>, targets: &Vector<f64>) -> f64
+        where F: Fn() -> BoxedModel
+    {
+        let k = 4;
+        let mut total = 0.0;
+        for f in 0..k {
+            let test = (0..inputs.rows()).filter(|i| i % k == f).collect::<Vec<_>>();
+            let train = (0..inputs.rows()).filter(|i| i % k != f).collect::<Vec<_>>();
+            let train_targets = Vector::new(train.iter().map(|&i| targets[i]).collect::<Vec<_>>());
+            let test_targets = Vector::new(test.iter().map(|&i| targets[i]).collect::<Vec<_>>());
+
+            let mut model = make_model();
+            model.train(&inputs.select_rows(&train), &train_targets).unwrap();
+            total += model.score(&inputs.select_rows(&test), &test_targets).unwrap();
+        }
+        total / k as f64
+    }
+
+    #[test]
+    fn test_out_of_fold_predictions() {
+        let (inputs, targets) = curved_data();
+        let mut ensemble = StackingEnsemble::new(stacking_members(),
+                                                 Box::new(LinRegressor::default()))
+            .with_seed(3);
+
+        let meta_features = ensemble.out_of_fold_predictions(&inputs, &targets).unwrap();
+        assert_eq!(meta_features.rows(), 60);
+        assert_eq!(meta_features.cols(), 2);
+
+        // The same seed gives the same folds
+        let again = ensemble.out_of_fold_predictions(&inputs, &targets).unwrap();
+        assert_eq!(meta_features, again);
+
+        // Held-out predictions differ from in-sample predictions
+        let mut knn = KNNRegressor::new(8);
+        knn.train(&inputs, &targets).unwrap();
+        let in_sample = knn.predict(&inputs).unwrap();
+        assert!((0..60).any(|i| (meta_features[[i, 1]] - in_sample[i]).abs() > 1e-8));
+    }
+
+    #[test]
+    fn test_stacking_beats_best_member() {
+        let (inputs, targets) = curved_data();
+
+        let stacked = cv_score(|| {
+                                   Box::new(StackingEnsemble::new(stacking_members(),
+                                                                  Box::new(LinRegressor::default()))
+                                       .with_seed(7))
+                               },
+                               &inputs,
+                               &targets);
+        let linear = cv_score(|| Box::new(LinRegressor::default()), &inputs, &targets);
+        let knn = cv_score(|| Box::new(KNNRegressor::new(8)), &inputs, &targets);
+
+        assert!(stacked >= linear.max(knn));
+    }
+
+    #[test]
+    fn test_stacking_invalid_data() {
+        let mut ensemble = StackingEnsemble::new(stacking_members(),
+                                                 Box::new(LinRegressor::default()));
+        let inputs = Matrix::new(3, 1, vec![1.0, 2.0, 3.0]);
+
+        assert!(ensemble.train(&inputs, &Vector::new(vec![1.0, 2.0])).is_err());
+        assert!(ensemble.train(&inputs, &Vector::new(vec![1.0, 2.0, 3.0])).is_err());
+        assert!(ensemble.predict(&inputs).is_err());
+    }
+}
@@ -30,13 +30,19 @@
 //! // Probabilities that each point comes from each Gaussian.
 //! println!("{:?}", post_probs.data());
 //! ```
+//!
+//! Training usually starts from randomly chosen means. With
+//! `set_warm_start(true)` the EM iterations instead start from the
+//! current means and covariances, which can be seeded with
+//! `set_parameters`.
 use linalg::{Matrix, MatrixSlice, Vector, BaseMatrix, BaseMatrixMut, Axes};
-use rulinalg::utils;
 
 use learning::{LearningResult, UnSupModel};
 use learning::toolkit::rand_utils;
 use learning::error::{Error, ErrorKind};
 
+use std::f64::consts::PI;
+
 /// Covariance options for GMMs.
 ///
 /// - Full : The full covariance structure.
@@ -64,6 +70,12 @@ pub struct GaussianMixtureModel {
     max_iters: usize,
     /// The covariance options for the GMM.
     pub cov_option: CovOption,
+    /// The seed for the initial means.
+    seed: Option<u64>,
+    /// The constant added to the covariance diagonals.
+    reg_covar: f64,
+    /// Whether training starts from the current means and covariances.
+    warm_start: bool,
 }
 
 impl UnSupModel<Matrix<f64>, Matrix<f64>> for GaussianMixtureModel {
@@ -78,14 +90,23 @@ impl UnSupModel<Matrix<f64>, Matrix<f64>> for GaussianMixtureModel {
         // Initialization:
         let k = self.comp_count;
 
-        self.model_covars = {
-            let cov_mat = try!(self.initialize_covariances(inputs, reg_value));
-            Some(vec![cov_mat; k])
+        let warm = match self.model_means {
+            Some(ref means) => self.warm_start && means.cols() == inputs.cols(),
+            None => false,
         };
-
-        let random_rows: Vec<usize> =
-            rand_utils::reservoir_sample(&(0..inputs.rows()).collect::<Vec<usize>>(), k);
-        self.model_means = Some(inputs.select_rows(&random_rows));
+        if !warm {
+            self.model_covars = {
+                let cov_mat = try!(self.initialize_covariances(inputs, reg_value));
+                Some(vec![cov_mat; k])
+            };
+
+            let mut rng = rand_utils::seeded_rng(self.seed);
+            let random_rows: Vec<usize> =
+                rand_utils::reservoir_sample_with_rng(&(0..inputs.rows()).collect::<Vec<usize>>(),
+                                                      k,
+                                                      &mut rng);
+            self.model_means = Some(inputs.select_rows(&random_rows));
+        }
 
         for _ in 0..self.max_iters {
             let log_lik_0 = self.log_lik;
@@ -136,6 +157,9 @@ impl GaussianMixtureModel {
             log_lik: 0f64,
             max_iters: 100,
             cov_option: CovOption::Full,
+            seed: None,
+            reg_covar: 1e-6,
+            warm_start: false,
         }
     }
 
@@ -178,6 +202,9 @@ impl GaussianMixtureModel {
                 log_lik: 0f64,
                 max_iters: 100,
                 cov_option: CovOption::Full,
+                seed: None,
+                reg_covar: 1e-6,
+                warm_start: false,
             })
         }
     }
@@ -209,6 +236,60 @@ impl GaussianMixtureModel {
         &self.mix_weights
     }
 
+    /// Sets the means and covariances of the model.
+    ///
+    /// When warm starting these are the starting point for the
+    /// EM iterations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::gmm::GaussianMixtureModel;
+    /// use rusty_machine::linalg::Matrix;
+    ///
+    /// let mut gmm = GaussianMixtureModel::new(2);
+    /// gmm.set_warm_start(true);
+    ///
+    /// let means = Matrix::new(2, 1, vec![-1.0, 1.0]);
+    /// gmm.set_parameters(means, vec![Matrix::ones(1, 1); 2]).unwrap();
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The means do not have a row for each component.
+    /// - There is not a square covariance matrix matching the means
+    ///   for each component.
+    pub fn set_parameters(&mut self,
+                          means: Matrix<f64>,
+                          covariances: Vec<Matrix<f64>>)
+                          -> LearningResult<()> {
+        if means.rows() != self.comp_count || covariances.len() != self.comp_count {
+            return Err(Error::new(ErrorKind::InvalidParameters,
+                                  "There must be a mean and covariance for each component."));
+        }
+        if covariances.iter().any(|c| c.rows() != means.cols() || c.cols() != means.cols()) {
+            return Err(Error::new(ErrorKind::InvalidParameters,
+                                  "Covariances must be square and match the means."));
+        }
+
+        self.model_means = Some(means);
+        self.model_covars = Some(covariances);
+        Ok(())
+    }
+
+    /// Whether training starts from the current means and covariances.
+    pub fn warm_start(&self) -> bool {
+        self.warm_start
+    }
+
+    /// Set whether training starts from the current means and covariances.
+    ///
+    /// If the model has no means, or they do not match the number of
+    /// input features, the usual random initialization is used.
+    pub fn set_warm_start(&mut self, warm_start: bool) {
+        self.warm_start = warm_start;
+    }
+
     /// Sets the max number of iterations for the EM algorithm.
     ///
     /// # Examples
@@ -223,11 +304,168 @@ impl GaussianMixtureModel {
         self.max_iters = iters;
     }
 
+    /// Sets the seed used to choose the initial means.
+    ///
+    /// Models with the same seed produce the same fit when
+    /// trained on the same data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::gmm::GaussianMixtureModel;
+    ///
+    /// let gmm = GaussianMixtureModel::new(2).with_seed(42);
+    /// ```
+    pub fn with_seed(mut self, seed: u64) -> GaussianMixtureModel {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// The responsibility of each component for each input.
+    ///
+    /// Returns a matrix with a row for each input and a column for each
+    /// component, holding the posterior probability that the input was
+    /// drawn from that component. Each row sums to one. This is the same
+    /// as `predict`, which returns the soft assignments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, BaseMatrix};
+    /// use rusty_machine::learning::gmm::GaussianMixtureModel;
+    /// use rusty_machine::learning::UnSupModel;
+    ///
+    /// let inputs = Matrix::new(6, 1, vec![1.0, 1.2, 0.9, 5.0, 5.1, 4.8]);
+    ///
+    /// let mut model = GaussianMixtureModel::new(2).with_seed(1);
+    /// model.train(&inputs).unwrap();
+    ///
+    /// let probs = model.predict_proba(&Matrix::new(1, 1, vec![1.1])).unwrap();
+    /// assert!((probs.sum() - 1.0).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The model has not been trained.
+    /// - The inputs do not have the dimension of the model.
+    pub fn predict_proba(&self, inputs: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        match self.model_means {
+            Some(ref means) if inputs.cols() != means.cols() => {
+                Err(Error::new(ErrorKind::InvalidData,
+                               "Input columns must match the model dimension."))
+            }
+            _ => self.predict(inputs),
+        }
+    }
+
+    /// The Bayesian information criterion of the model on the inputs.
+    ///
+    /// Computed as `-2 L + p ln(n)` where `L` is the log-likelihood of
+    /// the `n` inputs and `p` is the number of free parameters. Lower
+    /// values indicate a better trade off between fit and complexity,
+    /// so this can be used to choose the number of components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::Matrix;
+    /// use rusty_machine::learning::gmm::GaussianMixtureModel;
+    /// use rusty_machine::learning::UnSupModel;
+    ///
+    /// let inputs = Matrix::new(6, 1, vec![1.0, 1.2, 0.9, 5.0, 5.1, 4.8]);
+    ///
+    /// let mut model = GaussianMixtureModel::new(2).with_seed(1);
+    /// model.train(&inputs).unwrap();
+    ///
+    /// println!("{}", model.bic(&inputs).unwrap());
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The model has not been trained.
+    pub fn bic(&self, inputs: &Matrix<f64>) -> LearningResult<f64> {
+        let log_lik = self.log_likelihood(inputs)?;
+        let p = self.n_parameters(inputs.cols()) as f64;
+        Ok(-2f64 * log_lik + p * (inputs.rows() as f64).ln())
+    }
+
+    /// The Akaike information criterion of the model on the inputs.
+    ///
+    /// Computed as `-2 L + 2 p` where `L` is the log-likelihood of the
+    /// inputs and `p` is the number of free parameters. This penalizes
+    /// complexity less than the BIC.
+    ///
+    /// # Failures
+    ///
+    /// - The model has not been trained.
+    pub fn aic(&self, inputs: &Matrix<f64>) -> LearningResult<f64> {
+        let log_lik = self.log_likelihood(inputs)?;
+        let p = self.n_parameters(inputs.cols()) as f64;
+        Ok(-2f64 * log_lik + 2f64 * p)
+    }
+
+    /// The log-likelihood of the inputs under the trained model.
+    fn log_likelihood(&self, inputs: &Matrix<f64>) -> LearningResult<f64> {
+        match (&self.model_means, &self.model_covars) {
+            (Some(means), Some(_)) => {
+                if inputs.cols() != means.cols() {
+                    return Err(Error::new(ErrorKind::InvalidData,
+                                          "Input columns must match the model dimension."));
+                }
+                Ok(self.membership_weights(inputs)?.1)
+            }
+            _ => Err(Error::new_untrained()),
+        }
+    }
+
+    /// The number of free parameters for `d` dimensional inputs.
+    ///
+    /// These are the mixture weights, which sum to one, the means and
+    /// the entries of the covariances allowed by the covariance option.
+    fn n_parameters(&self, d: usize) -> usize {
+        let k = self.comp_count;
+        let cov_params = match self.cov_option {
+            CovOption::Full | CovOption::Regularized(_) => d * (d + 1) / 2,
+            CovOption::Diagonal => d,
+        };
+        (k - 1) + k * d + k * cov_params
+    }
+
+    /// The constant added to the diagonal of each covariance.
+    pub fn reg_covar(&self) -> f64 {
+        self.reg_covar
+    }
+
+    /// Sets the constant added to the diagonal of each covariance.
+    ///
+    /// This keeps the covariances positive definite when a component
+    /// collapses onto a few points or the data has a constant feature.
+    /// Defaults to `1e-6`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::gmm::GaussianMixtureModel;
+    ///
+    /// let mut gmm = GaussianMixtureModel::new(2);
+    /// gmm.set_reg_covar(1e-4);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The constant is negative or not finite.
+    pub fn set_reg_covar(&mut self, reg_covar: f64) {
+        assert!(reg_covar >= 0f64 && reg_covar.is_finite(),
+                "The covariance regularization must be non-negative and finite.");
+        self.reg_covar = reg_covar;
+    }
+
     fn initialize_covariances(&self, inputs: &Matrix<f64>, reg_value: f64) -> LearningResult<Matrix<f64>> {
         match self.cov_option {
             CovOption::Diagonal => {
                 let variance = try!(inputs.variance(Axes::Row));
-                Ok(Matrix::from_diag(&variance.data()) * reg_value.sqrt())
+                Ok(Matrix::from_diag(&variance.data()) * reg_value.sqrt() +
+                   Matrix::<f64>::identity(inputs.cols()) * self.reg_covar)
             }
 
             CovOption::Full | CovOption::Regularized(_) => {
@@ -241,31 +479,43 @@ impl GaussianMixtureModel {
                     }
                 }
                 cov_mat *= reg_value;
-                if let CovOption::Regularized(eps) = self.cov_option {
-                    cov_mat += Matrix::<f64>::identity(cov_mat.cols()) * eps;
-                }
+                cov_mat += Matrix::<f64>::identity(cov_mat.cols()) * self.diag_offset();
                 Ok(cov_mat)
             }
         }
     }
 
+    /// The total constant added to the covariance diagonals.
+    fn diag_offset(&self) -> f64 {
+        match self.cov_option {
+            CovOption::Regularized(eps) => self.reg_covar + eps,
+            _ => self.reg_covar,
+        }
+    }
+
     fn membership_weights(&self, inputs: &Matrix<f64>) -> LearningResult<(Matrix<f64>, f64)> {
         let n = inputs.rows();
+        let d = inputs.cols();
 
         let mut member_weights_data = Vec::with_capacity(n * self.comp_count);
 
-        // We compute the determinants and inverses now
-        let mut cov_sqrt_dets = Vec::with_capacity(self.comp_count);
-        let mut cov_invs = Vec::with_capacity(self.comp_count);
+        // We factor each covariance once. The densities are computed
+        // in log space from the Cholesky factor, which avoids the
+        // explicit inverse and the underflow of small densities.
+        let mut cov_chols = Vec::with_capacity(self.comp_count);
+        let mut log_norms = Vec::with_capacity(self.comp_count);
 
         if let Some(ref covars) = self.model_covars {
             for cov in covars {
-                // TODO: combine these. We compute det to get the inverse.
-                let covar_det = cov.det();
-                let covar_inv = try!(cov.inverse().map_err(Error::from));
+                let chol = cov.cholesky()?;
+                if (0..d).any(|i| !(chol[[i, i]] > 0f64 && chol[[i, i]].is_finite())) {
+                    return Err(Error::new(ErrorKind::InvalidState,
+                                          "A covariance matrix is not positive definite."));
+                }
 
-                cov_sqrt_dets.push(covar_det.sqrt());
-                cov_invs.push(covar_inv);
+                let log_sqrt_det = (0..d).map(|i| chol[[i, i]].ln()).sum::<f64>();
+                log_norms.push(-0.5 * d as f64 * (2f64 * PI).ln() - log_sqrt_det);
+                cov_chols.push(chol);
             }
         }
 
@@ -273,26 +523,24 @@ impl GaussianMixtureModel {
 
         // Now we compute the membership weights
         if let Some(ref means) = self.model_means {
-            for i in 0..n {
-                let mut pdfs = Vec::with_capacity(self.comp_count);
-                let x_i = MatrixSlice::from_matrix(inputs, [i, 0], 1, inputs.cols());
-
-                for j in 0..self.comp_count {
-                    let mu_j = MatrixSlice::from_matrix(means, [j, 0], 1, means.cols());
-                    let diff = x_i - mu_j;
+            let mut log_pdfs = Vec::with_capacity(self.comp_count);
 
-                    let pdf = (&diff * &cov_invs[j] * diff.transpose() * -0.5).into_vec()[0]
-                        .exp() / cov_sqrt_dets[j];
-                    pdfs.push(pdf);
-                }
+            for x_i in inputs.iter_rows() {
+                log_pdfs.clear();
 
-                let weighted_pdf_sum = utils::dot(&pdfs, self.mix_weights.data());
+                for (j, mu_j) in means.iter_rows().enumerate() {
+                    let diff: Vec<f64> = x_i.iter().zip(mu_j.iter()).map(|(x, mu)| x - mu).collect();
+                    let z = cov_chols[j].solve_l_triangular(Vector::new(diff))?;
 
-                for (idx, pdf) in pdfs.iter().enumerate() {
-                    member_weights_data.push(self.mix_weights[idx] * pdf / (weighted_pdf_sum));
+                    log_pdfs.push(self.mix_weights[j].ln() + log_norms[j] - 0.5 * z.dot(&z));
                 }
 
-                log_lik += weighted_pdf_sum.ln();
+                // Normalize with the log-sum-exp of the weighted densities
+                let max = log_pdfs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                let log_sum = max + log_pdfs.iter().map(|lp| (lp - max).exp()).sum::<f64>().ln();
+
+                member_weights_data.extend(log_pdfs.iter().map(|lp| (lp - log_sum).exp()));
+                log_lik += log_sum;
             }
         }
 
@@ -307,6 +555,9 @@ impl GaussianMixtureModel {
 
         self.mix_weights = &sum_weights / (n as f64);
 
+        // Components without any membership keep finite means
+        let sum_weights = sum_weights + 10f64 * f64::EPSILON;
+
         let mut new_means = membership_weights.transpose() * inputs;
 
         for (mean, w) in new_means.iter_rows_mut().zip(sum_weights.data().iter()) {
@@ -327,12 +578,10 @@ impl GaussianMixtureModel {
                 cov_mat += self.compute_cov(diff, membership_weights[[i, k]]);
             }
 
-            if let CovOption::Regularized(eps) = self.cov_option {
-                cov_mat += Matrix::<f64>::identity(cov_mat.cols()) * eps;
-            }
-
-            new_covs.push(cov_mat / sum_weights[k]);
+            cov_mat /= sum_weights[k];
+            cov_mat += Matrix::<f64>::identity(d) * self.diag_offset();
 
+            new_covs.push(cov_mat);
         }
 
         self.model_means = Some(new_means);
@@ -350,7 +599,8 @@ impl GaussianMixtureModel {
 #[cfg(test)]
 mod tests {
     use super::GaussianMixtureModel;
-    use linalg::Vector;
+    use learning::UnSupModel;
+    use linalg::{Matrix, BaseMatrix, Vector};
 
     #[test]
     fn test_means_none() {
@@ -379,4 +629,204 @@ mod tests {
         let gmm_res = GaussianMixtureModel::with_weights(3, mix_weights);
         assert!(gmm_res.is_err());
     }
+
+    #[test]
+    fn test_seeded_initial_means() {
+        let inputs = Matrix::new(10, 1, (0..10).map(|x| x as f64).collect::<Vec<_>>());
+
+        let means = |seed| {
+            let mut model = GaussianMixtureModel::new(2).with_seed(seed);
+            model.set_max_iters(0);
+            model.train(&inputs).unwrap();
+            model.means().unwrap().clone()
+        };
+
+        assert_eq!(means(3), means(3));
+        assert!(means(3) != means(4));
+    }
+
+    /// Five copies of a point and five points around another.
+    fn collapsing_data() -> Matrix<f64> {
+        Matrix::new(10, 2, vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0,
+                                -4.0, -5.2, -5.5, -4.1, -4.8, -4.6, -5.1, -5.9, -4.4, -5.3])
+    }
+
+    #[test]
+    fn test_reg_covar_prevents_collapse() {
+        for seed in 0..5 {
+            let mut model = GaussianMixtureModel::new(2).with_seed(seed);
+            model.train(&collapsing_data()).unwrap();
+
+            for cov in model.covariances().unwrap() {
+                assert!(cov.data().iter().all(|x| x.is_finite()));
+                assert!(cov.cholesky().is_ok());
+                assert!(cov[[0, 0]] >= model.reg_covar());
+            }
+            assert!(model.means().unwrap().data().iter().all(|x| x.is_finite()));
+
+            let probs = model.predict(&collapsing_data()).unwrap();
+            assert!(probs.data().iter().all(|x| x.is_finite()));
+        }
+    }
+
+    #[test]
+    fn test_unregularized_collapse_fails() {
+        let failures = (0..5)
+            .filter(|&seed| {
+                let mut model = GaussianMixtureModel::new(2).with_seed(seed);
+                model.set_reg_covar(0.0);
+                model.train(&collapsing_data()).is_err()
+            })
+            .count();
+        assert!(failures > 0);
+    }
+
+    #[test]
+    fn test_constant_feature() {
+        let inputs = Matrix::new(6, 2, vec![1.0, 3.0, 2.0, 3.0, 1.5, 3.0,
+                                            8.0, 3.0, 9.0, 3.0, 8.5, 3.0]);
+
+        let mut model = GaussianMixtureModel::new(2).with_seed(1);
+        model.set_reg_covar(0.0);
+        assert!(model.train(&inputs).is_err());
+
+        let mut model = GaussianMixtureModel::new(2).with_seed(1);
+        model.train(&inputs).unwrap();
+        assert!(model.covariances().unwrap().iter().all(|c| c.cholesky().is_ok()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_negative_reg_covar() {
+        let mut model = GaussianMixtureModel::new(2);
+        model.set_reg_covar(-1e-6);
+    }
+
+    /// Three well separated clusters of twenty points each.
+    fn three_clusters() -> Matrix<f64> {
+        let centers = [(0.0, 0.0), (8.0, 1.0), (3.0, 9.0)];
+        let mut data = Vec::with_capacity(120);
+        for &(cx, cy) in &centers {
+            for i in 0..20 {
+                let t = i as f64;
+                data.push(cx + (1.3 * t).sin());
+                data.push(cy + (2.7 * t).cos());
+            }
+        }
+        Matrix::new(60, 2, data)
+    }
+
+    #[test]
+    fn test_bic_selects_components() {
+        use super::CovOption;
+
+        let inputs = three_clusters();
+
+        // Keep the best of a few restarts to avoid poor local optima
+        let scores = (1..6)
+            .map(|k| {
+                (0..4)
+                    .map(|seed| {
+                        let mut model = GaussianMixtureModel::new(k).with_seed(seed);
+                        model.cov_option = CovOption::Diagonal;
+                        model.train(&inputs).unwrap();
+                        (model.bic(&inputs).unwrap(), model.aic(&inputs).unwrap())
+                    })
+                    .fold((f64::INFINITY, f64::INFINITY),
+                          |best, score| if score.0 < best.0 { score } else { best })
+            })
+            .collect::<Vec<_>>();
+
+        let best = (0..5)
+            .min_by(|&i, &j| scores[i].0.partial_cmp(&scores[j].0).unwrap())
+            .unwrap();
+        assert_eq!(best + 1, 3);
+
+        // The AIC penalizes the parameters less
+        for score in &scores {
+            assert!(score.1 < score.0);
+        }
+    }
+
+    #[test]
+    fn test_warm_start_from_parameters() {
+        let inputs = three_clusters();
+        let centers = [(0.0, 0.0), (8.0, 1.0), (3.0, 9.0)];
+
+        let mut model = GaussianMixtureModel::new(3);
+        assert!(!model.warm_start());
+        model.set_warm_start(true);
+        model.set_max_iters(5);
+
+        let means = Matrix::new(3, 2, vec![0.0, 0.0, 8.0, 1.0, 3.0, 9.0]);
+        model.set_parameters(means, vec![Matrix::identity(2); 3]).unwrap();
+        model.train(&inputs).unwrap();
+
+        // Each component stays with the cluster it started at
+        let fitted = model.means().unwrap();
+        for (i, &(cx, cy)) in centers.iter().enumerate() {
+            assert!((fitted[[i, 0]] - cx).abs() < 0.5);
+            assert!((fitted[[i, 1]] - cy).abs() < 0.5);
+        }
+
+        assert!(model.set_parameters(Matrix::zeros(2, 2), vec![Matrix::identity(2); 2]).is_err());
+        assert!(model.set_parameters(Matrix::zeros(3, 2), vec![Matrix::identity(3); 3]).is_err());
+    }
+
+    #[test]
+    fn test_information_criteria_untrained() {
+        let model = GaussianMixtureModel::new(2);
+        assert!(model.bic(&three_clusters()).is_err());
+        assert!(model.aic(&three_clusters()).is_err());
+    }
+
+    #[test]
+    fn test_n_parameters() {
+        use super::CovOption;
+
+        let mut model = GaussianMixtureModel::new(3);
+        // 2 weights, 6 means and 3 * 3 covariance entries
+        assert_eq!(model.n_parameters(2), 17);
+
+        model.cov_option = CovOption::Diagonal;
+        assert_eq!(model.n_parameters(2), 14);
+    }
+
+    #[test]
+    fn test_predict_proba() {
+        let inputs = three_clusters();
+        let mut model = GaussianMixtureModel::new(3).with_seed(2);
+        model.train(&inputs).unwrap();
+
+        let probs = model.predict_proba(&inputs).unwrap();
+        assert_eq!((probs.rows(), probs.cols()), (60, 3));
+
+        for row in probs.iter_rows() {
+            let sum: f64 = row.iter().sum();
+            assert!((sum - 1.0).abs() < 1e-12);
+            assert!(row.iter().all(|&p| (0.0..=1.0).contains(&p)));
+        }
+
+        // Points at the cluster centers belong to the component whose
+        // mean is closest, with near certainty
+        let means = model.means().unwrap().clone();
+        let centers = Matrix::new(3, 2, vec![0.0, 0.0, 8.0, 1.0, 3.0, 9.0]);
+        let probs = model.predict_proba(&centers).unwrap();
+
+        for (i, row) in probs.iter_rows().enumerate() {
+            let closest = (0..3)
+                .min_by(|&a, &b| {
+                    let dist = |j: usize| {
+                        (means[[j, 0]] - centers[[i, 0]]).powi(2) +
+                        (means[[j, 1]] - centers[[i, 1]]).powi(2)
+                    };
+                    dist(a).partial_cmp(&dist(b)).unwrap()
+                })
+                .unwrap();
+            assert!(row[closest] > 0.999);
+        }
+
+        assert!(model.predict_proba(&Matrix::zeros(1, 3)).is_err());
+        assert!(GaussianMixtureModel::new(2).predict_proba(&centers).is_err());
+    }
 }
@@ -41,11 +41,36 @@
 //! ## K-means++ initialization
 //!
 //! The [k-means++](https://en.wikipedia.org/wiki/K-means%2B%2B) scheme.
+//!
+//! # Reproducibility
+//!
+//! The initializations are random. A seed can be given with
+//! `with_seed` so that training is reproducible.
+//!
+//! # Warm Starts
+//!
+//! With `set_warm_start(true)` training starts from the current
+//! centroids, which can be seeded with `set_centroids`, instead of
+//! initializing them.
+//!
+//! # Online Updates
+//!
+//! The centroids can also be learned from a stream of batches with
+//! `partial_fit`, which never needs the full data set in memory.
+//!
+//! # Sample Weights
+//!
+//! Sample weights can be set with `set_weights`. Each centroid is then
+//! the weighted mean of its points, so a point with weight two has the
+//! same influence as two copies of it. The weights only apply to `train`,
+//! and `partial_fit` fails while they are set.
 
 use linalg::{Matrix, MatrixSlice, Axes, Vector, BaseMatrix};
 use learning::{LearningResult, UnSupModel};
 use learning::error::{Error, ErrorKind};
 
+use learning::toolkit::rand_utils;
+
 use rand::{Rng, thread_rng};
 use libnum::abs;
 
@@ -74,6 +99,14 @@ pub struct KMeansClassifier<InitAlg: Initializer> {
     centroids: Option<Matrix<f64>>,
     /// The initial algorithm to use.
     init_algorithm: InitAlg,
+    /// The seed for the initialization.
+    seed: Option<u64>,
+    /// The total weight of the points assigned to each centroid so far.
+    counts: Vec<f64>,
+    /// The sample weights used when training.
+    weights: Option<Vector<f64>>,
+    /// Whether training starts from the current centroids.
+    warm_start: bool,
 }
 
 impl<InitAlg: Initializer> UnSupModel<Matrix<f64>, Vector<usize>> for KMeansClassifier<InitAlg> {
@@ -89,16 +122,40 @@ impl<InitAlg: Initializer> UnSupModel<Matrix<f64>, Vector<usize>> for KMeansClas
     }
 
     /// Train the classifier using input data.
+    ///
+    /// The sample weights are used if set.
     fn train(&mut self, inputs: &Matrix<f64>) -> LearningResult<()> {
-        try!(self.init_centroids(inputs));
+        let weights = match self.weights {
+            Some(ref w) => {
+                if w.size() != inputs.rows() {
+                    return Err(Error::new(ErrorKind::InvalidData,
+                                          "The number of weights must match the number of \
+                                           samples."));
+                }
+                if w.data().iter().any(|&x| x < 0f64 || !x.is_finite()) {
+                    return Err(Error::new(ErrorKind::InvalidParameters,
+                                          "Sample weights must be non-negative and finite."));
+                }
+                w.clone()
+            }
+            None => Vector::ones(inputs.rows()),
+        };
+
+        let warm = match self.centroids {
+            Some(ref centroids) => self.warm_start && centroids.cols() == inputs.cols(),
+            None => false,
+        };
+        if !warm {
+            try!(self.init_centroids(inputs));
+        }
         let mut cost = 0.0;
         let eps = 1e-14;
 
         for _i in 0..self.iters {
             let (idx, distances) = try!(self.get_closest_centroids(inputs));
-            self.update_centroids(inputs, idx);
+            self.update_centroids(inputs, idx, &weights);
 
-            let cost_i = distances.sum();
+            let cost_i = distances.dot(&weights);
             if abs(cost - cost_i) < eps {
                 break;
             }
@@ -129,6 +186,10 @@ impl KMeansClassifier<KPlusPlus> {
             k: k,
             centroids: None,
             init_algorithm: KPlusPlus,
+            seed: None,
+            counts: Vec::new(),
+            weights: None,
+            warm_start: false,
         }
     }
 }
@@ -152,9 +213,30 @@ impl<InitAlg: Initializer> KMeansClassifier<InitAlg> {
             k: k,
             centroids: None,
             init_algorithm: algo,
+            seed: None,
+            counts: Vec::new(),
+            weights: None,
+            warm_start: false,
         }
     }
 
+    /// Sets the seed used to initialize the centroids.
+    ///
+    /// Models with the same seed produce the same centroids
+    /// when trained on the same data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::k_means::KMeansClassifier;
+    ///
+    /// let model = KMeansClassifier::new(5).with_seed(42);
+    /// ```
+    pub fn with_seed(mut self, seed: u64) -> KMeansClassifier<InitAlg> {
+        self.seed = Some(seed);
+        self
+    }
+
     /// Get the number of classes.
     pub fn k(&self) -> usize {
         self.k
@@ -180,6 +262,143 @@ impl<InitAlg: Initializer> KMeansClassifier<InitAlg> {
         self.iters = iters;
     }
 
+    /// Set the centroids of the model.
+    ///
+    /// When warm starting these are the starting point for training.
+    /// A following `partial_fit` starts from them as if no points had
+    /// been assigned yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::k_means::KMeansClassifier;
+    /// use rusty_machine::learning::UnSupModel;
+    /// use rusty_machine::linalg::Matrix;
+    ///
+    /// let inputs = Matrix::new(4, 1, vec![0.0, 1.0, 10.0, 11.0]);
+    ///
+    /// let mut model = KMeansClassifier::new(2);
+    /// model.set_warm_start(true);
+    /// model.set_centroids(Matrix::new(2, 1, vec![0.0, 10.0])).unwrap();
+    /// model.train(&inputs).unwrap();
+    ///
+    /// assert_eq!(model.centroids().as_ref().unwrap().data(), &[0.5, 10.5]);
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The centroids do not have exactly `k` rows.
+    pub fn set_centroids(&mut self, centroids: Matrix<f64>) -> LearningResult<()> {
+        if centroids.rows() != self.k {
+            return Err(Error::new(ErrorKind::InvalidParameters,
+                                  "Centroids must have exactly k rows."));
+        }
+        self.centroids = Some(centroids);
+        self.counts = vec![0f64; self.k];
+        Ok(())
+    }
+
+    /// Whether training starts from the current centroids.
+    pub fn warm_start(&self) -> bool {
+        self.warm_start
+    }
+
+    /// Set whether training starts from the current centroids.
+    ///
+    /// If the model has no centroids, or they do not match the
+    /// number of input features, the centroids are initialized as usual.
+    pub fn set_warm_start(&mut self, warm_start: bool) {
+        self.warm_start = warm_start;
+    }
+
+    /// Set the sample weights used when training.
+    ///
+    /// Each centroid is the weighted mean of the points assigned to it.
+    /// Setting `None` restores uniform weights. `partial_fit` fails while
+    /// weights are set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::k_means::KMeansClassifier;
+    /// use rusty_machine::learning::UnSupModel;
+    /// use rusty_machine::linalg::{Matrix, Vector};
+    ///
+    /// let inputs = Matrix::new(4, 1, vec![0.0, 1.0, 10.0, 11.0]);
+    ///
+    /// let mut model = KMeansClassifier::new(2).with_seed(1);
+    /// model.set_weights(Some(Vector::new(vec![3.0, 1.0, 1.0, 1.0])));
+    /// model.train(&inputs).unwrap();
+    /// ```
+    pub fn set_weights(&mut self, weights: Option<Vector<f64>>) {
+        self.weights = weights;
+    }
+
+    /// Get the sample weights used when training.
+    ///
+    /// Returns `None` if the samples are uniformly weighted.
+    pub fn weights(&self) -> Option<&Vector<f64>> {
+        self.weights.as_ref()
+    }
+
+    /// Update the centroids using a single batch of data.
+    ///
+    /// Each point in the batch is assigned to its closest centroid, which
+    /// then moves towards the point with a step size of one over the number
+    /// of points assigned to that centroid so far. Each centroid is
+    /// therefore the running mean of its points, as in mini-batch k-means.
+    ///
+    /// If the model has no centroids yet they are initialized from the
+    /// first batch, which must contain at least `k` points. Calling this
+    /// after `train` continues from the trained centroids, with each
+    /// training point counted by its sample weight.
+    ///
+    /// Sample weights cannot be given for the batch, so this fails while
+    /// weights are set with `set_weights`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::k_means::KMeansClassifier;
+    /// use rusty_machine::linalg::Matrix;
+    ///
+    /// let mut model = KMeansClassifier::new(2).with_seed(1);
+    ///
+    /// model.partial_fit(&Matrix::new(2, 1, vec![0.0, 10.0])).unwrap();
+    /// model.partial_fit(&Matrix::new(2, 1, vec![1.0, 11.0])).unwrap();
+    /// ```
+    pub fn partial_fit(&mut self, batch: &Matrix<f64>) -> LearningResult<()> {
+        if self.weights.is_some() {
+            return Err(Error::new(ErrorKind::InvalidState,
+                                  "Sample weights cannot be used with partial_fit."));
+        }
+
+        let cols = self.centroids.as_ref().map(|c| c.cols());
+        match cols {
+            None => {
+                self.init_centroids(batch)?;
+                self.counts = vec![0f64; self.k];
+            }
+            Some(cols) if cols != batch.cols() => {
+                return Err(Error::new(ErrorKind::InvalidData,
+                                      "The batch must have the same column count as the centroids."));
+            }
+            Some(_) => {}
+        }
+
+        let (idx, _) = self.get_closest_centroids(batch)?;
+        let centroids = self.centroids.as_mut().unwrap();
+
+        for (i, &c) in idx.iter().enumerate() {
+            self.counts[c] += 1f64;
+            let eta = 1.0 / self.counts[c];
+            for j in 0..batch.cols() {
+                centroids[[c, j]] += eta * (batch[[i, j]] - centroids[[c, j]]);
+            }
+        }
+        Ok(())
+    }
+
     /// Initialize the centroids.
     ///
     /// Used internally within model.
@@ -191,7 +410,13 @@ impl<InitAlg: Initializer> KMeansClassifier<InitAlg> {
                                    self.k,
                                    inputs.rows())))
         } else {
-            let centroids = try!(self.init_algorithm.init_centroids(self.k, inputs));
+            let centroids = match self.seed {
+                Some(seed) => {
+                    let mut rng = rand_utils::seeded_rng(Some(seed));
+                    self.init_algorithm.init_centroids_with_rng(self.k, inputs, &mut rng)?
+                }
+                None => self.init_algorithm.init_centroids(self.k, inputs)?,
+            };
 
             if centroids.rows() != self.k {
                 Err(Error::new(ErrorKind::InvalidState,
@@ -207,23 +432,36 @@ impl<InitAlg: Initializer> KMeansClassifier<InitAlg> {
 
     }
 
-    /// Updated the centroids by computing means of assigned classes.
+    /// Updated the centroids by computing weighted means of assigned classes.
     ///
+    /// Centroids whose points have no total weight are left in place.
     /// Used internally within model.
-    fn update_centroids(&mut self, inputs: &Matrix<f64>, classes: Vector<usize>) {
-        let mut new_centroids = Vec::with_capacity(self.k * inputs.cols());
-
-        let mut row_indexes = vec![Vec::new(); self.k];
-        for (i, c) in classes.into_vec().into_iter().enumerate() {
-            row_indexes.get_mut(c as usize).map(|v| v.push(i));
+    fn update_centroids(&mut self,
+                        inputs: &Matrix<f64>,
+                        classes: Vector<usize>,
+                        weights: &Vector<f64>) {
+        let mut new_centroids = self.centroids.take().unwrap();
+        let mut totals = vec![0f64; self.k];
+        let mut sums = Matrix::<f64>::zeros(self.k, inputs.cols());
+
+        for (i, &c) in classes.iter().enumerate() {
+            let w = weights[i];
+            totals[c] += w;
+            for j in 0..inputs.cols() {
+                sums[[c, j]] += w * inputs[[i, j]];
+            }
         }
 
-        for vec_i in row_indexes {
-            let mat_i = inputs.select_rows(&vec_i);
-            new_centroids.extend(mat_i.mean(Axes::Row).into_vec());
+        for (c, &total) in totals.iter().enumerate() {
+            if total > 0f64 {
+                for j in 0..inputs.cols() {
+                    new_centroids[[c, j]] = sums[[c, j]] / total;
+                }
+            }
         }
 
-        self.centroids = Some(Matrix::new(self.k, inputs.cols(), new_centroids));
+        self.centroids = Some(new_centroids);
+        self.counts = totals;
     }
 
     fn get_closest_centroids(&self,
@@ -268,6 +506,22 @@ pub trait Initializer: Debug {
     ///
     /// The `Matrix` returned must have `k` rows and the same column count as `inputs`.
     fn init_centroids(&self, k: usize, inputs: &Matrix<f64>) -> LearningResult<Matrix<f64>>;
+
+    /// Initialize the centroids using the given random number generator.
+    ///
+    /// This is used when the model is seeded through `with_seed`, so all
+    /// randomness must come from `rng` for training to be reproducible.
+    /// Initializers which are deterministic may ignore the generator,
+    /// and by default this calls `init_centroids`.
+    fn init_centroids_with_rng<R: Rng>(&self,
+                                       k: usize,
+                                       inputs: &Matrix<f64>,
+                                       _rng: &mut R)
+                                       -> LearningResult<Matrix<f64>>
+        where Self: Sized
+    {
+        self.init_centroids(k, inputs)
+    }
 }
 
 /// The Forgy initialization scheme.
@@ -276,8 +530,15 @@ pub struct Forgy;
 
 impl Initializer for Forgy {
     fn init_centroids(&self, k: usize, inputs: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        self.init_centroids_with_rng(k, inputs, &mut thread_rng())
+    }
+
+    fn init_centroids_with_rng<R: Rng>(&self,
+                                       k: usize,
+                                       inputs: &Matrix<f64>,
+                                       rng: &mut R)
+                                       -> LearningResult<Matrix<f64>> {
         let mut random_choices = Vec::with_capacity(k);
-        let mut rng = thread_rng();
         while random_choices.len() < k {
             let r = rng.gen_range(0, inputs.rows());
 
@@ -296,10 +557,17 @@ pub struct RandomPartition;
 
 impl Initializer for RandomPartition {
     fn init_centroids(&self, k: usize, inputs: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        self.init_centroids_with_rng(k, inputs, &mut thread_rng())
+    }
+
+    fn init_centroids_with_rng<R: Rng>(&self,
+                                       k: usize,
+                                       inputs: &Matrix<f64>,
+                                       rng: &mut R)
+                                       -> LearningResult<Matrix<f64>> {
 
         // Populate so we have something in each class.
         let mut random_assignments = (0..k).map(|i| vec![i]).collect::<Vec<Vec<usize>>>();
-        let mut rng = thread_rng();
         for i in k..inputs.rows() {
             let idx = rng.gen_range(0, k);
             unsafe {
@@ -324,8 +592,14 @@ pub struct KPlusPlus;
 
 impl Initializer for KPlusPlus {
     fn init_centroids(&self, k: usize, inputs: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
-        let mut rng = thread_rng();
+        self.init_centroids_with_rng(k, inputs, &mut thread_rng())
+    }
 
+    fn init_centroids_with_rng<R: Rng>(&self,
+                                       k: usize,
+                                       inputs: &Matrix<f64>,
+                                       rng: &mut R)
+                                       -> LearningResult<Matrix<f64>> {
         let mut init_centroids = Vec::with_capacity(k * inputs.cols());
         let first_cen = rng.gen_range(0usize, inputs.rows());
 
@@ -349,7 +623,7 @@ impl Initializer for KPlusPlus {
                                            initialization."));
                 }
 
-                let next_cen = sample_discretely(dist);
+                let next_cen = sample_discretely(dist, rng);
                 init_centroids.extend_from_slice(inputs.get_row_unchecked(next_cen));
             }
         }
@@ -361,12 +635,12 @@ impl Initializer for KPlusPlus {
 /// Sample from an unnormalized distribution.
 ///
 /// The input to this function is assumed to have all positive entries.
-fn sample_discretely(unnorm_dist: Vector<f64>) -> usize {
+fn sample_discretely<R: Rng>(unnorm_dist: Vector<f64>, rng: &mut R) -> usize {
     assert!(unnorm_dist.size() > 0, "No entries in distribution vector.");
 
     let sum = unnorm_dist.sum();
 
-    let rand = thread_rng().gen_range(0.0f64, sum);
+    let rand = rng.gen_range(0.0f64, sum);
 
     let mut tempsum = 0.0;
     for (i, p) in unnorm_dist.data().iter().enumerate() {
@@ -0,0 +1,457 @@
+//! K-Nearest Neighbours Module
+//!
+//! Contains implementations of k-nearest neighbours classification
+//! and regression.
+//!
+//! The models store the training data and predict from the targets
+//! of the `k` nearest training points to each input, using the
+//! Euclidean distance. The classifier predicts the class with the
+//! most votes, where the targets may be any set of `f64` class labels.
+//! The regressor predicts the mean of the neighbouring targets.
+//!
+//! Each neighbour can have equal weight, or a weight of `1 / distance`
+//! so that nearer neighbours count for more.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::knn::{KNNClassifier, Weights};
+//! use rusty_machine::learning::SupModel;
+//! use rusty_machine::linalg::{Matrix, Vector};
+//!
+//! let inputs = Matrix::new(6, 2, vec![0.0, 0.0, 0.5, 0.0, 0.0, 0.5,
+//!                                     5.0, 5.0, 5.5, 5.0, 5.0, 5.5]);
+//! let targets = Vector::new(vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0]);
+//!
+//! let mut model = KNNClassifier::new(3).with_weights(Weights::Distance);
+//! model.train(&inputs, &targets).unwrap();
+//!
+//! let output = model.predict(&Matrix::new(1, 2, vec![4.0, 4.5])).unwrap();
+//! assert_eq!(output[0], 1.0);
+//! ```
+
+use linalg::{Matrix, BaseMatrix};
+use linalg::Vector;
+
+use analysis::score;
+use learning::{LearningResult, SupModel};
+use learning::error::{Error, ErrorKind};
+use neighbours::k_nearest;
+
+/// The weighting of each neighbour in a prediction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Weights {
+    /// Each neighbour has the same weight.
+    Uniform,
+    /// Each neighbour is weighted by the inverse of its distance.
+    ///
+    /// If any neighbours are at distance zero only they are used,
+    /// with equal weight.
+    Distance,
+}
+
+/// K-Nearest Neighbours classifier.
+///
+/// Predicts the class with the largest total weight among the `k`
+/// nearest training points. Ties are broken in favour of the class
+/// of the nearest neighbour among the tied classes. Neighbours at the
+/// same distance are ordered by their position in the training data.
+#[derive(Debug)]
+pub struct KNNClassifier {
+    k: usize,
+    weights: Weights,
+    train_inputs: Option<Matrix<f64>>,
+    train_targets: Option<Vector<f64>>,
+}
+
+/// The default K-Nearest Neighbours classifier.
+///
+/// The defaults are:
+///
+/// - `k` = `5`
+/// - `weights` = `Weights::Uniform`
+impl Default for KNNClassifier {
+    fn default() -> KNNClassifier {
+        KNNClassifier::new(5)
+    }
+}
+
+impl KNNClassifier {
+    /// Constructs an untrained classifier using `k` neighbours
+    /// with uniform weights.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::knn::KNNClassifier;
+    ///
+    /// let model = KNNClassifier::new(3);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `k` is zero.
+    pub fn new(k: usize) -> KNNClassifier {
+        assert!(k > 0, "The number of neighbours must be positive.");
+
+        KNNClassifier {
+            k,
+            weights: Weights::Uniform,
+            train_inputs: None,
+            train_targets: None,
+        }
+    }
+
+    /// Sets the weighting of the neighbours.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::knn::{KNNClassifier, Weights};
+    ///
+    /// let model = KNNClassifier::new(3).with_weights(Weights::Distance);
+    /// ```
+    pub fn with_weights(mut self, weights: Weights) -> KNNClassifier {
+        self.weights = weights;
+        self
+    }
+
+    /// The number of neighbours used for each prediction.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// The weighting of the neighbours.
+    pub fn weights(&self) -> Weights {
+        self.weights
+    }
+}
+
+impl SupModel<Matrix<f64>, Vector<f64>> for KNNClassifier {
+    /// Store the training data.
+    ///
+    /// There must be at least `k` training points.
+    fn train(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
+        check_training_data(inputs, targets, self.k)?;
+        if targets.data().iter().any(|c| c.is_nan()) {
+            return Err(Error::new(ErrorKind::InvalidData, "Class labels cannot be NaN."));
+        }
+
+        self.train_inputs = Some(inputs.clone());
+        self.train_targets = Some(targets.clone());
+        Ok(())
+    }
+
+    /// Predict the class of each input.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        if let (Some(train_inputs), Some(train_targets)) =
+               (self.train_inputs.as_ref(), self.train_targets.as_ref()) {
+            check_inputs(inputs, train_inputs)?;
+
+            let predictions = inputs.iter_rows()
+                .map(|row| {
+                    let neighbours = k_nearest(row, train_inputs, self.k, None);
+                    let weights = neighbour_weights(&neighbours, self.weights);
+
+                    // Accumulate the weight of each class, in the order
+                    // each class is first seen from the nearest neighbour
+                    let mut votes: Vec<(f64, f64)> = Vec::with_capacity(self.k);
+                    for (&(idx, _), &w) in neighbours.iter().zip(weights.iter()) {
+                        let class = train_targets[idx];
+                        match votes.iter_mut().find(|v| v.0 == class) {
+                            Some(v) => v.1 += w,
+                            None => votes.push((class, w)),
+                        }
+                    }
+
+                    let mut best = 0;
+                    for (i, v) in votes.iter().enumerate() {
+                        if v.1 > votes[best].1 {
+                            best = i;
+                        }
+                    }
+                    votes[best].0
+                })
+                .collect::<Vec<_>>();
+
+            Ok(Vector::new(predictions))
+        } else {
+            Err(Error::new_untrained())
+        }
+    }
+
+    /// Score the model by the accuracy of its predictions.
+    fn score(&self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<f64> {
+        Ok(score::label_accuracy(&self.predict(inputs)?, targets))
+    }
+}
+
+/// K-Nearest Neighbours regressor.
+///
+/// Predicts the weighted mean of the targets of the `k` nearest
+/// training points.
+#[derive(Debug)]
+pub struct KNNRegressor {
+    k: usize,
+    weights: Weights,
+    train_inputs: Option<Matrix<f64>>,
+    train_targets: Option<Vector<f64>>,
+}
+
+/// The default K-Nearest Neighbours regressor.
+///
+/// The defaults are:
+///
+/// - `k` = `5`
+/// - `weights` = `Weights::Uniform`
+impl Default for KNNRegressor {
+    fn default() -> KNNRegressor {
+        KNNRegressor::new(5)
+    }
+}
+
+impl KNNRegressor {
+    /// Constructs an untrained regressor using `k` neighbours
+    /// with uniform weights.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::knn::KNNRegressor;
+    /// use rusty_machine::learning::SupModel;
+    /// use rusty_machine::linalg::{Matrix, Vector};
+    ///
+    /// let inputs = Matrix::new(4, 1, vec![0.0, 1.0, 2.0, 3.0]);
+    /// let targets = Vector::new(vec![0.0, 2.0, 4.0, 6.0]);
+    ///
+    /// let mut model = KNNRegressor::new(2);
+    /// model.train(&inputs, &targets).unwrap();
+    ///
+    /// let output = model.predict(&Matrix::new(1, 1, vec![1.4])).unwrap();
+    /// assert_eq!(output[0], 3.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `k` is zero.
+    pub fn new(k: usize) -> KNNRegressor {
+        assert!(k > 0, "The number of neighbours must be positive.");
+
+        KNNRegressor {
+            k,
+            weights: Weights::Uniform,
+            train_inputs: None,
+            train_targets: None,
+        }
+    }
+
+    /// Sets the weighting of the neighbours.
+    pub fn with_weights(mut self, weights: Weights) -> KNNRegressor {
+        self.weights = weights;
+        self
+    }
+
+    /// The number of neighbours used for each prediction.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// The weighting of the neighbours.
+    pub fn weights(&self) -> Weights {
+        self.weights
+    }
+}
+
+impl SupModel<Matrix<f64>, Vector<f64>> for KNNRegressor {
+    /// Store the training data.
+    ///
+    /// There must be at least `k` training points.
+    fn train(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
+        check_training_data(inputs, targets, self.k)?;
+
+        self.train_inputs = Some(inputs.clone());
+        self.train_targets = Some(targets.clone());
+        Ok(())
+    }
+
+    /// Predict the target of each input.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        if let (Some(train_inputs), Some(train_targets)) =
+               (self.train_inputs.as_ref(), self.train_targets.as_ref()) {
+            check_inputs(inputs, train_inputs)?;
+
+            let predictions = inputs.iter_rows()
+                .map(|row| {
+                    let neighbours = k_nearest(row, train_inputs, self.k, None);
+                    let weights = neighbour_weights(&neighbours, self.weights);
+
+                    let total = neighbours.iter()
+                        .zip(weights.iter())
+                        .map(|(&(idx, _), &w)| w * train_targets[idx])
+                        .sum::<f64>();
+                    total / weights.iter().sum::<f64>()
+                })
+                .collect::<Vec<_>>();
+
+            Ok(Vector::new(predictions))
+        } else {
+            Err(Error::new_untrained())
+        }
+    }
+}
+
+/// Checks that the training data can be used with `k` neighbours.
+fn check_training_data(inputs: &Matrix<f64>, targets: &Vector<f64>, k: usize) -> LearningResult<()> {
+    if inputs.rows() != targets.size() {
+        Err(Error::new(ErrorKind::InvalidData,
+                       "The number of inputs must match the number of targets."))
+    } else if inputs.rows() < k {
+        Err(Error::new(ErrorKind::InvalidData,
+                       "There must be at least k training points."))
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks that the inputs have the dimension of the training data.
+fn check_inputs(inputs: &Matrix<f64>, train_inputs: &Matrix<f64>) -> LearningResult<()> {
+    if inputs.cols() != train_inputs.cols() {
+        Err(Error::new(ErrorKind::InvalidData,
+                       "Inputs have different feature count than the training data."))
+    } else {
+        Ok(())
+    }
+}
+
+/// The weight of each neighbour under the given weighting.
+fn neighbour_weights(neighbours: &[(usize, f64)], weights: Weights) -> Vec<f64> {
+    match weights {
+        Weights::Uniform => vec![1f64; neighbours.len()],
+        Weights::Distance => {
+            if neighbours.iter().any(|&(_, d)| d == 0f64) {
+                neighbours.iter().map(|&(_, d)| if d == 0f64 { 1f64 } else { 0f64 }).collect()
+            } else {
+                neighbours.iter().map(|&(_, d)| 1f64 / d).collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KNNClassifier, KNNRegressor, Weights};
+
+    use learning::SupModel;
+    use linalg::{Matrix, Vector};
+
+    #[test]
+    fn test_nearer_minority_flips_distance_vote() {
+        let inputs = Matrix::new(3, 1, vec![0.1, 1.0, 1.1]);
+        let targets = Vector::new(vec![1.0, 0.0, 0.0]);
+        let query = Matrix::new(1, 1, vec![0.0]);
+
+        let mut uniform = KNNClassifier::new(3);
+        uniform.train(&inputs, &targets).unwrap();
+        assert_eq!(uniform.predict(&query).unwrap()[0], 0.0);
+
+        let mut distance = KNNClassifier::new(3).with_weights(Weights::Distance);
+        distance.train(&inputs, &targets).unwrap();
+        assert_eq!(distance.predict(&query).unwrap()[0], 1.0);
+    }
+
+    #[test]
+    fn test_tie_break_prefers_nearest_neighbour() {
+        let inputs = Matrix::new(4, 1, vec![3.0, -1.0, 2.0, -4.0]);
+        let targets = Vector::new(vec![2.0, 1.0, 2.0, 1.0]);
+
+        let mut model = KNNClassifier::new(4);
+        model.train(&inputs, &targets).unwrap();
+
+        // Both classes have two votes, the nearest neighbour is class 1
+        assert_eq!(model.predict(&Matrix::new(1, 1, vec![0.0])).unwrap()[0], 1.0);
+        // Here the nearest neighbour is class 2
+        assert_eq!(model.predict(&Matrix::new(1, 1, vec![1.5])).unwrap()[0], 2.0);
+
+        // Equidistant neighbours are ordered by training index
+        let inputs = Matrix::new(2, 1, vec![1.0, -1.0]);
+        let mut model = KNNClassifier::new(2);
+        model.train(&inputs, &Vector::new(vec![5.0, 3.0])).unwrap();
+        assert_eq!(model.predict(&Matrix::new(1, 1, vec![0.0])).unwrap()[0], 5.0);
+    }
+
+    #[test]
+    fn test_distance_weights_exact_match() {
+        let inputs = Matrix::new(3, 1, vec![0.0, 0.01, 0.02]);
+        let targets = Vector::new(vec![1.0, 0.0, 0.0]);
+
+        let mut model = KNNClassifier::new(3).with_weights(Weights::Distance);
+        model.train(&inputs, &targets).unwrap();
+
+        let output = model.predict(&Matrix::new(1, 1, vec![0.0])).unwrap();
+        assert_eq!(output[0], 1.0);
+    }
+
+    #[test]
+    fn test_knn_classifier_errors() {
+        let inputs = Matrix::new(2, 1, vec![0.0, 1.0]);
+        let targets = Vector::new(vec![0.0, 1.0]);
+
+        let mut model = KNNClassifier::new(3);
+        assert!(model.predict(&inputs).is_err());
+        assert!(model.train(&inputs, &targets).is_err());
+
+        let mut model = KNNClassifier::new(1);
+        assert!(model.train(&inputs, &Vector::new(vec![0.0])).is_err());
+        model.train(&inputs, &targets).unwrap();
+        assert!(model.predict(&Matrix::new(1, 2, vec![0.0, 1.0])).is_err());
+        assert_eq!(model.score(&inputs, &targets).unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_knn_regressor_interpolates_smooth_function() {
+        let n = 50;
+        let xs = (0..n).map(|i| i as f64 * 0.125).collect::<Vec<_>>();
+        let inputs = Matrix::new(n, 1, xs.clone());
+        let targets = Vector::new(xs.iter().map(|x| x.sin()).collect::<Vec<_>>());
+
+        // Query halfway between the training points
+        let queries = xs[..n - 1].iter().map(|x| x + 0.0625).collect::<Vec<_>>();
+        let query_inputs = Matrix::new(n - 1, 1, queries.clone());
+
+        for &weights in &[Weights::Uniform, Weights::Distance] {
+            let mut model = KNNRegressor::new(2).with_weights(weights);
+            model.train(&inputs, &targets).unwrap();
+
+            let outputs = model.predict(&query_inputs).unwrap();
+            for (out, x) in outputs.data().iter().zip(queries.iter()) {
+                assert!((out - x.sin()).abs() < 0.01);
+            }
+        }
+
+        // Distance weighted predictions at the training points use their own targets
+        let mut exact = KNNRegressor::new(3).with_weights(Weights::Distance);
+        exact.train(&inputs, &targets).unwrap();
+        let outputs = exact.predict(&inputs).unwrap();
+        for (out, t) in outputs.data().iter().zip(targets.data().iter()) {
+            assert!((out - t).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_knn_regressor_distance_weighting() {
+        let inputs = Matrix::new(2, 1, vec![0.0, 3.0]);
+        let targets = Vector::new(vec![1.0, 4.0]);
+        let query = Matrix::new(1, 1, vec![1.0]);
+
+        let mut uniform = KNNRegressor::new(2);
+        uniform.train(&inputs, &targets).unwrap();
+        assert_eq!(uniform.predict(&query).unwrap()[0], 2.5);
+
+        // Weights of 1 and 1/2
+        let mut distance = KNNRegressor::new(2).with_weights(Weights::Distance);
+        distance.train(&inputs, &targets).unwrap();
+        assert!((distance.predict(&query).unwrap()[0] - 2.0).abs() < 1e-12);
+
+        assert!(KNNRegressor::new(3).train(&inputs, &targets).is_err());
+    }
+}
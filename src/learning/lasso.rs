@@ -0,0 +1,481 @@
+//! Lasso Regression module
+//!
+//! Contains an implementation of L1 regularized linear regression,
+//! fitted by coordinate descent.
+//!
+//! The model minimizes
+//!
+//! ```text
+//! (1/2n) |y - b_0 - Xb|^2 + alpha |b|_1
+//! ```
+//!
+//! which drives the coefficients of uninformative features to
+//! exactly zero. The intercept `b_0` is not penalized, and is added
+//! automatically so you do not need to format the input matrices yourself.
+//!
+//! The data is centered internally to fit the intercept, which can be
+//! disabled with `set_center(false)` to fit through the origin. With
+//! `set_scale(true)` the features are also standardized before fitting,
+//! so that the penalty treats them equally regardless of their units.
+//! The parameters are always reported on the scale of the original data.
+//!
+//! # Usage
+//!
+//! ```
+//! use rusty_machine::learning::lasso::LassoRegressor;
+//! use rusty_machine::learning::SupModel;
+//! use rusty_machine::linalg::{Matrix, Vector};
+//!
+//! let inputs = Matrix::new(4, 2, vec![1.0, 0.3, 2.0, -0.1, 3.0, 0.2, 4.0, -0.4]);
+//! let targets = Vector::new(vec![3.0, 5.0, 7.0, 9.0]);
+//!
+//! let mut lasso = LassoRegressor::new(0.1);
+//! lasso.train(&inputs, &targets).unwrap();
+//!
+//! // The second feature is uninformative
+//! let params = lasso.parameters().unwrap();
+//! assert_eq!(params[2], 0.0);
+//! ```
+
+use linalg::{Matrix, BaseMatrix};
+use linalg::Vector;
+
+use learning::{FeatureImportances, LearningResult, SupModel};
+use learning::error::{Error, ErrorKind};
+use learning::standardize::{Standardization, check_data};
+use learning::optim::coordinate_descent::{CoordinateDescent, soft_threshold};
+
+/// Lasso Regression Model.
+///
+/// Contains option for optimized parameter.
+#[derive(Clone, Debug)]
+pub struct LassoRegressor {
+    /// The L1 penalty strength.
+    alpha: f64,
+    /// The coordinate descent algorithm used for fitting.
+    alg: CoordinateDescent,
+    /// The parameters for the regression model, intercept first.
+    parameters: Option<Vector<f64>>,
+    /// Whether training starts from the current parameters.
+    warm_start: bool,
+    /// Whether the data is centered, fitting an intercept.
+    center: bool,
+    /// Whether the features are scaled to unit variance.
+    scale: bool,
+}
+
+/// Constructs a lasso model with `alpha = 1` using
+/// the default coordinate descent algorithm.
+impl Default for LassoRegressor {
+    fn default() -> LassoRegressor {
+        LassoRegressor::new(1f64)
+    }
+}
+
+impl LassoRegressor {
+    /// Constructs an untrained lasso model with the given penalty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::lasso::LassoRegressor;
+    ///
+    /// let lasso = LassoRegressor::new(0.5);
+    /// ```
+    pub fn new(alpha: f64) -> LassoRegressor {
+        LassoRegressor::new_specified(alpha, CoordinateDescent::default())
+    }
+
+    /// Constructs an untrained lasso model with the given
+    /// penalty and coordinate descent algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::lasso::LassoRegressor;
+    /// use rusty_machine::learning::optim::coordinate_descent::CoordinateDescent;
+    ///
+    /// let lasso = LassoRegressor::new_specified(0.5, CoordinateDescent::new(100, 1e-6));
+    /// ```
+    pub fn new_specified(alpha: f64, alg: CoordinateDescent) -> LassoRegressor {
+        assert!(alpha >= 0f64, "The penalty (alpha) must be non-negative.");
+
+        LassoRegressor {
+            alpha,
+            alg,
+            parameters: None,
+            warm_start: false,
+            center: true,
+            scale: false,
+        }
+    }
+
+    /// The L1 penalty strength.
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Get the parameters from the model.
+    ///
+    /// The first parameter is the intercept.
+    ///
+    /// Returns an option that is None if the model has not been trained.
+    pub fn parameters(&self) -> Option<&Vector<f64>> {
+        self.parameters.as_ref()
+    }
+
+    /// Set the parameters of the model.
+    ///
+    /// The first parameter is the intercept. When warm starting
+    /// the remaining coefficients are the initial guess for training.
+    pub fn set_parameters(&mut self, params: Vector<f64>) {
+        self.parameters = Some(params);
+    }
+
+    /// Whether training starts from the current parameters.
+    pub fn warm_start(&self) -> bool {
+        self.warm_start
+    }
+
+    /// Set whether training starts from the current parameters.
+    ///
+    /// If the model has no parameters, or they do not match the
+    /// number of input features, training starts from zero.
+    pub fn set_warm_start(&mut self, warm_start: bool) {
+        self.warm_start = warm_start;
+    }
+
+    /// Whether the data is centered internally, fitting an intercept.
+    pub fn center(&self) -> bool {
+        self.center
+    }
+
+    /// Set whether the data is centered internally.
+    ///
+    /// Without centering no intercept is fitted, and the first
+    /// parameter is zero. Defaults to `true`.
+    pub fn set_center(&mut self, center: bool) {
+        self.center = center;
+    }
+
+    /// Whether the features are scaled to unit variance internally.
+    pub fn scale(&self) -> bool {
+        self.scale
+    }
+
+    /// Set whether the features are scaled to unit variance internally.
+    ///
+    /// The penalty then applies to the standardized coefficients. The
+    /// parameters are mapped back to the original scale, so new data is
+    /// passed to `predict` unscaled. Defaults to `false`.
+    pub fn set_scale(&mut self, scale: bool) {
+        self.scale = scale;
+    }
+
+    /// Fit the model along a sequence of penalties.
+    ///
+    /// Each fit is started from the solution of the previous one, which
+    /// is much faster than independent fits when neighbouring penalties
+    /// are close. The penalties are usually given in decreasing order,
+    /// starting from one large enough that all coefficients are zero.
+    ///
+    /// Returns the parameters for each penalty, intercept first. The
+    /// model itself is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::lasso::LassoRegressor;
+    /// use rusty_machine::linalg::{Matrix, Vector};
+    ///
+    /// let inputs = Matrix::new(4, 2, vec![1.0, 0.3, 2.0, -0.1, 3.0, 0.2, 4.0, -0.4]);
+    /// let targets = Vector::new(vec![3.0, 5.0, 7.0, 9.0]);
+    ///
+    /// let lasso = LassoRegressor::default();
+    /// let path = lasso.path(&inputs, &targets, &[10.0, 1.0, 0.1]).unwrap();
+    ///
+    /// assert_eq!(path.len(), 3);
+    /// ```
+    pub fn path(&self,
+                inputs: &Matrix<f64>,
+                targets: &Vector<f64>,
+                alphas: &[f64])
+                -> LearningResult<Vec<Vector<f64>>> {
+        check_data("lasso", inputs, targets)?;
+        if alphas.iter().any(|&a| a < 0f64) {
+            return Err(Error::new(ErrorKind::InvalidParameters,
+                                  "The penalties (alphas) must be non-negative."));
+        }
+
+        let problem = LassoProblem::new(inputs, targets, self.center, self.scale);
+        let mut coefs = vec![0f64; inputs.cols()];
+
+        Ok(alphas.iter()
+            .map(|&alpha| {
+                coefs = problem.fit(&self.alg, alpha, &coefs).0;
+                problem.parameters(&coefs)
+            })
+            .collect())
+    }
+}
+
+impl SupModel<Matrix<f64>, Vector<f64>> for LassoRegressor {
+    /// Train the lasso model by coordinate descent.
+    fn train(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
+        check_data("lasso", inputs, targets)?;
+
+        let start = match self.parameters {
+            Some(ref params) if self.warm_start && params.size() == inputs.cols() + 1 => {
+                params.data()[1..].to_vec()
+            }
+            _ => vec![0f64; inputs.cols()],
+        };
+
+        let problem = LassoProblem::new(inputs, targets, self.center, self.scale);
+        let (coefs, _) = problem.fit(&self.alg, self.alpha, &start);
+        self.parameters = Some(problem.parameters(&coefs));
+        Ok(())
+    }
+
+    /// Predict output value from input data.
+    ///
+    /// Model must be trained before prediction can be made.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        if let Some(ref v) = self.parameters {
+            let ones = Matrix::<f64>::ones(inputs.rows(), 1);
+            let full_inputs = ones.hcat(inputs);
+            Ok(full_inputs * v)
+        } else {
+            Err(Error::new_untrained())
+        }
+    }
+}
+
+impl FeatureImportances for LassoRegressor {
+    fn feature_importances(&self) -> Option<Vector<f64>> {
+        self.parameters.as_ref().map(|p| {
+            Vector::new(p.data()[1..].iter().map(|c| c.abs()).collect::<Vec<_>>())
+        })
+    }
+}
+
+/// The least squares problem with prepared inputs and targets.
+///
+/// Coefficients passed in and out are on the scale of the original inputs.
+struct LassoProblem {
+    inputs: Matrix<f64>,
+    targets: Vector<f64>,
+    standardization: Standardization,
+    target_mean: f64,
+}
+
+impl LassoProblem {
+    fn new(inputs: &Matrix<f64>, targets: &Vector<f64>, center: bool, scale: bool) -> LassoProblem {
+        let standardization = Standardization::new(inputs, center, scale);
+        let target_mean = if center { targets.mean() } else { 0f64 };
+
+        LassoProblem {
+            inputs: standardization.transform(inputs),
+            targets: targets - target_mean,
+            standardization,
+            target_mean,
+        }
+    }
+
+    /// Fit the coefficients from the given start, returning them
+    /// with the number of coordinate descent sweeps.
+    fn fit(&self, alg: &CoordinateDescent, alpha: f64, start: &[f64]) -> (Vec<f64>, usize) {
+        let start = self.standardization.scale_coefs(start);
+        let (coefs, sweeps) = alg.minimize(&self.inputs,
+                                           &self.targets,
+                                           &start,
+                                           |rho, z| soft_threshold(rho, alpha) / z);
+        (self.standardization.unscale_coefs(&coefs), sweeps)
+    }
+
+    /// The full parameters, with the intercept prepended to the coefficients.
+    fn parameters(&self, coefs: &[f64]) -> Vector<f64> {
+        self.standardization.parameters(self.target_mean, coefs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LassoRegressor, LassoProblem};
+
+    use learning::SupModel;
+    use learning::optim::coordinate_descent::CoordinateDescent;
+    use linalg::{Matrix, BaseMatrix, Vector};
+
+    /// Targets y = 1 + 3 x_0 - 2 x_3 + 0.5 x_4 with five features.
+    fn sparse_problem() -> (Matrix<f64>, Vector<f64>) {
+        let n = 60;
+        let mut data = Vec::with_capacity(n * 5);
+        let mut targets = Vec::with_capacity(n);
+
+        for i in 0..n {
+            let t = i as f64;
+            let row = [(0.3 * t).sin(),
+                       (0.7 * t).cos(),
+                       (1.1 * t).sin(),
+                       (1.7 * t).cos() + 0.5,
+                       (2.3 * t).sin()];
+            targets.push(1.0 + 3.0 * row[0] - 2.0 * row[3] + 0.5 * row[4]);
+            data.extend_from_slice(&row);
+        }
+
+        (Matrix::new(n, 5, data), Vector::new(targets))
+    }
+
+    fn nonzero(params: &Vector<f64>) -> usize {
+        params.data()[1..].iter().filter(|&&b| b != 0.0).count()
+    }
+
+    #[test]
+    fn test_small_alpha_matches_least_squares() {
+        let (inputs, targets) = sparse_problem();
+
+        let mut lasso = LassoRegressor::new(0.0);
+        lasso.train(&inputs, &targets).unwrap();
+
+        let expected = [1.0, 3.0, 0.0, 0.0, -2.0, 0.5];
+        for (x, y) in lasso.parameters().unwrap().data().iter().zip(expected.iter()) {
+            assert!((x - y).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_lasso_is_sparse() {
+        let (inputs, targets) = sparse_problem();
+
+        let mut lasso = LassoRegressor::new(0.05);
+        lasso.train(&inputs, &targets).unwrap();
+
+        let params = lasso.parameters().unwrap();
+        assert_eq!(params[2], 0.0);
+        assert_eq!(params[3], 0.0);
+        assert!(params[1] > 2.0);
+        assert!(params[4] < -1.0);
+    }
+
+    #[test]
+    fn test_path_sparser_with_larger_alpha() {
+        let (inputs, targets) = sparse_problem();
+        let alphas = [2.0, 1.0, 0.5, 0.2, 0.1, 0.01];
+
+        let path = LassoRegressor::default().path(&inputs, &targets, &alphas).unwrap();
+        assert_eq!(path.len(), alphas.len());
+
+        // The largest penalty removes every feature
+        assert_eq!(nonzero(&path[0]), 0);
+        for pair in path.windows(2) {
+            assert!(nonzero(&pair[0]) <= nonzero(&pair[1]));
+        }
+        assert!(nonzero(&path[alphas.len() - 1]) >= 3);
+    }
+
+    #[test]
+    fn test_path_matches_independent_fits() {
+        let (inputs, targets) = sparse_problem();
+        let alphas = [1.0, 0.3, 0.1, 0.03];
+
+        let alg = CoordinateDescent::new(10000, 1e-10);
+        let path = LassoRegressor::new_specified(1.0, alg).path(&inputs, &targets, &alphas).unwrap();
+
+        for (&alpha, params) in alphas.iter().zip(path.iter()) {
+            let mut lasso = LassoRegressor::new_specified(alpha, alg);
+            lasso.train(&inputs, &targets).unwrap();
+
+            for (x, y) in params.data().iter().zip(lasso.parameters().unwrap().data().iter()) {
+                assert!((x - y).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_warm_start_fewer_sweeps() {
+        let (inputs, targets) = sparse_problem();
+        let problem = LassoProblem::new(&inputs, &targets, true, false);
+        let alg = CoordinateDescent::default();
+
+        let (previous, _) = problem.fit(&alg, 0.1, &[0.0; 5]);
+        let (cold, cold_sweeps) = problem.fit(&alg, 0.09, &[0.0; 5]);
+        let (warm, warm_sweeps) = problem.fit(&alg, 0.09, &previous);
+
+        assert!(warm_sweeps < cold_sweeps);
+        for (x, y) in cold.iter().zip(warm.iter()) {
+            assert!((x - y).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_warm_start_train() {
+        let (inputs, targets) = sparse_problem();
+
+        let mut lasso = LassoRegressor::new(0.1);
+        lasso.set_warm_start(true);
+        lasso.set_parameters(Vector::new(vec![1.0, 3.0, 0.0, 0.0, -2.0, 0.5]));
+        lasso.train(&inputs, &targets).unwrap();
+
+        let mut cold = LassoRegressor::new(0.1);
+        cold.train(&inputs, &targets).unwrap();
+
+        for (x, y) in lasso.parameters().unwrap().data().iter().zip(cold.parameters().unwrap().data().iter()) {
+            assert!((x - y).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_mismatched_data() {
+        let mut lasso = LassoRegressor::default();
+        assert!(lasso.train(&Matrix::ones(3, 2), &Vector::ones(2)).is_err());
+        assert!(lasso.predict(&Matrix::ones(3, 2)).is_err());
+    }
+
+    #[test]
+    fn test_no_center_fits_through_origin() {
+        let inputs = Matrix::new(4, 1, vec![1.0, 2.0, 3.0, 4.0]);
+        let targets = Vector::new(vec![2.0, 4.0, 6.0, 8.0]);
+
+        let mut lasso = LassoRegressor::new(0.0);
+        lasso.set_center(false);
+        lasso.train(&inputs, &targets).unwrap();
+
+        let params = lasso.parameters().unwrap();
+        assert_eq!(params[0], 0.0);
+        assert!((params[1] - 2.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_scale_is_unit_invariant() {
+        let (inputs, targets) = sparse_problem();
+
+        // Measure the first feature in different units
+        let rescaled = Matrix::new(inputs.rows(), 5, inputs.iter_rows()
+            .flat_map(|row| {
+                let mut row = row.to_vec();
+                row[0] *= 1000.0;
+                row
+            })
+            .collect::<Vec<_>>());
+
+        let mut lasso = LassoRegressor::new(0.05);
+        lasso.set_scale(true);
+        lasso.train(&inputs, &targets).unwrap();
+        let params = lasso.parameters().unwrap().clone();
+
+        lasso.train(&rescaled, &targets).unwrap();
+        let rescaled_params = lasso.parameters().unwrap();
+
+        assert!((params[0] - rescaled_params[0]).abs() < 1e-6);
+        assert!((params[1] - 1000.0 * rescaled_params[1]).abs() < 1e-6);
+        for j in 2..6 {
+            assert!((params[j] - rescaled_params[j]).abs() < 1e-6);
+        }
+
+        // Predictions are made on the original scale
+        let outputs = lasso.predict(&rescaled).unwrap();
+        let residuals = &targets - outputs;
+        assert!(residuals.dot(&residuals) / (targets.size() as f64) < 0.1);
+    }
+}
@@ -6,6 +6,15 @@
 //! The regressor will automatically add the intercept term
 //! so you do not need to format the input matrices yourself.
 //!
+//! Weighted least squares is supported by setting sample weights
+//! with `set_weights`.
+//!
+//! The intercept can be disabled with `set_center(false)` to fit
+//! through the origin. With `set_scale(true)` the features are
+//! standardized before fitting, which improves the conditioning of the
+//! problem and the convergence of gradient descent. The parameters are
+//! always reported on the scale of the original data.
+//!
 //! # Usage
 //!
 //! ```
@@ -30,14 +39,15 @@
 //! assert!(output[0] > 17f64, "Our regressor isn't very good!");
 //! ```
 
-use linalg::{Matrix, BaseMatrix};
+use linalg::{Matrix, BaseMatrix, MatrixExt};
 use linalg::Vector;
-use learning::{LearningResult, SupModel};
+use learning::{FeatureImportances, LearningResult, SupModel};
 use learning::toolkit::cost_fn::CostFunc;
 use learning::toolkit::cost_fn::MeanSqError;
 use learning::optim::grad_desc::GradientDesc;
 use learning::optim::{OptimAlgorithm, Optimizable};
-use learning::error::Error;
+use learning::error::{Error, ErrorKind};
+use learning::standardize::Standardization;
 
 /// Linear Regression Model.
 ///
@@ -46,11 +56,22 @@ use learning::error::Error;
 pub struct LinRegressor {
     /// The parameters for the regression model.
     parameters: Option<Vector<f64>>,
+    /// The sample weights used for weighted least squares.
+    weights: Option<Vector<f64>>,
+    /// Whether an intercept is fitted.
+    center: bool,
+    /// Whether the features are scaled to unit variance.
+    scale: bool,
 }
 
 impl Default for LinRegressor {
     fn default() -> LinRegressor {
-        LinRegressor { parameters: None }
+        LinRegressor {
+            parameters: None,
+            weights: None,
+            center: true,
+            scale: false,
+        }
     }
 }
 
@@ -61,6 +82,87 @@ impl LinRegressor {
     pub fn parameters(&self) -> Option<&Vector<f64>> {
         self.parameters.as_ref()
     }
+
+    /// Set the sample weights used when training.
+    ///
+    /// With weights `W` the model solves the weighted least squares
+    /// problem `(X^T W X) b = X^T W y`. Setting `None` restores uniform
+    /// weights, giving ordinary least squares.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::lin_reg::LinRegressor;
+    /// use rusty_machine::learning::SupModel;
+    /// use rusty_machine::linalg::{Matrix, Vector};
+    ///
+    /// let inputs = Matrix::new(4, 1, vec![1.0, 2.0, 3.0, 4.0]);
+    /// let targets = Vector::new(vec![2.0, 4.0, 6.0, 20.0]);
+    ///
+    /// let mut lin_mod = LinRegressor::default();
+    ///
+    /// // Ignore the final sample
+    /// lin_mod.set_weights(Some(Vector::new(vec![1.0, 1.0, 1.0, 0.0])));
+    /// lin_mod.train(&inputs, &targets).unwrap();
+    ///
+    /// let params = lin_mod.parameters().unwrap();
+    /// assert!((params[1] - 2.0).abs() < 1e-8);
+    /// ```
+    pub fn set_weights(&mut self, weights: Option<Vector<f64>>) {
+        self.weights = weights;
+    }
+
+    /// Get the sample weights used when training.
+    ///
+    /// Returns `None` if the samples are uniformly weighted.
+    pub fn weights(&self) -> Option<&Vector<f64>> {
+        self.weights.as_ref()
+    }
+
+    /// Whether an intercept is fitted.
+    pub fn center(&self) -> bool {
+        self.center
+    }
+
+    /// Set whether an intercept is fitted.
+    ///
+    /// Without an intercept the model is fitted through the origin,
+    /// and the first parameter is zero. Defaults to `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::lin_reg::LinRegressor;
+    /// use rusty_machine::learning::SupModel;
+    /// use rusty_machine::linalg::{Matrix, Vector};
+    ///
+    /// let inputs = Matrix::new(3, 1, vec![1.0, 2.0, 3.0]);
+    /// let targets = Vector::new(vec![3.0, 5.0, 7.0]);
+    ///
+    /// let mut lin_mod = LinRegressor::default();
+    /// lin_mod.set_center(false);
+    /// lin_mod.train(&inputs, &targets).unwrap();
+    ///
+    /// assert_eq!(lin_mod.parameters().unwrap()[0], 0.0);
+    /// ```
+    pub fn set_center(&mut self, center: bool) {
+        self.center = center;
+    }
+
+    /// Whether the features are scaled to unit variance internally.
+    pub fn scale(&self) -> bool {
+        self.scale
+    }
+
+    /// Set whether the features are scaled to unit variance internally.
+    ///
+    /// Least squares is invariant to the scale of the features, so this
+    /// only affects the conditioning of the problem. The parameters are
+    /// mapped back to the original scale, so new data is passed to
+    /// `predict` unscaled. Defaults to `false`.
+    pub fn set_scale(&mut self, scale: bool) {
+        self.scale = scale;
+    }
 }
 
 impl SupModel<Matrix<f64>, Vector<f64>> for LinRegressor {
@@ -83,14 +185,12 @@ impl SupModel<Matrix<f64>, Vector<f64>> for LinRegressor {
     /// lin_mod.train(&inputs, &targets).unwrap();
     /// ```
     fn train(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
-        let ones = Matrix::<f64>::ones(inputs.rows(), 1);
-        let full_inputs = ones.hcat(inputs);
+        let standardization = Standardization::new(inputs, false, self.scale);
+        let (full_inputs, targets) = self.weighted_design(&standardization.transform(inputs),
+                                                          targets)?;
 
-        let xt = full_inputs.transpose();
-
-        self.parameters =
-            Some(((&xt * full_inputs).inverse().expect("Could not compute (X_T X) inverse.") *
-                  &xt) * targets);
+        let params = full_inputs.solve_regularized(&targets, 0f64)?;
+        self.parameters = Some(self.unscale_parameters(&standardization, params.data()));
 
         Ok(())
     }
@@ -109,6 +209,14 @@ impl SupModel<Matrix<f64>, Vector<f64>> for LinRegressor {
     }
 }
 
+impl FeatureImportances for LinRegressor {
+    fn feature_importances(&self) -> Option<Vector<f64>> {
+        self.parameters.as_ref().map(|p| {
+            Vector::new(p.data()[1..].iter().map(|c| c.abs()).collect::<Vec<_>>())
+        })
+    }
+}
+
 impl Optimizable for LinRegressor {
     type Inputs = Matrix<f64>;
     type Targets = Vector<f64>;
@@ -132,6 +240,10 @@ impl Optimizable for LinRegressor {
 impl LinRegressor {
     /// Train the linear regressor using Gradient Descent.
     ///
+    /// Sample weights are used if set. Each sample is scaled by the
+    /// root of its weight before optimizing, so the cost minimized is
+    /// the weighted mean squared error.
+    ///
     /// # Examples
     ///
     /// ```
@@ -152,14 +264,426 @@ impl LinRegressor {
     /// let new_point = Matrix::new(1,1,vec![10.]);
     /// let _ = lin_mod.predict(&new_point).unwrap();
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The number of targets or weights does not match the number of samples.
+    /// - The weights are negative.
     pub fn train_with_optimization(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) {
-        let ones = Matrix::<f64>::ones(inputs.rows(), 1);
-        let full_inputs = ones.hcat(inputs);
+        let standardization = Standardization::new(inputs, false, self.scale);
+        let (full_inputs, targets) =
+            match self.weighted_design(&standardization.transform(inputs), targets) {
+                Ok(design) => design,
+                Err(e) => panic!("{}", e),
+            };
 
         let initial_params = vec![0.; full_inputs.cols()];
 
         let gd = GradientDesc::default();
-        let optimal_w = gd.optimize(self, &initial_params[..], &full_inputs, targets);
-        self.parameters = Some(Vector::new(optimal_w));
+        let optimal_w = gd.optimize(self, &initial_params[..], &full_inputs, &targets);
+        self.parameters = Some(self.unscale_parameters(&standardization, &optimal_w));
+    }
+
+    /// Compute the residuals, the targets minus the predictions.
+    ///
+    /// Model must be trained before the residuals can be computed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::lin_reg::LinRegressor;
+    /// use rusty_machine::learning::SupModel;
+    /// use rusty_machine::linalg::{Matrix, Vector};
+    ///
+    /// let mut lin_mod = LinRegressor::default();
+    /// let inputs = Matrix::new(3, 1, vec![1.0, 2.0, 3.0]);
+    /// let targets = Vector::new(vec![1.0, 4.0, 5.0]);
+    ///
+    /// lin_mod.train(&inputs, &targets).unwrap();
+    /// let residuals = lin_mod.residuals(&inputs, &targets).unwrap();
+    ///
+    /// // The residuals of an OLS fit with intercept sum to zero
+    /// assert!(residuals.sum().abs() < 1e-10);
+    /// ```
+    pub fn residuals(&self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<Vector<f64>> {
+        if inputs.rows() != targets.size() {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "The number of targets must match the number of samples."));
+        }
+
+        Ok(targets - self.predict(inputs)?)
+    }
+
+    /// Compute the residual sum of squares of the model.
+    ///
+    /// Model must be trained before the residual sum of squares can be computed.
+    pub fn residual_sum_of_squares(&self,
+                                   inputs: &Matrix<f64>,
+                                   targets: &Vector<f64>)
+                                   -> LearningResult<f64> {
+        let residuals = self.residuals(inputs, targets)?;
+        Ok(residuals.dot(&residuals))
+    }
+
+    /// Compute the standard errors of the parameters.
+    ///
+    /// The errors are the square roots of the diagonal of
+    /// `s^2 (X^T W X)^-1`, where `s^2 = RSS / (n - p)` is the residual
+    /// variance with `n` samples and `p` parameters (including the
+    /// intercept). Sample weights are used if set. Without an intercept
+    /// (see `set_center`) there is one error per coefficient.
+    ///
+    /// Model must be trained before the errors can be computed, and
+    /// the inputs should be the data the model was trained on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::lin_reg::LinRegressor;
+    /// use rusty_machine::learning::SupModel;
+    /// use rusty_machine::linalg::{Matrix, Vector};
+    ///
+    /// let mut lin_mod = LinRegressor::default();
+    /// let inputs = Matrix::new(4, 1, vec![0.0, 1.0, 2.0, 3.0]);
+    /// let targets = Vector::new(vec![1.1, 2.9, 5.2, 6.8]);
+    ///
+    /// lin_mod.train(&inputs, &targets).unwrap();
+    /// let std_errors = lin_mod.coef_std_errors(&inputs, &targets).unwrap();
+    ///
+    /// assert_eq!(std_errors.size(), 2);
+    /// ```
+    pub fn coef_std_errors(&self,
+                           inputs: &Matrix<f64>,
+                           targets: &Vector<f64>)
+                           -> LearningResult<Vector<f64>> {
+        self.std_errors_with_dof(inputs, targets).map(|(std_errors, _)| std_errors)
+    }
+
+    /// The standard errors together with the residual degrees of freedom `n - p`.
+    fn std_errors_with_dof(&self,
+                           inputs: &Matrix<f64>,
+                           targets: &Vector<f64>)
+                           -> LearningResult<(Vector<f64>, usize)> {
+        let params = self.fitted_parameters()?;
+        let (full_inputs, targets) = self.weighted_design(inputs, targets)?;
+
+        if full_inputs.cols() != params.size() {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "Inputs have different feature count than the model."));
+        }
+
+        let (n, p) = (full_inputs.rows(), full_inputs.cols());
+        if n <= p {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "There must be more samples than parameters."));
+        }
+
+        let residuals = &targets - &full_inputs * &params;
+        let res_var = residuals.dot(&residuals) / (n - p) as f64;
+
+        let gram_inv = full_inputs.gram().inverse().map_err(Error::from)?;
+        let variances = gram_inv.diag().into_vec();
+        let std_errors = variances.into_iter().map(|c| (res_var * c).sqrt()).collect::<Vec<_>>();
+        Ok((Vector::new(std_errors), n - p))
+    }
+
+    /// Compute the t-statistic of each parameter.
+    ///
+    /// This is each parameter divided by its standard error,
+    /// see `coef_std_errors`.
+    pub fn t_statistics(&self,
+                        inputs: &Matrix<f64>,
+                        targets: &Vector<f64>)
+                        -> LearningResult<Vector<f64>> {
+        let std_errors = self.coef_std_errors(inputs, targets)?;
+        Ok(self.fitted_parameters()?.elediv(&std_errors))
+    }
+
+    /// Compute two-sided p-values for each parameter.
+    ///
+    /// The p-values test whether each parameter is zero. Under the usual
+    /// assumption of normal errors each t-statistic follows a Student's
+    /// t distribution with `n - p` degrees of freedom, see `coef_std_errors`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::lin_reg::LinRegressor;
+    /// use rusty_machine::learning::SupModel;
+    /// use rusty_machine::linalg::{Matrix, Vector};
+    ///
+    /// let mut lin_mod = LinRegressor::default();
+    /// let inputs = Matrix::new(4, 1, vec![0.0, 1.0, 2.0, 3.0]);
+    /// let targets = Vector::new(vec![1.1, 2.9, 5.2, 6.8]);
+    ///
+    /// lin_mod.train(&inputs, &targets).unwrap();
+    /// let p_values = lin_mod.p_values(&inputs, &targets).unwrap();
+    ///
+    /// assert!(p_values[1] < 0.01);
+    /// ```
+    pub fn p_values(&self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<Vector<f64>> {
+        let (std_errors, dof) = self.std_errors_with_dof(inputs, targets)?;
+        let t_stats = self.fitted_parameters()?.elediv(&std_errors);
+        Ok(t_stats.apply(&|t| student_t_two_sided_p(t, dof as f64)))
+    }
+
+    /// Compute the leverage of each sample.
+    ///
+    /// The leverages are the diagonal of the hat matrix
+    /// `X (X^T W X)^-1 X^T W`, where `X` includes the intercept column
+    /// if an intercept is fitted,
+    /// and measure how strongly each sample pulls the fit towards itself.
+    /// They lie in `[0, 1]` and sum to the number of parameters. Sample
+    /// weights are used if set.
+    ///
+    /// The hat matrix is not formed. Instead each row is solved against
+    /// the Cholesky factor of `X^T W X`. The model does not need to be
+    /// trained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::lin_reg::LinRegressor;
+    /// use rusty_machine::linalg::Matrix;
+    ///
+    /// let lin_mod = LinRegressor::default();
+    /// let inputs = Matrix::new(4, 1, vec![0.0, 1.0, 2.0, 10.0]);
+    ///
+    /// // The isolated sample has the highest leverage
+    /// let leverage = lin_mod.leverage(&inputs).unwrap();
+    /// assert!(leverage[3] > 0.9);
+    /// ```
+    pub fn leverage(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        let (full_inputs, _) = self.weighted_design(inputs, &Vector::zeros(inputs.rows()))?;
+
+        let chol = full_inputs.gram().cholesky().map_err(Error::from)?;
+        if (0..chol.rows()).any(|i| chol[[i, i]] <= 0f64 || !chol[[i, i]].is_finite()) {
+            return Err(Error::new(ErrorKind::LinearAlgebra,
+                                  "The design matrix is rank deficient."));
+        }
+
+        let mut leverage = Vec::with_capacity(full_inputs.rows());
+        for row in full_inputs.iter_rows() {
+            let z = chol.solve_l_triangular(Vector::new(row.to_vec())).map_err(Error::from)?;
+            leverage.push(z.dot(&z));
+        }
+        Ok(Vector::new(leverage))
     }
+
+    /// Compute Cook's distance of each sample.
+    ///
+    /// Cook's distance measures how much the fitted values would change
+    /// if the sample were removed,
+    ///
+    /// ```text
+    /// D_i = e_i^2 h_i / (p s^2 (1 - h_i)^2)
+    /// ```
+    ///
+    /// where `e_i` is the residual, `h_i` the leverage (see `leverage`),
+    /// `p` the number of parameters and `s^2 = RSS / (n - p)` the residual
+    /// variance. Sample weights are used if set. Samples with distances
+    /// far above the rest are influential, a common rule of thumb flags
+    /// distances above `4 / n`.
+    ///
+    /// Model must be trained before the distances can be computed, and
+    /// the inputs should be the data the model was trained on. Samples
+    /// with a leverage of one have infinite distance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::lin_reg::LinRegressor;
+    /// use rusty_machine::learning::SupModel;
+    /// use rusty_machine::linalg::{Matrix, Vector};
+    ///
+    /// let mut lin_mod = LinRegressor::default();
+    /// let inputs = Matrix::new(5, 1, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+    /// let targets = Vector::new(vec![0.1, 0.9, 2.1, 2.9, 8.0]);
+    ///
+    /// lin_mod.train(&inputs, &targets).unwrap();
+    /// let distances = lin_mod.cooks_distance(&inputs, &targets).unwrap();
+    ///
+    /// assert!(distances.data()[..4].iter().all(|&d| d < distances[4]));
+    /// ```
+    pub fn cooks_distance(&self,
+                          inputs: &Matrix<f64>,
+                          targets: &Vector<f64>)
+                          -> LearningResult<Vector<f64>> {
+        let params = self.fitted_parameters()?;
+        let (full_inputs, weighted_targets) = self.weighted_design(inputs, targets)?;
+
+        if full_inputs.cols() != params.size() {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "Inputs have different feature count than the model."));
+        }
+
+        let (n, p) = (full_inputs.rows(), full_inputs.cols());
+        if n <= p {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "There must be more samples than parameters."));
+        }
+
+        let residuals = &weighted_targets - &full_inputs * &params;
+        let res_var = residuals.dot(&residuals) / (n - p) as f64;
+        let leverage = self.leverage(inputs)?;
+
+        let distances = residuals.data()
+            .iter()
+            .zip(leverage.data().iter())
+            .map(|(&e, &h)| {
+                let rest = 1f64 - h;
+                e * e * h / (p as f64 * res_var * rest * rest)
+            })
+            .collect::<Vec<_>>();
+        Ok(Vector::new(distances))
+    }
+
+    /// The parameters matching the columns of the design matrix,
+    /// without the intercept if it is not fitted.
+    fn fitted_parameters(&self) -> LearningResult<Vector<f64>> {
+        let params = self.parameters.as_ref().ok_or_else(Error::new_untrained)?;
+        if self.center {
+            Ok(params.clone())
+        } else {
+            Ok(Vector::new(params.data()[1..].to_vec()))
+        }
+    }
+
+    /// The full parameters on the original scale from the parameters
+    /// fitted to the design matrix of the standardized inputs.
+    fn unscale_parameters(&self, standardization: &Standardization, fitted: &[f64]) -> Vector<f64> {
+        let (intercept, coefs) = if self.center {
+            (fitted[0], &fitted[1..])
+        } else {
+            (0f64, fitted)
+        };
+        standardization.parameters(intercept, &standardization.unscale_coefs(coefs))
+    }
+
+    /// Build the design matrix with intercept column and the targets,
+    /// scaling each sample by the root of its weight if weights are set.
+    /// The intercept column is left out if no intercept is fitted.
+    ///
+    /// Scaling by the root of the weights gives `X^T W X` and
+    /// `X^T W y` from the usual normal equations.
+    fn weighted_design(&self,
+                       inputs: &Matrix<f64>,
+                       targets: &Vector<f64>)
+                       -> LearningResult<(Matrix<f64>, Vector<f64>)> {
+        if inputs.rows() != targets.size() {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "The number of targets must match the number of samples."));
+        }
+
+        let full_inputs = if self.center {
+            Matrix::<f64>::ones(inputs.rows(), 1).hcat(inputs)
+        } else {
+            inputs.clone()
+        };
+
+        if let Some(ref weights) = self.weights {
+            if weights.size() != full_inputs.rows() {
+                return Err(Error::new(ErrorKind::InvalidData,
+                                      "The number of weights must match the number of samples."));
+            }
+            if weights.data().iter().any(|&w| w < 0f64) {
+                return Err(Error::new(ErrorKind::InvalidParameters,
+                                      "Sample weights must be non-negative."));
+            }
+
+            let root_w = weights.clone().apply(&f64::sqrt);
+            Ok((full_inputs.scale_rows(&root_w), targets.elemul(&root_w)))
+        } else {
+            Ok((full_inputs, targets.clone()))
+        }
+    }
+}
+
+/// Two-sided p-value of `t` under Student's t distribution with `dof` degrees of freedom.
+///
+/// This is the regularized incomplete beta function `I_x(dof / 2, 1 / 2)`
+/// at `x = dof / (dof + t^2)`.
+fn student_t_two_sided_p(t: f64, dof: f64) -> f64 {
+    if t.is_nan() {
+        return t;
+    }
+    incomplete_beta(dof / 2f64, 0.5, dof / (dof + t * t))
+}
+
+/// The regularized incomplete beta function `I_x(a, b)`.
+///
+/// Evaluates the continued fraction of Numerical Recipes (6.4), using the
+/// symmetry `I_x(a, b) = 1 - I_{1-x}(b, a)` where it converges faster.
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0f64 {
+        return 0f64;
+    }
+    if x >= 1f64 {
+        return 1f64;
+    }
+
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (-x).ln_1p();
+    let front = ln_front.exp();
+
+    if x < (a + 1f64) / (a + b + 2f64) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1f64 - front * beta_continued_fraction(b, a, 1f64 - x) / b
+    }
+}
+
+/// The continued fraction for the incomplete beta function, by the modified Lentz method.
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const MAX_ITERS: usize = 300;
+    const EPS: f64 = 1e-15;
+    const TINY: f64 = 1e-300;
+
+    let guard = |v: f64| if v.abs() < TINY { TINY } else { v };
+
+    let mut c = 1f64;
+    let mut d = 1f64 / guard(1f64 - (a + b) * x / (a + 1f64));
+    let mut h = d;
+
+    for m in 1..MAX_ITERS + 1 {
+        let m = m as f64;
+
+        // The even step of the fraction
+        let num = m * (b - m) * x / ((a + 2f64 * m - 1f64) * (a + 2f64 * m));
+        d = 1f64 / guard(1f64 + num * d);
+        c = guard(1f64 + num / c);
+        h *= d * c;
+
+        // The odd step of the fraction
+        let num = -(a + m) * (a + b + m) * x / ((a + 2f64 * m) * (a + 2f64 * m + 1f64));
+        d = 1f64 / guard(1f64 + num * d);
+        c = guard(1f64 + num / c);
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1f64).abs() < EPS {
+            break;
+        }
+    }
+    h
+}
+
+/// The log of the gamma function for positive `x`, by the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFS: [f64; 9] = [0.9999999999998099,
+                             676.5203681218851,
+                             -1259.1392167224028,
+                             771.3234287776531,
+                             -176.6150291621406,
+                             12.507343278686905,
+                             -0.13857109526572012,
+                             9.984369578019572e-6,
+                             1.5056327351493116e-7];
+
+    let x = x - 1f64;
+    let t = x + 7.5;
+    let series = COEFS[1..].iter()
+        .enumerate()
+        .fold(COEFS[0], |acc, (i, &c)| acc + c / (x + (i + 1) as f64));
+
+    0.5 * (2f64 * ::std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
 }
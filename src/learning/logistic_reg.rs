@@ -33,25 +33,33 @@
 //! We could have been more specific about the learning of the model
 //! by using the `new` constructor instead. This allows us to provide
 //! a `GradientDesc` object with custom parameters.
+//!
+//! Training usually starts from a fixed initial guess. With
+//! `set_warm_start(true)` the model instead starts from its current
+//! parameters, which can be seeded with `set_parameters`. This lets
+//! refitting after small changes to the data resume from the previous fit.
 
 use linalg::{Matrix, BaseMatrix};
 use linalg::Vector;
+use analysis::score;
 use learning::{LearningResult, SupModel};
 use learning::toolkit::activ_fn::{ActivationFunc, Sigmoid};
 use learning::toolkit::cost_fn::{CostFunc, CrossEntropyError};
 use learning::optim::grad_desc::GradientDesc;
 use learning::optim::{OptimAlgorithm, Optimizable};
 use learning::error::Error;
+use learning::multiclass::BinaryClassifier;
 
 /// Logistic Regression Model.
 ///
 /// Contains option for optimized parameter.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct LogisticRegressor<A>
     where A: OptimAlgorithm<BaseLogisticRegressor>
 {
     base: BaseLogisticRegressor,
     alg: A,
+    warm_start: bool,
 }
 
 /// Constructs a default Logistic Regression model
@@ -61,6 +69,7 @@ impl Default for LogisticRegressor<GradientDesc> {
         LogisticRegressor {
             base: BaseLogisticRegressor::new(),
             alg: GradientDesc::default(),
+            warm_start: false,
         }
     }
 }
@@ -81,6 +90,7 @@ impl<A: OptimAlgorithm<BaseLogisticRegressor>> LogisticRegressor<A> {
         LogisticRegressor {
             base: BaseLogisticRegressor::new(),
             alg: alg,
+            warm_start: false,
         }
     }
 
@@ -90,6 +100,38 @@ impl<A: OptimAlgorithm<BaseLogisticRegressor>> LogisticRegressor<A> {
     pub fn parameters(&self) -> Option<&Vector<f64>> {
         self.base.parameters()
     }
+
+    /// Set the parameters of the model.
+    ///
+    /// The first parameter is the intercept. When warm starting
+    /// these are used as the initial guess for training.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::logistic_reg::LogisticRegressor;
+    /// use rusty_machine::linalg::Vector;
+    ///
+    /// let mut logistic_mod = LogisticRegressor::default();
+    /// logistic_mod.set_warm_start(true);
+    /// logistic_mod.set_parameters(Vector::new(vec![-4.0, 1.0]));
+    /// ```
+    pub fn set_parameters(&mut self, params: Vector<f64>) {
+        self.base.set_parameters(params);
+    }
+
+    /// Whether training starts from the current parameters.
+    pub fn warm_start(&self) -> bool {
+        self.warm_start
+    }
+
+    /// Set whether training starts from the current parameters.
+    ///
+    /// If the model has no parameters, or they do not match the
+    /// number of input features, the usual initial guess is used.
+    pub fn set_warm_start(&mut self, warm_start: bool) {
+        self.warm_start = warm_start;
+    }
 }
 
 impl<A> SupModel<Matrix<f64>, Vector<f64>> for LogisticRegressor<A>
@@ -117,7 +159,12 @@ impl<A> SupModel<Matrix<f64>, Vector<f64>> for LogisticRegressor<A>
         let ones = Matrix::<f64>::ones(inputs.rows(), 1);
         let full_inputs = ones.hcat(inputs);
 
-        let initial_params = vec![0.5; full_inputs.cols()];
+        let initial_params = match self.base.parameters() {
+            Some(params) if self.warm_start && params.size() == full_inputs.cols() => {
+                params.data().clone()
+            }
+            _ => vec![0.5; full_inputs.cols()],
+        };
 
         let optimal_w = self.alg.optimize(&self.base, &initial_params[..], &full_inputs, targets);
         self.base.set_parameters(Vector::new(optimal_w));
@@ -136,12 +183,34 @@ impl<A> SupModel<Matrix<f64>, Vector<f64>> for LogisticRegressor<A>
             Err(Error::new_untrained())
         }
     }
+
+    /// Score the model by the accuracy of its predictions.
+    ///
+    /// Predicted probabilities of at least `0.5` are assigned the class `1`.
+    fn score(&self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<f64> {
+        let classes = self.predict(inputs)?.apply(&|p| if p >= 0.5 { 1f64 } else { 0f64 });
+        Ok(score::label_accuracy(&classes, targets))
+    }
+}
+
+/// The logistic regressor separates the `0` and `1` classes
+/// by the predicted probability of the `1` class.
+impl<A> BinaryClassifier for LogisticRegressor<A>
+    where A: OptimAlgorithm<BaseLogisticRegressor>
+{
+    fn negative_label(&self) -> f64 {
+        0f64
+    }
+
+    fn decision_function(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        self.predict(inputs)
+    }
 }
 
 /// The Base Logistic Regression model.
 ///
 /// This struct cannot be instantianated and is used internally only.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct BaseLogisticRegressor {
     parameters: Option<Vector<f64>>,
 }
@@ -193,3 +262,97 @@ impl Optimizable for BaseLogisticRegressor {
         (cost, grad.into_vec())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{LogisticRegressor, BaseLogisticRegressor};
+
+    use learning::SupModel;
+    use learning::optim::{Optimizable, OptimAlgorithm};
+    use linalg::{Matrix, Vector, Metric};
+
+    use std::cell::Cell;
+
+    /// Gradient descent which stops once the gradient is small,
+    /// recording the number of iterations it ran.
+    struct CountingGD {
+        iters: Cell<usize>,
+    }
+
+    impl OptimAlgorithm<BaseLogisticRegressor> for CountingGD {
+        fn optimize(&self,
+                    model: &BaseLogisticRegressor,
+                    start: &[f64],
+                    inputs: &Matrix<f64>,
+                    targets: &Vector<f64>)
+                    -> Vec<f64> {
+            let mut params = Vector::new(start.to_vec());
+            let mut iters = 0;
+            while iters < 100000 {
+                let (_, grad) = model.compute_grad(params.data(), inputs, targets);
+                let grad = Vector::new(grad);
+                if grad.norm() < 1e-6 {
+                    break;
+                }
+                params = params - grad * 0.1;
+                iters += 1;
+            }
+            self.iters.set(iters);
+            params.into_vec()
+        }
+    }
+
+    fn overlapping_classes() -> (Matrix<f64>, Vector<f64>) {
+        let inputs = Matrix::new(8, 1, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        let targets = Vector::new(vec![0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0]);
+        (inputs, targets)
+    }
+
+    #[test]
+    fn test_warm_start_default_off() {
+        let model = LogisticRegressor::default();
+        assert!(!model.warm_start());
+    }
+
+    #[test]
+    fn test_warm_start_fewer_iterations() {
+        let (inputs, targets) = overlapping_classes();
+
+        let mut cold = LogisticRegressor::new(CountingGD { iters: Cell::new(0) });
+        cold.train(&inputs, &targets).unwrap();
+        let cold_iters = cold.alg.iters.get();
+        let cold_params = cold.parameters().unwrap().clone();
+
+        // Refit with one target changed, starting from the previous fit
+        let changed = Vector::new(vec![0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0]);
+
+        let mut warm = LogisticRegressor::new(CountingGD { iters: Cell::new(0) });
+        warm.set_warm_start(true);
+        warm.set_parameters(cold_params);
+        warm.train(&inputs, &changed).unwrap();
+        let warm_iters = warm.alg.iters.get();
+
+        let mut fresh = LogisticRegressor::new(CountingGD { iters: Cell::new(0) });
+        fresh.train(&inputs, &changed).unwrap();
+        let fresh_iters = fresh.alg.iters.get();
+
+        assert!(cold_iters > 0);
+        assert!(warm_iters < fresh_iters);
+
+        // Both fits converge to the same solution
+        let diff = warm.parameters().unwrap() - fresh.parameters().unwrap();
+        assert!(diff.norm() < 1e-4);
+    }
+
+    #[test]
+    fn test_warm_start_ignores_mismatched_parameters() {
+        let (inputs, targets) = overlapping_classes();
+
+        let mut model = LogisticRegressor::new(CountingGD { iters: Cell::new(0) });
+        model.set_warm_start(true);
+        model.set_parameters(Vector::new(vec![1.0, 2.0, 3.0]));
+        model.train(&inputs, &targets).unwrap();
+
+        assert_eq!(model.parameters().unwrap().size(), 2);
+    }
+}
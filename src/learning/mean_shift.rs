@@ -0,0 +1,278 @@
+//! Mean Shift Clustering
+//!
+//! Provides an implementation of mean shift clustering with a flat kernel.
+//!
+//! Each point is repeatedly moved to the mean of the training points
+//! within `bandwidth` of it until it converges to a mode of the data
+//! density. Modes closer than `bandwidth` are merged, and each mode
+//! becomes a cluster center. Unlike k-means the number of clusters
+//! does not need to be specified.
+//!
+//! # Usage
+//!
+//! ```
+//! use rusty_machine::linalg::{Matrix, BaseMatrix};
+//! use rusty_machine::learning::mean_shift::MeanShift;
+//! use rusty_machine::learning::UnSupModel;
+//!
+//! let inputs = Matrix::new(6, 2, vec![1.0, 1.0, 1.2, 0.8, 0.9, 1.1,
+//!                                     5.0, 5.0, 5.1, 4.9, 4.8, 5.2]);
+//!
+//! let mut model = MeanShift::new(1.0);
+//! model.train(&inputs).unwrap();
+//!
+//! assert_eq!(model.centers().unwrap().rows(), 2);
+//!
+//! let test_inputs = Matrix::new(2, 2, vec![1.0, 0.9, 5.2, 5.0]);
+//! let classes = model.predict(&test_inputs).unwrap();
+//! assert!(classes[0] != classes[1]);
+//! ```
+
+use linalg::{Matrix, BaseMatrix};
+use linalg::Vector;
+use rulinalg::utils;
+
+use learning::{LearningResult, UnSupModel};
+use learning::error::{Error, ErrorKind};
+use neighbours::distance;
+
+use std::cmp::Reverse;
+
+/// Mean Shift Clustering model.
+#[derive(Debug)]
+pub struct MeanShift {
+    /// The radius of the flat kernel.
+    bandwidth: f64,
+    /// Max iterations for shifting each point.
+    iters: usize,
+    /// The fitted cluster centers.
+    centers: Option<Matrix<f64>>,
+    /// The cluster of each training point.
+    labels: Option<Vector<usize>>,
+}
+
+impl MeanShift {
+    /// Constructs an untrained mean shift model with the given bandwidth.
+    ///
+    /// Defaults to a maximum of 300 iterations per point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::mean_shift::MeanShift;
+    ///
+    /// let model = MeanShift::new(0.5);
+    /// ```
+    pub fn new(bandwidth: f64) -> MeanShift {
+        MeanShift::new_specified(bandwidth, 300)
+    }
+
+    /// Constructs an untrained mean shift model with the given
+    /// bandwidth and maximum number of iterations per point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::mean_shift::MeanShift;
+    ///
+    /// let model = MeanShift::new_specified(0.5, 100);
+    /// ```
+    pub fn new_specified(bandwidth: f64, iters: usize) -> MeanShift {
+        assert!(bandwidth > 0f64, "The bandwidth must be positive.");
+
+        MeanShift {
+            bandwidth,
+            iters,
+            centers: None,
+            labels: None,
+        }
+    }
+
+    /// The bandwidth of the model.
+    pub fn bandwidth(&self) -> f64 {
+        self.bandwidth
+    }
+
+    /// The maximum number of iterations per point.
+    pub fn iters(&self) -> usize {
+        self.iters
+    }
+
+    /// The cluster centers found during training.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn centers(&self) -> Option<&Matrix<f64>> {
+        self.centers.as_ref()
+    }
+
+    /// The cluster of each training point.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn labels(&self) -> Option<&Vector<usize>> {
+        self.labels.as_ref()
+    }
+
+    /// Shift a point to the mode of the data density.
+    ///
+    /// Returns the mode and the number of training points within
+    /// `bandwidth` of it.
+    fn shift_to_mode(&self, start: &[f64], inputs: &Matrix<f64>) -> (Vec<f64>, usize) {
+        let tol = 1e-3 * self.bandwidth;
+        let mut point = start.to_vec();
+        let mut count = 0;
+
+        for _ in 0..self.iters {
+            let mut sum = vec![0f64; inputs.cols()];
+            count = 0;
+
+            for row in inputs.iter_rows() {
+                if distance(row, &point) <= self.bandwidth {
+                    utils::in_place_vec_bin_op(&mut sum, row, |x, &y| *x += y);
+                    count += 1;
+                }
+            }
+
+            // The starting point is always a training point, so count > 0
+            let new_point = sum.iter().map(|x| x / (count as f64)).collect::<Vec<_>>();
+            let shift = distance(&new_point, &point);
+            point = new_point;
+
+            if shift < tol {
+                break;
+            }
+        }
+
+        (point, count)
+    }
+}
+
+impl UnSupModel<Matrix<f64>, Vector<usize>> for MeanShift {
+    /// Find the cluster centers of the input data.
+    fn train(&mut self, inputs: &Matrix<f64>) -> LearningResult<()> {
+        if inputs.rows() == 0 {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "Cannot train mean shift on empty data."));
+        }
+
+        let mut modes = inputs.iter_rows()
+            .map(|row| self.shift_to_mode(row, inputs))
+            .collect::<Vec<_>>();
+
+        // Keep the densest modes, discarding any within bandwidth of a kept mode
+        modes.sort_by_key(|m| Reverse(m.1));
+
+        let mut centers: Vec<Vec<f64>> = Vec::new();
+        for (mode, _) in modes {
+            if centers.iter().all(|c| distance(c, &mode) > self.bandwidth) {
+                centers.push(mode);
+            }
+        }
+
+        let n_centers = centers.len();
+        let data = centers.into_iter().flat_map(|c| c.into_iter()).collect::<Vec<_>>();
+        let centers = Matrix::new(n_centers, inputs.cols(), data);
+
+        self.labels = Some(closest_centers(&centers, inputs));
+        self.centers = Some(centers);
+        Ok(())
+    }
+
+    /// Predict the closest cluster center for each input.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<usize>> {
+        if let Some(ref centers) = self.centers {
+            if inputs.cols() != centers.cols() {
+                return Err(Error::new(ErrorKind::InvalidData,
+                                      "Inputs have different feature count than the centers."));
+            }
+
+            Ok(closest_centers(centers, inputs))
+        } else {
+            Err(Error::new_untrained())
+        }
+    }
+}
+
+/// Find the index of the closest center for each input.
+fn closest_centers(centers: &Matrix<f64>, inputs: &Matrix<f64>) -> Vector<usize> {
+    let idx = inputs.iter_rows()
+        .map(|row| {
+            let distances = centers.iter_rows().map(|c| distance(c, row)).collect::<Vec<_>>();
+            utils::argmin(&distances).0
+        })
+        .collect::<Vec<_>>();
+    Vector::new(idx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MeanShift;
+
+    use learning::UnSupModel;
+    use linalg::{Matrix, BaseMatrix};
+
+    use rand::{SeedableRng, StdRng};
+    use rand::distributions::{IndependentSample, Normal};
+
+    fn two_blobs() -> Matrix<f64> {
+        let seed: &[_] = &[1, 2, 3, 4];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let noise = Normal::new(0.0, 0.5);
+
+        let mut data = Vec::new();
+        for &(x, y) in &[(0.0, 0.0), (6.0, 6.0)] {
+            for _ in 0..40 {
+                data.push(x + noise.ind_sample(&mut rng));
+                data.push(y + noise.ind_sample(&mut rng));
+            }
+        }
+
+        Matrix::new(80, 2, data)
+    }
+
+    #[test]
+    fn test_two_blobs() {
+        let inputs = two_blobs();
+
+        let mut model = MeanShift::new(2.0);
+        model.train(&inputs).unwrap();
+
+        let centers = model.centers().unwrap();
+        assert_eq!(centers.rows(), 2);
+
+        // Each blob is assigned to a single cluster
+        let labels = model.labels().unwrap();
+        for i in 0..40 {
+            assert_eq!(labels[i], labels[0]);
+            assert_eq!(labels[i + 40], labels[40]);
+        }
+        assert!(labels[0] != labels[40]);
+
+        // The centers are close to the blob means
+        for row in centers.iter_rows() {
+            let near_origin = row[0].abs() < 0.5 && row[1].abs() < 0.5;
+            let near_other = (row[0] - 6.0).abs() < 0.5 && (row[1] - 6.0).abs() < 0.5;
+            assert!(near_origin || near_other);
+        }
+    }
+
+    #[test]
+    fn test_predict() {
+        let inputs = two_blobs();
+
+        let mut model = MeanShift::new(2.0);
+        model.train(&inputs).unwrap();
+
+        let test_inputs = Matrix::new(2, 2, vec![0.3, -0.2, 5.5, 6.4]);
+        let classes = model.predict(&test_inputs).unwrap();
+        let labels = model.labels().unwrap();
+
+        assert_eq!(classes[0], labels[0]);
+        assert_eq!(classes[1], labels[40]);
+    }
+
+    #[test]
+    fn test_untrained() {
+        let model = MeanShift::new(1.0);
+        assert!(model.predict(&Matrix::zeros(1, 2)).is_err());
+    }
+}
@@ -0,0 +1,437 @@
+//! Multiclass classification module
+//!
+//! Contains strategies for building multiclass classifiers
+//! from binary classifiers.
+//!
+//! The `OneVsRest` strategy trains one binary model per class,
+//! separating that class from all others. The `OneVsOne` strategy
+//! trains one binary model for each pair of classes and predicts
+//! by majority vote.
+//!
+//! Any model implementing the `BinaryClassifier` trait can be used
+//! as the base model. The targets passed to the multiclass model may
+//! be any set of distinct `f64` class labels.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::multiclass::OneVsRest;
+//! use rusty_machine::learning::logistic_reg::LogisticRegressor;
+//! use rusty_machine::learning::optim::grad_desc::GradientDesc;
+//! use rusty_machine::learning::SupModel;
+//! use rusty_machine::linalg::{Matrix, Vector};
+//!
+//! let inputs = Matrix::new(6, 2, vec![0.0, 0.0, 0.5, 0.0,
+//!                                     4.0, 0.0, 4.5, 0.5,
+//!                                     0.0, 4.0, 0.5, 4.5]);
+//! let targets = Vector::new(vec![0.0, 0.0, 1.0, 1.0, 2.0, 2.0]);
+//!
+//! let base = LogisticRegressor::new(GradientDesc::new(0.5, 1000));
+//! let mut model = OneVsRest::new(base);
+//!
+//! model.train(&inputs, &targets).unwrap();
+//!
+//! let new_point = Matrix::new(1, 2, vec![4.2, 0.2]);
+//! let output = model.predict(&new_point).unwrap();
+//!
+//! assert_eq!(output[0], 1.0);
+//! ```
+
+use linalg::{Matrix, BaseMatrix};
+use linalg::Vector;
+
+use analysis::score;
+use learning::{LearningResult, SupModel};
+use learning::error::{Error, ErrorKind};
+
+/// A binary classifier which can be used to build multiclass models.
+///
+/// The positive class is labelled by `1` and the negative class by
+/// `negative_label`.
+pub trait BinaryClassifier: SupModel<Matrix<f64>, Vector<f64>> {
+    /// The target value used to label the negative class.
+    fn negative_label(&self) -> f64;
+
+    /// The confidence of the model that each input belongs to the positive class.
+    ///
+    /// Larger values indicate greater confidence.
+    fn decision_function(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>>;
+}
+
+/// One-vs-Rest multiclass classifier.
+///
+/// Trains a copy of the base model for each class, predicting
+/// the class whose model is most confident.
+#[derive(Debug)]
+pub struct OneVsRest<M: BinaryClassifier + Clone> {
+    base: M,
+    classes: Option<Vec<f64>>,
+    models: Vec<M>,
+}
+
+impl<M: BinaryClassifier + Clone> OneVsRest<M> {
+    /// Constructs an untrained One-vs-Rest model.
+    ///
+    /// The base model is cloned for each class during training.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::multiclass::OneVsRest;
+    /// use rusty_machine::learning::logistic_reg::LogisticRegressor;
+    ///
+    /// let model = OneVsRest::new(LogisticRegressor::default());
+    /// ```
+    pub fn new(base: M) -> OneVsRest<M> {
+        OneVsRest {
+            base,
+            classes: None,
+            models: Vec::new(),
+        }
+    }
+
+    /// The class labels seen during training, in ascending order.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn classes(&self) -> Option<&[f64]> {
+        self.classes.as_ref().map(|c| &c[..])
+    }
+
+    /// The confidence of each per-class model for the inputs.
+    ///
+    /// Column `j` of the output corresponds to the `j`th class.
+    pub fn decision_function(&self, inputs: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        if self.models.is_empty() {
+            return Err(Error::new_untrained());
+        }
+
+        let mut scores = Vec::with_capacity(self.models.len());
+        for model in &self.models {
+            scores.push(model.decision_function(inputs)?);
+        }
+
+        let mut data = Vec::with_capacity(inputs.rows() * scores.len());
+        for i in 0..inputs.rows() {
+            data.extend(scores.iter().map(|s| s[i]));
+        }
+
+        Ok(Matrix::new(inputs.rows(), scores.len(), data))
+    }
+}
+
+impl<M: BinaryClassifier + Clone> SupModel<Matrix<f64>, Vector<f64>> for OneVsRest<M> {
+    /// Train a binary model for each class.
+    ///
+    /// The targets must contain at least two distinct classes.
+    fn train(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
+        if inputs.rows() != targets.size() {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "Inputs and targets have different sample counts."));
+        }
+
+        let classes = unique_classes(targets)?;
+        let neg = self.base.negative_label();
+
+        let mut models = Vec::with_capacity(classes.len());
+        for &class in &classes {
+            let binary_targets = targets.clone().apply(&|t| if t == class { 1f64 } else { neg });
+
+            let mut model = self.base.clone();
+            model.train(inputs, &binary_targets)?;
+            models.push(model);
+        }
+
+        self.classes = Some(classes);
+        self.models = models;
+        Ok(())
+    }
+
+    /// Predict the class of each input.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        let scores = self.decision_function(inputs)?;
+        let classes = self.classes.as_ref().unwrap();
+
+        let predictions = scores.iter_rows()
+            .map(|row| classes[argmax(row)])
+            .collect::<Vec<_>>();
+        Ok(Vector::new(predictions))
+    }
+
+    /// Score the model by the accuracy of its predictions.
+    fn score(&self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<f64> {
+        Ok(score::label_accuracy(&self.predict(inputs)?, targets))
+    }
+}
+
+/// One-vs-One multiclass classifier.
+///
+/// Trains a copy of the base model for each pair of classes,
+/// using only the training data from those two classes. Each
+/// pairwise model votes for one of its classes and the class
+/// with the most votes is predicted. Ties are broken in favour
+/// of the smaller class label.
+#[derive(Debug)]
+pub struct OneVsOne<M: BinaryClassifier + Clone> {
+    base: M,
+    classes: Option<Vec<f64>>,
+    models: Vec<((usize, usize), M)>,
+}
+
+impl<M: BinaryClassifier + Clone> OneVsOne<M> {
+    /// Constructs an untrained One-vs-One model.
+    ///
+    /// The base model is cloned for each pair of classes during training.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::multiclass::OneVsOne;
+    /// use rusty_machine::learning::svm::SVM;
+    ///
+    /// let model = OneVsOne::new(SVM::default());
+    /// ```
+    pub fn new(base: M) -> OneVsOne<M> {
+        OneVsOne {
+            base,
+            classes: None,
+            models: Vec::new(),
+        }
+    }
+
+    /// The class labels seen during training, in ascending order.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn classes(&self) -> Option<&[f64]> {
+        self.classes.as_ref().map(|c| &c[..])
+    }
+
+    /// The number of votes each class receives for the inputs.
+    ///
+    /// Column `j` of the output corresponds to the `j`th class.
+    pub fn votes(&self, inputs: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        let classes = match self.classes {
+            Some(ref classes) => classes,
+            None => return Err(Error::new_untrained()),
+        };
+
+        let k = classes.len();
+        let midpoint = (1f64 + self.base.negative_label()) / 2f64;
+
+        let mut votes = Matrix::zeros(inputs.rows(), k);
+        for &((i, j), ref model) in &self.models {
+            let outputs = model.predict(inputs)?;
+            for (row, &p) in outputs.data().iter().enumerate() {
+                if p > midpoint {
+                    votes[[row, i]] += 1f64;
+                } else {
+                    votes[[row, j]] += 1f64;
+                }
+            }
+        }
+
+        Ok(votes)
+    }
+}
+
+impl<M: BinaryClassifier + Clone> SupModel<Matrix<f64>, Vector<f64>> for OneVsOne<M> {
+    /// Train a binary model for each pair of classes.
+    ///
+    /// The targets must contain at least two distinct classes.
+    fn train(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
+        if inputs.rows() != targets.size() {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "Inputs and targets have different sample counts."));
+        }
+
+        let classes = unique_classes(targets)?;
+        let neg = self.base.negative_label();
+
+        let mut models = Vec::with_capacity(classes.len() * (classes.len() - 1) / 2);
+        for i in 0..classes.len() {
+            for j in i + 1..classes.len() {
+                let (pos_class, neg_class) = (classes[i], classes[j]);
+
+                let rows = targets.data()
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &t)| t == pos_class || t == neg_class)
+                    .map(|(idx, _)| idx)
+                    .collect::<Vec<_>>();
+
+                let pair_inputs = inputs.select_rows(&rows);
+                let pair_targets = rows.iter()
+                    .map(|&idx| if targets[idx] == pos_class { 1f64 } else { neg })
+                    .collect::<Vec<_>>();
+
+                let mut model = self.base.clone();
+                model.train(&pair_inputs, &Vector::new(pair_targets))?;
+                models.push(((i, j), model));
+            }
+        }
+
+        self.classes = Some(classes);
+        self.models = models;
+        Ok(())
+    }
+
+    /// Predict the class of each input.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        let votes = self.votes(inputs)?;
+        let classes = self.classes.as_ref().unwrap();
+
+        let predictions = votes.iter_rows()
+            .map(|row| classes[argmax(row)])
+            .collect::<Vec<_>>();
+        Ok(Vector::new(predictions))
+    }
+
+    /// Score the model by the accuracy of its predictions.
+    fn score(&self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<f64> {
+        Ok(score::label_accuracy(&self.predict(inputs)?, targets))
+    }
+}
+
+/// Returns the sorted distinct class labels in the targets.
+fn unique_classes(targets: &Vector<f64>) -> LearningResult<Vec<f64>> {
+    let mut classes = targets.data().clone();
+    if classes.iter().any(|c| c.is_nan()) {
+        return Err(Error::new(ErrorKind::InvalidData, "Class labels cannot be NaN."));
+    }
+
+    classes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    classes.dedup();
+
+    if classes.len() < 2 {
+        return Err(Error::new(ErrorKind::InvalidData,
+                              "Targets must contain at least two classes."));
+    }
+
+    Ok(classes)
+}
+
+/// Returns the index of the largest value, preferring the first on ties.
+fn argmax(values: &[f64]) -> usize {
+    let mut best = 0;
+    for (i, &v) in values.iter().enumerate() {
+        if v > values[best] {
+            best = i;
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OneVsRest, OneVsOne};
+
+    use learning::SupModel;
+    use learning::logistic_reg::LogisticRegressor;
+    use learning::optim::grad_desc::GradientDesc;
+    use linalg::{Matrix, BaseMatrix, Vector};
+
+    fn three_class_data() -> (Matrix<f64>, Vector<f64>) {
+        let centers = [(0.0, 0.0), (5.0, 0.0), (0.0, 5.0)];
+        let offsets = [(0.0, 0.0), (0.5, 0.3), (-0.4, 0.6), (0.3, -0.5), (-0.6, -0.2)];
+
+        let mut data = Vec::new();
+        let mut targets = Vec::new();
+        for (c, &(x, y)) in centers.iter().enumerate() {
+            for &(dx, dy) in &offsets {
+                data.push(x + dx);
+                data.push(y + dy);
+                targets.push(c as f64 * 2.0 + 1.0);
+            }
+        }
+
+        (Matrix::new(targets.len(), 2, data), Vector::new(targets))
+    }
+
+    #[test]
+    fn test_one_vs_rest_recovers_labels() {
+        let (inputs, targets) = three_class_data();
+
+        let base = LogisticRegressor::new(GradientDesc::new(0.5, 1000));
+        let mut model = OneVsRest::new(base);
+        model.train(&inputs, &targets).unwrap();
+
+        assert_eq!(model.classes().unwrap(), &[1.0, 3.0, 5.0]);
+
+        let outputs = model.predict(&inputs).unwrap();
+        assert_eq!(outputs, targets);
+        assert_eq!(model.score(&inputs, &targets).unwrap(), 1.0);
+
+        let scores = model.decision_function(&inputs).unwrap();
+        assert_eq!(scores.rows(), 15);
+        assert_eq!(scores.cols(), 3);
+    }
+
+    #[test]
+    fn test_one_vs_rest_single_class() {
+        let inputs = Matrix::new(2, 1, vec![1.0, 2.0]);
+        let targets = Vector::new(vec![1.0, 1.0]);
+
+        let mut model = OneVsRest::new(LogisticRegressor::default());
+        assert!(model.train(&inputs, &targets).is_err());
+    }
+
+    #[test]
+    fn test_one_vs_rest_sample_count_mismatch() {
+        let inputs = Matrix::new(3, 1, vec![1.0, 2.0, 3.0]);
+        let targets = Vector::new(vec![0.0, 1.0]);
+
+        let mut model = OneVsRest::new(LogisticRegressor::default());
+        assert!(model.train(&inputs, &targets).is_err());
+    }
+
+    #[test]
+    fn test_one_vs_rest_untrained() {
+        let model = OneVsRest::new(LogisticRegressor::default());
+        assert!(model.predict(&Matrix::ones(1, 2)).is_err());
+    }
+
+    #[test]
+    fn test_one_vs_one_recovers_labels() {
+        let (inputs, targets) = three_class_data();
+
+        let base = LogisticRegressor::new(GradientDesc::new(0.5, 1000));
+        let mut model = OneVsOne::new(base);
+        model.train(&inputs, &targets).unwrap();
+
+        assert_eq!(model.classes().unwrap(), &[1.0, 3.0, 5.0]);
+
+        let outputs = model.predict(&inputs).unwrap();
+        assert_eq!(outputs, targets);
+
+        // Each input receives one vote from each of the three pairs
+        let votes = model.votes(&inputs).unwrap();
+        for row in votes.iter_rows() {
+            assert_eq!(row.iter().sum::<f64>(), 3.0);
+        }
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        let (inputs, targets) = three_class_data();
+        let test_inputs = Matrix::new(4, 2, vec![0.2, -0.1, 5.5, 0.4, -0.3, 5.2, 4.0, 1.0]);
+
+        let base = LogisticRegressor::new(GradientDesc::new(0.5, 1000));
+
+        let mut ovr = OneVsRest::new(base.clone());
+        ovr.train(&inputs, &targets).unwrap();
+
+        let mut ovo = OneVsOne::new(base);
+        ovo.train(&inputs, &targets).unwrap();
+
+        let ovr_outputs = ovr.predict(&test_inputs).unwrap();
+        let ovo_outputs = ovo.predict(&test_inputs).unwrap();
+
+        assert_eq!(ovr_outputs, Vector::new(vec![1.0, 3.0, 5.0, 3.0]));
+        assert_eq!(ovr_outputs, ovo_outputs);
+    }
+
+    #[test]
+    fn test_one_vs_one_untrained() {
+        let model = OneVsOne::new(LogisticRegressor::default());
+        assert!(model.predict(&Matrix::ones(1, 2)).is_err());
+    }
+}
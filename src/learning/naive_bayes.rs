@@ -41,6 +41,8 @@
 //! ```
 
 use linalg::{Matrix, Axes, BaseMatrix, BaseMatrixMut};
+use analysis::score;
+use data::stats;
 use learning::{LearningResult, SupModel};
 use learning::error::{Error, ErrorKind};
 use rulinalg::utils;
@@ -131,6 +133,12 @@ impl<T: Distribution> SupModel<Matrix<f64>, Matrix<f64>> for NaiveBayes<T> {
             Err(Error::new(ErrorKind::UntrainedModel, "The model has not been trained."))
         }
     }
+
+    /// Score the model by the fraction of correctly classified inputs.
+    fn score(&self, inputs: &Matrix<f64>, targets: &Matrix<f64>) -> LearningResult<f64> {
+        let outputs = self.predict(inputs)?;
+        Ok(score::row_accuracy(&outputs, targets))
+    }
 }
 
 impl<T: Distribution> NaiveBayes<T> {
@@ -147,10 +155,13 @@ impl<T: Distribution> NaiveBayes<T> {
 
     fn update_params(&mut self, inputs: &Matrix<f64>, targets: &Matrix<f64>) -> LearningResult<()> {
         let class_count = targets.cols();
-        let total_data = inputs.rows();
+        if targets.rows() == 0 {
+            return Err(Error::new(ErrorKind::InvalidData, "Cannot train on empty data."));
+        }
 
         self.class_counts = vec![0; class_count];
         let mut class_data = vec![Vec::new(); class_count];
+        let mut labels = Vec::with_capacity(targets.rows());
 
         for (idx, row) in targets.iter_rows().enumerate() {
             // Find the class of this input
@@ -159,6 +170,7 @@ impl<T: Distribution> NaiveBayes<T> {
             // Note the class of the input
             class_data[class].push(idx);
             self.class_counts[class] += 1;
+            labels.push(class);
         }
 
         if let Some(ref mut distr) = self.distr {
@@ -174,12 +186,8 @@ impl<T: Distribution> NaiveBayes<T> {
             }
         }
 
-        let mut class_prior = Vec::with_capacity(class_count);
-
         // Compute the prior as the proportion in each class
-        class_prior.extend(self.class_counts.iter().map(|c| *c as f64 / total_data as f64));
-
-        self.class_prior = Some(class_prior);
+        self.class_prior = Some(stats::class_priors::<f64>(&labels, class_count).into_vec());
         self.cluster_count = Some(class_count);
         Ok(())
     }
@@ -502,4 +510,18 @@ mod tests {
         let outputs = model.predict(&inputs).unwrap();
         assert_eq!(outputs.into_vec(), targets.into_vec());
     }
+
+    #[test]
+    fn test_score_is_accuracy() {
+        let inputs = Matrix::new(4,
+                                 3,
+                                 vec![1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0]);
+
+        let targets = Matrix::new(4, 2, vec![1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 1.0]);
+
+        let mut model = NaiveBayes::<Bernoulli>::new();
+        model.train(&inputs, &targets).unwrap();
+
+        assert_eq!(model.score(&inputs, &targets).unwrap(), 1.0);
+    }
 }
@@ -0,0 +1,947 @@
+//! Neural Network Layers
+//!
+//! Contains layers which can be used to build networks beyond the
+//! dense feed forward `NeuralNet`. Each layer computes a forward pass
+//! and a backward pass returning the gradients with respect to its
+//! input and its parameters, so that layers can be chained and trained
+//! with any gradient based algorithm.
+//!
+//! Layers which act on sequences take a matrix with a row for each
+//! step of the sequence and a column for each channel.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::nnet::layers::{Layer, Conv1d};
+//! use rusty_machine::linalg::{Matrix, BaseMatrix};
+//!
+//! // A convolution with 2 input channels, 3 output channels and a kernel of size 4
+//! let conv = Conv1d::new(2, 3, 4, 1);
+//!
+//! // A sequence of length 10 with 2 channels
+//! let input = Matrix::zeros(10, 2);
+//! let output = conv.forward(&input).unwrap();
+//!
+//! assert_eq!((output.rows(), output.cols()), (7, 3));
+//! ```
+
+use linalg::{Matrix, BaseMatrix};
+
+use learning::LearningResult;
+use learning::error::{Error, ErrorKind};
+use learning::toolkit::rand_utils;
+
+use rand::{Rng, thread_rng};
+use rand::distributions::{Sample, range};
+
+/// Trait for neural network layers.
+///
+/// The parameters of a layer are stored as a flat vector. The backward
+/// pass takes the input given to the forward pass and the gradient of
+/// the loss with respect to the output, and returns the gradients of
+/// the loss with respect to the input and the parameters.
+pub trait Layer {
+    /// The parameters of the layer.
+    fn params(&self) -> &[f64];
+
+    /// The mutable parameters of the layer, used to update them.
+    fn params_mut(&mut self) -> &mut [f64];
+
+    /// Computes the output of the layer.
+    fn forward(&self, input: &Matrix<f64>) -> LearningResult<Matrix<f64>>;
+
+    /// Computes the input and parameter gradients of the layer.
+    ///
+    /// Returns the gradient with respect to the input, which has the
+    /// same shape as the input, and the gradient with respect to the
+    /// parameters, which has the same length as `params`.
+    fn backward(&self,
+                input: &Matrix<f64>,
+                output_grad: &Matrix<f64>)
+                -> LearningResult<(Matrix<f64>, Vec<f64>)>;
+}
+
+/// Draws `n` weights uniformly from `[-eps, eps]` with
+/// `eps = sqrt(6 / (fan_in + fan_out))`.
+fn init_weights<R: Rng>(n: usize, fan_in: usize, fan_out: usize, rng: &mut R) -> Vec<f64> {
+    let mut between = range::Range::new(0f64, 1f64);
+    let eps_init = (6f64 / (fan_in + fan_out) as f64).sqrt();
+    (0..n).map(|_| (between.sample(rng) * 2f64 * eps_init) - eps_init).collect()
+}
+
+/// The number of windows of `size` at multiples of `stride` which fit in `len`.
+fn window_count(len: usize, size: usize, stride: usize) -> Option<usize> {
+    if len < size {
+        None
+    } else {
+        Some((len - size) / stride + 1)
+    }
+}
+
+/// Checks that the output gradient has the shape of the output.
+fn check_output_grad(output_grad: &Matrix<f64>, rows: usize, cols: usize) -> LearningResult<()> {
+    if output_grad.rows() != rows || output_grad.cols() != cols {
+        Err(Error::new(ErrorKind::InvalidData,
+                       "The output gradient must have the same shape as the output."))
+    } else {
+        Ok(())
+    }
+}
+
+/// One dimensional convolution layer.
+///
+/// Slides `out_channels` kernels of size `kernel_size` over the sequence,
+/// moving `stride` steps at a time. Each kernel spans all of the input
+/// channels and has a bias. For an input of length `n` the output has
+/// length `(n - kernel_size) / stride + 1`.
+///
+/// The parameters are the kernel weights followed by the biases. The
+/// weight for output channel `o`, kernel offset `k` and input channel
+/// `c` is at index `(o * kernel_size + k) * in_channels + c`.
+#[derive(Debug, Clone)]
+pub struct Conv1d {
+    in_channels: usize,
+    out_channels: usize,
+    kernel_size: usize,
+    stride: usize,
+    params: Vec<f64>,
+}
+
+impl Conv1d {
+    /// Constructs a convolution layer with randomly initialized weights
+    /// and zero biases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::layers::Conv1d;
+    ///
+    /// // One input channel, four output channels, kernel size 3 and stride 2
+    /// let conv = Conv1d::new(1, 4, 3, 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - Any of the sizes or the stride is zero.
+    pub fn new(in_channels: usize,
+               out_channels: usize,
+               kernel_size: usize,
+               stride: usize)
+               -> Conv1d {
+        assert!(in_channels > 0 && out_channels > 0,
+                "The number of channels must be positive.");
+        assert!(kernel_size > 0, "The kernel size must be positive.");
+        assert!(stride > 0, "The stride must be positive.");
+
+        let mut conv = Conv1d {
+            in_channels,
+            out_channels,
+            kernel_size,
+            stride,
+            params: Vec::new(),
+        };
+        conv.init_params(&mut thread_rng());
+        conv
+    }
+
+    /// Reinitializes the weights using the given seed.
+    pub fn with_seed(mut self, seed: u64) -> Conv1d {
+        self.init_params(&mut rand_utils::seeded_rng(Some(seed)));
+        self
+    }
+
+    fn init_params<R: Rng>(&mut self, rng: &mut R) {
+        let fan_in = self.kernel_size * self.in_channels;
+        let fan_out = self.kernel_size * self.out_channels;
+        self.params = init_weights(self.out_channels * fan_in, fan_in, fan_out, rng);
+        self.params.extend(vec![0f64; self.out_channels]);
+    }
+
+    /// The number of input channels.
+    pub fn in_channels(&self) -> usize {
+        self.in_channels
+    }
+
+    /// The number of output channels.
+    pub fn out_channels(&self) -> usize {
+        self.out_channels
+    }
+
+    /// The size of the kernels.
+    pub fn kernel_size(&self) -> usize {
+        self.kernel_size
+    }
+
+    /// The stride between kernel positions.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// The length of the output for an input of the given length.
+    ///
+    /// Returns `None` if the input is shorter than the kernel.
+    pub fn output_len(&self, input_len: usize) -> Option<usize> {
+        window_count(input_len, self.kernel_size, self.stride)
+    }
+
+    fn weight_index(&self, o: usize, k: usize, c: usize) -> usize {
+        (o * self.kernel_size + k) * self.in_channels + c
+    }
+
+    fn check_input(&self, input: &Matrix<f64>) -> LearningResult<usize> {
+        if input.cols() != self.in_channels {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "The input columns must match the input channels."));
+        }
+        self.output_len(input.rows()).ok_or_else(|| {
+            Error::new(ErrorKind::InvalidData,
+                       "The input must be at least as long as the kernel.")
+        })
+    }
+}
+
+impl Layer for Conv1d {
+    fn params(&self) -> &[f64] {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut [f64] {
+        &mut self.params
+    }
+
+    fn forward(&self, input: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        let out_len = self.check_input(input)?;
+        let bias_start = self.out_channels * self.kernel_size * self.in_channels;
+
+        let mut output = Vec::with_capacity(out_len * self.out_channels);
+        for t in 0..out_len {
+            for o in 0..self.out_channels {
+                let mut z = self.params[bias_start + o];
+                for k in 0..self.kernel_size {
+                    for c in 0..self.in_channels {
+                        z += self.params[self.weight_index(o, k, c)] *
+                             input[[t * self.stride + k, c]];
+                    }
+                }
+                output.push(z);
+            }
+        }
+
+        Ok(Matrix::new(out_len, self.out_channels, output))
+    }
+
+    fn backward(&self,
+                input: &Matrix<f64>,
+                output_grad: &Matrix<f64>)
+                -> LearningResult<(Matrix<f64>, Vec<f64>)> {
+        let out_len = self.check_input(input)?;
+        check_output_grad(output_grad, out_len, self.out_channels)?;
+        let bias_start = self.out_channels * self.kernel_size * self.in_channels;
+
+        let mut input_grad = Matrix::zeros(input.rows(), input.cols());
+        let mut param_grad = vec![0f64; self.params.len()];
+
+        // Each kernel is applied at every position, so its
+        // gradient accumulates over all of them
+        for t in 0..out_len {
+            for o in 0..self.out_channels {
+                let g = output_grad[[t, o]];
+                param_grad[bias_start + o] += g;
+                for k in 0..self.kernel_size {
+                    for c in 0..self.in_channels {
+                        let w = self.weight_index(o, k, c);
+                        param_grad[w] += g * input[[t * self.stride + k, c]];
+                        input_grad[[t * self.stride + k, c]] += g * self.params[w];
+                    }
+                }
+            }
+        }
+
+        Ok((input_grad, param_grad))
+    }
+}
+
+/// Checks the window of a pooling layer and returns the output length.
+fn check_pool_input(input: &Matrix<f64>, window: usize, stride: usize) -> LearningResult<usize> {
+    window_count(input.rows(), window, stride).ok_or_else(|| {
+        Error::new(ErrorKind::InvalidData,
+                   "The input must be at least as long as the window.")
+    })
+}
+
+/// One dimensional max-pooling layer.
+///
+/// Takes the maximum of each channel over windows of `window` steps,
+/// moving `stride` steps at a time. The gradient of each output is
+/// passed only to the position which attained the maximum, taking the
+/// first such position when there are ties.
+///
+/// The layer has no parameters.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxPool1d {
+    window: usize,
+    stride: usize,
+}
+
+impl MaxPool1d {
+    /// Constructs a max-pooling layer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::layers::{Layer, MaxPool1d};
+    /// use rusty_machine::linalg::{Matrix, BaseMatrix};
+    ///
+    /// let pool = MaxPool1d::new(2, 2);
+    /// let input = Matrix::new(4, 1, vec![1.0, 3.0, 2.0, 0.0]);
+    ///
+    /// assert_eq!(*pool.forward(&input).unwrap().data(), vec![3.0, 2.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The window or stride is zero.
+    pub fn new(window: usize, stride: usize) -> MaxPool1d {
+        assert!(window > 0, "The window must be positive.");
+        assert!(stride > 0, "The stride must be positive.");
+        MaxPool1d { window, stride }
+    }
+
+    /// The size of the pooling window.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// The stride between windows.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// The row of the maximum in the window starting at `start`.
+    fn argmax(&self, input: &Matrix<f64>, start: usize, c: usize) -> usize {
+        let mut best = start;
+        for i in start + 1..start + self.window {
+            if input[[i, c]] > input[[best, c]] {
+                best = i;
+            }
+        }
+        best
+    }
+}
+
+impl Layer for MaxPool1d {
+    fn params(&self) -> &[f64] {
+        &[]
+    }
+
+    fn params_mut(&mut self) -> &mut [f64] {
+        &mut []
+    }
+
+    fn forward(&self, input: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        let out_len = check_pool_input(input, self.window, self.stride)?;
+        Ok(Matrix::from_fn(out_len, input.cols(), |c, t| {
+            input[[self.argmax(input, t * self.stride, c), c]]
+        }))
+    }
+
+    fn backward(&self,
+                input: &Matrix<f64>,
+                output_grad: &Matrix<f64>)
+                -> LearningResult<(Matrix<f64>, Vec<f64>)> {
+        let out_len = check_pool_input(input, self.window, self.stride)?;
+        check_output_grad(output_grad, out_len, input.cols())?;
+
+        let mut input_grad = Matrix::zeros(input.rows(), input.cols());
+        for t in 0..out_len {
+            for c in 0..input.cols() {
+                input_grad[[self.argmax(input, t * self.stride, c), c]] += output_grad[[t, c]];
+            }
+        }
+
+        Ok((input_grad, Vec::new()))
+    }
+}
+
+/// One dimensional average-pooling layer.
+///
+/// Takes the mean of each channel over windows of `window` steps,
+/// moving `stride` steps at a time. The gradient of each output is
+/// shared equally by the positions in its window.
+///
+/// The layer has no parameters.
+#[derive(Debug, Clone, Copy)]
+pub struct AvgPool1d {
+    window: usize,
+    stride: usize,
+}
+
+impl AvgPool1d {
+    /// Constructs an average-pooling layer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::layers::{Layer, AvgPool1d};
+    /// use rusty_machine::linalg::{Matrix, BaseMatrix};
+    ///
+    /// let pool = AvgPool1d::new(2, 2);
+    /// let input = Matrix::new(4, 1, vec![1.0, 3.0, 2.0, 0.0]);
+    ///
+    /// assert_eq!(*pool.forward(&input).unwrap().data(), vec![2.0, 1.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The window or stride is zero.
+    pub fn new(window: usize, stride: usize) -> AvgPool1d {
+        assert!(window > 0, "The window must be positive.");
+        assert!(stride > 0, "The stride must be positive.");
+        AvgPool1d { window, stride }
+    }
+
+    /// The size of the pooling window.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// The stride between windows.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+}
+
+impl Layer for AvgPool1d {
+    fn params(&self) -> &[f64] {
+        &[]
+    }
+
+    fn params_mut(&mut self) -> &mut [f64] {
+        &mut []
+    }
+
+    fn forward(&self, input: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        let out_len = check_pool_input(input, self.window, self.stride)?;
+        Ok(Matrix::from_fn(out_len, input.cols(), |c, t| {
+            let start = t * self.stride;
+            (start..start + self.window).map(|i| input[[i, c]]).sum::<f64>() / self.window as f64
+        }))
+    }
+
+    fn backward(&self,
+                input: &Matrix<f64>,
+                output_grad: &Matrix<f64>)
+                -> LearningResult<(Matrix<f64>, Vec<f64>)> {
+        let out_len = check_pool_input(input, self.window, self.stride)?;
+        check_output_grad(output_grad, out_len, input.cols())?;
+
+        let mut input_grad = Matrix::zeros(input.rows(), input.cols());
+        for t in 0..out_len {
+            for c in 0..input.cols() {
+                let g = output_grad[[t, c]] / self.window as f64;
+                for i in t * self.stride..t * self.stride + self.window {
+                    input_grad[[i, c]] += g;
+                }
+            }
+        }
+
+        Ok((input_grad, Vec::new()))
+    }
+}
+
+/// Embedding lookup layer.
+///
+/// Maps each index in `0..vocab_size` to a learnable vector of length
+/// `embedding_dim`, stored as row `index` of a weight matrix. The input
+/// is a sequence of indices, so this layer does not implement `Layer`
+/// and has no input gradient.
+///
+/// The parameters are the weight matrix in row-major order.
+#[derive(Debug, Clone)]
+pub struct Embedding {
+    weights: Matrix<f64>,
+}
+
+impl Embedding {
+    /// Constructs an embedding layer with randomly initialized weights.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::layers::Embedding;
+    /// use rusty_machine::linalg::BaseMatrix;
+    ///
+    /// // Ten words embedded in three dimensions
+    /// let embedding = Embedding::new(10, 3);
+    /// let vectors = embedding.forward(&[4, 1, 4]).unwrap();
+    ///
+    /// assert_eq!((vectors.rows(), vectors.cols()), (3, 3));
+    /// assert_eq!(vectors[[0, 1]], vectors[[2, 1]]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The vocabulary size or embedding dimension is zero.
+    pub fn new(vocab_size: usize, embedding_dim: usize) -> Embedding {
+        assert!(vocab_size > 0, "The vocabulary size must be positive.");
+        assert!(embedding_dim > 0, "The embedding dimension must be positive.");
+
+        Embedding::with_rng(vocab_size, embedding_dim, &mut thread_rng())
+    }
+
+    /// Reinitializes the weights using the given seed.
+    pub fn with_seed(self, seed: u64) -> Embedding {
+        Embedding::with_rng(self.vocab_size(),
+                            self.embedding_dim(),
+                            &mut rand_utils::seeded_rng(Some(seed)))
+    }
+
+    fn with_rng<R: Rng>(vocab_size: usize, embedding_dim: usize, rng: &mut R) -> Embedding {
+        let data = init_weights(vocab_size * embedding_dim, vocab_size, embedding_dim, rng);
+        Embedding { weights: Matrix::new(vocab_size, embedding_dim, data) }
+    }
+
+    /// The number of indices which can be embedded.
+    pub fn vocab_size(&self) -> usize {
+        self.weights.rows()
+    }
+
+    /// The length of each embedding vector.
+    pub fn embedding_dim(&self) -> usize {
+        self.weights.cols()
+    }
+
+    /// The weight matrix, with a row for each index.
+    pub fn weights(&self) -> &Matrix<f64> {
+        &self.weights
+    }
+
+    /// The parameters of the layer.
+    pub fn params(&self) -> &[f64] {
+        self.weights.data()
+    }
+
+    /// The mutable parameters of the layer, used to update them.
+    pub fn params_mut(&mut self) -> &mut [f64] {
+        self.weights.mut_data()
+    }
+
+    fn check_indices(&self, indices: &[usize]) -> LearningResult<()> {
+        if indices.iter().any(|&i| i >= self.vocab_size()) {
+            Err(Error::new(ErrorKind::InvalidData,
+                           "The indices must be less than the vocabulary size."))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Looks up the embedding of each index.
+    ///
+    /// Returns a matrix with a row for each index.
+    pub fn forward(&self, indices: &[usize]) -> LearningResult<Matrix<f64>> {
+        self.check_indices(indices)?;
+        Ok(self.weights.select_rows(indices))
+    }
+
+    /// Computes the parameter gradient of the layer.
+    ///
+    /// The gradient of each output row is added to the row of its
+    /// index, so only the rows which were looked up are non-zero.
+    pub fn backward(&self,
+                    indices: &[usize],
+                    output_grad: &Matrix<f64>)
+                    -> LearningResult<Vec<f64>> {
+        self.check_indices(indices)?;
+        check_output_grad(output_grad, indices.len(), self.embedding_dim())?;
+
+        let dim = self.embedding_dim();
+        let mut param_grad = vec![0f64; self.params().len()];
+        for (t, &idx) in indices.iter().enumerate() {
+            for j in 0..dim {
+                param_grad[idx * dim + j] += output_grad[[t, j]];
+            }
+        }
+
+        Ok(param_grad)
+    }
+}
+
+/// Vanilla recurrent layer.
+///
+/// Processes the sequence one step at a time, updating a hidden state
+/// with the same weights at every step:
+///
+/// `h_t = tanh(W_x x_t + W_h h_(t-1) + b)`
+///
+/// where the initial hidden state is zero. The output has a row for
+/// each step holding the hidden state after that step. The backward
+/// pass uses backpropagation through time.
+///
+/// The parameters are `W_x` (`hidden_size x input_size`), then `W_h`
+/// (`hidden_size x hidden_size`), both in row-major order, followed by
+/// the biases `b`.
+#[derive(Debug, Clone)]
+pub struct RNN {
+    input_size: usize,
+    hidden_size: usize,
+    params: Vec<f64>,
+}
+
+impl RNN {
+    /// Constructs a recurrent layer with randomly initialized weights
+    /// and zero biases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::layers::{Layer, RNN};
+    /// use rusty_machine::linalg::{Matrix, BaseMatrix};
+    ///
+    /// // Two input channels and a hidden state of size 4
+    /// let rnn = RNN::new(2, 4);
+    /// let states = rnn.forward(&Matrix::zeros(5, 2)).unwrap();
+    ///
+    /// assert_eq!((states.rows(), states.cols()), (5, 4));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The input or hidden size is zero.
+    pub fn new(input_size: usize, hidden_size: usize) -> RNN {
+        assert!(input_size > 0, "The input size must be positive.");
+        assert!(hidden_size > 0, "The hidden size must be positive.");
+
+        let mut rnn = RNN {
+            input_size,
+            hidden_size,
+            params: Vec::new(),
+        };
+        rnn.init_params(&mut thread_rng());
+        rnn
+    }
+
+    /// Reinitializes the weights using the given seed.
+    pub fn with_seed(mut self, seed: u64) -> RNN {
+        self.init_params(&mut rand_utils::seeded_rng(Some(seed)));
+        self
+    }
+
+    fn init_params<R: Rng>(&mut self, rng: &mut R) {
+        let (n, h) = (self.input_size, self.hidden_size);
+        self.params = init_weights(h * n, n, h, rng);
+        self.params.extend(init_weights(h * h, h, h, rng));
+        self.params.extend(vec![0f64; h]);
+    }
+
+    /// The number of input channels.
+    pub fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    /// The size of the hidden state.
+    pub fn hidden_size(&self) -> usize {
+        self.hidden_size
+    }
+
+    /// The offsets of `W_h` and `b` in the parameters.
+    fn offsets(&self) -> (usize, usize) {
+        let w_h = self.hidden_size * self.input_size;
+        (w_h, w_h + self.hidden_size * self.hidden_size)
+    }
+
+    fn check_input(&self, input: &Matrix<f64>) -> LearningResult<()> {
+        if input.cols() != self.input_size {
+            Err(Error::new(ErrorKind::InvalidData,
+                           "The input columns must match the input size."))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Layer for RNN {
+    fn params(&self) -> &[f64] {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut [f64] {
+        &mut self.params
+    }
+
+    fn forward(&self, input: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        self.check_input(input)?;
+        let (n, h) = (self.input_size, self.hidden_size);
+        let (w_h, b) = self.offsets();
+
+        let mut states = Matrix::zeros(input.rows(), h);
+        for t in 0..input.rows() {
+            for i in 0..h {
+                let mut z = self.params[b + i];
+                for j in 0..n {
+                    z += self.params[i * n + j] * input[[t, j]];
+                }
+                if t > 0 {
+                    for j in 0..h {
+                        z += self.params[w_h + i * h + j] * states[[t - 1, j]];
+                    }
+                }
+                states[[t, i]] = z.tanh();
+            }
+        }
+
+        Ok(states)
+    }
+
+    fn backward(&self,
+                input: &Matrix<f64>,
+                output_grad: &Matrix<f64>)
+                -> LearningResult<(Matrix<f64>, Vec<f64>)> {
+        let states = self.forward(input)?;
+        check_output_grad(output_grad, input.rows(), self.hidden_size)?;
+        let (n, h) = (self.input_size, self.hidden_size);
+        let (w_h, b) = self.offsets();
+
+        let mut input_grad = Matrix::zeros(input.rows(), n);
+        let mut param_grad = vec![0f64; self.params.len()];
+        // The gradient flowing into the hidden state from later steps
+        let mut carry = vec![0f64; h];
+
+        for t in (0..input.rows()).rev() {
+            let delta: Vec<f64> = (0..h)
+                .map(|i| {
+                    let s = states[[t, i]];
+                    (output_grad[[t, i]] + carry[i]) * (1f64 - s * s)
+                })
+                .collect();
+
+            for c in carry.iter_mut() {
+                *c = 0f64;
+            }
+
+            for (i, &d) in delta.iter().enumerate() {
+                param_grad[b + i] += d;
+                for j in 0..n {
+                    param_grad[i * n + j] += d * input[[t, j]];
+                    input_grad[[t, j]] += d * self.params[i * n + j];
+                }
+                if t > 0 {
+                    for j in 0..h {
+                        param_grad[w_h + i * h + j] += d * states[[t - 1, j]];
+                        carry[j] += d * self.params[w_h + i * h + j];
+                    }
+                }
+            }
+        }
+
+        Ok((input_grad, param_grad))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Layer, Conv1d, MaxPool1d, AvgPool1d, Embedding, RNN};
+    use linalg::{Matrix, BaseMatrix};
+
+    /// The loss `sum(output .* weights)` whose output gradient is `weights`.
+    fn weighted_loss<L: Layer>(layer: &L, input: &Matrix<f64>, weights: &Matrix<f64>) -> f64 {
+        layer.forward(input).unwrap().elemul(weights).sum()
+    }
+
+    /// Compares the backward pass with central finite differences.
+    fn check_gradients<L: Layer + Clone>(layer: &L, input: &Matrix<f64>) {
+        let output = layer.forward(input).unwrap();
+        let weights = Matrix::from_fn(output.rows(), output.cols(), |i, j| {
+            ((3 * i + 7 * j) as f64).sin()
+        });
+        let (input_grad, param_grad) = layer.backward(input, &weights).unwrap();
+        let h = 1e-6;
+
+        for p in 0..layer.params().len() {
+            let (mut plus, mut minus) = (layer.clone(), layer.clone());
+            plus.params_mut()[p] += h;
+            minus.params_mut()[p] -= h;
+            let numerical = (weighted_loss(&plus, input, &weights) -
+                             weighted_loss(&minus, input, &weights)) / (2f64 * h);
+            assert!((numerical - param_grad[p]).abs() < 1e-6);
+        }
+
+        for i in 0..input.rows() {
+            for j in 0..input.cols() {
+                let (mut plus, mut minus) = (input.clone(), input.clone());
+                plus[[i, j]] += h;
+                minus[[i, j]] -= h;
+                let numerical = (weighted_loss(layer, &plus, &weights) -
+                                 weighted_loss(layer, &minus, &weights)) / (2f64 * h);
+                assert!((numerical - input_grad[[i, j]]).abs() < 1e-6);
+            }
+        }
+    }
+
+    fn sequence(len: usize, channels: usize) -> Matrix<f64> {
+        Matrix::from_fn(len, channels, |i, j| ((i * channels + j) as f64 * 0.7).cos())
+    }
+
+    #[test]
+    fn test_conv1d_output_shape() {
+        let conv = Conv1d::new(2, 3, 3, 2);
+
+        assert_eq!(conv.params().len(), 3 * 3 * 2 + 3);
+        assert_eq!(conv.output_len(9), Some(4));
+        assert_eq!(conv.output_len(2), None);
+
+        let output = conv.forward(&sequence(9, 2)).unwrap();
+        assert_eq!((output.rows(), output.cols()), (4, 3));
+
+        assert!(conv.forward(&sequence(2, 2)).is_err());
+        assert!(conv.forward(&sequence(9, 3)).is_err());
+    }
+
+    #[test]
+    fn test_conv1d_edge_detection() {
+        let mut conv = Conv1d::new(1, 1, 2, 1);
+        conv.params_mut().copy_from_slice(&[-1.0, 1.0, 0.0]);
+
+        let step = Matrix::new(6, 1, vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0]);
+        let edges = conv.forward(&step).unwrap();
+
+        assert_eq!(*edges.data(), vec![0.0, 0.0, 1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_conv1d_gradient_check() {
+        check_gradients(&Conv1d::new(2, 3, 3, 1).with_seed(1), &sequence(7, 2));
+        check_gradients(&Conv1d::new(3, 2, 2, 3).with_seed(2), &sequence(8, 3));
+    }
+
+    #[test]
+    fn test_conv1d_bad_output_grad() {
+        let conv = Conv1d::new(1, 2, 2, 1);
+        let input = sequence(4, 1);
+        assert!(conv.backward(&input, &Matrix::zeros(3, 1)).is_err());
+    }
+
+    #[test]
+    fn test_max_pool_forward() {
+        let pool = MaxPool1d::new(2, 2);
+        let input = Matrix::new(6, 2, vec![1.0, -1.0, 4.0, -2.0, 2.0, 0.0, 2.0, 5.0, -3.0, 1.0, 0.0, 1.0]);
+        let output = pool.forward(&input).unwrap();
+
+        assert_eq!((output.rows(), output.cols()), (3, 2));
+        assert_eq!(*output.data(), vec![4.0, -1.0, 2.0, 5.0, 0.0, 1.0]);
+
+        // Overlapping windows and a dropped tail
+        let pool = MaxPool1d::new(3, 2);
+        let output = pool.forward(&Matrix::new(6, 1, vec![1.0, 3.0, 2.0, 0.0, 5.0, 9.0])).unwrap();
+        assert_eq!(*output.data(), vec![3.0, 5.0]);
+    }
+
+    #[test]
+    fn test_max_pool_gradient_routing() {
+        let pool = MaxPool1d::new(2, 2);
+        let input = Matrix::new(4, 2, vec![1.0, 7.0, 4.0, 2.0, 2.0, 2.0, 0.0, 2.0]);
+        let output_grad = Matrix::new(2, 2, vec![10.0, 20.0, 30.0, 40.0]);
+        let (input_grad, param_grad) = pool.backward(&input, &output_grad).unwrap();
+
+        assert!(param_grad.is_empty());
+        // Ties send the gradient to the first maximum only
+        assert_eq!(*input_grad.data(),
+                   vec![0.0, 20.0, 10.0, 0.0, 30.0, 40.0, 0.0, 0.0]);
+
+        // An element which is the maximum of two windows receives both gradients
+        let pool = MaxPool1d::new(2, 1);
+        let input = Matrix::new(3, 1, vec![0.0, 5.0, 1.0]);
+        let (input_grad, _) = pool.backward(&input, &Matrix::new(2, 1, vec![1.0, 2.0])).unwrap();
+        assert_eq!(*input_grad.data(), vec![0.0, 3.0, 0.0]);
+    }
+
+    #[test]
+    fn test_avg_pool_forward() {
+        let pool = AvgPool1d::new(2, 2);
+        let input = Matrix::new(4, 2, vec![1.0, -1.0, 3.0, 5.0, 2.0, 0.0, 6.0, 1.0]);
+        let output = pool.forward(&input).unwrap();
+
+        assert_eq!((output.rows(), output.cols()), (2, 2));
+        assert_eq!(*output.data(), vec![2.0, 2.0, 4.0, 0.5]);
+        assert!(pool.forward(&Matrix::zeros(1, 2)).is_err());
+    }
+
+    #[test]
+    fn test_avg_pool_gradient_routing() {
+        let pool = AvgPool1d::new(2, 1);
+        let input = sequence(3, 1);
+        let (input_grad, param_grad) = pool.backward(&input, &Matrix::new(2, 1, vec![2.0, 4.0]))
+            .unwrap();
+
+        assert!(param_grad.is_empty());
+        assert_eq!(*input_grad.data(), vec![1.0, 3.0, 2.0]);
+
+        check_gradients(&AvgPool1d::new(3, 2), &sequence(9, 2));
+        check_gradients(&MaxPool1d::new(3, 2), &sequence(9, 2));
+    }
+
+    #[test]
+    fn test_embedding_lookup() {
+        let mut embedding = Embedding::new(4, 2);
+        assert_eq!(embedding.vocab_size(), 4);
+        assert_eq!(embedding.embedding_dim(), 2);
+
+        embedding.params_mut().copy_from_slice(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+        let output = embedding.forward(&[2, 0, 2]).unwrap();
+
+        assert_eq!(*output.data(), vec![4.0, 5.0, 0.0, 1.0, 4.0, 5.0]);
+        assert!(embedding.forward(&[1, 4]).is_err());
+    }
+
+    #[test]
+    fn test_embedding_gradient_touches_indexed_rows() {
+        let embedding = Embedding::new(5, 2).with_seed(3);
+        let output_grad = Matrix::new(3, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let grad = embedding.backward(&[3, 1, 3], &output_grad).unwrap();
+
+        // Row 3 accumulates the gradient of both of its lookups
+        assert_eq!(grad,
+                   vec![0.0, 0.0, 3.0, 4.0, 0.0, 0.0, 6.0, 8.0, 0.0, 0.0]);
+
+        assert!(embedding.backward(&[3, 1], &output_grad).is_err());
+        assert!(embedding.backward(&[3, 1, 5], &output_grad).is_err());
+    }
+
+    #[test]
+    fn test_rnn_gradient_check() {
+        check_gradients(&RNN::new(2, 3).with_seed(4), &sequence(5, 2));
+        check_gradients(&RNN::new(1, 2).with_seed(5), &sequence(4, 1));
+    }
+
+    #[test]
+    fn test_rnn_hidden_state_carries_information() {
+        let mut rnn = RNN::new(1, 1);
+        rnn.params_mut().copy_from_slice(&[1.0, 0.9, 0.0]);
+
+        // The sequences differ only at the first step
+        let first = Matrix::new(4, 1, vec![1.0, 0.0, 0.0, 0.0]);
+        let second = Matrix::new(4, 1, vec![-1.0, 0.0, 0.0, 0.0]);
+        let h_first = rnn.forward(&first).unwrap();
+        let h_second = rnn.forward(&second).unwrap();
+
+        assert!(h_first[[3, 0]] > 0.1);
+        assert!((h_first[[3, 0]] + h_second[[3, 0]]).abs() < 1e-12);
+
+        // Without recurrent weights the state is forgotten
+        rnn.params_mut()[1] = 0.0;
+        assert_eq!(rnn.forward(&first).unwrap()[[3, 0]], 0.0);
+
+        // The loss at the last step depends on the first input
+        let mut output_grad = Matrix::zeros(4, 1);
+        output_grad[[3, 0]] = 1.0;
+        rnn.params_mut()[1] = 0.9;
+        let (input_grad, _) = rnn.backward(&first, &output_grad).unwrap();
+        assert!(input_grad[[0, 0]] > 0.0);
+    }
+
+    #[test]
+    fn test_rnn_bad_input() {
+        let rnn = RNN::new(2, 3);
+        assert!(rnn.forward(&Matrix::zeros(4, 3)).is_err());
+        assert!(rnn.backward(&Matrix::zeros(4, 2), &Matrix::zeros(4, 2)).is_err());
+    }
+}
@@ -0,0 +1,1059 @@
+//! Neural Network module
+//!
+//! Contains implementation of simple feed forward neural network.
+//!
+//! # Usage
+//!
+//! ```
+//! use rusty_machine::learning::nnet::{NeuralNet, BCECriterion};
+//! use rusty_machine::learning::toolkit::regularization::Regularization;
+//! use rusty_machine::learning::optim::grad_desc::StochasticGD;
+//! use rusty_machine::linalg::Matrix;
+//! use rusty_machine::learning::SupModel;
+//!
+//! let inputs = Matrix::new(5,3, vec![1.,1.,1.,2.,2.,2.,3.,3.,3.,
+//!                                 4.,4.,4.,5.,5.,5.,]);
+//! let targets = Matrix::new(5,3, vec![1.,0.,0.,0.,1.,0.,0.,0.,1.,
+//!                                     0.,0.,1.,0.,0.,1.]);
+//!
+//! // Set the layer sizes - from input to output
+//! let layers = &[3,5,11,7,3];
+//!
+//! // Choose the BCE criterion with L2 regularization (`lambda=0.1`).
+//! let criterion = BCECriterion::new(Regularization::L2(0.1));
+//!
+//! // We will just use the default stochastic gradient descent.
+//! let mut model = NeuralNet::new(layers, criterion, StochasticGD::default());
+//!
+//! // Train the model!
+//! model.train(&inputs, &targets).unwrap();
+//!
+//! let test_inputs = Matrix::new(2,3, vec![1.5,1.5,1.5,5.1,5.1,5.1]);
+//!
+//! // And predict new output from the test inputs
+//! let outputs = model.predict(&test_inputs).unwrap();
+//! ```
+//!
+//! The neural networks are specified via a criterion - similar to
+//! [Torch](https://github.com/torch/nn/blob/master/doc/criterion.md).
+//! The criterions combine an activation function and a cost function.
+//!
+//! You can define your own criterion by implementing the `Criterion`
+//! trait with a concrete `ActivationFunc` and `CostFunc`.
+//!
+//! Training always continues from the current weights of the network,
+//! so calling `train` again resumes from the previous fit. Weights
+//! saved with `parameters` can be restored with `set_parameters` to
+//! warm start another network with the same layer sizes.
+
+pub mod layers;
+
+use linalg::{Matrix, MatrixSlice, BaseMatrix, BaseMatrixMut};
+
+use learning::{LearningResult, SupModel};
+use learning::error::{Error, ErrorKind};
+use learning::toolkit::activ_fn;
+use learning::toolkit::activ_fn::ActivationFunc;
+use learning::toolkit::cost_fn;
+use learning::toolkit::cost_fn::CostFunc;
+use learning::toolkit::regularization::Regularization;
+use learning::optim::{Optimizable, OptimAlgorithm};
+use learning::optim::grad_desc::StochasticGD;
+
+use learning::toolkit::rand_utils;
+
+use rand::{Rng, thread_rng};
+
+use std::cmp;
+use rand::distributions::{Sample, range};
+
+/// Neural Network Model
+///
+/// The Neural Network struct specifies a Criterion and
+/// a gradient descent algorithm.
+#[derive(Debug)]
+pub struct NeuralNet<'a, T, A>
+    where T: Criterion,
+          A: OptimAlgorithm<BaseNeuralNet<'a, T>>
+{
+    base: BaseNeuralNet<'a, T>,
+    alg: A,
+}
+
+/// Supervised learning for the Neural Network.
+///
+/// The model is trained using back propagation.
+impl<'a, T, A> SupModel<Matrix<f64>, Matrix<f64>> for NeuralNet<'a, T, A>
+    where T: Criterion,
+          A: OptimAlgorithm<BaseNeuralNet<'a, T>>
+{
+    /// Predict neural network output using forward propagation.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        self.base.forward_prop(inputs)
+    }
+
+    /// Train the model using gradient optimization and back propagation.
+    fn train(&mut self, inputs: &Matrix<f64>, targets: &Matrix<f64>) -> LearningResult<()> {
+        let optimal_w = self.alg.optimize(&self.base, &self.base.weights, inputs, targets);
+        self.base.weights = optimal_w;
+        Ok(())
+    }
+}
+
+impl<'a> NeuralNet<'a, BCECriterion, StochasticGD> {
+    /// Creates a neural network with the specified layer sizes.
+    ///
+    /// The layer sizes slice should include the input, hidden layers, and output layer sizes.
+    /// The type of activation function must be specified.
+    ///
+    /// Uses the default settings (stochastic gradient descent and sigmoid activation function).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::NeuralNet;
+    ///
+    /// // Create a neural net with 4 layers, 3 neurons in each.
+    /// let layers = &[3; 4];
+    /// let mut net = NeuralNet::default(layers);
+    /// ```
+    pub fn default(layer_sizes: &[usize]) -> NeuralNet<BCECriterion, StochasticGD> {
+        NeuralNet {
+            base: BaseNeuralNet::default(layer_sizes),
+            alg: StochasticGD::default(),
+        }
+    }
+}
+
+impl<'a, T, A> NeuralNet<'a, T, A>
+    where T: Criterion,
+          A: OptimAlgorithm<BaseNeuralNet<'a, T>>
+{
+    /// Create a new neural network with the specified layer sizes.
+    ///
+    /// The layer sizes slice should include the input, hidden layers, and output layer sizes.
+    /// The type of activation function must be specified.
+    ///
+    /// Currently defaults to simple batch Gradient Descent for optimization.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::BCECriterion;
+    /// use rusty_machine::learning::nnet::NeuralNet;
+    /// use rusty_machine::learning::optim::grad_desc::StochasticGD;
+    ///
+    /// // Create a neural net with 4 layers, 3 neurons in each.
+    /// let layers = &[3; 4];
+    /// let mut net = NeuralNet::new(layers, BCECriterion::default(), StochasticGD::default());
+    /// ```
+    pub fn new(layer_sizes: &'a [usize], criterion: T, alg: A) -> NeuralNet<'a, T, A> {
+        NeuralNet {
+            base: BaseNeuralNet::new(layer_sizes, criterion),
+            alg: alg,
+        }
+    }
+
+    /// Gets matrix of weights between specified layer and forward layer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::BaseMatrix;
+    /// use rusty_machine::learning::nnet::NeuralNet;
+    ///
+    /// // Create a neural net with 4 layers, 3 neurons in each.
+    /// let layers = &[3; 4];
+    /// let mut net = NeuralNet::default(layers);
+    ///
+    /// let w = &net.get_net_weights(2);
+    ///
+    /// // We add a bias term to the weight matrix
+    /// assert_eq!(w.rows(), 4);
+    /// assert_eq!(w.cols(), 3);
+    /// ```
+    pub fn get_net_weights(&self, idx: usize) -> MatrixSlice<f64> {
+        self.base.get_layer_weights(&self.base.weights[..], idx)
+    }
+
+    /// The weights of the network, flattened layer by layer.
+    ///
+    /// Each layer's weight matrix, including the bias row, is stored
+    /// in row major order.
+    pub fn parameters(&self) -> &[f64] {
+        &self.base.weights
+    }
+
+    /// Sets the weights of the network, flattened as in `parameters`.
+    ///
+    /// Training starts from these weights.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::NeuralNet;
+    ///
+    /// let layers = &[3, 2];
+    /// let trained = NeuralNet::default(layers);
+    ///
+    /// let mut net = NeuralNet::default(layers);
+    /// net.set_parameters(trained.parameters().to_vec()).unwrap();
+    ///
+    /// assert_eq!(net.parameters(), trained.parameters());
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The number of weights does not match the layer sizes.
+    pub fn set_parameters(&mut self, params: Vec<f64>) -> LearningResult<()> {
+        if params.len() != self.base.weights.len() {
+            return Err(Error::new(ErrorKind::InvalidParameters,
+                                  "The number of weights must match the layer sizes."));
+        }
+        self.base.weights = params;
+        Ok(())
+    }
+
+    /// Computes the outputs of each layer of the network.
+    ///
+    /// Returns one matrix for every layer after the input layer, without
+    /// the bias column. The last matrix is the output of `predict`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::NeuralNet;
+    /// use rusty_machine::linalg::{Matrix, BaseMatrix};
+    ///
+    /// let layers = &[2, 5, 3, 1];
+    /// let net = NeuralNet::default(layers);
+    ///
+    /// let inputs = Matrix::new(4, 2, vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0]);
+    /// let activations = net.activations(&inputs).unwrap();
+    ///
+    /// assert_eq!(activations.len(), 3);
+    /// assert_eq!(activations[0].cols(), 5);
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The inputs do not match the size of the first layer.
+    pub fn activations(&self, inputs: &Matrix<f64>) -> LearningResult<Vec<Matrix<f64>>> {
+        self.base.forward_activations(inputs)
+    }
+
+    /// The number of micro-batches each gradient is accumulated over.
+    pub fn accumulation_steps(&self) -> usize {
+        self.base.accumulation_steps
+    }
+
+    /// Sets the number of micro-batches to accumulate gradients over.
+    ///
+    /// Each time the optimization algorithm requests a gradient the
+    /// data is split into `steps` micro-batches. Forward and backward
+    /// propagation are run on each in turn and the gradients are
+    /// accumulated, so that the algorithm takes a single step with the
+    /// gradient of the full batch. This reduces the memory used for the
+    /// activations of large batches at the cost of speed.
+    ///
+    /// The default is `1`, which processes the batch at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::NeuralNet;
+    ///
+    /// let layers = &[3; 4];
+    /// let mut net = NeuralNet::default(layers);
+    /// net.set_accumulation_steps(4);
+    ///
+    /// assert_eq!(net.accumulation_steps(), 4);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `steps` is zero.
+    pub fn set_accumulation_steps(&mut self, steps: usize) {
+        assert!(steps > 0, "The number of accumulation steps must be positive.");
+        self.base.accumulation_steps = steps;
+    }
+
+    /// Whether the weights between the specified layer and the forward
+    /// layer are updated during training.
+    ///
+    /// # Panics
+    ///
+    /// - `idx` is not the index of a weight layer.
+    pub fn is_trainable(&self, idx: usize) -> bool {
+        assert!(idx < self.base.trainable.len(), "The layer index is out of range.");
+        self.base.trainable[idx]
+    }
+
+    /// Freezes or unfreezes the weights between the specified layer and
+    /// the forward layer.
+    ///
+    /// Frozen weights are still used for forward propagation, but their
+    /// gradient is zero so training leaves them unchanged. This allows
+    /// fine-tuning only some layers of a pretrained network. All layers
+    /// are trainable by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::NeuralNet;
+    ///
+    /// let layers = &[3; 4];
+    /// let mut net = NeuralNet::default(layers);
+    ///
+    /// // Only train the output layer
+    /// net.set_trainable(0, false);
+    /// net.set_trainable(1, false);
+    ///
+    /// assert!(!net.is_trainable(0));
+    /// assert!(net.is_trainable(2));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `idx` is not the index of a weight layer.
+    pub fn set_trainable(&mut self, idx: usize, trainable: bool) {
+        assert!(idx < self.base.trainable.len(), "The layer index is out of range.");
+        self.base.trainable[idx] = trainable;
+    }
+
+    /// Trains the model on a sequence of mini-batches.
+    ///
+    /// Each item of `batches` is a pair of inputs and targets. For each
+    /// batch the gradient is computed by a forward and backward pass and
+    /// the weights take a single step of size `learning_rate` against it.
+    /// The optimization algorithm of the network is not used. Only one
+    /// batch is held at a time, so the batches may be read or generated
+    /// lazily, and several epochs are run by repeating the batches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::NeuralNet;
+    /// use rusty_machine::linalg::Matrix;
+    ///
+    /// let layers = &[2, 3, 1];
+    /// let mut net = NeuralNet::default(layers);
+    ///
+    /// // Generate the batches as they are needed
+    /// let batches = (0..5).map(|i| {
+    ///     let x = i as f64;
+    ///     (Matrix::new(2, 2, vec![x, 0.0, 0.0, x]), Matrix::new(2, 1, vec![1.0, 0.0]))
+    /// });
+    ///
+    /// net.train_minibatch(batches, 0.1).unwrap();
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The learning rate is not positive.
+    /// - A batch has inputs or targets which do not match the layer sizes.
+    /// - A batch has different numbers of input and target rows.
+    pub fn train_minibatch<I>(&mut self, batches: I, learning_rate: f64) -> LearningResult<()>
+        where I: Iterator<Item = (Matrix<f64>, Matrix<f64>)>
+    {
+        if learning_rate.is_nan() || learning_rate <= 0f64 {
+            return Err(Error::new(ErrorKind::InvalidParameters,
+                                  "The learning rate must be positive."));
+        }
+
+        let output_size = self.base.layer_sizes[self.base.layer_sizes.len() - 1];
+
+        for (inputs, targets) in batches {
+            if inputs.cols() != self.base.layer_sizes[0] || targets.cols() != output_size {
+                return Err(Error::new(ErrorKind::InvalidData,
+                                      "The batch dimensions must match the layer sizes."));
+            }
+            if inputs.rows() != targets.rows() {
+                return Err(Error::new(ErrorKind::InvalidData,
+                                      "The batch inputs and targets must have the same \
+                                       number of rows."));
+            }
+
+            let (_, grad) = self.base.compute_grad(&self.base.weights, &inputs, &targets);
+            for (w, g) in self.base.weights.iter_mut().zip(grad) {
+                *w -= learning_rate * g;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reinitializes the weights of the network using the given seed.
+    ///
+    /// Networks with the same layer sizes and seed start from the same
+    /// weights. The optimization algorithm may also be random and should
+    /// be seeded separately to make training fully reproducible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::{NeuralNet, BCECriterion};
+    /// use rusty_machine::learning::optim::grad_desc::StochasticGD;
+    ///
+    /// let layers = &[3; 4];
+    /// let sgd = StochasticGD::default().with_seed(42);
+    /// let net = NeuralNet::new(layers, BCECriterion::default(), sgd).with_seed(42);
+    /// ```
+    pub fn with_seed(mut self, seed: u64) -> NeuralNet<'a, T, A> {
+        let mut rng = rand_utils::seeded_rng(Some(seed));
+        self.base.weights = BaseNeuralNet::<T>::create_weights_with_rng(self.base.layer_sizes,
+                                                                        &mut rng);
+        self
+    }
+}
+
+/// Base Neural Network struct
+///
+/// This struct cannot be instantianated and is used internally only.
+#[derive(Debug)]
+pub struct BaseNeuralNet<'a, T: Criterion> {
+    layer_sizes: &'a [usize],
+    weights: Vec<f64>,
+    criterion: T,
+    accumulation_steps: usize,
+    trainable: Vec<bool>,
+}
+
+
+impl<'a> BaseNeuralNet<'a, BCECriterion> {
+    /// Creates a base neural network with the specified layer sizes.
+    fn default(layer_sizes: &[usize]) -> BaseNeuralNet<BCECriterion> {
+        BaseNeuralNet::new(layer_sizes, BCECriterion::default())
+    }
+}
+
+
+impl<'a, T: Criterion> BaseNeuralNet<'a, T> {
+    /// Create a new base neural network with the specified layer sizes.
+    fn new(layer_sizes: &[usize], criterion: T) -> BaseNeuralNet<T> {
+        BaseNeuralNet {
+            layer_sizes: layer_sizes,
+            weights: BaseNeuralNet::<T>::create_weights(layer_sizes),
+            criterion: criterion,
+            accumulation_steps: 1,
+            trainable: vec![true; layer_sizes.len() - 1],
+        }
+    }
+
+    /// Creates initial weights for all neurons in the network.
+    fn create_weights(layer_sizes: &[usize]) -> Vec<f64> {
+        BaseNeuralNet::<T>::create_weights_with_rng(layer_sizes, &mut thread_rng())
+    }
+
+    /// Creates initial weights using the given random number generator.
+    fn create_weights_with_rng<R: Rng>(layer_sizes: &[usize], rng: &mut R) -> Vec<f64> {
+        let mut between = range::Range::new(0f64, 1f64);
+        layer_sizes.windows(2)
+            .flat_map(|w| {
+                let l_in = w[0] + 1;
+                let l_out = w[1];
+                let eps_init = (6f64 / (l_in + l_out) as f64).sqrt();
+                (0..l_in * l_out)
+                    .map(|_i| (between.sample(rng) * 2f64 * eps_init) - eps_init)
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Gets matrix of weights between specified layer and forward layer for the weights.
+    fn get_layer_weights(&self, weights: &[f64], idx: usize) -> MatrixSlice<f64> {
+        debug_assert!(idx < self.layer_sizes.len() - 1);
+
+        // Check that the weights are the right size.
+        let mut full_size = 0usize;
+        for l in 0..self.layer_sizes.len() - 1 {
+            full_size += (self.layer_sizes[l] + 1) * self.layer_sizes[l + 1];
+        }
+
+        debug_assert_eq!(full_size, weights.len());
+
+        let mut start = 0usize;
+
+        for l in 0..idx {
+            start += (self.layer_sizes[l] + 1) * self.layer_sizes[l + 1]
+        }
+
+        unsafe {
+            MatrixSlice::from_raw_parts(weights.as_ptr().offset(start as isize),
+                                        self.layer_sizes[idx] + 1,
+                                        self.layer_sizes[idx + 1],
+                                        self.layer_sizes[idx + 1])
+        }
+
+    }
+
+    /// Gets matrix of weights between specified layer and forward layer
+    /// for the base model.
+    fn get_net_weights(&self, idx: usize) -> MatrixSlice<f64> {
+        self.get_layer_weights(&self.weights[..], idx)
+    }
+
+    /// Gets the weights for a layer excluding the bias weights.
+    fn get_non_bias_weights(&self, weights: &[f64], idx: usize) -> MatrixSlice<f64> {
+        let layer_weights = self.get_layer_weights(weights, idx);
+        layer_weights.reslice([1, 0], layer_weights.rows() - 1, layer_weights.cols())
+    }
+
+    /// Compute the gradient using the back propagation algorithm.
+    ///
+    /// If `accumulation_steps` is greater than one the inputs are split
+    /// into that many micro-batches. The gradients of the micro-batches
+    /// are accumulated and combined into the gradient of the full batch.
+    fn compute_grad(&self,
+                    weights: &[f64],
+                    inputs: &Matrix<f64>,
+                    targets: &Matrix<f64>)
+                    -> (f64, Vec<f64>) {
+        assert_eq!(inputs.cols(), self.layer_sizes[0]);
+
+        let n = inputs.rows();
+        let steps = cmp::max(1, cmp::min(self.accumulation_steps, n));
+
+        let (mut cost, layer_grads) = if steps == 1 {
+            self.back_prop(weights, inputs, targets)
+        } else {
+            let batch_size = n.div_ceil(steps);
+            let mut cost = 0f64;
+            let mut layer_grads: Option<Vec<Matrix<f64>>> = None;
+
+            for start in (0..n).step_by(batch_size) {
+                let rows = (start..cmp::min(start + batch_size, n)).collect::<Vec<_>>();
+                let (batch_cost, batch_grads) = self.back_prop(weights,
+                                                               &inputs.select_rows(&rows),
+                                                               &targets.select_rows(&rows));
+
+                // The batch costs are row means so are weighted by the batch size
+                cost += batch_cost * rows.len() as f64;
+                layer_grads = Some(match layer_grads {
+                    None => batch_grads,
+                    Some(acc) => acc.into_iter().zip(batch_grads).map(|(a, g)| a + g).collect(),
+                });
+            }
+
+            (cost / n as f64, layer_grads.unwrap())
+        };
+
+        let mut gradients = Vec::with_capacity(weights.len());
+
+        for (l, mut g) in layer_grads.into_iter().enumerate() {
+            // Frozen layers have no gradient so the optimizer leaves them be
+            if !self.trainable[l] {
+                gradients.extend(vec![0f64; g.rows() * g.cols()]);
+                continue;
+            }
+
+            // Add the regularized gradient
+            if self.criterion.is_regularized() {
+                let non_bias_weights = self.get_non_bias_weights(weights, l);
+                let zeros = Matrix::zeros(1, non_bias_weights.cols());
+                g += zeros.vcat(&self.criterion.reg_cost_grad(non_bias_weights));
+            }
+
+            gradients.append(&mut (g / n as f64).into_vec());
+        }
+
+        // Add the regularized cost
+        if self.criterion.is_regularized() {
+            for i in 0..self.layer_sizes.len() - 1 {
+                cost += self.criterion.reg_cost(self.get_non_bias_weights(weights, i));
+            }
+        }
+
+        (cost, gradients)
+    }
+
+    /// Runs forward and backward propagation on a batch.
+    ///
+    /// Returns the unregularized cost and the gradient of each layer's
+    /// weights summed over the rows of the batch.
+    fn back_prop(&self,
+                 weights: &[f64],
+                 inputs: &Matrix<f64>,
+                 targets: &Matrix<f64>)
+                 -> (f64, Vec<Matrix<f64>>) {
+        let mut forward_weights = Vec::with_capacity(self.layer_sizes.len() - 1);
+        let mut activations = Vec::with_capacity(self.layer_sizes.len());
+
+        let net_data = Matrix::ones(inputs.rows(), 1).hcat(inputs);
+
+        activations.push(net_data.clone());
+
+        // Forward propagation
+        {
+            let mut z = net_data * self.get_layer_weights(weights, 0);
+            forward_weights.push(z.clone());
+
+            for l in 1..self.layer_sizes.len() - 1 {
+                let mut a = self.criterion.activate(z.clone());
+                let ones = Matrix::ones(a.rows(), 1);
+
+                a = ones.hcat(&a);
+
+                z = &a * self.get_layer_weights(weights, l);
+                activations.push(a);
+                forward_weights.push(z.clone());
+            }
+
+            activations.push(self.criterion.activate(z));
+        }
+
+        let mut deltas = Vec::with_capacity(self.layer_sizes.len() - 1);
+        // Backward propagation
+        {
+            let z = forward_weights[self.layer_sizes.len() - 2].clone();
+            let g = self.criterion.grad_activ(z);
+
+            // Take GRAD_cost to compute this delta.
+            let mut delta = self.criterion
+                .cost_grad(&activations[self.layer_sizes.len() - 1], targets)
+                .elemul(&g);
+
+            deltas.push(delta.clone());
+
+            for l in (1..self.layer_sizes.len() - 1).rev() {
+                let mut z = forward_weights[l - 1].clone();
+                let ones = Matrix::ones(z.rows(), 1);
+                z = ones.hcat(&z);
+
+                let g = self.criterion.grad_activ(z);
+                delta = (delta * Matrix::from(self.get_layer_weights(weights, l)).transpose())
+                    .elemul(&g);
+
+                let non_one_rows = &(1..delta.cols()).collect::<Vec<usize>>()[..];
+                delta = delta.select_cols(non_one_rows);
+                deltas.push(delta.clone());
+            }
+        }
+
+        let gradients = activations.iter()
+            .take(self.layer_sizes.len() - 1)
+            .enumerate()
+            .map(|(l, activ_item)| deltas[self.layer_sizes.len() - 2 - l].transpose() * activ_item)
+            .collect();
+
+        // Compute the cost
+        let cost = self.criterion.cost(&activations[activations.len() - 1], targets);
+
+        (cost, gradients)
+    }
+
+    /// Forward propagation of the model weights to get the outputs.
+    fn forward_prop(&self, inputs: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        self.forward(inputs, false).map(|(a, _)| a)
+    }
+
+    /// Forward propagation returning the outputs of every layer.
+    fn forward_activations(&self, inputs: &Matrix<f64>) -> LearningResult<Vec<Matrix<f64>>> {
+        self.forward(inputs, true).map(|(a, mut hidden)| {
+            hidden.push(a);
+            hidden
+        })
+    }
+
+    /// Forward propagation, optionally keeping the hidden layer outputs.
+    ///
+    /// Returns the outputs of the network and, if `retain` is true, the
+    /// outputs of each hidden layer without the bias column.
+    fn forward(&self,
+               inputs: &Matrix<f64>,
+               retain: bool)
+               -> LearningResult<(Matrix<f64>, Vec<Matrix<f64>>)> {
+        if inputs.cols() != self.layer_sizes[0] {
+            Err(Error::new(ErrorKind::InvalidData,
+                           "The input data dimensions must match the first layer."))
+        } else {
+            let mut hidden = Vec::new();
+            let net_data = Matrix::ones(inputs.rows(), 1).hcat(inputs);
+
+            let mut z = net_data * self.get_net_weights(0);
+            let mut a = self.criterion.activate(z.clone());
+
+            for l in 1..self.layer_sizes.len() - 1 {
+                if retain {
+                    hidden.push(a.clone());
+                }
+                let ones = Matrix::ones(a.rows(), 1);
+                a = ones.hcat(&a);
+                z = a * self.get_net_weights(l);
+                a = self.criterion.activate(z.clone());
+            }
+
+            Ok((a, hidden))
+        }
+    }
+}
+
+/// Compute the gradient of the Neural Network using the
+/// back propagation algorithm.
+impl<'a, T: Criterion> Optimizable for BaseNeuralNet<'a, T> {
+    type Inputs = Matrix<f64>;
+    type Targets = Matrix<f64>;
+
+    /// Compute the gradient of the neural network.
+    fn compute_grad(&self,
+                    params: &[f64],
+                    inputs: &Matrix<f64>,
+                    targets: &Matrix<f64>)
+                    -> (f64, Vec<f64>) {
+        self.compute_grad(params, inputs, targets)
+    }
+}
+
+/// Criterion for Neural Networks
+///
+/// Specifies an activation function and a cost function.
+pub trait Criterion {
+    /// The activation function for the criterion.
+    type ActFunc: ActivationFunc;
+    /// The cost function for the criterion.
+    type Cost: CostFunc<Matrix<f64>>;
+
+    /// The activation function applied to a matrix.
+    fn activate(&self, mat: Matrix<f64>) -> Matrix<f64> {
+        mat.apply(&Self::ActFunc::func)
+    }
+
+    /// The gradient of the activation function applied to a matrix.
+    fn grad_activ(&self, mat: Matrix<f64>) -> Matrix<f64> {
+        mat.apply(&Self::ActFunc::func_grad)
+    }
+
+    /// The cost function.
+    ///
+    /// Returns a scalar cost.
+    fn cost(&self, outputs: &Matrix<f64>, targets: &Matrix<f64>) -> f64 {
+        Self::Cost::cost(outputs, targets)
+    }
+
+    /// The gradient of the cost function.
+    ///
+    /// Returns a matrix of cost gradients.
+    fn cost_grad(&self, outputs: &Matrix<f64>, targets: &Matrix<f64>) -> Matrix<f64> {
+        Self::Cost::grad_cost(outputs, targets)
+    }
+
+    /// Returns the regularization for this criterion.
+    ///
+    /// Will return `Regularization::None` by default.
+    fn regularization(&self) -> Regularization<f64> {
+        Regularization::None
+    }
+
+    /// Checks if the current criterion includes regularization.
+    ///
+    /// Will return `false` by default.
+    fn is_regularized(&self) -> bool {
+        match self.regularization() {
+            Regularization::None => false,
+            _ => true,
+        }
+    }
+
+    /// Returns the regularization cost for the criterion.
+    ///
+    /// Will return `0` by default.
+    ///
+    /// This method will not be invoked by the neural network
+    /// if there is explicitly no regularization.
+    fn reg_cost(&self, reg_weights: MatrixSlice<f64>) -> f64 {
+        self.regularization().reg_cost(reg_weights)
+    }
+
+    /// Returns the regularization gradient for the criterion.
+    ///
+    /// Will return a matrix of zeros by default.
+    ///
+    /// This method will not be invoked by the neural network
+    /// if there is explicitly no regularization.
+    fn reg_cost_grad(&self, reg_weights: MatrixSlice<f64>) -> Matrix<f64> {
+        self.regularization().reg_grad(reg_weights)
+    }
+}
+
+/// The binary cross entropy criterion.
+///
+/// Uses the Sigmoid activation function and the
+/// cross entropy error.
+#[derive(Clone, Copy, Debug)]
+pub struct BCECriterion {
+    regularization: Regularization<f64>,
+}
+
+impl Criterion for BCECriterion {
+    type ActFunc = activ_fn::Sigmoid;
+    type Cost = cost_fn::CrossEntropyError;
+
+    fn regularization(&self) -> Regularization<f64> {
+        self.regularization
+    }
+}
+
+/// Creates an MSE Criterion without any regularization.
+impl Default for BCECriterion {
+    fn default() -> Self {
+        BCECriterion { regularization: Regularization::None }
+    }
+}
+
+impl BCECriterion {
+    /// Constructs a new BCECriterion with the given regularization.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::BCECriterion;
+    /// use rusty_machine::learning::toolkit::regularization::Regularization;
+    ///
+    /// // Create a new BCE criterion with L2 regularization of 0.3.
+    /// let criterion = BCECriterion::new(Regularization::L2(0.3f64));
+    /// ```
+    pub fn new(regularization: Regularization<f64>) -> Self {
+        BCECriterion { regularization: regularization }
+    }
+}
+
+/// The mean squared error criterion.
+///
+/// Uses the Linear activation function and the
+/// mean squared error.
+#[derive(Clone, Copy, Debug)]
+pub struct MSECriterion {
+    regularization: Regularization<f64>,
+}
+
+impl Criterion for MSECriterion {
+    type ActFunc = activ_fn::Linear;
+    type Cost = cost_fn::MeanSqError;
+
+    fn regularization(&self) -> Regularization<f64> {
+        self.regularization
+    }
+}
+
+/// Creates an MSE Criterion without any regularization.
+impl Default for MSECriterion {
+    fn default() -> Self {
+        MSECriterion { regularization: Regularization::None }
+    }
+}
+
+impl MSECriterion {
+    /// Constructs a new BCECriterion with the given regularization.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::nnet::MSECriterion;
+    /// use rusty_machine::learning::toolkit::regularization::Regularization;
+    ///
+    /// // Create a new MSE criterion with L2 regularization of 0.3.
+    /// let criterion = MSECriterion::new(Regularization::L2(0.3f64));
+    /// ```
+    pub fn new(regularization: Regularization<f64>) -> Self {
+        MSECriterion { regularization: regularization }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NeuralNet, BCECriterion, MSECriterion};
+    use learning::SupModel;
+    use learning::optim::grad_desc::{GradientDesc, StochasticGD};
+    use learning::toolkit::activ_fn::{ActivationFunc, Sigmoid};
+    use learning::toolkit::regularization::Regularization;
+    use linalg::{BaseMatrix, BaseMatrixMut, Matrix};
+
+    #[test]
+    fn test_seeded_nets_match() {
+        let layers = &[2, 3, 1];
+        let inputs = Matrix::new(4, 2, vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0]);
+        let targets = Matrix::new(4, 1, vec![0.0, 1.0, 1.0, 0.0]);
+
+        let net = |seed| {
+            let sgd = StochasticGD::new(0.1, 0.3, 5).with_seed(seed);
+            let mut net = NeuralNet::new(layers, BCECriterion::default(), sgd).with_seed(seed);
+            net.train(&inputs, &targets).unwrap();
+            net.predict(&inputs).unwrap()
+        };
+
+        assert_eq!(net(5), net(5));
+        assert!(net(5) != net(6));
+    }
+
+    #[test]
+    fn test_set_parameters_warm_starts() {
+        let layers = &[2, 3, 1];
+        let inputs = Matrix::new(4, 2, vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0]);
+        let targets = Matrix::new(4, 1, vec![0.0, 1.0, 1.0, 0.0]);
+        let gd = || GradientDesc::new(0.5, 20);
+
+        // Training twice continues from the first fit
+        let mut twice = NeuralNet::new(layers, BCECriterion::default(), gd()).with_seed(3);
+        twice.train(&inputs, &targets).unwrap();
+        let first = twice.parameters().to_vec();
+        twice.train(&inputs, &targets).unwrap();
+
+        let mut resumed = NeuralNet::new(layers, BCECriterion::default(), gd()).with_seed(9);
+        resumed.set_parameters(first).unwrap();
+        resumed.train(&inputs, &targets).unwrap();
+
+        assert_eq!(twice.parameters(), resumed.parameters());
+        assert!(resumed.set_parameters(vec![0.0; 3]).is_err());
+    }
+
+    /// Two well separated groups of points in the plane.
+    fn separable_data() -> (Matrix<f64>, Matrix<f64>) {
+        let mut inputs = Vec::new();
+        let mut targets = Vec::new();
+        for i in 0..40 {
+            let t = i as f64 / 40f64;
+            let class = (i % 2) as f64;
+            inputs.push(class * 2f64 - 1f64 + 0.3 * (7f64 * t).sin());
+            inputs.push(class * 2f64 - 1f64 + 0.3 * (11f64 * t).cos());
+            targets.push(class);
+        }
+        (Matrix::new(40, 2, inputs), Matrix::new(40, 1, targets))
+    }
+
+    #[test]
+    fn test_activations() {
+        let layers = &[2, 4, 3, 1];
+        let (inputs, targets) = separable_data();
+
+        let sgd = StochasticGD::new(0.1, 0.3, 5).with_seed(3);
+        let mut net = NeuralNet::new(layers, BCECriterion::default(), sgd).with_seed(3);
+        net.train(&inputs, &targets).unwrap();
+
+        let activations = net.activations(&inputs).unwrap();
+        assert_eq!(activations.len(), layers.len() - 1);
+        for (a, &size) in activations.iter().zip(layers[1..].iter()) {
+            assert_eq!(a.rows(), 40);
+            assert_eq!(a.cols(), size);
+        }
+        assert_eq!(activations[2], net.predict(&inputs).unwrap());
+
+        // Each layer is computed from the previous one
+        let hidden = Matrix::ones(40, 1).hcat(&activations[0]) *
+                     Matrix::from(net.get_net_weights(1));
+        assert_eq!(hidden.apply(&Sigmoid::func), activations[1]);
+
+        assert!(net.activations(&Matrix::zeros(1, 3)).is_err());
+    }
+
+    #[test]
+    fn test_frozen_layer_is_unchanged() {
+        let layers = &[2, 4, 1];
+        let (inputs, targets) = separable_data();
+
+        let sgd = StochasticGD::new(0.1, 0.3, 20).with_seed(2);
+        let criterion = BCECriterion::new(Regularization::L2(0.1));
+        let mut net = NeuralNet::new(layers, criterion, sgd).with_seed(2);
+        net.set_trainable(0, false);
+
+        let frozen = Matrix::from(net.get_net_weights(0));
+        let trained = Matrix::from(net.get_net_weights(1));
+        net.train(&inputs, &targets).unwrap();
+
+        assert_eq!(Matrix::from(net.get_net_weights(0)), frozen);
+        assert!(Matrix::from(net.get_net_weights(1)) != trained);
+
+        // Unfreezing lets the layer train again
+        net.set_trainable(0, true);
+        net.train(&inputs, &targets).unwrap();
+        assert!(Matrix::from(net.get_net_weights(0)) != frozen);
+    }
+
+    #[test]
+    fn test_train_minibatch_single_step() {
+        let layers = &[2, 4, 1];
+        let (inputs, targets) = separable_data();
+        let mut net = NeuralNet::new(layers, BCECriterion::default(), StochasticGD::default())
+            .with_seed(1);
+
+        let start = net.base.weights.clone();
+        let (_, grad) = net.base.compute_grad(&start, &inputs, &targets);
+
+        let batch = vec![(inputs.clone(), targets.clone())];
+        net.train_minibatch(batch.into_iter(), 0.5).unwrap();
+
+        for ((w, s), g) in net.base.weights.iter().zip(&start).zip(&grad) {
+            assert!((w - (s - 0.5 * g)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_train_minibatch_reduces_loss() {
+        let layers = &[2, 4, 1];
+        let (inputs, targets) = separable_data();
+        let mut net = NeuralNet::new(layers, BCECriterion::default(), StochasticGD::default())
+            .with_seed(1);
+        let loss = |net: &NeuralNet<BCECriterion, StochasticGD>| {
+            net.base.compute_grad(&net.base.weights, &inputs, &targets).0
+        };
+        let initial_loss = loss(&net);
+
+        // Many epochs over four batches, one step per batch
+        let batches = (0..200 * 4).map(|i| {
+            let b = i % 4;
+            let rows = (10 * b..10 * (b + 1)).collect::<Vec<_>>();
+            (inputs.select_rows(&rows), targets.select_rows(&rows))
+        });
+        net.train_minibatch(batches, 0.5).unwrap();
+
+        assert!(loss(&net) < 0.5 * initial_loss);
+    }
+
+    #[test]
+    fn test_train_minibatch_bad_dimensions() {
+        let layers = &[2, 3, 1];
+        let mut net = NeuralNet::default(layers);
+
+        let batches = vec![(Matrix::zeros(2, 3), Matrix::zeros(2, 1))];
+        assert!(net.train_minibatch(batches.into_iter(), 0.1).is_err());
+
+        let batches = vec![(Matrix::zeros(2, 2), Matrix::zeros(3, 1))];
+        assert!(net.train_minibatch(batches.into_iter(), 0.1).is_err());
+
+        let batches = vec![(Matrix::zeros(2, 2), Matrix::zeros(2, 1))];
+        assert!(net.train_minibatch(batches.into_iter(), 0.0).is_err());
+    }
+
+    #[test]
+    fn test_accumulated_gradient_matches_full_batch() {
+        // A single linear layer with the MSE loss
+        let layers = &[2, 1];
+        let (inputs, _) = separable_data();
+        let targets = inputs.iter_rows().map(|r| r[0] - 2f64 * r[1]).collect::<Vec<_>>();
+        let targets = Matrix::new(40, 1, targets);
+
+        for &criterion in &[MSECriterion::default(), MSECriterion::new(Regularization::L2(0.5))] {
+            let net = NeuralNet::new(layers, criterion, GradientDesc::new(0.1, 1)).with_seed(3);
+            let (full_cost, full_grad) = net.base.compute_grad(&net.base.weights, &inputs, &targets);
+
+            // Uneven micro-batches of 14, 14 and 12 rows
+            let mut accumulated = NeuralNet::new(layers, criterion, GradientDesc::new(0.1, 1))
+                .with_seed(3);
+            accumulated.set_accumulation_steps(3);
+            let (acc_cost, acc_grad) =
+                accumulated.base.compute_grad(&net.base.weights, &inputs, &targets);
+
+            assert!((full_cost - acc_cost).abs() < 1e-12);
+            for (g, a) in full_grad.iter().zip(acc_grad.iter()) {
+                assert!((g - a).abs() < 1e-12);
+            }
+
+            // One update step gives the same weights
+            let mut full_net = net;
+            full_net.train(&inputs, &targets).unwrap();
+            accumulated.train(&inputs, &targets).unwrap();
+            for (w, a) in full_net.base.weights.iter().zip(accumulated.base.weights.iter()) {
+                assert!((w - a).abs() < 1e-12);
+            }
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Coordinate Descent
+//!
+//! Implementation of cyclic coordinate descent for regularized
+//! least squares problems of the form
+//!
+//! ```text
+//! min_b (1/2n) |y - Xb|^2 + sum_j P(b_j)
+//! ```
+//!
+//! The routine handles the residual bookkeeping and convergence checks,
+//! while the penalty `P` enters only through a per-coordinate update. This
+//! lets lasso, elastic net and similar models share the same core by
+//! supplying their own update, typically built from `soft_threshold`.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::optim::coordinate_descent::{CoordinateDescent, soft_threshold};
+//! use rusty_machine::linalg::{Matrix, Vector};
+//!
+//! let inputs = Matrix::new(4, 2, vec![1.0, 0.0, 0.0, 1.0, 1.0, 1.0, -1.0, 1.0]);
+//! let targets = Vector::new(vec![2.0, 0.0, 2.0, -2.0]);
+//!
+//! // The lasso update with penalty 0.1
+//! let cd = CoordinateDescent::default();
+//! let (params, _) = cd.minimize(&inputs, &targets, &[0.0, 0.0],
+//!                               |rho, z| soft_threshold(rho, 0.1) / z);
+//!
+//! assert!(params[0] > 1.5);
+//! assert_eq!(params[1], 0.0);
+//! ```
+
+use linalg::{Matrix, BaseMatrix};
+use linalg::Vector;
+use rulinalg::utils;
+
+/// Cyclic Coordinate Descent algorithm
+#[derive(Clone, Copy, Debug)]
+pub struct CoordinateDescent {
+    /// The maximum number of sweeps over the coordinates.
+    iters: usize,
+    /// The largest coordinate change at which a sweep is considered converged.
+    tol: f64,
+}
+
+/// The default coordinate descent algorithm.
+///
+/// The defaults are:
+///
+/// - iters = 1000
+/// - tol = 1e-8
+impl Default for CoordinateDescent {
+    fn default() -> CoordinateDescent {
+        CoordinateDescent {
+            iters: 1000,
+            tol: 1e-8,
+        }
+    }
+}
+
+impl CoordinateDescent {
+    /// Construct a coordinate descent algorithm.
+    ///
+    /// Requires the maximum number of sweeps and the
+    /// convergence tolerance to be specified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::optim::coordinate_descent::CoordinateDescent;
+    ///
+    /// let cd = CoordinateDescent::new(500, 1e-6);
+    /// ```
+    pub fn new(iters: usize, tol: f64) -> CoordinateDescent {
+        assert!(tol > 0f64, "The tolerance must be greater than 0.");
+
+        CoordinateDescent {
+            iters,
+            tol,
+        }
+    }
+
+    /// The maximum number of sweeps over the coordinates.
+    pub fn iters(&self) -> usize {
+        self.iters
+    }
+
+    /// The convergence tolerance.
+    pub fn tol(&self) -> f64 {
+        self.tol
+    }
+
+    /// Minimize the regularized least squares objective from the given start.
+    ///
+    /// For each coordinate `j` the update is called as `update(rho, z)` where
+    ///
+    /// - `rho = x_j^T (y - Xb + x_j b_j) / n` is the correlation of the
+    ///   column with the partial residual, and
+    /// - `z = x_j^T x_j / n` is the scaled squared norm of the column.
+    ///
+    /// It should return the minimizer of `z b^2 / 2 - rho b + P(b)`. For
+    /// the unpenalized problem this is `rho / z`. Columns which are
+    /// entirely zero are skipped.
+    ///
+    /// Returns the parameters and the number of sweeps which were run.
+    ///
+    /// # Panics
+    ///
+    /// - The number of targets does not match the number of input rows.
+    /// - The start does not have one entry per input column.
+    pub fn minimize<F>(&self,
+                       inputs: &Matrix<f64>,
+                       targets: &Vector<f64>,
+                       start: &[f64],
+                       update: F)
+                       -> (Vec<f64>, usize)
+        where F: Fn(f64, f64) -> f64
+    {
+        assert!(targets.size() == inputs.rows(),
+                "The number of targets must match the number of input rows.");
+        assert!(start.len() == inputs.cols(),
+                "The start must have one parameter per input column.");
+
+        let n = inputs.rows() as f64;
+        let columns = inputs.transpose();
+        let sq_norms = columns.iter_rows().map(|x| utils::dot(x, x) / n).collect::<Vec<_>>();
+
+        let mut params = start.to_vec();
+        let mut residuals = (targets - inputs * Vector::new(start.to_vec())).into_vec();
+
+        for iter in 0..self.iters {
+            let mut max_change = 0f64;
+
+            for (j, x_j) in columns.iter_rows().enumerate() {
+                let z = sq_norms[j];
+                if z == 0f64 {
+                    continue;
+                }
+
+                let old = params[j];
+                let rho = utils::dot(x_j, &residuals) / n + z * old;
+                let new = update(rho, z);
+
+                let change = new - old;
+                if change != 0f64 {
+                    utils::in_place_vec_bin_op(&mut residuals, x_j, |r, &x| *r -= x * change);
+                    params[j] = new;
+                    max_change = max_change.max(change.abs());
+                }
+            }
+
+            if max_change < self.tol {
+                return (params, iter + 1);
+            }
+        }
+
+        (params, self.iters)
+    }
+}
+
+/// The soft-thresholding operator `sign(x) max(|x| - lambda, 0)`.
+///
+/// This is the proximal operator of `lambda |x|`, giving the
+/// coordinate update for L1 penalties.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::learning::optim::coordinate_descent::soft_threshold;
+///
+/// assert_eq!(soft_threshold(3.0, 1.0), 2.0);
+/// assert_eq!(soft_threshold(-3.0, 1.0), -2.0);
+/// assert_eq!(soft_threshold(0.5, 1.0), 0.0);
+/// ```
+pub fn soft_threshold(x: f64, lambda: f64) -> f64 {
+    if x > lambda {
+        x - lambda
+    } else if x < -lambda {
+        x + lambda
+    } else {
+        0f64
+    }
+}
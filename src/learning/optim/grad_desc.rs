@@ -13,6 +13,7 @@ use linalg::Vector;
 use linalg::{Matrix, BaseMatrix};
 use rulinalg::utils;
 
+use learning::optim::line_search::Backtracking;
 use learning::toolkit::rand_utils;
 
 const LEARNING_EPS: f64 = 1e-20;
@@ -24,6 +25,8 @@ pub struct GradientDesc {
     alpha: f64,
     /// The number of iterations to run.
     iters: usize,
+    /// The line search used to choose the step size.
+    line_search: Option<Backtracking>,
 }
 
 /// The default gradient descent algorithm.
@@ -37,6 +40,7 @@ impl Default for GradientDesc {
         GradientDesc {
             alpha: 0.3,
             iters: 100,
+            line_search: None,
         }
     }
 }
@@ -61,8 +65,28 @@ impl GradientDesc {
         GradientDesc {
             alpha: alpha,
             iters: iters,
+            line_search: None,
         }
     }
+
+    /// Chooses the step size of each iteration with a line search.
+    ///
+    /// Each search starts from the step size `alpha`, which is shrunk
+    /// until the cost decreases sufficiently. This prevents divergence
+    /// when `alpha` is too large for the problem.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::optim::grad_desc::GradientDesc;
+    /// use rusty_machine::learning::optim::line_search::Backtracking;
+    ///
+    /// let gd = GradientDesc::new(1.0, 100).with_line_search(Backtracking::default());
+    /// ```
+    pub fn with_line_search(mut self, line_search: Backtracking) -> GradientDesc {
+        self.line_search = Some(line_search);
+        self
+    }
 }
 
 impl<M: Optimizable> OptimAlgorithm<M> for GradientDesc {
@@ -86,8 +110,20 @@ impl<M: Optimizable> OptimAlgorithm<M> for GradientDesc {
             if (start_iter_cost - cost).abs() < LEARNING_EPS {
                 break;
             } else {
+                let direction = Vector::new(grad) * self.alpha;
+                let step = match self.line_search {
+                    Some(ls) => {
+                        let slope = direction.dot(&direction) / self.alpha;
+                        ls.search(|x| model.compute_cost(x.data(), inputs, targets),
+                                  &optimizing_val,
+                                  &direction,
+                                  cost,
+                                  slope)
+                    }
+                    None => 1f64,
+                };
                 // Update the optimal parameters using gradient descent
-                optimizing_val = &optimizing_val - Vector::new(grad) * self.alpha;
+                optimizing_val = &optimizing_val - direction * step;
                 // Update the latest cost
                 start_iter_cost = cost;
             }
@@ -107,6 +143,8 @@ pub struct StochasticGD {
     mu: f64,
     /// The number of passes through the data.
     iters: usize,
+    /// The seed used to shuffle the data.
+    seed: Option<u64>,
 }
 
 /// The default Stochastic GD algorithm.
@@ -122,6 +160,7 @@ impl Default for StochasticGD {
             alpha: 0.1,
             mu: 0.1,
             iters: 20,
+            seed: None,
         }
     }
 }
@@ -147,8 +186,23 @@ impl StochasticGD {
             alpha: alpha,
             mu: mu,
             iters: iters,
+            seed: None,
         }
     }
+
+    /// Sets the seed used to shuffle the data on each pass.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::optim::grad_desc::StochasticGD;
+    ///
+    /// let sgd = StochasticGD::default().with_seed(42);
+    /// ```
+    pub fn with_seed(mut self, seed: u64) -> StochasticGD {
+        self.seed = Some(seed);
+        self
+    }
 }
 
 impl<M> OptimAlgorithm<M> for StochasticGD
@@ -168,6 +222,7 @@ impl<M> OptimAlgorithm<M> for StochasticGD
 
         // Set up the indices for permutation
         let mut permutation = (0..inputs.rows()).collect::<Vec<_>>();
+        let mut rng = rand_utils::seeded_rng(self.seed);
         // The cost at the start of each iteration
         let mut start_iter_cost = 0f64;
 
@@ -175,7 +230,7 @@ impl<M> OptimAlgorithm<M> for StochasticGD
             // The cost at the end of each stochastic gd pass
             let mut end_cost = 0f64;
             // Permute the indices
-            rand_utils::in_place_fisher_yates(&mut permutation);
+            rand_utils::in_place_fisher_yates_with_rng(&mut permutation, &mut rng);
             for i in &permutation {
                 // Compute the cost and gradient for this data pair
                 let (cost, vec_data) = model.compute_grad(optimizing_val.data(),
@@ -212,6 +267,7 @@ pub struct AdaGrad {
     alpha: f64,
     tau: f64,
     iters: usize,
+    seed: Option<u64>,
 }
 
 impl AdaGrad {
@@ -235,8 +291,23 @@ impl AdaGrad {
             alpha: alpha,
             tau: tau,
             iters: iters,
+            seed: None,
         }
     }
+
+    /// Sets the seed used to shuffle the data on each pass.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::optim::grad_desc::AdaGrad;
+    ///
+    /// let gd = AdaGrad::default().with_seed(42);
+    /// ```
+    pub fn with_seed(mut self, seed: u64) -> AdaGrad {
+        self.seed = Some(seed);
+        self
+    }
 }
 
 impl Default for AdaGrad {
@@ -245,6 +316,7 @@ impl Default for AdaGrad {
             alpha: 1f64,
             tau: 3f64,
             iters: 100,
+            seed: None,
         }
     }
 }
@@ -264,6 +336,7 @@ impl<M: Optimizable<Inputs = Matrix<f64>, Targets = Matrix<f64>>> OptimAlgorithm
 
         // Set up the indices for permutation
         let mut permutation = (0..inputs.rows()).collect::<Vec<_>>();
+        let mut rng = rand_utils::seeded_rng(self.seed);
         // The cost at the start of each iteration
         let mut start_iter_cost = 0f64;
 
@@ -271,7 +344,7 @@ impl<M: Optimizable<Inputs = Matrix<f64>, Targets = Matrix<f64>>> OptimAlgorithm
             // The cost at the end of each stochastic gd pass
             let mut end_cost = 0f64;
             // Permute the indices
-            rand_utils::in_place_fisher_yates(&mut permutation);
+            rand_utils::in_place_fisher_yates_with_rng(&mut permutation, &mut rng);
             for i in &permutation {
                 // Compute the cost and gradient for this data pair
                 let (cost, mut vec_data) = model.compute_grad(optimizing_val.data(),
@@ -316,6 +389,8 @@ pub struct RMSProp {
     epsilon: f64,
     /// The number of passes through the data
     iters: usize,
+    /// The seed used to shuffle the data
+    seed: Option<u64>,
 }
 
 /// The default RMSProp configuration
@@ -332,7 +407,8 @@ impl Default for RMSProp {
             learning_rate: 0.01,
             decay_rate: 0.9,
             epsilon: 1.0e-5,
-            iters: 50
+            iters: 50,
+            seed: None,
         }
     }
 }
@@ -358,9 +434,24 @@ impl RMSProp {
             decay_rate: decay_rate,
             learning_rate: learning_rate,
             epsilon: epsilon,
-            iters: iters
+            iters: iters,
+            seed: None,
         }
     }
+
+    /// Sets the seed used to shuffle the data on each pass.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::optim::grad_desc::RMSProp;
+    ///
+    /// let rms = RMSProp::default().with_seed(42);
+    /// ```
+    pub fn with_seed(mut self, seed: u64) -> RMSProp {
+        self.seed = Some(seed);
+        self
+    }
 }
 
 impl<M> OptimAlgorithm<M> for RMSProp
@@ -378,6 +469,7 @@ impl<M> OptimAlgorithm<M> for RMSProp
 
         // Set up indices for permutation
         let mut permutation = (0..inputs.rows()).collect::<Vec<_>>();
+        let mut rng = rand_utils::seeded_rng(self.seed);
         // The cost from the previous iteration
         let mut prev_cost = 0f64;
 
@@ -385,7 +477,7 @@ impl<M> OptimAlgorithm<M> for RMSProp
             // The cost at end of each pass
             let mut end_cost = 0f64;
             // Permute the vertices
-            rand_utils::in_place_fisher_yates(&mut permutation);
+            rand_utils::in_place_fisher_yates_with_rng(&mut permutation, &mut rng);
             for i in &permutation {
                 let (cost, grad) = model.compute_grad(params.data(),
                                                       &inputs.select_rows(&[*i]),
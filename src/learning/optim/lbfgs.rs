@@ -0,0 +1,229 @@
+//! Limited-memory BFGS
+//!
+//! Implementation of the L-BFGS quasi-Newton algorithm. The algorithm
+//! keeps the most recent parameter and gradient differences and uses
+//! the two-loop recursion to apply an approximation of the inverse
+//! Hessian to the gradient.
+//!
+//! Only gradients are required. For smooth convex objectives, such as
+//! the losses of logistic regression and GLMs, this typically converges
+//! in far fewer iterations than gradient descent.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::optim::lbfgs::LBFGS;
+//! use rusty_machine::learning::logistic_reg::LogisticRegressor;
+//!
+//! let model = LogisticRegressor::new(LBFGS::default());
+//! ```
+
+use learning::optim::{Optimizable, OptimAlgorithm};
+use learning::optim::line_search::Backtracking;
+use linalg::{Metric, Vector};
+
+use std::collections::VecDeque;
+
+/// Limited-memory BFGS algorithm
+#[derive(Clone, Copy, Debug)]
+pub struct LBFGS {
+    /// The number of correction pairs to keep.
+    history: usize,
+    /// The maximum number of iterations.
+    iters: usize,
+    /// The gradient norm at which to stop.
+    tol: f64,
+    /// The line search used to choose the step size.
+    line_search: Option<Backtracking>,
+}
+
+/// The default L-BFGS algorithm.
+///
+/// The defaults are:
+///
+/// - history = 10
+/// - iters = 100
+/// - tol = 1e-8
+/// - line search = `Backtracking::default()`
+impl Default for LBFGS {
+    fn default() -> LBFGS {
+        LBFGS {
+            history: 10,
+            iters: 100,
+            tol: 1e-8,
+            line_search: Some(Backtracking::default()),
+        }
+    }
+}
+
+impl LBFGS {
+    /// Construct an L-BFGS algorithm.
+    ///
+    /// Requires the number of correction pairs to keep, the maximum
+    /// number of iterations and the gradient norm tolerance at which
+    /// to stop. Full quasi-Newton steps are taken unless a line search
+    /// is added with `with_line_search`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::optim::lbfgs::LBFGS;
+    ///
+    /// let lbfgs = LBFGS::new(5, 50, 1e-6);
+    /// ```
+    pub fn new(history: usize, iters: usize, tol: f64) -> LBFGS {
+        assert!(history > 0, "The history size must be greater than 0.");
+        assert!(tol > 0f64, "The tolerance must be greater than 0.");
+
+        LBFGS {
+            history,
+            iters,
+            tol,
+            line_search: None,
+        }
+    }
+
+    /// Chooses the step size of each iteration with a line search.
+    ///
+    /// Each search starts from the full quasi-Newton step, which is
+    /// shrunk until the cost decreases sufficiently. If the step is
+    /// not a descent direction the history is discarded and the
+    /// gradient is used instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::optim::lbfgs::LBFGS;
+    /// use rusty_machine::learning::optim::line_search::Backtracking;
+    ///
+    /// let lbfgs = LBFGS::new(5, 50, 1e-6).with_line_search(Backtracking::default());
+    /// ```
+    pub fn with_line_search(mut self, line_search: Backtracking) -> LBFGS {
+        self.line_search = Some(line_search);
+        self
+    }
+
+    /// The number of correction pairs kept.
+    pub fn history(&self) -> usize {
+        self.history
+    }
+
+    /// The maximum number of iterations.
+    pub fn iters(&self) -> usize {
+        self.iters
+    }
+
+    /// The gradient norm tolerance.
+    pub fn tol(&self) -> f64 {
+        self.tol
+    }
+}
+
+/// A stored correction pair `(s, y, 1 / s.y)`.
+type Correction = (Vector<f64>, Vector<f64>, f64);
+
+/// Applies the inverse Hessian approximation to the gradient.
+///
+/// This is the two-loop recursion. Without any corrections the
+/// gradient is scaled so that the step has length at most one.
+fn two_loop(grad: &Vector<f64>, history: &VecDeque<Correction>) -> Vector<f64> {
+    let mut q = grad.clone();
+    let mut alphas = Vec::with_capacity(history.len());
+
+    for (s, y, rho) in history.iter().rev() {
+        let a = rho * s.dot(&q);
+        q -= y * a;
+        alphas.push(a);
+    }
+
+    let gamma = match history.back() {
+        Some((s, y, _)) => s.dot(y) / y.dot(y),
+        None => 1f64 / grad.norm().max(1f64),
+    };
+    let mut r = q * gamma;
+
+    for ((s, y, rho), a) in history.iter().zip(alphas.iter().rev()) {
+        let b = rho * y.dot(&r);
+        r += s * (a - b);
+    }
+
+    r
+}
+
+impl<M: Optimizable> OptimAlgorithm<M> for LBFGS {
+    fn optimize(&self,
+                model: &M,
+                start: &[f64],
+                inputs: &M::Inputs,
+                targets: &M::Targets)
+                -> Vec<f64> {
+        let mut params = Vector::new(start.to_vec());
+        let (mut cost, grad) = model.compute_grad(params.data(), inputs, targets);
+        let mut grad = Vector::new(grad);
+        let mut history: VecDeque<Correction> = VecDeque::with_capacity(self.history);
+
+        for _ in 0..self.iters {
+            if grad.norm() < self.tol {
+                break;
+            }
+
+            let mut direction = two_loop(&grad, &history);
+
+            if let Some(ls) = self.line_search {
+                let mut slope = grad.dot(&direction);
+                if slope <= 0f64 {
+                    history.clear();
+                    direction = two_loop(&grad, &history);
+                    slope = grad.dot(&direction);
+                }
+
+                let step = ls.search(|x| model.compute_cost(x.data(), inputs, targets),
+                                     &params,
+                                     &direction,
+                                     cost,
+                                     slope);
+                direction *= step;
+            }
+
+            let new_params = &params - direction;
+            let (new_cost, new_grad) = model.compute_grad(new_params.data(), inputs, targets);
+            let new_grad = Vector::new(new_grad);
+
+            let s = &new_params - &params;
+            let y = &new_grad - &grad;
+            let sy = s.dot(&y);
+
+            // Only keep pairs with positive curvature so the
+            // approximation stays positive definite
+            if sy > 1e-10 * y.dot(&y) {
+                if history.len() == self.history {
+                    history.pop_front();
+                }
+                history.push_back((s, y, 1f64 / sy));
+            }
+
+            params = new_params;
+            grad = new_grad;
+            cost = new_cost;
+        }
+
+        params.into_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LBFGS;
+
+    #[test]
+    #[should_panic]
+    fn lbfgs_zero_history() {
+        let _ = LBFGS::new(0, 10, 1e-6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn lbfgs_neg_tolerance() {
+        let _ = LBFGS::new(5, 10, -1e-6);
+    }
+}
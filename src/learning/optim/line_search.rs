@@ -0,0 +1,167 @@
+//! Line Search
+//!
+//! Implementation of a backtracking line search using the Armijo
+//! (sufficient decrease) condition. This is used by the gradient
+//! descent and L-BFGS algorithms to choose the step size on each
+//! iteration, preventing divergence when a fixed step is too large.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::optim::grad_desc::GradientDesc;
+//! use rusty_machine::learning::optim::line_search::Backtracking;
+//!
+//! // Start each search from a step of 1 and halve it until
+//! // the cost decreases sufficiently
+//! let gd = GradientDesc::new(1.0, 100).with_line_search(Backtracking::default());
+//! ```
+
+use linalg::Vector;
+
+/// Backtracking line search with the Armijo condition.
+///
+/// Starting from an initial step `t` along a descent direction `d`, the
+/// step is shrunk by the factor `rho` until
+///
+/// ```text
+/// f(x - t d) <= f(x) - c t (g . d)
+/// ```
+///
+/// where `g` is the gradient at `x`.
+#[derive(Clone, Copy, Debug)]
+pub struct Backtracking {
+    /// The sufficient decrease constant.
+    c: f64,
+    /// The factor the step is shrunk by.
+    rho: f64,
+    /// The maximum number of times the step is shrunk.
+    max_steps: usize,
+}
+
+/// The default backtracking line search.
+///
+/// The defaults are:
+///
+/// - c = 1e-4
+/// - rho = 0.5
+/// - max_steps = 50
+impl Default for Backtracking {
+    fn default() -> Backtracking {
+        Backtracking {
+            c: 1e-4,
+            rho: 0.5,
+            max_steps: 50,
+        }
+    }
+}
+
+impl Backtracking {
+    /// Construct a backtracking line search.
+    ///
+    /// Requires the sufficient decrease constant, the shrink factor and
+    /// the maximum number of times the step is shrunk.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::optim::line_search::Backtracking;
+    ///
+    /// let ls = Backtracking::new(1e-3, 0.8, 20);
+    /// ```
+    pub fn new(c: f64, rho: f64, max_steps: usize) -> Backtracking {
+        assert!(0f64 < c && c < 1f64, "The constant c must be between 0 and 1.");
+        assert!(0f64 < rho && rho < 1f64, "The shrink factor must be between 0 and 1.");
+
+        Backtracking {
+            c,
+            rho,
+            max_steps,
+        }
+    }
+
+    /// Finds a step size along the descent direction.
+    ///
+    /// The parameters are moved to `params - t * direction` and `cost`
+    /// evaluates the cost at the new parameters. The `cost_0` and `slope`
+    /// are the cost and the dot product of the gradient with the direction
+    /// at `params`. Returns the first step, starting from `1`, which
+    /// satisfies the Armijo condition. If none is found within `max_steps`
+    /// the smallest step tried, `rho^(max_steps - 1)`, is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::optim::line_search::Backtracking;
+    /// use rusty_machine::linalg::Vector;
+    ///
+    /// // The cost x^2 at x = 1, with the gradient as the direction
+    /// let cost = |x: &Vector<f64>| x[0] * x[0];
+    /// let x = Vector::new(vec![1.0]);
+    /// let grad = Vector::new(vec![2.0]);
+    ///
+    /// let step = Backtracking::default().search(cost, &x, &grad, 1.0, 4.0);
+    /// assert_eq!(step, 0.5);
+    /// ```
+    pub fn search<F>(&self,
+                     cost: F,
+                     params: &Vector<f64>,
+                     direction: &Vector<f64>,
+                     cost_0: f64,
+                     slope: f64)
+                     -> f64
+        where F: Fn(&Vector<f64>) -> f64
+    {
+        let mut step = 1f64;
+
+        for i in 0..self.max_steps {
+            let candidate = params - direction * step;
+
+            if cost(&candidate) <= cost_0 - self.c * step * slope {
+                break;
+            }
+
+            // Only shrink to steps which will be tried
+            if i + 1 < self.max_steps {
+                step *= self.rho;
+            }
+        }
+
+        step
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Backtracking;
+
+    #[test]
+    #[should_panic]
+    fn backtracking_invalid_c() {
+        let _ = Backtracking::new(1.5, 0.5, 10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn backtracking_invalid_rho() {
+        let _ = Backtracking::new(1e-4, 0f64, 10);
+    }
+
+    #[test]
+    fn backtracking_returns_last_step_tried() {
+        use linalg::Vector;
+        use std::cell::RefCell;
+
+        // The cost never decreases, so every step is rejected
+        let tried = RefCell::new(Vec::new());
+        let cost = |x: &Vector<f64>| {
+            tried.borrow_mut().push(1.0 - x[0]);
+            10.0
+        };
+
+        let ls = Backtracking::new(1e-4, 0.5, 3);
+        let step = ls.search(cost, &Vector::new(vec![1.0]), &Vector::new(vec![1.0]), 1.0, 1.0);
+
+        assert_eq!(*tried.borrow(), vec![1.0, 0.5, 0.25]);
+        assert_eq!(step, 0.25);
+    }
+}
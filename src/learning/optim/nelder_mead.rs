@@ -0,0 +1,299 @@
+//! Nelder-Mead simplex search
+//!
+//! Implementation of the derivative-free Nelder-Mead algorithm. The
+//! algorithm keeps a simplex of `n + 1` points in the `n` dimensional
+//! parameter space. Each iteration it tries to replace the worst point by
+//! reflecting it through the centroid of the others, expanding or
+//! contracting the step depending on how good the new point is. If none of
+//! these help the simplex shrinks towards the best point.
+//!
+//! Only the costs are used, so the algorithm works for non-smooth and
+//! black-box objectives. It is slower than the gradient-based algorithms
+//! on smooth problems and best suited to a small number of parameters.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::optim::nelder_mead::NelderMead;
+//! use rusty_machine::linalg::Vector;
+//!
+//! // Minimize |x - 1| + |y + 2|, which has no gradient at the minimum
+//! let f = |x: &Vector<f64>| (x[0] - 1.0).abs() + (x[1] + 2.0).abs();
+//!
+//! let x = NelderMead::default().minimize(f, &Vector::zeros(2));
+//! assert!((x[0] - 1.0).abs() < 1e-4 && (x[1] + 2.0).abs() < 1e-4);
+//! ```
+
+use learning::optim::{Optimizable, OptimAlgorithm};
+use linalg::Vector;
+
+use std::cmp::Ordering;
+
+/// Nelder-Mead simplex algorithm
+#[derive(Clone, Copy, Debug)]
+pub struct NelderMead {
+    /// The maximum number of iterations.
+    iters: usize,
+    /// The spread of the simplex at which to stop.
+    tol: f64,
+    /// The reflection coefficient.
+    reflection: f64,
+    /// The expansion coefficient.
+    expansion: f64,
+    /// The contraction coefficient.
+    contraction: f64,
+    /// The shrink coefficient.
+    shrink: f64,
+}
+
+/// The default Nelder-Mead algorithm.
+///
+/// The defaults are:
+///
+/// - iters = 1000
+/// - tol = 1e-8
+/// - reflection = 1
+/// - expansion = 2
+/// - contraction = 0.5
+/// - shrink = 0.5
+impl Default for NelderMead {
+    fn default() -> NelderMead {
+        NelderMead::new(1000, 1e-8)
+    }
+}
+
+impl NelderMead {
+    /// Construct a Nelder-Mead algorithm.
+    ///
+    /// Requires the maximum number of iterations and the tolerance. The
+    /// search stops once both the costs and the coordinates of the simplex
+    /// points are within the tolerance of the best point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::optim::nelder_mead::NelderMead;
+    ///
+    /// let nm = NelderMead::new(500, 1e-6);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The tolerance is not positive.
+    pub fn new(iters: usize, tol: f64) -> NelderMead {
+        assert!(tol > 0f64, "The tolerance must be greater than 0.");
+
+        NelderMead {
+            iters,
+            tol,
+            reflection: 1f64,
+            expansion: 2f64,
+            contraction: 0.5,
+            shrink: 0.5,
+        }
+    }
+
+    /// Sets the reflection, expansion, contraction and shrink coefficients.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::optim::nelder_mead::NelderMead;
+    ///
+    /// let nm = NelderMead::default().with_coefficients(1.0, 3.0, 0.25, 0.5);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The reflection is not positive.
+    /// - The expansion is not greater than both one and the reflection.
+    /// - The contraction or shrink is not in (0, 1).
+    pub fn with_coefficients(mut self,
+                             reflection: f64,
+                             expansion: f64,
+                             contraction: f64,
+                             shrink: f64)
+                             -> NelderMead {
+        assert!(reflection > 0f64, "The reflection must be greater than 0.");
+        assert!(expansion > 1f64 && expansion > reflection,
+                "The expansion must be greater than 1 and the reflection.");
+        assert!(contraction > 0f64 && contraction < 1f64,
+                "The contraction must be in (0, 1).");
+        assert!(shrink > 0f64 && shrink < 1f64, "The shrink must be in (0, 1).");
+
+        self.reflection = reflection;
+        self.expansion = expansion;
+        self.contraction = contraction;
+        self.shrink = shrink;
+        self
+    }
+
+    /// Minimizes the function starting from `x0`.
+    ///
+    /// The initial simplex perturbs each coordinate of `x0` in turn by 5%,
+    /// or by `0.00025` for coordinates which are zero.
+    pub fn minimize<F>(&self, f: F, x0: &Vector<f64>) -> Vector<f64>
+        where F: Fn(&Vector<f64>) -> f64
+    {
+        Vector::new(self.search(|x| f(&Vector::new(x)), x0.data()))
+    }
+
+    /// Runs the simplex search on a function of a slice.
+    fn search<F: Fn(&[f64]) -> f64>(&self, f: F, start: &[f64]) -> Vec<f64> {
+        let n = start.len();
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let mut simplex = Vec::with_capacity(n + 1);
+        simplex.push(start.to_vec());
+        for i in 0..n {
+            let mut point = start.to_vec();
+            point[i] = if point[i] == 0f64 { 0.00025 } else { 1.05 * point[i] };
+            simplex.push(point);
+        }
+        let mut costs = simplex.iter().map(|x| f(x)).collect::<Vec<_>>();
+
+        for _ in 0..self.iters {
+            // Order the points from best to worst
+            let mut order = (0..n + 1).collect::<Vec<_>>();
+            order.sort_by(|&a, &b| costs[a].partial_cmp(&costs[b]).unwrap_or(Ordering::Equal));
+            simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+            costs = order.iter().map(|&i| costs[i]).collect();
+
+            if self.converged(&simplex, &costs) {
+                break;
+            }
+
+            let mut centroid = vec![0f64; n];
+            for point in &simplex[..n] {
+                for (c, x) in centroid.iter_mut().zip(point) {
+                    *c += x / n as f64;
+                }
+            }
+
+            let worst = simplex[n].clone();
+            let reflected = towards(&centroid, &worst, -self.reflection);
+            let reflected_cost = f(&reflected);
+
+            if reflected_cost < costs[0] {
+                let expanded = towards(&centroid, &worst, -self.reflection * self.expansion);
+                let expanded_cost = f(&expanded);
+                if expanded_cost < reflected_cost {
+                    simplex[n] = expanded;
+                    costs[n] = expanded_cost;
+                } else {
+                    simplex[n] = reflected;
+                    costs[n] = reflected_cost;
+                }
+                continue;
+            }
+
+            if reflected_cost < costs[n - 1] {
+                simplex[n] = reflected;
+                costs[n] = reflected_cost;
+                continue;
+            }
+
+            // Contract outside the simplex if the reflection improved on the worst point
+            let (contracted, bound) = if reflected_cost < costs[n] {
+                (towards(&centroid, &worst, -self.reflection * self.contraction), reflected_cost)
+            } else {
+                (towards(&centroid, &worst, self.contraction), costs[n])
+            };
+            let contracted_cost = f(&contracted);
+
+            if contracted_cost < bound {
+                simplex[n] = contracted;
+                costs[n] = contracted_cost;
+            } else {
+                let best = simplex[0].clone();
+                for i in 1..n + 1 {
+                    simplex[i] = towards(&best, &simplex[i], self.shrink);
+                    costs[i] = f(&simplex[i]);
+                }
+            }
+        }
+
+        let best = (1..n + 1).fold(0, |b, i| if costs[i] < costs[b] { i } else { b });
+        simplex.swap_remove(best)
+    }
+
+    /// Whether the simplex has collapsed onto its best point.
+    fn converged(&self, simplex: &[Vec<f64>], costs: &[f64]) -> bool {
+        let cost_spread = costs[1..].iter().all(|c| (c - costs[0]).abs() <= self.tol);
+        let point_spread = simplex[1..].iter().all(|point| {
+            point.iter().zip(&simplex[0]).all(|(x, b)| (x - b).abs() <= self.tol)
+        });
+        cost_spread && point_spread
+    }
+}
+
+/// The point `from + t (to - from)`.
+fn towards(from: &[f64], to: &[f64], t: f64) -> Vec<f64> {
+    from.iter().zip(to).map(|(a, b)| a + t * (b - a)).collect()
+}
+
+/// Minimizes the cost of the model, ignoring the gradient.
+impl<M: Optimizable> OptimAlgorithm<M> for NelderMead {
+    fn optimize(&self,
+                model: &M,
+                start: &[f64],
+                inputs: &M::Inputs,
+                targets: &M::Targets)
+                -> Vec<f64> {
+        self.search(|x| model.compute_cost(x, inputs, targets), start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NelderMead;
+    use learning::optim::minimize;
+    use linalg::Vector;
+
+    fn rosenbrock(x: &Vector<f64>) -> f64 {
+        (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2)
+    }
+
+    #[test]
+    fn test_rosenbrock_minimum() {
+        let x = NelderMead::new(2000, 1e-10).minimize(rosenbrock, &Vector::new(vec![-1.2, 1.0]));
+
+        assert!((x[0] - 1.0).abs() < 1e-4);
+        assert!((x[1] - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_non_smooth_minimum() {
+        let f = |x: &Vector<f64>| (x[0] - 2.0).abs() + 3.0 * (x[1] + 1.0).abs();
+
+        let x = NelderMead::default().minimize(f, &Vector::new(vec![0.5, 0.5]));
+        assert!((x[0] - 2.0).abs() < 1e-4);
+        assert!((x[1] + 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_optimizes_objective() {
+        // The gradient is ignored, so a wrong one does not matter
+        let objective = |x: &Vector<f64>| ((x[0] - 4.0).powi(2), Vector::zeros(1));
+
+        let x = minimize(objective, &Vector::ones(1), &NelderMead::default());
+        assert!((x[0] - 4.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_custom_coefficients() {
+        let nm = NelderMead::new(2000, 1e-10).with_coefficients(1.0, 2.5, 0.4, 0.6);
+        let x = nm.minimize(rosenbrock, &Vector::new(vec![2.0, -1.0]));
+
+        assert!((x[0] - 1.0).abs() < 1e-3);
+        assert!((x[1] - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_contraction() {
+        let _ = NelderMead::default().with_coefficients(1.0, 2.0, 1.5, 0.5);
+    }
+}
@@ -0,0 +1,232 @@
+//! Custom objectives
+//!
+//! Allows the optimization algorithms to minimize any function for
+//! which the value and gradient can be computed, not just the costs
+//! of the models.
+//!
+//! The objective is a closure taking the parameters and returning the
+//! value and the gradient at those parameters. It is wrapped in a
+//! `FnObjective`, which implements `Optimizable` without any inputs or
+//! targets. Algorithms which sample the inputs, such as `StochasticGD`,
+//! therefore cannot be used.
+//!
+//! When the gradient is not known it can be approximated with
+//! `numerical_gradient`, which is also useful for checking an
+//! analytic gradient.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::optim::minimize;
+//! use rusty_machine::learning::optim::lbfgs::LBFGS;
+//! use rusty_machine::linalg::Vector;
+//!
+//! // Minimize (x - 3)^2 + (y + 1)^2
+//! let objective = |x: &Vector<f64>| {
+//!     let value = (x[0] - 3.0).powi(2) + (x[1] + 1.0).powi(2);
+//!     let grad = Vector::new(vec![2.0 * (x[0] - 3.0), 2.0 * (x[1] + 1.0)]);
+//!     (value, grad)
+//! };
+//!
+//! let x = minimize(objective, &Vector::zeros(2), &LBFGS::default());
+//! assert!((x[0] - 3.0).abs() < 1e-6 && (x[1] + 1.0).abs() < 1e-6);
+//! ```
+
+use learning::optim::{Optimizable, OptimAlgorithm};
+use linalg::Vector;
+
+use libnum::{Float, FromPrimitive};
+
+use std::fmt;
+
+/// An objective function to be minimized.
+///
+/// The function returns the value and gradient at the given parameters.
+pub struct FnObjective<F>
+    where F: Fn(&Vector<f64>) -> (f64, Vector<f64>)
+{
+    objective: F,
+}
+
+impl<F> fmt::Debug for FnObjective<F>
+    where F: Fn(&Vector<f64>) -> (f64, Vector<f64>)
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FnObjective").finish()
+    }
+}
+
+impl<F> FnObjective<F>
+    where F: Fn(&Vector<f64>) -> (f64, Vector<f64>)
+{
+    /// Wraps a function returning the value and gradient of an objective.
+    pub fn new(objective: F) -> FnObjective<F> {
+        FnObjective { objective }
+    }
+}
+
+impl<F> Optimizable for FnObjective<F>
+    where F: Fn(&Vector<f64>) -> (f64, Vector<f64>)
+{
+    type Inputs = ();
+    type Targets = ();
+
+    fn compute_grad(&self, params: &[f64], _: &(), _: &()) -> (f64, Vec<f64>) {
+        let (value, grad) = (self.objective)(&Vector::new(params));
+        assert!(grad.size() == params.len(),
+                "The gradient must have one entry per parameter.");
+        (value, grad.into_vec())
+    }
+}
+
+/// Minimizes the objective starting from `x0` using the given algorithm.
+///
+/// The objective returns the value and gradient at the parameters it is
+/// given. The algorithm's own stopping criteria decide when to stop, so
+/// the returned parameters are only as accurate as it allows.
+///
+/// # Panics
+///
+/// - The objective returns a gradient of the wrong length.
+pub fn minimize<F, A>(objective: F, x0: &Vector<f64>, alg: &A) -> Vector<f64>
+    where F: Fn(&Vector<f64>) -> (f64, Vector<f64>),
+          A: OptimAlgorithm<FnObjective<F>>
+{
+    let model = FnObjective::new(objective);
+    Vector::new(alg.optimize(&model, x0.data(), &(), &()))
+}
+
+/// Approximates the gradient of `f` at `x` using central differences.
+///
+/// Each entry is `(f(x + eps e_i) - f(x - eps e_i)) / 2 eps`, which has an
+/// error proportional to `eps^2` for smooth functions. This costs two
+/// evaluations of `f` per parameter. Values of `eps` around the cube root
+/// of the machine epsilon, about `1e-5` for `f64`, balance the truncation
+/// and rounding errors.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::learning::optim::numerical_gradient;
+/// use rusty_machine::linalg::Vector;
+///
+/// let f = |x: &Vector<f64>| x[0] * x[0] + 3.0 * x[1];
+/// let grad = numerical_gradient(f, &Vector::new(vec![2.0, 1.0]), 1e-5);
+///
+/// assert!((grad[0] - 4.0).abs() < 1e-8);
+/// assert!((grad[1] - 3.0).abs() < 1e-8);
+/// ```
+///
+/// # Panics
+///
+/// - `eps` is not positive.
+pub fn numerical_gradient<T, F>(f: F, x: &Vector<T>, eps: T) -> Vector<T>
+    where T: Float + FromPrimitive,
+          F: Fn(&Vector<T>) -> T
+{
+    assert!(eps > T::zero(), "The step size must be positive.");
+
+    let two_eps = T::from_f64(2.0).unwrap() * eps;
+    let mut point = x.clone();
+    let grad = (0..x.size())
+        .map(|i| {
+            point[i] = x[i] + eps;
+            let forward = f(&point);
+            point[i] = x[i] - eps;
+            let backward = f(&point);
+            point[i] = x[i];
+            (forward - backward) / two_eps
+        })
+        .collect::<Vec<_>>();
+    Vector::new(grad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{minimize, numerical_gradient};
+    use learning::optim::fmincg::ConjugateGD;
+    use learning::optim::grad_desc::GradientDesc;
+    use learning::optim::lbfgs::LBFGS;
+    use linalg::Vector;
+
+    fn rosenbrock(x: &Vector<f64>) -> (f64, Vector<f64>) {
+        let (a, b) = (x[0], x[1]);
+        let value = (1.0 - a).powi(2) + 100.0 * (b - a * a).powi(2);
+        let grad = Vector::new(vec![-2.0 * (1.0 - a) - 400.0 * a * (b - a * a),
+                                    200.0 * (b - a * a)]);
+        (value, grad)
+    }
+
+    #[test]
+    fn test_rosenbrock_minimum() {
+        let start = Vector::new(vec![-1.2, 1.0]);
+
+        let x = minimize(rosenbrock, &start, &LBFGS::new(10, 500, 1e-10));
+        assert!((x[0] - 1.0).abs() < 1e-4);
+        assert!((x[1] - 1.0).abs() < 1e-4);
+        assert!(rosenbrock(&x).0 < 1e-8);
+
+        let x = minimize(rosenbrock, &start, &ConjugateGD { iters: 500, ..ConjugateGD::default() });
+        assert!((x[0] - 1.0).abs() < 1e-3);
+        assert!((x[1] - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_quadratic_with_gradient_descent() {
+        let target = Vector::new(vec![1.0, -2.0, 0.5]);
+        let objective = |x: &Vector<f64>| {
+            let diff = x - &target;
+            (diff.dot(&diff), diff * 2.0)
+        };
+
+        let x = minimize(objective, &Vector::zeros(3), &GradientDesc::new(0.1, 200));
+        for i in 0..3 {
+            assert!((x[i] - target[i]).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_gradient_length() {
+        let objective = |x: &Vector<f64>| (x.dot(x), Vector::zeros(1));
+        let _ = minimize(objective, &Vector::ones(2), &GradientDesc::default());
+    }
+
+    #[test]
+    fn test_numerical_gradient_of_quadratic() {
+        // f(x) = x^T A x / 2 + b^T x has gradient A x + b
+        let a = [[2.0, 0.5, 0.0], [0.5, 1.0, -0.3], [0.0, -0.3, 4.0]];
+        let b = [1.0, -1.0, 0.25];
+        let f = |x: &Vector<f64>| {
+            let mut value = 0.0;
+            for i in 0..3 {
+                for j in 0..3 {
+                    value += 0.5 * x[i] * a[i][j] * x[j];
+                }
+                value += b[i] * x[i];
+            }
+            value
+        };
+
+        let x = Vector::new(vec![0.3, -1.2, 2.0]);
+        let grad = numerical_gradient(f, &x, 1e-5);
+        for i in 0..3 {
+            let exact = (0..3).map(|j| a[i][j] * x[j]).sum::<f64>() + b[i];
+            assert!((grad[i] - exact).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_numerical_gradient_checks_rosenbrock() {
+        let x = Vector::new(vec![-0.7, 1.3]);
+        let grad = numerical_gradient(|x| rosenbrock(x).0, &x, 1e-5);
+        let exact = rosenbrock(&x).1;
+
+        for i in 0..2 {
+            assert!((grad[i] - exact[i]).abs() < 1e-5 * exact[i].abs().max(1.0));
+        }
+
+        let grad = numerical_gradient(|x: &Vector<f32>| x[0] * x[0], &Vector::new(vec![1.5f32]), 1e-2);
+        assert!((grad[0] - 3.0).abs() < 1e-3);
+    }
+}
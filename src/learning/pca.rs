@@ -0,0 +1,569 @@
+//! Principal Component Analysis Module
+//!
+//! Contains an implementation of incremental Principal Component Analysis.
+//!
+//! The `IncrementalPCA` model updates its principal subspace with
+//! batches of data via an incremental SVD. This allows PCA to be
+//! computed for data sets which are too large to fit in memory.
+//!
+//! The data is centered by its running mean, which is stored and used
+//! to center new data in `predict`. Centering can be disabled with
+//! `set_center(false)` for data which is already centered, or to
+//! compute the uncentered principal subspace.
+//!
+//! With `set_scale(true)` the features are also divided by their running
+//! standard deviation, giving the principal components of the correlation
+//! matrix. The scales are stored and used to scale new data in `predict`.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::pca::IncrementalPCA;
+//! use rusty_machine::linalg::{Matrix, BaseMatrix};
+//!
+//! let batch_1 = Matrix::new(3, 2, vec![1.0, 2.0, 2.0, 4.1, 3.0, 5.9]);
+//! let batch_2 = Matrix::new(3, 2, vec![4.0, 8.1, 5.0, 9.9, 6.0, 12.0]);
+//!
+//! // Keep the single principal component
+//! let mut model = IncrementalPCA::new(1);
+//!
+//! model.partial_fit(&batch_1).unwrap();
+//! model.partial_fit(&batch_2).unwrap();
+//!
+//! // The components are stored in the columns
+//! let components = model.components().unwrap();
+//! assert_eq!(components.cols(), 1);
+//! ```
+
+use linalg::{Matrix, BaseMatrix, BaseMatrixMut, Axes};
+use linalg::Vector;
+use rulinalg::utils;
+
+use data::stats::RunningStats;
+use learning::{LearningResult, UnSupModel};
+use learning::error::{Error, ErrorKind};
+
+/// Incremental Principal Component Analysis
+///
+/// Uses the incremental SVD of Ross et al. (2008) to update the
+/// principal components as each batch of data arrives.
+#[derive(Debug)]
+pub struct IncrementalPCA {
+    /// The number of components to keep.
+    n_components: usize,
+    /// The number of samples seen so far.
+    n_samples_seen: usize,
+    /// The mean of the samples seen so far.
+    mean: Option<Vector<f64>>,
+    /// The singular values of the centered data seen so far.
+    singular_values: Option<Vector<f64>>,
+    /// The principal components, stored in columns.
+    components: Option<Matrix<f64>>,
+    /// Whether the data is centered by its mean.
+    center: bool,
+    /// Whether the features are scaled to unit variance.
+    scale: bool,
+    /// The standard deviations the features are divided by.
+    input_scale: Option<Vector<f64>>,
+    /// The running statistics used to estimate the scales.
+    stats: RunningStats<f64>,
+}
+
+impl IncrementalPCA {
+    /// Constructs an untrained incremental PCA model
+    /// which keeps `n_components` principal components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::pca::IncrementalPCA;
+    ///
+    /// let model = IncrementalPCA::new(3);
+    /// ```
+    pub fn new(n_components: usize) -> IncrementalPCA {
+        assert!(n_components > 0, "The number of components must be positive.");
+
+        IncrementalPCA {
+            n_components,
+            n_samples_seen: 0,
+            mean: None,
+            singular_values: None,
+            components: None,
+            center: true,
+            scale: false,
+            input_scale: None,
+            stats: RunningStats::default(),
+        }
+    }
+
+    /// Whether the data is centered by its mean.
+    pub fn center(&self) -> bool {
+        self.center
+    }
+
+    /// Set whether the data is centered by its mean.
+    ///
+    /// Without centering the stored mean is zero. This should be
+    /// set before fitting, and defaults to `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::pca::IncrementalPCA;
+    ///
+    /// let mut model = IncrementalPCA::new(2);
+    /// model.set_center(false);
+    /// ```
+    pub fn set_center(&mut self, center: bool) {
+        self.center = center;
+    }
+
+    /// Whether the features are scaled to unit variance.
+    pub fn scale(&self) -> bool {
+        self.scale
+    }
+
+    /// Set whether the features are scaled to unit variance.
+    ///
+    /// The scales are the running standard deviations of the features,
+    /// with features of zero variance left unscaled. The subspace fitted
+    /// so far is rescaled whenever a batch changes them. This should be
+    /// set before fitting, and defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::pca::IncrementalPCA;
+    ///
+    /// let mut model = IncrementalPCA::new(2);
+    /// model.set_scale(true);
+    /// ```
+    pub fn set_scale(&mut self, scale: bool) {
+        self.scale = scale;
+    }
+
+    /// The number of components kept by the model.
+    pub fn n_components(&self) -> usize {
+        self.n_components
+    }
+
+    /// The number of samples the model has been fitted to.
+    pub fn n_samples_seen(&self) -> usize {
+        self.n_samples_seen
+    }
+
+    /// The mean of the data the model has been fitted to.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn mean(&self) -> Option<&Vector<f64>> {
+        self.mean.as_ref()
+    }
+
+    /// The standard deviations the features are divided by.
+    ///
+    /// These are all one without scaling. Returns `None` if the model
+    /// has not been trained.
+    pub fn input_scale(&self) -> Option<&Vector<f64>> {
+        self.input_scale.as_ref()
+    }
+
+    /// The principal components of the model.
+    ///
+    /// Each column of the matrix is a principal component,
+    /// ordered by decreasing explained variance.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn components(&self) -> Option<&Matrix<f64>> {
+        self.components.as_ref()
+    }
+
+    /// The singular values corresponding to each component.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn singular_values(&self) -> Option<&Vector<f64>> {
+        self.singular_values.as_ref()
+    }
+
+    /// The variance explained by each component.
+    ///
+    /// Returns `None` if the model has been trained on fewer than two samples.
+    pub fn explained_variance(&self) -> Option<Vector<f64>> {
+        if self.n_samples_seen < 2 {
+            return None;
+        }
+
+        let n = (self.n_samples_seen - 1) as f64;
+        self.singular_values.as_ref().map(|s| s.elemul(s) / n)
+    }
+
+    /// Update the principal components with a batch of data.
+    ///
+    /// The first batch must contain at least `n_components` rows, and the
+    /// number of components cannot exceed the number of features.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::pca::IncrementalPCA;
+    /// use rusty_machine::linalg::Matrix;
+    ///
+    /// let mut model = IncrementalPCA::new(2);
+    ///
+    /// let batch = Matrix::new(3, 2, vec![1.0, 2.0, 2.0, 1.0, 0.0, 3.0]);
+    /// model.partial_fit(&batch).unwrap();
+    /// ```
+    pub fn partial_fit(&mut self, inputs: &Matrix<f64>) -> LearningResult<()> {
+        if inputs.rows() == 0 {
+            return Err(Error::new(ErrorKind::InvalidData, "Cannot fit to an empty batch."));
+        }
+
+        if self.n_components > inputs.cols() {
+            return Err(Error::new(ErrorKind::InvalidParameters,
+                                  "Number of components exceeds the number of features."));
+        }
+
+        let batch_mean = if self.center {
+            inputs.mean(Axes::Row)
+        } else {
+            Vector::zeros(inputs.cols())
+        };
+        let batch_size = inputs.rows();
+
+        if let Some(ref mean) = self.mean {
+            if inputs.cols() != mean.size() {
+                return Err(Error::new(ErrorKind::InvalidData,
+                                      "Inputs have different feature count than the model."));
+            }
+        } else if batch_size < self.n_components {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "First batch must have at least n_components rows."));
+        }
+
+        let new_scale = if self.scale {
+            for row in inputs.iter_rows() {
+                self.stats.update(&Vector::new(row.to_vec()));
+            }
+            self.stats
+                .variance()
+                .map(|v| v.apply(&|v: f64| if v > 0f64 { v.sqrt() } else { 1f64 }))
+                .unwrap_or_else(|| Vector::ones(inputs.cols()))
+        } else {
+            Vector::ones(inputs.cols())
+        };
+        let batch_rows = scale_cols(center_rows(inputs, &batch_mean), &new_scale);
+
+        let (combined, new_mean) = match (self.mean.as_ref(),
+                                          self.input_scale.as_ref(),
+                                          self.singular_values.as_ref(),
+                                          self.components.as_ref()) {
+            (Some(mean), Some(old_scale), Some(sing_vals), Some(components)) => {
+                let n = self.n_samples_seen as f64;
+                let m = batch_size as f64;
+                let new_mean = (mean * n + &batch_mean * m) / (n + m);
+
+                // Scale the components by their singular values, and
+                // move them from the old feature scales to the new
+                let mut weighted_components = components.transpose();
+                for (row, s) in weighted_components.iter_rows_mut().zip(sing_vals.data()) {
+                    for x in row.iter_mut() {
+                        *x *= *s;
+                    }
+                }
+                let weighted_components = scale_cols(weighted_components,
+                                                     &new_scale.elediv(old_scale));
+
+                let combined = weighted_components.vcat(&batch_rows);
+
+                // Correct for the shift in the mean
+                if self.center {
+                    let mean_correction = (mean - &batch_mean).elediv(&new_scale) *
+                                          (n * m / (n + m)).sqrt();
+                    let mean_correction = Matrix::new(1, mean_correction.size(), mean_correction);
+                    (combined.vcat(&mean_correction), new_mean)
+                } else {
+                    (combined, new_mean)
+                }
+            }
+            _ => (batch_rows, batch_mean.clone()),
+        };
+
+        let (sigma, _, v) = combined.svd()?;
+
+        let k = self.n_components;
+        let sing_vals = sigma.diag().into_vec().into_iter().take(k).collect::<Vec<_>>();
+        let components = v.select_cols(&(0..k).collect::<Vec<_>>());
+
+        self.n_samples_seen += batch_size;
+        self.mean = Some(new_mean);
+        self.input_scale = Some(new_scale);
+        self.singular_values = Some(Vector::new(sing_vals));
+        self.components = Some(components);
+
+        Ok(())
+    }
+}
+
+/// Subtract the mean from each row of the inputs.
+fn center_rows(inputs: &Matrix<f64>, mean: &Vector<f64>) -> Matrix<f64> {
+    let mut centered = inputs.clone();
+    for row in centered.iter_rows_mut() {
+        utils::in_place_vec_bin_op(row, mean.data(), |x, &y| *x -= y);
+    }
+    centered
+}
+
+/// Divide each column of the inputs by its scale.
+fn scale_cols(mut inputs: Matrix<f64>, scale: &Vector<f64>) -> Matrix<f64> {
+    for row in inputs.iter_rows_mut() {
+        utils::in_place_vec_bin_op(row, scale.data(), |x, &s| *x /= s);
+    }
+    inputs
+}
+
+/// Train the model and project data onto the principal components.
+impl UnSupModel<Matrix<f64>, Matrix<f64>> for IncrementalPCA {
+    /// Fit the model to the inputs as a single batch.
+    ///
+    /// This discards any previously fitted state.
+    fn train(&mut self, inputs: &Matrix<f64>) -> LearningResult<()> {
+        self.n_samples_seen = 0;
+        self.mean = None;
+        self.singular_values = None;
+        self.components = None;
+        self.input_scale = None;
+        self.stats = RunningStats::default();
+
+        self.partial_fit(inputs)
+    }
+
+    /// Project the inputs onto the principal components.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        if let (Some(mean), Some(scale), Some(components)) =
+               (self.mean.as_ref(), self.input_scale.as_ref(), self.components.as_ref()) {
+            if inputs.cols() != mean.size() {
+                return Err(Error::new(ErrorKind::InvalidData,
+                                      "Inputs have different feature count than the model."));
+            }
+
+            Ok(scale_cols(center_rows(inputs, mean), scale) * components)
+        } else {
+            Err(Error::new_untrained())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IncrementalPCA;
+    use super::{center_rows, scale_cols};
+
+    use learning::UnSupModel;
+    use linalg::{Matrix, BaseMatrix, Axes, Metric, Vector};
+
+    fn sample_data() -> Matrix<f64> {
+        let n = 120;
+        let mut data = Vec::with_capacity(n * 3);
+        for i in 0..n {
+            let t = i as f64;
+            let a = 5.0 * (0.7 * t).sin();
+            let b = 2.0 * (1.3 * t).cos();
+            let c = 0.05 * (2.9 * t).sin();
+
+            data.push(a + b + 1.0);
+            data.push(a - b + c - 3.0);
+            data.push(0.5 * a + c + 10.0);
+        }
+
+        Matrix::new(n, 3, data)
+    }
+
+    fn batch_components(inputs: &Matrix<f64>) -> Matrix<f64> {
+        let centered = center_rows(inputs, &inputs.mean(Axes::Row));
+        let (_, _, v) = centered.svd().unwrap();
+        v
+    }
+
+    fn column(mat: &Matrix<f64>, j: usize) -> Vector<f64> {
+        Vector::new(mat.iter_rows().map(|r| r[j]).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_partial_fit_matches_batch() {
+        let inputs = sample_data();
+        let mut model = IncrementalPCA::new(2);
+
+        for b in 0..6 {
+            let rows = (b * 20..(b + 1) * 20).collect::<Vec<_>>();
+            model.partial_fit(&inputs.select_rows(&rows)).unwrap();
+        }
+
+        assert_eq!(model.n_samples_seen(), 120);
+
+        let inc_mean = model.mean().unwrap();
+        let batch_mean = inputs.mean(Axes::Row);
+        for (x, y) in inc_mean.data().iter().zip(batch_mean.data().iter()) {
+            assert!((x - y).abs() < 1e-10);
+        }
+
+        let batch = batch_components(&inputs);
+        let inc = model.components().unwrap();
+
+        for j in 0..2 {
+            // Components are only defined up to sign
+            let similarity = column(inc, j).dot(&column(&batch, j)).abs();
+            assert!((similarity - 1.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_full_rank_matches_batch_exactly() {
+        let inputs = sample_data();
+        let mut model = IncrementalPCA::new(3);
+
+        for b in 0..4 {
+            let rows = (b * 30..(b + 1) * 30).collect::<Vec<_>>();
+            model.partial_fit(&inputs.select_rows(&rows)).unwrap();
+        }
+
+        let batch = batch_components(&inputs);
+        let inc = model.components().unwrap();
+
+        for j in 0..3 {
+            let similarity = column(inc, j).dot(&column(&batch, j)).abs();
+            assert!((similarity - 1.0).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_train_and_predict() {
+        let inputs = sample_data();
+        let mut model = IncrementalPCA::new(2);
+
+        model.train(&inputs).unwrap();
+        let projected = model.predict(&inputs).unwrap();
+
+        assert_eq!(projected.rows(), 120);
+        assert_eq!(projected.cols(), 2);
+
+        // The projections are centered
+        for m in projected.mean(Axes::Row).data() {
+            assert!(m.abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_untrained_predict() {
+        let model = IncrementalPCA::new(1);
+        assert!(model.predict(&Matrix::zeros(2, 2)).is_err());
+    }
+
+    #[test]
+    fn test_too_many_components() {
+        let mut model = IncrementalPCA::new(3);
+        assert!(model.partial_fit(&Matrix::ones(5, 2)).is_err());
+    }
+
+    #[test]
+    fn test_mismatched_features() {
+        let inputs = sample_data();
+        let mut model = IncrementalPCA::new(1);
+
+        model.partial_fit(&inputs).unwrap();
+        assert!(model.partial_fit(&Matrix::ones(5, 2)).is_err());
+    }
+
+    #[test]
+    fn test_center_matches_manual_centering() {
+        // Offset the data far from the origin
+        let inputs = sample_data() + 100.0;
+        let manual = center_rows(&inputs, &inputs.mean(Axes::Row));
+
+        let mut centered = IncrementalPCA::new(2);
+        centered.train(&inputs).unwrap();
+
+        let mut uncentered = IncrementalPCA::new(2);
+        uncentered.set_center(false);
+        uncentered.train(&manual).unwrap();
+
+        let a = centered.components().unwrap();
+        let b = uncentered.components().unwrap();
+        for j in 0..2 {
+            let similarity = column(a, j).dot(&column(b, j)).abs();
+            assert!((similarity - 1.0).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_no_center_on_offset_data() {
+        let inputs = sample_data() + 100.0;
+
+        let mut model = IncrementalPCA::new(1);
+        model.set_center(false);
+        model.train(&inputs).unwrap();
+
+        assert!(model.mean().unwrap().data().iter().all(|&m| m == 0.0));
+
+        // The first uncentered component points towards the offset
+        let offset = Vector::new(vec![1.0, 1.0, 1.0]) / 3f64.sqrt();
+        let first = column(model.components().unwrap(), 0);
+        assert!((first.dot(&offset).abs() - 1.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_scale_matches_standardized_data() {
+        // Give the features very different units
+        let mut inputs = sample_data();
+        for i in 0..inputs.rows() {
+            inputs[[i, 1]] *= 1000.0;
+        }
+        let manual = scale_cols(center_rows(&inputs, &inputs.mean(Axes::Row)),
+                                &inputs.variance(Axes::Row).unwrap().apply(&f64::sqrt));
+
+        let mut scaled = IncrementalPCA::new(2);
+        scaled.set_scale(true);
+        for b in 0..4 {
+            let rows = (b * 30..(b + 1) * 30).collect::<Vec<_>>();
+            scaled.partial_fit(&inputs.select_rows(&rows)).unwrap();
+        }
+
+        let mut plain = IncrementalPCA::new(2);
+        plain.train(&manual).unwrap();
+
+        let a = scaled.components().unwrap();
+        let b = plain.components().unwrap();
+        for j in 0..2 {
+            let similarity = column(a, j).dot(&column(b, j)).abs();
+            assert!((similarity - 1.0).abs() < 1e-4);
+        }
+
+        // New data is scaled by the stored scales
+        let projected = scaled.predict(&inputs).unwrap();
+        let expected = plain.predict(&manual).unwrap();
+        for j in 0..2 {
+            let p = column(&projected, j);
+            let e = column(&expected, j);
+            assert!((p.dot(&e).abs() / (p.norm() * e.norm()) - 1.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_no_center_incremental_matches_batch() {
+        let inputs = sample_data();
+
+        let mut batch = IncrementalPCA::new(3);
+        batch.set_center(false);
+        batch.train(&inputs).unwrap();
+
+        let mut inc = IncrementalPCA::new(3);
+        inc.set_center(false);
+        for b in 0..4 {
+            let rows = (b * 30..(b + 1) * 30).collect::<Vec<_>>();
+            inc.partial_fit(&inputs.select_rows(&rows)).unwrap();
+        }
+
+        for (x, y) in batch.singular_values().unwrap().data().iter()
+            .zip(inc.singular_values().unwrap().data().iter()) {
+            assert!((x - y).abs() < 1e-8);
+        }
+    }
+}
@@ -0,0 +1,312 @@
+//! Partial Least Squares Module
+//!
+//! Contains an implementation of partial least squares (PLS) regression.
+//!
+//! PLS projects the inputs onto a small number of latent components,
+//! chosen one at a time to have maximal covariance with the target,
+//! and regresses the target on these components. Because only a few
+//! directions are used, PLS handles collinear features and data sets
+//! with more features than samples, where ordinary least squares fails.
+//!
+//! The components are found with the NIPALS algorithm, deflating the
+//! inputs and the target after each component.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::pls::PLSRegressor;
+//! use rusty_machine::learning::SupModel;
+//! use rusty_machine::linalg::{Matrix, Vector};
+//!
+//! // The second feature duplicates the first
+//! let inputs = Matrix::new(4, 2, vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0]);
+//! let targets = Vector::new(vec![3.0, 5.0, 7.0, 9.0]);
+//!
+//! let mut model = PLSRegressor::new(1);
+//! model.train(&inputs, &targets).unwrap();
+//!
+//! let output = model.predict(&Matrix::new(1, 2, vec![5.0, 5.0])).unwrap();
+//! assert!((output[0] - 11.0).abs() < 1e-10);
+//! ```
+
+use linalg::{Matrix, BaseMatrix};
+use linalg::Vector;
+
+use learning::{LearningResult, SupModel};
+use learning::error::{Error, ErrorKind};
+use learning::standardize::Standardization;
+
+/// Partial Least Squares regression
+#[derive(Debug)]
+pub struct PLSRegressor {
+    /// The maximum number of latent components.
+    n_components: usize,
+    /// The mean of the training inputs.
+    x_mean: Option<Vector<f64>>,
+    /// The mean of the training targets.
+    y_mean: f64,
+    /// The input weights of each component, stored in columns.
+    x_weights: Option<Matrix<f64>>,
+    /// The regression coefficients on the centered inputs.
+    coefficients: Option<Vector<f64>>,
+}
+
+impl PLSRegressor {
+    /// Constructs an untrained PLS model using at most
+    /// `n_components` latent components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::pls::PLSRegressor;
+    ///
+    /// let model = PLSRegressor::new(2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `n_components` is zero.
+    pub fn new(n_components: usize) -> PLSRegressor {
+        assert!(n_components > 0, "The number of components must be positive.");
+
+        PLSRegressor {
+            n_components,
+            x_mean: None,
+            y_mean: 0f64,
+            x_weights: None,
+            coefficients: None,
+        }
+    }
+
+    /// The maximum number of latent components.
+    pub fn n_components(&self) -> usize {
+        self.n_components
+    }
+
+    /// The input weights of each fitted component.
+    ///
+    /// Each column is a unit vector. Fewer than `n_components` columns
+    /// are kept if the target is fully explained by earlier components.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn x_weights(&self) -> Option<&Matrix<f64>> {
+        self.x_weights.as_ref()
+    }
+
+    /// The regression coefficients of the centered inputs.
+    ///
+    /// Returns `None` if the model has not been trained.
+    pub fn coefficients(&self) -> Option<&Vector<f64>> {
+        self.coefficients.as_ref()
+    }
+}
+
+impl SupModel<Matrix<f64>, Vector<f64>> for PLSRegressor {
+    /// Train the PLS model.
+    ///
+    /// The inputs and targets are centered by their means.
+    fn train(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
+        if inputs.rows() != targets.size() {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "The number of inputs must match the number of targets."));
+        }
+        if inputs.rows() < 2 {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "At least two samples are needed to train PLS."));
+        }
+
+        let n = inputs.rows();
+        let d = inputs.cols();
+        let centering = Standardization::new(inputs, true, false);
+        let y_mean = targets.sum() / n as f64;
+
+        let mut x = centering.transform(inputs);
+        let mut y = targets - y_mean;
+
+        let mut weights = Vec::new();
+        let mut loadings = Vec::new();
+        let mut y_loadings = Vec::new();
+
+        for _ in 0..self.n_components.min(d) {
+            // The direction of maximal covariance with the residual target
+            let w = x.transpose() * &y;
+            let w_norm = w.dot(&w).sqrt();
+            if w_norm < 1e-12 * (1f64 + y.dot(&y).sqrt()) {
+                break;
+            }
+            let w = w / w_norm;
+
+            let t = &x * &w;
+            let tt = t.dot(&t);
+            if tt == 0f64 {
+                break;
+            }
+            let p = x.transpose() * &t / tt;
+            let q = y.dot(&t) / tt;
+
+            // Deflate the inputs and the target
+            for i in 0..n {
+                for j in 0..d {
+                    x[[i, j]] -= t[i] * p[j];
+                }
+            }
+            y -= &t * q;
+
+            weights.push(w);
+            loadings.push(p);
+            y_loadings.push(q);
+        }
+
+        let k = weights.len();
+        if k == 0 {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "The inputs have no covariance with the target."));
+        }
+
+        let w = Matrix::from_fn(d, k, |c, r| weights[c][r]);
+        let p = Matrix::from_fn(d, k, |c, r| loadings[c][r]);
+
+        // The coefficients W (P^T W)^-1 q map the inputs to the target
+        let pw = p.transpose() * &w;
+        let coefs = &w * pw.solve(Vector::new(y_loadings))?;
+
+        self.x_mean = Some(centering.mean);
+        self.y_mean = y_mean;
+        self.x_weights = Some(w);
+        self.coefficients = Some(coefs);
+        Ok(())
+    }
+
+    /// Predict the target of each input.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        if let (Some(x_mean), Some(coefs)) = (self.x_mean.as_ref(), self.coefficients.as_ref()) {
+            if inputs.cols() != x_mean.size() {
+                return Err(Error::new(ErrorKind::InvalidData,
+                                      "Inputs have different feature count than the training data."));
+            }
+
+            let offset = self.y_mean - x_mean.dot(coefs);
+            Ok(inputs * coefs + offset)
+        } else {
+            Err(Error::new_untrained())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PLSRegressor;
+    use learning::SupModel;
+    use learning::lin_reg::LinRegressor;
+    use linalg::{Matrix, BaseMatrix, Vector};
+
+    use rand::{SeedableRng, StdRng};
+    use rand::distributions::{IndependentSample, Normal};
+
+    /// Thirty features built from two latent factors, with a target
+    /// depending on the factors.
+    fn collinear_data(n: usize, seed: usize) -> (Matrix<f64>, Vector<f64>) {
+        let seed: &[usize] = &[seed];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let normal = Normal::new(0.0, 1.0);
+
+        let mut inputs = Vec::with_capacity(30 * n);
+        let mut targets = Vec::with_capacity(n);
+        for _ in 0..n {
+            let a = normal.ind_sample(&mut rng);
+            let b = normal.ind_sample(&mut rng);
+            for j in 0..30 {
+                let (wa, wb) = ((j as f64 * 0.3).sin(), (j as f64 * 0.7).cos());
+                inputs.push(wa * a + wb * b + 0.01 * normal.ind_sample(&mut rng));
+            }
+            targets.push(2.0 * a - b + 0.01 * normal.ind_sample(&mut rng));
+        }
+
+        (Matrix::new(n, 30, inputs), Vector::new(targets))
+    }
+
+    fn mse(a: &Vector<f64>, b: &Vector<f64>) -> f64 {
+        let diff = a - b;
+        diff.dot(&diff) / a.size() as f64
+    }
+
+    #[test]
+    fn test_pls_collinear_wide_data() {
+        // Fewer samples than features
+        let (inputs, targets) = collinear_data(15, 1);
+        let (test_inputs, test_targets) = collinear_data(50, 2);
+
+        let mut pls = PLSRegressor::new(2);
+        pls.train(&inputs, &targets).unwrap();
+        let pls_mse = mse(&pls.predict(&test_inputs).unwrap(), &test_targets);
+        assert!(pls_mse < 0.01);
+    }
+
+    #[test]
+    fn test_pls_beats_least_squares_on_collinear_data() {
+        // More samples than parameters, so least squares has a unique fit
+        let (inputs, targets) = collinear_data(40, 1);
+        let (test_inputs, test_targets) = collinear_data(200, 2);
+
+        let mut pls = PLSRegressor::new(2);
+        pls.train(&inputs, &targets).unwrap();
+        let pls_mse = mse(&pls.predict(&test_inputs).unwrap(), &test_targets);
+
+        let mut ols = LinRegressor::default();
+        ols.train(&inputs, &targets).unwrap();
+        let ols_mse = mse(&ols.predict(&test_inputs).unwrap(), &test_targets);
+
+        assert!(pls_mse < 0.01);
+        assert!(ols_mse > 5.0 * pls_mse);
+    }
+
+    #[test]
+    fn test_pls_full_components_matches_least_squares() {
+        let inputs = Matrix::new(6, 2, vec![1.0, 0.5, 2.0, -1.0, 3.0, 2.0,
+                                            4.0, 0.0, 5.0, 1.5, 6.0, -0.5]);
+        let targets = Vector::new(vec![2.0, 1.0, 6.5, 4.0, 7.5, 5.0]);
+
+        let mut pls = PLSRegressor::new(2);
+        pls.train(&inputs, &targets).unwrap();
+
+        let mut ols = LinRegressor::default();
+        ols.train(&inputs, &targets).unwrap();
+
+        let pls_out = pls.predict(&inputs).unwrap();
+        let ols_out = ols.predict(&inputs).unwrap();
+        for (a, b) in pls_out.data().iter().zip(ols_out.data().iter()) {
+            assert!((a - b).abs() < 1e-8);
+        }
+        assert_eq!(pls.x_weights().unwrap().cols(), 2);
+    }
+
+    #[test]
+    fn test_pls_stops_when_target_explained() {
+        let inputs = Matrix::new(4, 3, vec![1.0, 2.0, 0.0,
+                                            2.0, 4.0, 1.0,
+                                            3.0, 6.0, 0.0,
+                                            4.0, 8.0, 1.0]);
+        let targets = Vector::new(vec![1.0, 2.0, 3.0, 4.0]);
+
+        let mut pls = PLSRegressor::new(3);
+        pls.train(&inputs, &targets).unwrap();
+
+        assert!(pls.x_weights().unwrap().cols() < 3);
+        let out = pls.predict(&inputs).unwrap();
+        for (a, b) in out.data().iter().zip(targets.data().iter()) {
+            assert!((a - b).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_pls_errors() {
+        let mut pls = PLSRegressor::new(1);
+        let inputs = Matrix::new(2, 1, vec![1.0, 2.0]);
+
+        assert!(pls.predict(&inputs).is_err());
+        assert!(pls.train(&inputs, &Vector::new(vec![1.0])).is_err());
+
+        pls.train(&inputs, &Vector::new(vec![1.0, 3.0])).unwrap();
+        assert!(pls.predict(&Matrix::new(1, 2, vec![1.0, 2.0])).is_err());
+    }
+}
@@ -0,0 +1,583 @@
+//! Ridge Regression module
+//!
+//! Contains an implementation of L2 regularized linear regression.
+//!
+//! The model minimizes
+//!
+//! ```text
+//! |y - b_0 - Xb|^2 + lambda |b|^2
+//! ```
+//!
+//! which shrinks the coefficients towards zero and keeps the problem
+//! well posed when the features are collinear. The intercept `b_0` is
+//! not penalized, and is added automatically so you do not need to
+//! format the input matrices yourself. It is fitted by centering the
+//! inputs and targets before solving the regularized normal equations.
+//!
+//! Centering can be disabled with `set_center(false)` to fit through
+//! the origin. With `set_scale(true)` the features are also standardized
+//! before fitting, so that the penalty treats them equally regardless of
+//! their units. The parameters are always reported on the scale of the
+//! original data.
+//!
+//! # Usage
+//!
+//! ```
+//! use rusty_machine::learning::ridge::RidgeRegressor;
+//! use rusty_machine::learning::SupModel;
+//! use rusty_machine::linalg::{Matrix, Vector};
+//!
+//! let inputs = Matrix::new(4, 1, vec![1.0, 2.0, 3.0, 4.0]);
+//! let targets = Vector::new(vec![3.0, 5.0, 7.0, 9.0]);
+//!
+//! let mut ridge = RidgeRegressor::new(0.5);
+//! ridge.train(&inputs, &targets).unwrap();
+//!
+//! // The slope is shrunk below 2
+//! let params = ridge.parameters().unwrap();
+//! assert!(params[1] < 2.0);
+//! ```
+
+use linalg::{Matrix, BaseMatrix, MatrixExt};
+use linalg::Vector;
+
+use learning::{FeatureImportances, LearningResult, SupModel};
+use learning::error::{Error, ErrorKind};
+use learning::standardize::{Standardization, check_data};
+
+/// Ridge Regression Model.
+///
+/// Contains option for optimized parameter.
+#[derive(Clone, Debug)]
+pub struct RidgeRegressor {
+    /// The L2 penalty strength.
+    lambda: f64,
+    /// The parameters for the regression model, intercept first.
+    parameters: Option<Vector<f64>>,
+    /// Whether the data is centered, fitting an intercept.
+    center: bool,
+    /// Whether the features are scaled to unit variance.
+    scale: bool,
+}
+
+/// Constructs a ridge model with `lambda = 1`.
+impl Default for RidgeRegressor {
+    fn default() -> RidgeRegressor {
+        RidgeRegressor::new(1f64)
+    }
+}
+
+impl RidgeRegressor {
+    /// Constructs an untrained ridge model with the given penalty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::ridge::RidgeRegressor;
+    ///
+    /// let ridge = RidgeRegressor::new(0.1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `lambda` is negative or not finite.
+    pub fn new(lambda: f64) -> RidgeRegressor {
+        assert!(lambda >= 0f64 && lambda.is_finite(),
+                "The penalty (lambda) must be non-negative and finite.");
+
+        RidgeRegressor {
+            lambda,
+            parameters: None,
+            center: true,
+            scale: false,
+        }
+    }
+
+    /// The L2 penalty strength.
+    pub fn lambda(&self) -> f64 {
+        self.lambda
+    }
+
+    /// Get the parameters from the model.
+    ///
+    /// The first parameter is the intercept.
+    ///
+    /// Returns an option that is None if the model has not been trained.
+    pub fn parameters(&self) -> Option<&Vector<f64>> {
+        self.parameters.as_ref()
+    }
+
+    /// Whether the data is centered internally, fitting an intercept.
+    pub fn center(&self) -> bool {
+        self.center
+    }
+
+    /// Set whether the data is centered internally.
+    ///
+    /// Without centering no intercept is fitted, and the first
+    /// parameter is zero. Defaults to `true`.
+    pub fn set_center(&mut self, center: bool) {
+        self.center = center;
+    }
+
+    /// Whether the features are scaled to unit variance internally.
+    pub fn scale(&self) -> bool {
+        self.scale
+    }
+
+    /// Set whether the features are scaled to unit variance internally.
+    ///
+    /// The penalty then applies to the standardized coefficients. The
+    /// parameters are mapped back to the original scale, so new data is
+    /// passed to `predict` unscaled. Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::ridge::RidgeRegressor;
+    /// use rusty_machine::learning::SupModel;
+    /// use rusty_machine::linalg::{Matrix, Vector};
+    ///
+    /// // The second feature is measured in much smaller units
+    /// let inputs = Matrix::new(4, 2, vec![1.0, 100.0, 2.0, 300.0, 3.0, 200.0, 4.0, 400.0]);
+    /// let targets = Vector::new(vec![3.0, 7.0, 7.0, 11.0]);
+    ///
+    /// let mut ridge = RidgeRegressor::new(0.5);
+    /// ridge.set_scale(true);
+    /// ridge.train(&inputs, &targets).unwrap();
+    ///
+    /// let predictions = ridge.predict(&inputs).unwrap();
+    /// assert_eq!(predictions.size(), 4);
+    /// ```
+    pub fn set_scale(&mut self, scale: bool) {
+        self.scale = scale;
+    }
+
+    /// Centers and scales the inputs as configured.
+    fn standardization(&self, inputs: &Matrix<f64>) -> Standardization {
+        Standardization::new(inputs, self.center, self.scale)
+    }
+
+    /// Computes the effective degrees of freedom of the fit on `inputs`.
+    ///
+    /// This is `trace(X (X^T X + lambda I)^-1 X^T)` for the centered
+    /// (and scaled, if set) inputs `X`, and does not count the unpenalized
+    /// intercept. It decreases from the rank of `X` at `lambda = 0`
+    /// towards zero as `lambda` grows, and is used in model selection
+    /// criteria such as generalized cross-validation.
+    ///
+    /// It is computed in closed form as `sum s_i^2 / (s_i^2 + lambda)`
+    /// from the singular values `s_i` of `X`.
+    ///
+    /// The model does not need to be trained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::ridge::RidgeRegressor;
+    /// use rusty_machine::linalg::Matrix;
+    ///
+    /// let inputs = Matrix::new(4, 2, vec![1.0, 0.0, 2.0, 1.0, 3.0, 0.0, 4.0, 1.0]);
+    ///
+    /// let dof = RidgeRegressor::new(1.0).effective_dof(&inputs).unwrap();
+    /// assert!(dof > 0.0 && dof < 2.0);
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The inputs are centered and have fewer than two rows.
+    pub fn effective_dof(&self, inputs: &Matrix<f64>) -> LearningResult<f64> {
+        if self.center && inputs.rows() < 2 {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "At least two rows are needed to center the inputs."));
+        }
+
+        let centered = self.standardization(inputs).transform(inputs);
+        let (sq, _) = squared_svd(&centered);
+        Ok(singular_value_dof(&sq, self.lambda))
+    }
+
+    /// Selects the penalty minimizing the generalized cross-validation score.
+    ///
+    /// For each candidate `lambda` the GCV score is
+    ///
+    /// ```text
+    /// n |y - y_hat|^2 / (n - 1 - dof)^2
+    /// ```
+    ///
+    /// where `dof` is the effective degrees of freedom and the extra
+    /// degree of freedom is the intercept, which is dropped without
+    /// centering. Every candidate is scored in closed form from a single
+    /// SVD of the centered inputs, without refitting. Ties are resolved
+    /// in favour of the earlier candidate.
+    ///
+    /// The model's own penalty is not used or changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::ridge::RidgeRegressor;
+    /// use rusty_machine::linalg::{Matrix, Vector};
+    ///
+    /// let inputs = Matrix::new(5, 1, vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+    /// let targets = Vector::new(vec![1.1, 1.9, 3.2, 3.9, 5.1]);
+    ///
+    /// let ridge = RidgeRegressor::default();
+    /// let lambda = ridge.select_lambda_gcv(&inputs, &targets, &[0.01, 1.0, 100.0]).unwrap();
+    /// assert_eq!(lambda, 0.01);
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The candidates are empty, or contain a negative or non-finite value.
+    /// - The inputs do not match the targets, or are centered and have
+    ///   fewer than two rows.
+    pub fn select_lambda_gcv(&self,
+                             inputs: &Matrix<f64>,
+                             targets: &Vector<f64>,
+                             candidates: &[f64])
+                             -> LearningResult<f64> {
+        if candidates.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidParameters,
+                                  "At least one candidate penalty is needed."));
+        }
+        if candidates.iter().any(|&l| l < 0f64 || !l.is_finite()) {
+            return Err(Error::new(ErrorKind::InvalidParameters,
+                                  "Candidate penalties must be non-negative and finite."));
+        }
+        check_data("ridge", inputs, targets)?;
+        if self.center && inputs.rows() < 2 {
+            return Err(Error::new(ErrorKind::InvalidData,
+                                  "At least two rows are needed to center the inputs."));
+        }
+
+        let n = inputs.rows() as f64;
+        let centered = self.standardization(inputs).transform(inputs);
+        let (y, intercept_dof) = if self.center {
+            (targets - targets.mean(), 1f64)
+        } else {
+            (targets.clone(), 0f64)
+        };
+        let (sq, u) = squared_svd(&centered);
+
+        // Project the target onto the left singular vectors. The part of
+        // the target outside their span is never fitted.
+        let proj = u.transpose() * &y;
+        let outside = y.dot(&y) - proj.dot(&proj);
+
+        let gcv = |lambda: f64| {
+            let mut rss = outside.max(0f64);
+            for (&sq, &p) in sq.iter().zip(proj.data()) {
+                let shrink = if sq > 0f64 { lambda / (sq + lambda) } else { 1f64 };
+                rss += shrink * shrink * p * p;
+            }
+
+            let denom = n - intercept_dof - singular_value_dof(&sq, lambda);
+            if denom > 0f64 {
+                n * rss / (denom * denom)
+            } else {
+                f64::INFINITY
+            }
+        };
+
+        let mut best = (candidates[0], gcv(candidates[0]));
+        for &lambda in &candidates[1..] {
+            let score = gcv(lambda);
+            if score < best.1 {
+                best = (lambda, score);
+            }
+        }
+        Ok(best.0)
+    }
+}
+
+impl SupModel<Matrix<f64>, Vector<f64>> for RidgeRegressor {
+    /// Train the ridge model.
+    ///
+    /// # Failures
+    ///
+    /// - The inputs are empty or do not match the targets.
+    /// - `lambda` is zero and the centered inputs are rank deficient.
+    fn train(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
+        check_data("ridge", inputs, targets)?;
+
+        let standardization = self.standardization(inputs);
+        let target_mean = if self.center { targets.mean() } else { 0f64 };
+        let coefs = standardization.transform(inputs)
+            .solve_regularized(&(targets - target_mean), self.lambda)?;
+
+        let coefs = standardization.unscale_coefs(coefs.data());
+        self.parameters = Some(standardization.parameters(target_mean, &coefs));
+        Ok(())
+    }
+
+    /// Predict output value from input data.
+    ///
+    /// Model must be trained before prediction can be made.
+    fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        if let Some(ref v) = self.parameters {
+            if inputs.cols() + 1 != v.size() {
+                return Err(Error::new(ErrorKind::InvalidData,
+                                      "Inputs have different feature count than the training data."));
+            }
+
+            let ones = Matrix::<f64>::ones(inputs.rows(), 1);
+            let full_inputs = ones.hcat(inputs);
+            Ok(full_inputs * v)
+        } else {
+            Err(Error::new_untrained())
+        }
+    }
+}
+
+impl FeatureImportances for RidgeRegressor {
+    fn feature_importances(&self) -> Option<Vector<f64>> {
+        self.parameters.as_ref().map(|p| {
+            Vector::new(p.data()[1..].iter().map(|c| c.abs()).collect::<Vec<_>>())
+        })
+    }
+}
+
+/// The squared singular values and left singular vectors of `inputs`.
+///
+/// Singular values below the usual numerical rank tolerance,
+/// `max(m, n) * eps` times the largest, are treated as zero.
+fn squared_svd(inputs: &Matrix<f64>) -> (Vec<f64>, Matrix<f64>) {
+    let (s, u, _) = inputs.svd_jacobi();
+    let s = (0..s.rows()).map(|k| s[[k, k]]).collect::<Vec<_>>();
+
+    let largest = s.iter().fold(0f64, |acc, &x| acc.max(x));
+    let tol = largest * inputs.rows().max(inputs.cols()) as f64 * f64::EPSILON;
+    (s.into_iter().map(|x| if x > tol { x * x } else { 0f64 }).collect(), u)
+}
+
+/// The effective degrees of freedom `sum s_i^2 / (s_i^2 + lambda)`,
+/// from the squared singular values `s_i^2`.
+///
+/// Zero singular values are skipped, so at `lambda = 0` this is the rank.
+fn singular_value_dof(sq: &[f64], lambda: f64) -> f64 {
+    sq.iter().filter(|&&x| x > 0f64).map(|&x| x / (x + lambda)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RidgeRegressor;
+    use learning::{FeatureImportances, SupModel};
+    use learning::lin_reg::LinRegressor;
+    use linalg::{Matrix, BaseMatrix, Vector, VectorExt};
+
+    use rand::{SeedableRng, StdRng};
+    use rand::distributions::{IndependentSample, Normal};
+
+    fn sample_data() -> (Matrix<f64>, Vector<f64>) {
+        let inputs = Matrix::new(6, 3, vec![1.0, 0.5, -1.0,
+                                            2.0, -1.0, 0.0,
+                                            3.0, 2.0, 1.0,
+                                            4.0, 0.0, -0.5,
+                                            5.0, 1.5, 2.0,
+                                            6.0, -0.5, 0.5]);
+        let targets = Vector::new(vec![2.0, 1.0, 6.5, 4.0, 7.5, 5.0]);
+        (inputs, targets)
+    }
+
+    #[test]
+    fn test_ridge_zero_lambda_is_least_squares() {
+        let (inputs, targets) = sample_data();
+
+        let mut ridge = RidgeRegressor::new(0.0);
+        ridge.train(&inputs, &targets).unwrap();
+
+        let mut lin = LinRegressor::default();
+        lin.train(&inputs, &targets).unwrap();
+
+        assert!(ridge.parameters().unwrap().approx_eq(lin.parameters().unwrap(), 1e-8));
+    }
+
+    #[test]
+    fn test_ridge_shrinks_coefficients() {
+        let (inputs, targets) = sample_data();
+
+        let mut norms = Vec::new();
+        for &lambda in &[0.0, 1.0, 10.0, 100.0] {
+            let mut ridge = RidgeRegressor::new(lambda);
+            ridge.train(&inputs, &targets).unwrap();
+            let coefs = &ridge.parameters().unwrap().data()[1..];
+            norms.push(coefs.iter().map(|c| c * c).sum::<f64>());
+        }
+
+        for w in norms.windows(2) {
+            assert!(w[1] < w[0]);
+        }
+    }
+
+    #[test]
+    fn test_ridge_feature_importances() {
+        let (inputs, targets) = sample_data();
+        let mut ridge = RidgeRegressor::new(0.5);
+        assert!(ridge.feature_importances().is_none());
+
+        ridge.train(&inputs, &targets).unwrap();
+        let importances = ridge.feature_importances().unwrap();
+        let params = ridge.parameters().unwrap();
+
+        assert_eq!(importances.size(), 3);
+        for j in 0..3 {
+            assert_eq!(importances[j], params[j + 1].abs());
+        }
+    }
+
+    #[test]
+    fn test_ridge_without_center() {
+        let (inputs, targets) = sample_data();
+
+        let mut ridge = RidgeRegressor::new(0.0);
+        ridge.set_center(false);
+        ridge.train(&inputs, &targets).unwrap();
+
+        let mut lin = LinRegressor::default();
+        lin.set_center(false);
+        lin.train(&inputs, &targets).unwrap();
+
+        let params = ridge.parameters().unwrap();
+        assert_eq!(params[0], 0.0);
+        assert!(params.approx_eq(lin.parameters().unwrap(), 1e-8));
+
+        // A single row is enough without centering
+        let row = Matrix::new(1, 3, vec![1.0, 2.0, 3.0]);
+        let mut penalized = RidgeRegressor::new(1.0);
+        assert!(penalized.effective_dof(&row).is_err());
+        penalized.set_center(false);
+        assert!(penalized.effective_dof(&row).is_ok());
+    }
+
+    #[test]
+    fn test_ridge_scale_is_unit_invariant() {
+        let (inputs, targets) = sample_data();
+        let mut rescaled = inputs.clone();
+        for i in 0..rescaled.rows() {
+            rescaled[[i, 1]] *= 1000.0;
+        }
+
+        let fit = |inputs: &Matrix<f64>| {
+            let mut ridge = RidgeRegressor::new(2.0);
+            ridge.set_scale(true);
+            ridge.train(inputs, &targets).unwrap();
+            ridge.predict(inputs).unwrap()
+        };
+
+        // Standardized features give the same fit whatever their units
+        assert!(fit(&inputs).approx_eq(&fit(&rescaled), 1e-8));
+
+        let mut ridge = RidgeRegressor::new(2.0);
+        ridge.set_scale(true);
+        ridge.train(&rescaled, &targets).unwrap();
+        let mut unscaled = RidgeRegressor::new(2.0);
+        unscaled.train(&rescaled, &targets).unwrap();
+        assert!(!ridge.parameters().unwrap().approx_eq(unscaled.parameters().unwrap(), 1e-4));
+    }
+
+    #[test]
+    fn test_effective_dof_limits() {
+        let (inputs, _) = sample_data();
+
+        let dof = |lambda: f64| RidgeRegressor::new(lambda).effective_dof(&inputs).unwrap();
+
+        assert!((dof(0.0) - 3.0).abs() < 1e-10);
+        assert!((dof(1e-8) - 3.0).abs() < 1e-6);
+        assert!(dof(1e8) < 1e-6);
+
+        let mut prev = dof(0.0);
+        for &lambda in &[0.1, 1.0, 10.0, 100.0] {
+            let d = dof(lambda);
+            assert!(d < prev && d > 0.0);
+            prev = d;
+        }
+    }
+
+    /// Noisy targets from ten features, only three of which are used.
+    fn noisy_data(n: usize, rng: &mut StdRng) -> (Matrix<f64>, Vector<f64>) {
+        let normal = Normal::new(0.0, 1.0);
+        let inputs = Matrix::from_fn(n, 10, |_, _| normal.ind_sample(rng));
+        let targets = (0..n)
+            .map(|i| {
+                inputs[[i, 0]] - 0.5 * inputs[[i, 1]] + 0.25 * inputs[[i, 2]] + 1.0 +
+                normal.ind_sample(rng)
+            })
+            .collect::<Vec<_>>();
+        (inputs, Vector::new(targets))
+    }
+
+    #[test]
+    fn test_select_lambda_gcv_beats_extremes() {
+        let seed: &[usize] = &[11];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let (inputs, targets) = noisy_data(15, &mut rng);
+        let (test_inputs, test_targets) = noisy_data(500, &mut rng);
+
+        let candidates = (0..13).map(|i| 10f64.powi(i - 6)).collect::<Vec<_>>();
+        let ridge = RidgeRegressor::default();
+        let lambda = ridge.select_lambda_gcv(&inputs, &targets, &candidates).unwrap();
+
+        let test_error = |lambda: f64| {
+            let mut model = RidgeRegressor::new(lambda);
+            model.train(&inputs, &targets).unwrap();
+            let diff = model.predict(&test_inputs).unwrap() - &test_targets;
+            diff.dot(&diff) / test_targets.size() as f64
+        };
+
+        let selected = test_error(lambda);
+        assert!(selected < test_error(candidates[0]));
+        assert!(selected < test_error(candidates[12]));
+    }
+
+    #[test]
+    fn test_select_lambda_gcv_matches_refit() {
+        let (inputs, targets) = sample_data();
+        let n = inputs.rows() as f64;
+
+        // The closed form score agrees with refitting at each candidate
+        let candidates = [0.01, 0.5, 3.0, 50.0];
+        let scores = candidates.iter()
+            .map(|&lambda| {
+                let mut model = RidgeRegressor::new(lambda);
+                model.train(&inputs, &targets).unwrap();
+                let diff = model.predict(&inputs).unwrap() - &targets;
+                let dof = model.effective_dof(&inputs).unwrap();
+                n * diff.dot(&diff) / ((n - 1.0 - dof) * (n - 1.0 - dof))
+            })
+            .collect::<Vec<_>>();
+        let best = (0..4).fold(0, |b, i| if scores[i] < scores[b] { i } else { b });
+
+        let ridge = RidgeRegressor::default();
+        let lambda = ridge.select_lambda_gcv(&inputs, &targets, &candidates).unwrap();
+        assert_eq!(lambda, candidates[best]);
+
+        assert!(ridge.select_lambda_gcv(&inputs, &targets, &[]).is_err());
+        assert!(ridge.select_lambda_gcv(&inputs, &targets, &[-1.0]).is_err());
+    }
+
+    #[test]
+    fn test_ridge_errors() {
+        let (inputs, targets) = sample_data();
+        let mut ridge = RidgeRegressor::default();
+
+        assert!(ridge.predict(&inputs).is_err());
+        assert!(ridge.train(&inputs, &Vector::new(vec![1.0, 2.0])).is_err());
+        assert!(ridge.effective_dof(&Matrix::new(1, 3, vec![1.0; 3])).is_err());
+
+        ridge.train(&inputs, &targets).unwrap();
+        assert!(ridge.predict(&Matrix::new(1, 2, vec![1.0, 2.0])).is_err());
+
+        // Without a penalty collinear columns count by their rank
+        let collinear = Matrix::new(3, 2, vec![1.0, 2.0, 2.0, 4.0, 3.0, 6.0]);
+        let dof = RidgeRegressor::new(0.0).effective_dof(&collinear).unwrap();
+        assert!((dof - 1.0).abs() < 1e-12);
+        assert!(RidgeRegressor::new(1.0).effective_dof(&collinear).unwrap() < 1.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ridge_negative_lambda() {
+        let _ = RidgeRegressor::new(-1.0);
+    }
+}
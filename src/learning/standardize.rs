@@ -0,0 +1,89 @@
+//! Column standardization and data checks shared by the linear models.
+//!
+//! Centering the inputs and targets removes the unpenalized intercept
+//! from the fit, after which it is recovered from the means. Scaling
+//! divides each feature by its standard deviation, so that a penalty
+//! treats the features equally regardless of their units.
+
+use linalg::{Matrix, BaseMatrix, BaseMatrixMut, Axes, MatrixExt};
+use linalg::Vector;
+use rulinalg::utils;
+
+use learning::LearningResult;
+use learning::error::{Error, ErrorKind};
+
+/// The column means and scales learned from the training inputs.
+#[derive(Clone, Debug)]
+pub struct Standardization {
+    /// The column means, zero without centering.
+    pub mean: Vector<f64>,
+    /// The column standard deviations, one without scaling.
+    pub scale: Vector<f64>,
+}
+
+impl Standardization {
+    /// Learns the column statistics of `inputs`.
+    ///
+    /// Features with zero variance, or a single row, are left unscaled.
+    pub fn new(inputs: &Matrix<f64>, center: bool, scale: bool) -> Standardization {
+        let mean = if center && inputs.rows() > 0 {
+            inputs.mean(Axes::Row)
+        } else {
+            Vector::zeros(inputs.cols())
+        };
+
+        let scale = if scale && inputs.rows() > 1 {
+            inputs.variance_cols().apply(&|v: f64| if v > 0f64 { v.sqrt() } else { 1f64 })
+        } else {
+            Vector::ones(inputs.cols())
+        };
+
+        Standardization { mean, scale }
+    }
+
+    /// Centers and scales the columns of `inputs`.
+    pub fn transform(&self, inputs: &Matrix<f64>) -> Matrix<f64> {
+        let mut prepared = inputs.clone();
+        for row in prepared.iter_rows_mut() {
+            utils::in_place_vec_bin_op(row, self.mean.data(), |x, &m| *x -= m);
+            utils::in_place_vec_bin_op(row, self.scale.data(), |x, &s| *x /= s);
+        }
+        prepared
+    }
+
+    /// Maps coefficients on the original scale to the standardized scale.
+    pub fn scale_coefs(&self, coefs: &[f64]) -> Vec<f64> {
+        utils::vec_bin_op(coefs, self.scale.data(), |b, s| b * s)
+    }
+
+    /// Maps coefficients on the standardized scale back to the original scale.
+    pub fn unscale_coefs(&self, coefs: &[f64]) -> Vec<f64> {
+        utils::vec_bin_op(coefs, self.scale.data(), |b, s| b / s)
+    }
+
+    /// The full parameters, with the intercept prepended to the
+    /// coefficients on the original scale.
+    pub fn parameters(&self, target_mean: f64, coefs: &[f64]) -> Vector<f64> {
+        let intercept = target_mean - utils::dot(self.mean.data(), coefs);
+
+        let mut params = Vec::with_capacity(coefs.len() + 1);
+        params.push(intercept);
+        params.extend_from_slice(coefs);
+        Vector::new(params)
+    }
+}
+
+/// Check that the inputs and targets are non-empty and compatible.
+///
+/// The `model` name is used in the error message.
+pub fn check_data(model: &str, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
+    if inputs.rows() == 0 {
+        return Err(Error::new(ErrorKind::InvalidData,
+                              format!("Cannot train {} on empty data.", model)));
+    }
+    if inputs.rows() != targets.size() {
+        return Err(Error::new(ErrorKind::InvalidData,
+                              "The number of targets must match the number of input rows."));
+    }
+    Ok(())
+}
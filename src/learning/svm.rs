@@ -36,15 +36,18 @@
 use linalg::{Matrix, BaseMatrix};
 use linalg::Vector;
 
+use analysis::score;
 use learning::toolkit::kernel::{Kernel, SquaredExp};
 use learning::{LearningResult, SupModel};
 use learning::error::{Error, ErrorKind};
+use learning::multiclass::BinaryClassifier;
+
+use learning::toolkit::rand_utils;
 
-use rand;
 use rand::Rng;
 
 /// Support Vector Machine
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SVM<K: Kernel> {
     ker: K,
     alpha: Option<Vector<f64>>,
@@ -53,6 +56,8 @@ pub struct SVM<K: Kernel> {
     lambda: f64,
     /// Number of iterations for training.
     pub optim_iters: usize,
+    /// The seed for sampling the training points.
+    seed: Option<u64>,
 }
 
 /// The default Support Vector Machine.
@@ -71,6 +76,7 @@ impl Default for SVM<SquaredExp> {
             train_targets: None,
             lambda: 0.3f64,
             optim_iters: 100,
+            seed: None,
         }
     }
 }
@@ -96,11 +102,26 @@ impl<K: Kernel> SVM<K> {
             train_targets: None,
             lambda: lambda,
             optim_iters: 100,
+            seed: None,
         }
     }
 }
 
 impl<K: Kernel> SVM<K> {
+    /// Sets the seed used to sample points during training.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::learning::svm::SVM;
+    ///
+    /// let svm = SVM::default().with_seed(42);
+    /// ```
+    pub fn with_seed(mut self, seed: u64) -> SVM<K> {
+        self.seed = Some(seed);
+        self
+    }
+
     /// Construct a kernel matrix
     fn ker_mat(&self, m1: &Matrix<f64>, m2: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
         if m1.cols() != m2.cols() {
@@ -125,26 +146,14 @@ impl<K: Kernel> SVM<K> {
 /// predict the model output from new data.
 impl<K: Kernel> SupModel<Matrix<f64>, Vector<f64>> for SVM<K> {
     fn predict(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
-        let ones = Matrix::<f64>::ones(inputs.rows(), 1);
-        let full_inputs = ones.hcat(inputs);
-
-        if let (&Some(ref alpha), &Some(ref train_inputs), &Some(ref train_targets)) =
-               (&self.alpha, &self.train_inputs, &self.train_targets) {
-            let ker_mat = try!(self.ker_mat(&full_inputs, train_inputs));
-            let weight_vec = alpha.elemul(train_targets) / self.lambda;
-
-            let plane_dist = ker_mat * weight_vec;
-
-            Ok(plane_dist.apply(&|d| d.signum()))
-        } else {
-            Err(Error::new_untrained())
-        }
+        let plane_dist = self.decision_function(inputs)?;
+        Ok(plane_dist.apply(&|d| d.signum()))
     }
 
     fn train(&mut self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<()> {
         let n = inputs.rows();
 
-        let mut rng = rand::thread_rng();
+        let mut rng = rand_utils::seeded_rng(self.seed);
 
         let mut alpha = vec![0f64; n];
 
@@ -169,4 +178,32 @@ impl<K: Kernel> SupModel<Matrix<f64>, Vector<f64>> for SVM<K> {
 
         Ok(())
     }
+
+    /// Score the model by the accuracy of its predictions.
+    fn score(&self, inputs: &Matrix<f64>, targets: &Vector<f64>) -> LearningResult<f64> {
+        Ok(score::label_accuracy(&self.predict(inputs)?, targets))
+    }
+}
+
+/// The SVM separates the `-1` and `1` classes by the
+/// signed distance of each input to the hyperplane.
+impl<K: Kernel> BinaryClassifier for SVM<K> {
+    fn negative_label(&self) -> f64 {
+        -1f64
+    }
+
+    fn decision_function(&self, inputs: &Matrix<f64>) -> LearningResult<Vector<f64>> {
+        let ones = Matrix::<f64>::ones(inputs.rows(), 1);
+        let full_inputs = ones.hcat(inputs);
+
+        if let (Some(alpha), Some(train_inputs), Some(train_targets)) =
+               (self.alpha.as_ref(), self.train_inputs.as_ref(), self.train_targets.as_ref()) {
+            let ker_mat = self.ker_mat(&full_inputs, train_inputs)?;
+            let weight_vec = alpha.elemul(train_targets) / self.lambda;
+
+            Ok(ker_mat * weight_vec)
+        } else {
+            Err(Error::new_untrained())
+        }
+    }
 }
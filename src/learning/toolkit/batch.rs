@@ -0,0 +1,225 @@
+//! Batching utilities
+//!
+//! Provides the `Batch` trait for data which can be split into
+//! batches of samples and stitched back together.
+//!
+//! This is used by `SupModel::predict_batched` and
+//! `UnSupModel::predict_batched`, through `predict_in_batches`, which
+//! bound the memory used by a prediction by only passing a few samples
+//! to the model at a time.
+//! The `batches` iterator can also be used directly to handle the
+//! predictions of each batch as they are produced.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_machine::learning::toolkit::batch;
+//! use rusty_machine::linalg::{Matrix, BaseMatrix};
+//!
+//! let inputs = Matrix::new(5, 2, (0..10).map(|x| x as f64).collect::<Vec<_>>());
+//!
+//! let sizes = batch::batches(&inputs, 2).map(|b| b.rows()).collect::<Vec<_>>();
+//! assert_eq!(sizes, vec![2, 2, 1]);
+//! ```
+
+use linalg::{Matrix, BaseMatrix, Vector};
+use learning::LearningResult;
+
+/// Data made up of samples which can be split into batches.
+pub trait Batch: Sized {
+    /// The number of samples.
+    fn n_samples(&self) -> usize;
+
+    /// The samples in `start..end`.
+    fn select(&self, start: usize, end: usize) -> Self;
+
+    /// Joins the batches back together, in order.
+    ///
+    /// The batches must not be empty.
+    fn concat(batches: Vec<Self>) -> Self;
+}
+
+/// The rows of a matrix are its samples.
+impl<T: Copy> Batch for Matrix<T> {
+    fn n_samples(&self) -> usize {
+        self.rows()
+    }
+
+    fn select(&self, start: usize, end: usize) -> Matrix<T> {
+        self.select_rows(&(start..end).collect::<Vec<_>>())
+    }
+
+    fn concat(batches: Vec<Matrix<T>>) -> Matrix<T> {
+        let rows = batches.iter().map(|b| b.rows()).sum();
+        let cols = batches[0].cols();
+        let mut data = Vec::with_capacity(rows * cols);
+        for b in batches {
+            assert!(b.cols() == cols, "All batches must have the same column count.");
+            data.extend(b.into_vec());
+        }
+        Matrix::new(rows, cols, data)
+    }
+}
+
+/// The entries of a vector are its samples.
+impl<T: Copy> Batch for Vector<T> {
+    fn n_samples(&self) -> usize {
+        self.size()
+    }
+
+    fn select(&self, start: usize, end: usize) -> Vector<T> {
+        Vector::new(&self.data()[start..end])
+    }
+
+    fn concat(batches: Vec<Vector<T>>) -> Vector<T> {
+        let mut data = Vec::with_capacity(batches.iter().map(|b| b.size()).sum());
+        for b in batches {
+            data.extend(b.into_vec());
+        }
+        Vector::new(data)
+    }
+}
+
+/// An iterator over consecutive batches of samples.
+///
+/// Created by `batches`.
+#[derive(Debug)]
+pub struct Batches<'a, B: 'a> {
+    data: &'a B,
+    batch_size: usize,
+    start: usize,
+}
+
+/// Splits the data into consecutive batches of at most `batch_size` samples.
+///
+/// Only the last batch may be smaller than `batch_size`.
+///
+/// # Panics
+///
+/// - `batch_size` is zero.
+pub fn batches<'a, B: Batch>(data: &'a B, batch_size: usize) -> Batches<'a, B> {
+    assert!(batch_size > 0, "The batch size must be positive.");
+
+    Batches {
+        data,
+        batch_size,
+        start: 0,
+    }
+}
+
+impl<'a, B: Batch> Iterator for Batches<'a, B> {
+    type Item = B;
+
+    fn next(&mut self) -> Option<B> {
+        let n = self.data.n_samples();
+        if self.start >= n {
+            return None;
+        }
+
+        let end = (self.start + self.batch_size).min(n);
+        let batch = self.data.select(self.start, end);
+        self.start = end;
+        Some(batch)
+    }
+}
+
+/// Predicts the outputs of consecutive batches with `predict` and joins them.
+///
+/// Data with at most `batch_size` samples is predicted in one call.
+///
+/// # Panics
+///
+/// - `batch_size` is zero.
+pub fn predict_in_batches<T, U, F>(inputs: &T, batch_size: usize, predict: F) -> LearningResult<U>
+    where T: Batch,
+          U: Batch,
+          F: Fn(&T) -> LearningResult<U>
+{
+    assert!(batch_size > 0, "The batch size must be positive.");
+    if inputs.n_samples() <= batch_size {
+        return predict(inputs);
+    }
+
+    let outputs = batches(inputs, batch_size)
+        .map(|batch| predict(&batch))
+        .collect::<LearningResult<Vec<_>>>()?;
+    Ok(U::concat(outputs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{batches, predict_in_batches, Batch};
+    use learning::{SupModel, UnSupModel};
+    use learning::k_means::KMeansClassifier;
+    use learning::lin_reg::LinRegressor;
+    use learning::nnet::{NeuralNet, BCECriterion};
+    use learning::optim::grad_desc::StochasticGD;
+    use learning::toolkit::regularization::Regularization;
+    use linalg::{Matrix, BaseMatrix, Vector};
+
+    fn inputs() -> Matrix<f64> {
+        Matrix::new(7, 2, (0..14).map(|x| (x as f64 * 0.7).sin()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_batches_round_trip() {
+        let inputs = inputs();
+        for size in 1..9 {
+            let pieces = batches(&inputs, size).collect::<Vec<_>>();
+            assert_eq!(pieces.len(), (7 + size - 1) / size);
+            assert_eq!(Matrix::concat(pieces), inputs);
+        }
+
+        let v = Vector::new(vec![1, 2, 3, 4, 5]);
+        assert_eq!(Vector::concat(batches(&v, 2).collect()), v);
+    }
+
+    #[test]
+    fn test_predict_in_batches_sizes() {
+        let inputs = inputs();
+
+        // Each output is the size of the batch it was predicted in
+        let outputs = predict_in_batches(&inputs, 3, |b| Ok(Vector::new(vec![b.rows(); b.rows()])))
+            .unwrap();
+        assert_eq!(outputs, Vector::new(vec![3, 3, 3, 3, 3, 3, 1]));
+
+        let whole = predict_in_batches(&inputs, 7, |b| Ok(Vector::new(vec![b.rows(); b.rows()])))
+            .unwrap();
+        assert_eq!(whole, Vector::new(vec![7; 7]));
+    }
+
+    #[test]
+    fn test_neural_net_batched_prediction() {
+        let inputs = inputs();
+        let targets = Matrix::new(7, 2, vec![1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0,
+                                             1.0, 0.0, 0.0, 1.0, 1.0, 0.0]);
+
+        let criterion = BCECriterion::new(Regularization::None);
+        let mut model = NeuralNet::new(&[2, 4, 2], criterion, StochasticGD::default());
+        model.train(&inputs, &targets).unwrap();
+
+        let outputs = model.predict(&inputs).unwrap();
+        for size in &[1, 3, 7, 10] {
+            assert_eq!(model.predict_batched(&inputs, *size).unwrap(), outputs);
+        }
+    }
+
+    #[test]
+    fn test_model_batched_prediction() {
+        let inputs = inputs();
+
+        let mut lin_reg = LinRegressor::default();
+        lin_reg.train(&inputs, &Vector::new(vec![1.0, 2.0, 0.5, 3.0, 1.0, 2.5, 0.0]))
+            .unwrap();
+        assert_eq!(lin_reg.predict_batched(&inputs, 3).unwrap(),
+                   lin_reg.predict(&inputs).unwrap());
+
+        let mut k_means = KMeansClassifier::new(2).with_seed(1);
+        k_means.train(&inputs).unwrap();
+        assert_eq!(k_means.predict_batched(&inputs, 2).unwrap(),
+                   k_means.predict(&inputs).unwrap());
+
+        let untrained = LinRegressor::default();
+        assert!(untrained.predict_batched(&inputs, 3).is_err());
+    }
+}
@@ -2,8 +2,45 @@
 //!
 //! This module provides sampling and shuffling which are used
 //! within the learning modules.
+//!
+//! The `_with_rng` variants take the random number generator to use,
+//! and `seeded_rng` creates one which is reproducible given a seed.
+
+use rand::{Rng, SeedableRng, StdRng, thread_rng};
 
-use rand::{Rng, thread_rng};
+/// Creates a random number generator.
+///
+/// If a seed is given the generator is seeded with it, so that the
+/// same seed always produces the same sequence of values. Otherwise
+/// the generator is seeded randomly.
+///
+/// This is used by the models which accept a seed through `with_seed`.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::learning::toolkit::rand_utils;
+///
+/// let mut a = (0..5).collect::<Vec<_>>();
+/// let mut b = a.clone();
+///
+/// rand_utils::in_place_fisher_yates_with_rng(&mut a, &mut rand_utils::seeded_rng(Some(7)));
+/// rand_utils::in_place_fisher_yates_with_rng(&mut b, &mut rand_utils::seeded_rng(Some(7)));
+/// assert_eq!(a, b);
+/// ```
+pub fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => {
+            let seed: &[usize] = &[(seed & 0xffff_ffff) as usize, (seed >> 32) as usize];
+            StdRng::from_seed(seed)
+        }
+        None => {
+            let mut rng = thread_rng();
+            let seed: Vec<usize> = (0..4).map(|_| rng.gen()).collect();
+            StdRng::from_seed(&seed[..])
+        }
+    }
+}
 
 /// ```
 /// use rusty_machine::learning::toolkit::rand_utils;
@@ -14,6 +51,16 @@ use rand::{Rng, thread_rng};
 /// println!("{:?}", sample);
 /// ```
 pub fn reservoir_sample<T: Copy>(pool: &[T], reservoir_size: usize) -> Vec<T> {
+    reservoir_sample_with_rng(pool, reservoir_size, &mut thread_rng())
+}
+
+/// Reservoir sampling using the given random number generator.
+///
+/// See `reservoir_sample`.
+pub fn reservoir_sample_with_rng<T: Copy, R: Rng>(pool: &[T],
+                                                  reservoir_size: usize,
+                                                  rng: &mut R)
+                                                  -> Vec<T> {
     assert!(pool.len() >= reservoir_size,
             "Sample size is greater than total.");
 
@@ -23,7 +70,6 @@ pub fn reservoir_sample<T: Copy>(pool: &[T], reservoir_size: usize) -> Vec<T> {
     pool_mut = &pool_mut[reservoir_size..];
 
     let mut ele_seen = reservoir_size;
-    let mut rng = thread_rng();
 
     while pool_mut.len() > 0 {
         ele_seen += 1;
@@ -97,8 +143,15 @@ pub fn fisher_yates<T: Copy>(arr: &[T]) -> Vec<T> {
 /// rand_utils::in_place_fisher_yates(&mut a);
 /// ```
 pub fn in_place_fisher_yates<T>(arr: &mut [T]) {
+    in_place_fisher_yates_with_rng(arr, &mut thread_rng());
+}
+
+/// The in place Fisher-Yates shuffle using the given random
+/// number generator.
+///
+/// See `in_place_fisher_yates`.
+pub fn in_place_fisher_yates_with_rng<T, R: Rng>(arr: &mut [T], rng: &mut R) {
     let n = arr.len();
-    let mut rng = thread_rng();
 
     for i in 0..n {
         // Swap i with a random point after it
@@ -141,4 +194,23 @@ mod tests {
             assert!(a.contains(&val));
         }
     }
+
+    #[test]
+    fn test_seeded_rng() {
+        let a = (0..20).collect::<Vec<_>>();
+
+        let mut b = a.clone();
+        let mut c = a.clone();
+        let mut d = a.clone();
+        in_place_fisher_yates_with_rng(&mut b, &mut seeded_rng(Some(3)));
+        in_place_fisher_yates_with_rng(&mut c, &mut seeded_rng(Some(3)));
+        in_place_fisher_yates_with_rng(&mut d, &mut seeded_rng(Some(4)));
+
+        assert_eq!(b, c);
+        assert!(b != d);
+
+        let e = reservoir_sample_with_rng(&a, 5, &mut seeded_rng(Some(3)));
+        let f = reservoir_sample_with_rng(&a, 5, &mut seeded_rng(Some(3)));
+        assert_eq!(e, f);
+    }
 }
@@ -4,7 +4,14 @@
 //! within machine learning algorithms.
 //!
 //! The module contains a `Regularization` enum which provides access to
-//! `L1`, `L2` and `ElasticNet` regularization.
+//! `L1`, `L2`, `ElasticNet` and `Trace` regularization.
+//!
+//! The `Trace` regularization penalizes the trace norm (or nuclear norm)
+//! of the weights, the sum of their singular values. This is a convex
+//! surrogate for the rank, and encourages low-rank weight matrices in
+//! models with multiple outputs. Both its cost and its gradient compute
+//! a full SVD of the weights on every evaluation, so it is much more
+//! expensive than the other penalties for large weight matrices.
 //!
 //! # Examples
 //!
@@ -15,7 +22,7 @@
 //! ```
 
 use linalg::Metric;
-use linalg::{Matrix, MatrixSlice, BaseMatrix};
+use linalg::{Matrix, MatrixSlice, BaseMatrix, MatrixExt};
 use libnum::{FromPrimitive, Float};
 
 /// Model Regularization
@@ -27,6 +34,10 @@ pub enum Regularization<T: Float> {
     L2(T),
     /// Elastic Net Regularization (L1 and L2)
     ElasticNet(T, T),
+    /// Trace norm Regularization
+    ///
+    /// The cost and gradient each compute a full SVD of the weights.
+    Trace(T),
     /// No Regularization
     None,
 }
@@ -40,6 +51,7 @@ impl<T: Float + FromPrimitive> Regularization<T> {
             Regularization::ElasticNet(x, y) => {
                 Self::l1_reg_cost(&mat, x) + Self::l2_reg_cost(&mat, y)
             }
+            Regularization::Trace(x) => Self::trace_reg_cost(&mat, x),
             Regularization::None => T::zero(),
         }
     }
@@ -52,6 +64,7 @@ impl<T: Float + FromPrimitive> Regularization<T> {
             Regularization::ElasticNet(x, y) => {
                 Self::l1_reg_grad(&mat, x) + Self::l2_reg_grad(&mat, y)
             }
+            Regularization::Trace(x) => Self::trace_reg_grad(&mat, x),
             Regularization::None => Matrix::zeros(mat.rows(), mat.cols()),
         }
     }
@@ -84,12 +97,28 @@ impl<T: Float + FromPrimitive> Regularization<T> {
     fn l2_reg_grad(mat: &MatrixSlice<T>, x: T) -> Matrix<T> {
         mat * (x / FromPrimitive::from_usize(mat.rows()).unwrap())
     }
+
+    fn trace_reg_cost(mat: &MatrixSlice<T>, x: T) -> T {
+        // The trace norm is trace(sqrt(W^T W)), the trace of the singular values
+        let (sigma, _, _) = Matrix::from(*mat).svd_jacobi();
+        let m_2 = (T::one() + T::one()) * FromPrimitive::from_usize(mat.rows()).unwrap();
+        sigma.trace() * x / m_2
+    }
+
+    fn trace_reg_grad(mat: &MatrixSlice<T>, x: T) -> Matrix<T> {
+        // U V^T is a subgradient of the trace norm
+        let (_, u, v) = Matrix::from(*mat).svd_jacobi();
+        let m_2 = (T::one() + T::one()) * FromPrimitive::from_usize(mat.rows()).unwrap();
+        let mut grad = Matrix::zeros(mat.rows(), mat.cols());
+        grad.rank_k_update(&u, &v, x / m_2);
+        grad
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Regularization;
-    use linalg::{Matrix, BaseMatrix};
+    use linalg::{Matrix, BaseMatrix, MatrixExt};
     use linalg::Metric;
 
     #[test]
@@ -171,4 +200,59 @@ mod tests {
             assert!(eps < 1e-12);
         }
     }
+
+    #[test]
+    fn test_trace_reg() {
+        let input_mat = Matrix::new(2, 2, vec![3.0, 0.0, 0.0, -4.0]);
+        let mat_slice = input_mat.as_slice();
+
+        let trace_reg: Regularization<f64> = Regularization::Trace(0.5);
+
+        let a = trace_reg.reg_cost(mat_slice);
+        let b = trace_reg.reg_grad(mat_slice);
+
+        // The singular values are 3 and 4
+        assert!((a - (3.5f64 / 4f64)).abs() < 1e-12);
+
+        // U V^T is the sign of the diagonal
+        let true_grad = Matrix::new(2, 2, vec![0.125, 0.0, 0.0, -0.125]);
+        for eps in (b - true_grad).into_vec() {
+            assert!(eps.abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_trace_reg_reduces_rank() {
+        // Multi-target least squares with a full rank coefficient
+        // matrix whose third direction is weak.
+        let inputs = Matrix::new(6, 3, vec![1.0, 0.0, 0.0,
+                                            0.0, 1.0, 0.0,
+                                            0.0, 0.0, 1.0,
+                                            1.0, 1.0, 0.0,
+                                            0.0, 1.0, 1.0,
+                                            1.0, 0.0, 1.0]);
+        let true_weights = Matrix::new(3, 3, vec![2.0, 1.0, 0.0,
+                                                  1.0, 2.0, 0.5,
+                                                  3.0, 3.0, 0.0]);
+        let targets = &inputs * &true_weights;
+
+        let fit = |reg: Regularization<f64>| {
+            let mut weights = Matrix::<f64>::zeros(3, 3);
+            for _ in 0..5000 {
+                let residuals = &inputs * &weights - &targets;
+                let grad = inputs.transpose() * residuals / 6.0 + reg.reg_grad(weights.as_slice());
+                weights = weights - grad * 0.05;
+            }
+            weights
+        };
+
+        let effective_rank = |weights: Matrix<f64>| {
+            let (sigma, _, _) = weights.svd_jacobi();
+            let sigma = sigma.diag().into_vec();
+            sigma.iter().filter(|&&s| s > 0.05 * sigma[0]).count()
+        };
+
+        assert_eq!(effective_rank(fit(Regularization::None)), 3);
+        assert_eq!(effective_rank(fit(Regularization::Trace(1.0))), 2);
+    }
 }
@@ -16,15 +16,25 @@
 //! The currently supported techniques are:
 //!
 //! - Linear Regression
+//! - Lasso Regression
+//! - Ridge Regression
 //! - Logistic Regression
 //! - Generalized Linear Models
 //! - K-Means Clustering
+//! - K-Nearest Neighbours Classification and Regression
 //! - Neural Networks
 //! - Gaussian Process Regression
 //! - Support Vector Machines
 //! - Gaussian Mixture Models
 //! - Naive Bayes Classifiers
 //! - DBSCAN
+//! - Mean Shift Clustering
+//! - Incremental Principal Component Analysis
+//! - Canonical Correlation Analysis
+//! - Partial Least Squares Regression
+//! - One-vs-Rest and One-vs-One Multiclass Classification
+//! - Voting and Stacking Ensembles
+//! - Anomaly Detection (Isolation Forest, Local Outlier Factor)
 //!
 //! ### linalg
 //!
@@ -112,35 +122,66 @@ extern crate rand;
 
 pub mod prelude;
 
+mod folds;
+mod neighbours;
+
 /// The linear algebra module
 ///
 /// This module contains reexports of common tools from the rulinalg crate.
+/// It also provides extension traits which add functionality to these types.
 pub mod linalg {
     pub use rulinalg::matrix::{Axes, Matrix, MatrixSlice, MatrixSliceMut, BaseMatrix, BaseMatrixMut};
     pub use rulinalg::vector::Vector;
     pub use rulinalg::Metric;
+
+    mod bytes;
+    mod matrix;
+    mod vector;
+
+    pub mod utils;
+
+    pub use self::bytes::{ByteElement, ParseError};
+    pub use self::matrix::MatrixExt;
+    pub use self::vector::VectorExt;
 }
 
 /// Module for data handling
 pub mod data {
+    pub mod bin;
+    pub mod feature_selection;
+    pub mod grid;
+    pub mod split;
+    pub mod stats;
     pub mod transforms;
 }
 
 /// Module for machine learning.
 pub mod learning {
+    pub mod cca;
     pub mod dbscan;
+    pub mod ensemble;
     pub mod glm;
     pub mod gmm;
     pub mod lin_reg;
+    pub mod lasso;
     pub mod logistic_reg;
     pub mod k_means;
+    pub mod knn;
     pub mod nnet;
     pub mod gp;
     pub mod svm;
     pub mod naive_bayes;
+    pub mod pca;
+    pub mod pls;
+    pub mod ridge;
+    pub mod multiclass;
+    pub mod anomaly;
+    pub mod mean_shift;
 
     pub mod error;
 
+    mod standardize;
+
     /// A new type which provides clean access to the learning errors
     pub type LearningResult<T> = Result<T, error::Error>;
 
@@ -151,6 +192,34 @@ pub mod learning {
 
         /// Train the model using inputs and targets.
         fn train(&mut self, inputs: &T, targets: &U) -> LearningResult<()>;
+
+        /// Score the predictions of the model against the targets.
+        ///
+        /// Higher scores are better. By default this is the R<sup>2</sup>
+        /// score of the predictions, see `analysis::score::DefaultScore`.
+        /// Classifiers override this to return the accuracy, see
+        /// `analysis::score::label_accuracy`.
+        fn score(&self, inputs: &T, targets: &U) -> LearningResult<f64>
+            where U: ::analysis::score::DefaultScore
+        {
+            let outputs = self.predict(inputs)?;
+            Ok(U::default_score(&outputs, targets))
+        }
+
+        /// Predict output from inputs in batches of at most `batch_size` samples.
+        ///
+        /// The outputs are the same as those of `predict`, but the model
+        /// only works on one batch at a time. See `toolkit::batch`.
+        ///
+        /// # Panics
+        ///
+        /// - `batch_size` is zero.
+        fn predict_batched(&self, inputs: &T, batch_size: usize) -> LearningResult<U>
+            where T: toolkit::batch::Batch,
+                  U: toolkit::batch::Batch
+        {
+            toolkit::batch::predict_in_batches(inputs, batch_size, |batch| self.predict(batch))
+        }
     }
 
     /// Trait for unsupervised model.
@@ -160,6 +229,36 @@ pub mod learning {
 
         /// Train the model using inputs.
         fn train(&mut self, inputs: &T) -> LearningResult<()>;
+
+        /// Predict output from inputs in batches of at most `batch_size` samples.
+        ///
+        /// See `SupModel::predict_batched`.
+        ///
+        /// # Panics
+        ///
+        /// - `batch_size` is zero.
+        fn predict_batched(&self, inputs: &T, batch_size: usize) -> LearningResult<U>
+            where T: toolkit::batch::Batch,
+                  U: toolkit::batch::Batch
+        {
+            toolkit::batch::predict_in_batches(inputs, batch_size, |batch| self.predict(batch))
+        }
+    }
+
+    /// Trait for models which measure the importance of each input feature.
+    ///
+    /// This is used by `data::feature_selection::RecursiveFeatureElimination`.
+    ///
+    /// The linear models (`LinRegressor`, `RidgeRegressor` and
+    /// `LassoRegressor`) use the absolute value of each coefficient,
+    /// excluding the intercept. The coefficients are on the scale of the
+    /// original inputs, so the features should have comparable units for
+    /// their importances to be compared.
+    pub trait FeatureImportances {
+        /// The importance of each input feature, higher is more important.
+        ///
+        /// Returns `None` if the model has not been trained.
+        fn feature_importances(&self) -> Option<::linalg::Vector<f64>>;
     }
 
     /// Module for optimization in machine learning setting.
@@ -178,6 +277,19 @@ pub mod learning {
                             inputs: &Self::Inputs,
                             targets: &Self::Targets)
                             -> (f64, Vec<f64>);
+
+            /// Compute the cost for the model.
+            ///
+            /// This is used by line searches which only need the cost.
+            /// The default implementation calls `compute_grad`, models
+            /// may override it to avoid computing the gradient.
+            fn compute_cost(&self,
+                            params: &[f64],
+                            inputs: &Self::Inputs,
+                            targets: &Self::Targets)
+                            -> f64 {
+                self.compute_grad(params, inputs, targets).0
+            }
         }
 
         /// Trait for optimization algorithms.
@@ -195,11 +307,19 @@ pub mod learning {
 
         pub mod grad_desc;
         pub mod fmincg;
+        pub mod coordinate_descent;
+        pub mod lbfgs;
+        pub mod line_search;
+        pub mod nelder_mead;
+        pub mod objective;
+
+        pub use self::objective::{minimize, numerical_gradient};
     }
 
     /// Module for learning tools.
     pub mod toolkit {
         pub mod activ_fn;
+        pub mod batch;
         pub mod cost_fn;
         pub mod kernel;
         pub mod rand_utils;
@@ -217,6 +337,8 @@ pub mod stats {
 
 /// Module for evaluating models.
 pub mod analysis {
+    pub mod calibration;
+    pub mod clustering;
     pub mod confusion_matrix;
     pub mod cross_validation;
     pub mod score;
@@ -0,0 +1,96 @@
+//! Binary encoding of matrices
+//!
+//! This module contains the `ByteElement` trait for element types
+//! which can be written as little-endian bytes, and the `ParseError`
+//! returned when decoding fails. These are used by
+//! `MatrixExt::to_bytes` and `MatrixExt::from_bytes`.
+//!
+//! The format is the number of rows and columns, each as a
+//! little-endian `u64`, followed by the elements in row-major order.
+
+use std::error;
+use std::fmt;
+
+/// The size in bytes of the header holding the rows and columns.
+pub const HEADER_SIZE: usize = 16;
+
+/// Element types which can be encoded as little-endian bytes.
+pub trait ByteElement: Copy {
+    /// The number of bytes used for each element.
+    const SIZE: usize;
+
+    /// Appends the little-endian bytes of the element to `out`.
+    fn write_le(&self, out: &mut Vec<u8>);
+
+    /// Reads an element from the first `SIZE` bytes.
+    fn read_le(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_byte_element {
+    ($($t:ty),*) => {
+        $(
+            impl ByteElement for $t {
+                const SIZE: usize = ::std::mem::size_of::<$t>();
+
+                fn write_le(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn read_le(bytes: &[u8]) -> $t {
+                    let mut buf = [0u8; ::std::mem::size_of::<$t>()];
+                    buf.copy_from_slice(&bytes[..Self::SIZE]);
+                    <$t>::from_le_bytes(buf)
+                }
+            }
+        )*
+    }
+}
+
+impl_byte_element!(f32, f64, u8, u16, u32, u64, i8, i16, i32, i64);
+
+/// Errors encountered while decoding a matrix from bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// There are fewer bytes than the header and data require.
+    Truncated {
+        /// The number of bytes required.
+        expected: usize,
+        /// The number of bytes given.
+        found: usize,
+    },
+    /// There are bytes left after the data.
+    TrailingBytes {
+        /// The number of bytes required.
+        expected: usize,
+        /// The number of bytes given.
+        found: usize,
+    },
+    /// The dimensions in the header are too large to represent.
+    InvalidShape,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::Truncated { expected, found } => {
+                write!(f, "Truncated matrix bytes: expected {} bytes, found {}.", expected, found)
+            }
+            ParseError::TrailingBytes { expected, found } => {
+                write!(f, "Trailing matrix bytes: expected {} bytes, found {}.", expected, found)
+            }
+            ParseError::InvalidShape => write!(f, "Matrix dimensions are too large."),
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Reads a little-endian `u64` header field as a `usize`.
+pub fn read_dim(bytes: &[u8]) -> Result<usize, ParseError> {
+    let dim = u64::read_le(bytes);
+    if dim > usize::MAX as u64 {
+        Err(ParseError::InvalidShape)
+    } else {
+        Ok(dim as usize)
+    }
+}
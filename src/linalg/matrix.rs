@@ -0,0 +1,2955 @@
+//! Matrix extension methods
+//!
+//! This module contains the `MatrixExt` trait which extends the
+//! rulinalg `Matrix` with additional functionality used throughout
+//! rusty-machine.
+
+use linalg::{Axes, Matrix, BaseMatrix, BaseMatrixMut, Vector};
+use linalg::bytes::{self, ByteElement, ParseError};
+use data::stats::RunningStats;
+
+use libnum::{Float, FromPrimitive, Zero};
+
+use rulinalg::error::Error;
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::iter;
+use std::ops::{Add, Mul};
+
+/// Extension methods for the `Matrix` struct.
+///
+/// The trait is implemented for `Matrix<T>` and can be accessed
+/// by importing `rusty_machine::linalg::MatrixExt` (or the prelude).
+pub trait MatrixExt<T> {
+    /// Permutes the rows of the matrix.
+    ///
+    /// The permutation is given as an index vector, so that row `i`
+    /// of the output is row `perm[i]` of the input. This avoids
+    /// forming and multiplying by a dense permutation matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(3, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    /// let b = a.permute_rows(&[2, 0, 1]);
+    ///
+    /// assert_eq!(*b.data(), vec![5.0, 6.0, 1.0, 2.0, 3.0, 4.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `perm` is not a permutation of the row indices.
+    fn permute_rows(&self, perm: &[usize]) -> Matrix<T> where T: Copy;
+
+    /// Permutes the columns of the matrix.
+    ///
+    /// The permutation is given as an index vector, so that column `j`
+    /// of the output is column `perm[j]` of the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    /// let b = a.permute_cols(&[2, 0, 1]);
+    ///
+    /// assert_eq!(*b.data(), vec![3.0, 1.0, 2.0, 6.0, 4.0, 5.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `perm` is not a permutation of the column indices.
+    fn permute_cols(&self, perm: &[usize]) -> Matrix<T> where T: Copy;
+
+    /// Computes the QR decomposition of the matrix using Givens rotations.
+    ///
+    /// Returns `(Q, R)` where `Q` is orthogonal and `R` is upper triangular.
+    /// Each subdiagonal entry is zeroed by a single rotation, and entries
+    /// which are already zero are skipped. This makes the decomposition
+    /// cheaper than `qr_decomp` for sparse or nearly triangular matrices,
+    /// such as upper Hessenberg matrices.
+    ///
+    /// The factors agree with `qr_decomp` up to the signs of the
+    /// rows of `R` (and the corresponding columns of `Q`). To make the
+    /// result reproducible the signs are fixed so that the largest
+    /// magnitude entry of each column of `Q` is positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, BaseMatrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(3, 3, vec![4.0f64, 1.0, 2.0,
+    ///                                   3.0, 5.0, 1.0,
+    ///                                   0.0, 2.0, 6.0]);
+    /// let (q, r) = a.clone().qr_decomp_givens();
+    ///
+    /// let b = q * r;
+    /// for (x, y) in a.data().iter().zip(b.data().iter()) {
+    ///     assert!((x - y).abs() < 1e-10);
+    /// }
+    /// ```
+    fn qr_decomp_givens(self) -> (Matrix<T>, Matrix<T>) where T: Float;
+
+    /// Computes the singular value decomposition using one-sided Jacobi rotations.
+    ///
+    /// Returns `(Σ, U, V)` with the same layout as `svd`: for an `m x n`
+    /// matrix with `k = min(m, n)`, `Σ` is a `k x k` diagonal matrix of
+    /// singular values in decreasing order, `U` is `m x k` and `V` is `n x k`.
+    ///
+    /// Unlike `svd` this does not fail for rank deficient matrices. The
+    /// columns of `U` corresponding to zero singular values are zero.
+    /// NaN entries do not panic, and any NaN singular values are placed
+    /// after the others.
+    ///
+    /// The singular vectors are only determined up to sign, so each
+    /// pair of columns of `U` and `V` is negated if needed to make the
+    /// largest magnitude entry of the column of `U` positive. Where the
+    /// column of `U` is zero the column of `V` is used instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, BaseMatrix, MatrixExt};
+    ///
+    /// // A rank one matrix
+    /// let a = Matrix::new(3, 2, vec![1.0f64, 2.0, 2.0, 4.0, 3.0, 6.0]);
+    /// let (s, u, v) = a.svd_jacobi();
+    ///
+    /// assert!((s[[0, 0]] - 70f64.sqrt()).abs() < 1e-10);
+    /// assert!(s[[1, 1]].abs() < 1e-10);
+    ///
+    /// let b = u * s * v.transpose();
+    /// for (x, y) in a.data().iter().zip(b.data().iter()) {
+    ///     assert!((x - y).abs() < 1e-10);
+    /// }
+    /// ```
+    fn svd_jacobi(&self) -> (Matrix<T>, Matrix<T>, Matrix<T>) where T: Float;
+
+    /// Returns the symmetric part of the matrix, `(A + A^T) / 2`.
+    ///
+    /// This is useful for removing small asymmetries introduced by
+    /// floating point error, for example in covariance matrices,
+    /// before using methods which assume a symmetric input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0, 2.0, 4.0, 3.0]);
+    /// let b = a.symmetrize();
+    ///
+    /// assert_eq!(b, Matrix::new(2, 2, vec![1.0, 3.0, 3.0, 3.0]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    fn symmetrize(&self) -> Matrix<T> where T: Float;
+
+    /// Computes the Gram matrix of the columns, `X^T X`.
+    ///
+    /// Only the upper triangle is computed and it is mirrored into
+    /// the lower triangle, so the output is exactly symmetric.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(3, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    /// let g = a.gram();
+    ///
+    /// assert_eq!(g, Matrix::new(2, 2, vec![35.0, 44.0, 44.0, 56.0]));
+    /// ```
+    fn gram(&self) -> Matrix<T> where T: Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>;
+
+    /// Computes the Gram matrix of the rows, `X X^T`.
+    ///
+    /// Only the upper triangle is computed and it is mirrored into
+    /// the lower triangle, so the output is exactly symmetric.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    /// let g = a.gram_rows();
+    ///
+    /// assert_eq!(g, Matrix::new(2, 2, vec![14.0, 32.0, 32.0, 77.0]));
+    /// ```
+    fn gram_rows(&self) -> Matrix<T>
+        where T: Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>;
+
+    /// Computes the trace of the matrix, the sum of its diagonal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+    /// assert_eq!(a.trace(), 5.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    fn trace(&self) -> T where T: Copy + Zero + Add<T, Output = T>;
+
+    /// Computes the mean and sample variance of each column in a single pass.
+    ///
+    /// Uses Welford's algorithm, via `data::stats::RunningStats`, which
+    /// accumulates squared deviations from the running mean. This avoids
+    /// the catastrophic cancellation of the sum of squares formula
+    /// `(Σx² - (Σx)²/n) / (n - 1)` when the column values are large
+    /// relative to their spread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(3, 2, vec![1.0, 2.0, 3.0, 6.0, 5.0, 10.0]);
+    /// let (mean, var) = a.mean_variance_cols();
+    ///
+    /// assert_eq!(*mean.data(), vec![3.0, 6.0]);
+    /// assert_eq!(*var.data(), vec![4.0, 16.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix has fewer than two rows.
+    fn mean_variance_cols(&self) -> (Vector<T>, Vector<T>) where T: Float + FromPrimitive;
+
+    /// Computes the sample variance of each column in a single pass.
+    ///
+    /// See `mean_variance_cols` for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(3, 1, vec![1e8 + 1.0, 1e8 + 2.0, 1e8 + 3.0]);
+    /// assert_eq!(*a.variance_cols().data(), vec![1.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix has fewer than two rows.
+    fn variance_cols(&self) -> Vector<T> where T: Float + FromPrimitive;
+
+    /// Returns the upper triangular part of the matrix on and above the `k`-th diagonal.
+    ///
+    /// Entries below the `k`-th diagonal are set to zero. The main diagonal
+    /// is `k = 0`, `k > 0` is above it and `k < 0` is below it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 3, vec![1, 2, 3, 4, 5, 6]);
+    ///
+    /// assert_eq!(*a.triu(0).data(), vec![1, 2, 3, 0, 5, 6]);
+    /// assert_eq!(*a.triu(1).data(), vec![0, 2, 3, 0, 0, 6]);
+    /// ```
+    fn triu(&self, k: isize) -> Matrix<T> where T: Copy + Zero;
+
+    /// Returns the lower triangular part of the matrix on and below the `k`-th diagonal.
+    ///
+    /// Entries above the `k`-th diagonal are set to zero. The main diagonal
+    /// is `k = 0`, `k > 0` is above it and `k < 0` is below it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 3, vec![1, 2, 3, 4, 5, 6]);
+    ///
+    /// assert_eq!(*a.tril(0).data(), vec![1, 0, 0, 4, 5, 0]);
+    /// assert_eq!(*a.tril(-1).data(), vec![0, 0, 0, 4, 0, 0]);
+    /// ```
+    fn tril(&self, k: isize) -> Matrix<T> where T: Copy + Zero;
+
+    /// Multiplies each row of the matrix by the corresponding factor.
+    ///
+    /// This is equivalent to `diag(factors) * self` without
+    /// constructing the diagonal matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt, Vector};
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+    /// let b = a.scale_rows(&Vector::new(vec![2.0, -1.0]));
+    ///
+    /// assert_eq!(*b.data(), vec![2.0, 4.0, -3.0, -4.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The number of factors does not match the number of rows.
+    fn scale_rows(&self, factors: &Vector<T>) -> Matrix<T> where T: Copy + Mul<T, Output = T>;
+
+    /// Multiplies each column of the matrix by the corresponding factor.
+    ///
+    /// This is equivalent to `self * diag(factors)` without
+    /// constructing the diagonal matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt, Vector};
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+    /// let b = a.scale_cols(&Vector::new(vec![2.0, -1.0]));
+    ///
+    /// assert_eq!(*b.data(), vec![2.0, -2.0, 6.0, -4.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The number of factors does not match the number of columns.
+    fn scale_cols(&self, factors: &Vector<T>) -> Matrix<T> where T: Copy + Mul<T, Output = T>;
+
+    /// Counts the number of non-zero entries in the matrix.
+    ///
+    /// Only exact zeros are treated as zero, no tolerance is applied.
+    /// Entries which are merely small, such as rounding errors, are
+    /// counted as non-zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0, 0.0, 0.0, 1e-20]);
+    /// assert_eq!(a.nnz(), 2);
+    /// ```
+    fn nnz(&self) -> usize where T: Zero;
+
+    /// The fraction of entries in the matrix which are exactly zero.
+    ///
+    /// See `nnz` for how zeros are counted. An empty matrix
+    /// has sparsity zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0, 0.0, 0.0, 2.0]);
+    /// assert_eq!(a.sparsity(), 0.5);
+    /// ```
+    fn sparsity(&self) -> f64 where T: Zero;
+
+    /// Solves the Tikhonov regularized least squares problem.
+    ///
+    /// Returns the `x` minimizing `|Ax - b|^2 + lambda |x|^2`, which is
+    /// the solution of `(A^T A + lambda I) x = A^T b`. With `lambda = 0`
+    /// this is the ordinary least squares solution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt, Vector};
+    ///
+    /// let a = Matrix::new(3, 2, vec![1.0f64, 0.0, 0.0, 1.0, 1.0, 1.0]);
+    /// let b = Vector::new(vec![1.0, 2.0, 3.0]);
+    ///
+    /// let x = a.solve_regularized(&b, 0.0).unwrap();
+    /// assert!((x[0] - 1.0).abs() < 1e-10 && (x[1] - 2.0).abs() < 1e-10);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The size of `b` does not match the number of rows.
+    ///
+    /// # Failures
+    ///
+    /// - The regularized system is singular, for example when `lambda = 0`
+    ///   and the columns of the matrix are linearly dependent.
+    fn solve_regularized(&self, b: &Vector<T>, lambda: T) -> Result<Vector<T>, Error>
+        where T: Any + Float;
+
+    /// Constructs a block diagonal matrix from the given blocks.
+    ///
+    /// The blocks are placed along the diagonal in order, with zeros
+    /// elsewhere. The blocks may have different and non-square sizes,
+    /// giving a matrix whose rows and columns are the sums of theirs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(1, 1, vec![1]);
+    /// let b = Matrix::new(2, 1, vec![2, 3]);
+    /// let c = Matrix::block_diag(&[a, b]);
+    ///
+    /// assert_eq!(*c.data(), vec![1, 0,
+    ///                            0, 2,
+    ///                            0, 3]);
+    /// ```
+    fn block_diag(blocks: &[Matrix<T>]) -> Matrix<T> where T: Copy + Zero, Self: Sized;
+
+    /// Removes duplicate rows from the matrix.
+    ///
+    /// Returns the matrix of unique rows and the indices of the rows
+    /// which were kept. The first occurrence of each row is kept and
+    /// the original order of the rows is preserved.
+    ///
+    /// Rows are compared with exact equality. For floating point data
+    /// rows which differ only by rounding error are not duplicates,
+    /// rows containing `NaN` are never duplicates and `0.0` equals `-0.0`.
+    /// Round the data first if a tolerance is needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(3, 2, vec![1.0, 2.0,
+    ///                                3.0, 4.0,
+    ///                                1.0, 2.0]);
+    /// let (unique, kept) = a.unique_rows();
+    ///
+    /// assert_eq!(unique, Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]));
+    /// assert_eq!(kept, vec![0, 1]);
+    /// ```
+    fn unique_rows(&self) -> (Matrix<T>, Vec<usize>) where T: Copy + PartialEq;
+
+    /// Encodes the matrix as bytes.
+    ///
+    /// The rows and columns are written as little-endian `u64`s followed
+    /// by the little-endian elements in row-major order. The result can
+    /// be decoded with `from_bytes`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0f64, 2.0, 3.0, 4.0]);
+    /// let bytes = a.to_bytes();
+    ///
+    /// assert_eq!(bytes.len(), 16 + 4 * 8);
+    /// assert_eq!(Matrix::<f64>::from_bytes(&bytes).unwrap(), a);
+    /// ```
+    fn to_bytes(&self) -> Vec<u8> where T: ByteElement;
+
+    /// Decodes a matrix from the bytes written by `to_bytes`.
+    ///
+    /// The element type must match the type which was encoded, this
+    /// is not recorded in the bytes.
+    ///
+    /// # Failures
+    ///
+    /// - There are fewer bytes than the header and data require.
+    /// - There are bytes left over after the data.
+    /// - The dimensions in the header are too large.
+    fn from_bytes(bytes: &[u8]) -> Result<Matrix<T>, ParseError>
+        where T: ByteElement, Self: Sized;
+
+    /// Performs the rank-k update `self += alpha * u * v^T` in place.
+    ///
+    /// For an `m x n` matrix `u` must be `m x k` and `v` must be `n x k`.
+    /// The entries are updated directly so the product `u * v^T` is
+    /// never formed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let mut a = Matrix::<f64>::zeros(2, 2);
+    /// let u = Matrix::new(2, 1, vec![1.0, 2.0]);
+    ///
+    /// // Accumulate the outer product of u with itself
+    /// a.rank_k_update(&u, &u, 0.5);
+    /// assert_eq!(a, Matrix::new(2, 2, vec![0.5, 1.0, 1.0, 2.0]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The rows of `u` do not match the rows of the matrix.
+    /// - The rows of `v` do not match the columns of the matrix.
+    /// - `u` and `v` have different numbers of columns.
+    fn rank_k_update(&mut self, u: &Matrix<T>, v: &Matrix<T>, alpha: T)
+        where T: Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>;
+
+    /// Estimates the 1-norm condition number of the matrix.
+    ///
+    /// The condition number is `|A|_1 |A^-1|_1`. The norm of the inverse
+    /// is estimated with the Hager-Higham algorithm, which uses a few
+    /// solves with the LUP factors rather than forming the inverse or an
+    /// SVD. The estimate is a lower bound and is usually within a small
+    /// factor of the true value, which makes it a cheap check for
+    /// ill-conditioning in solvers.
+    ///
+    /// Returns infinity if the matrix is singular.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0, 0.0, 0.0, 1e-6]);
+    ///
+    /// let cond = a.cond_estimate();
+    /// assert!((cond - 1e6f64).abs() < 1e-3);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    fn cond_estimate(&self) -> T where T: Any + Float;
+
+    /// Reconstructs a matrix from its QR factors.
+    ///
+    /// Returns `q * r`, checking that the factors are compatible. This
+    /// is useful to test decompositions and to form the matrix again
+    /// after modifying `r`.
+    ///
+    /// The LUP factors from `lup_decomp` satisfy `PA = LU`, so the
+    /// matrix is reconstructed as `p.transpose() * l * u`. Forgetting
+    /// the transpose, or multiplying by `p` on the wrong side, gives a
+    /// matrix with permuted rows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(3, 2, vec![1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    /// let (q, r) = a.clone().qr_decomp().unwrap();
+    ///
+    /// let b = Matrix::from_qr(&q, &r);
+    /// assert!(b.data().iter().zip(a.data()).all(|(x, y)| (x - y).abs() < 1e-10));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The columns of `q` do not match the rows of `r`.
+    fn from_qr(q: &Matrix<T>, r: &Matrix<T>) -> Matrix<T> where T: Any + Float, Self: Sized;
+
+    /// Returns a reference to the entry in row `i` and column `j`.
+    ///
+    /// Returns `None` if the index is out of range, rather than
+    /// panicking like `self[[i, j]]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![1, 2, 3, 4]);
+    ///
+    /// assert_eq!(a.get(1, 0), Some(&3));
+    /// assert_eq!(a.get(2, 0), None);
+    /// ```
+    fn get(&self, i: usize, j: usize) -> Option<&T>;
+
+    /// Returns a mutable reference to the entry in row `i` and column `j`.
+    ///
+    /// Returns `None` if the index is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let mut a = Matrix::new(2, 2, vec![1, 2, 3, 4]);
+    ///
+    /// if let Some(x) = a.get_mut(0, 1) {
+    ///     *x = 5;
+    /// }
+    /// assert_eq!(a[[0, 1]], 5);
+    /// assert!(a.get_mut(0, 2).is_none());
+    /// ```
+    fn get_mut(&mut self, i: usize, j: usize) -> Option<&mut T>;
+
+    /// Sums the matrix along the given axis.
+    ///
+    /// This follows the convention of `mean` and `variance`:
+    /// `Axes::Row` sums over the rows, giving a vector with an entry
+    /// for each column, and `Axes::Col` sums over the columns, giving
+    /// an entry for each row.
+    ///
+    /// The name avoids a clash with `BaseMatrix::sum`, which sums
+    /// every entry and is also available as `sum_all`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Axes, Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+    ///
+    /// assert_eq!(*a.sum_axis(Axes::Row).data(), vec![4.0, 6.0]);
+    /// assert_eq!(*a.sum_axis(Axes::Col).data(), vec![3.0, 7.0]);
+    /// ```
+    fn sum_axis(&self, axis: Axes) -> Vector<T> where T: Copy + Zero + Add<T, Output = T>;
+
+    /// Sums every entry of the matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+    /// assert_eq!(a.sum_all(), 10.0);
+    /// ```
+    fn sum_all(&self) -> T where T: Copy + Zero + Add<T, Output = T>;
+
+    /// Checks whether two matrices are equal within an absolute tolerance.
+    ///
+    /// The matrices are equal if they have the same dimensions and every
+    /// pair of entries differs by at most `tol`. Entries which are NaN
+    /// are never equal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+    /// let b = Matrix::new(2, 2, vec![1.0, 2.0 + 1e-10, 3.0, 4.0]);
+    ///
+    /// assert!(a.approx_eq(&b, 1e-8));
+    /// assert!(!a.approx_eq(&b, 1e-12));
+    /// ```
+    fn approx_eq(&self, other: &Matrix<T>, tol: T) -> bool where T: Float;
+
+    /// Computes the Euclidean norm of each row.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![3.0, 4.0, 0.0, -2.0]);
+    ///
+    /// assert_eq!(*a.row_norms().data(), vec![5.0, 2.0]);
+    /// ```
+    fn row_norms(&self) -> Vector<T> where T: Float;
+
+    /// Computes the Euclidean norm of each column.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![3.0, 0.0, 4.0, -2.0]);
+    ///
+    /// assert_eq!(*a.col_norms().data(), vec![5.0, 2.0]);
+    /// ```
+    fn col_norms(&self) -> Vector<T> where T: Float;
+
+    /// Computes an orthonormal basis for the column space.
+    ///
+    /// Uses modified Gram-Schmidt with reorthogonalization, which gives
+    /// the `Q` of a thin QR decomposition. Columns whose remaining norm
+    /// after orthogonalization is at most `sqrt(epsilon)` times their
+    /// original norm depend on the earlier columns and are omitted, so
+    /// the result has as many columns as the numerical rank.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt, BaseMatrix};
+    ///
+    /// // The third column is the sum of the first two
+    /// let a = Matrix::new(3, 3, vec![1.0, 0.0, 1.0,
+    ///                                1.0, 1.0, 2.0,
+    ///                                0.0, 1.0, 1.0]);
+    /// let q = a.orthonormal_basis();
+    ///
+    /// assert_eq!(q.cols(), 2);
+    /// assert!((q.transpose() * &q).approx_eq(&Matrix::identity(2), 1e-12));
+    /// ```
+    fn orthonormal_basis(&self) -> Matrix<T> where T: Float;
+
+    /// Projects each row onto the column space of `basis`.
+    ///
+    /// The columns of `basis` need not be orthonormal. Each row `x` is
+    /// mapped to `B (B^T B)^-1 B^T x`, with the coefficients computed by
+    /// `solve_regularized`. The regularization is `epsilon` times the
+    /// trace of `B^T B`, so dependent basis columns are tolerated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// // The plane z = 0
+    /// let basis = Matrix::new(3, 2, vec![1.0, 1.0, 0.0, 2.0, 0.0, 0.0]);
+    /// let x = Matrix::new(1, 3, vec![3.0, -1.0, 5.0]);
+    ///
+    /// let p = x.project_onto(&basis);
+    /// assert!(p.approx_eq(&Matrix::new(1, 3, vec![3.0, -1.0, 0.0]), 1e-10));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The number of rows of `basis` does not match the number of columns.
+    fn project_onto(&self, basis: &Matrix<T>) -> Matrix<T> where T: Any + Float;
+
+    /// Computes the pseudo-determinant of a square matrix.
+    ///
+    /// This is the product of the singular values larger than `tol`,
+    /// computed with `svd_jacobi`. For symmetric positive semi-definite
+    /// matrices, such as covariance matrices, it is the product of the
+    /// non-zero eigenvalues and is used in the density of a degenerate
+    /// Gaussian. The pseudo-determinant of a matrix with no singular
+    /// values above `tol` is the empty product, one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// // Singular, with eigenvalues 3 and 0
+    /// let a = Matrix::new(2, 2, vec![1.5f64, 1.5, 1.5, 1.5]);
+    ///
+    /// assert!((a.pseudo_det(1e-10) - 3.0).abs() < 1e-10);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    fn pseudo_det(&self, tol: T) -> T where T: Float;
+
+    /// Whitens each row using the Cholesky factor of a covariance.
+    ///
+    /// With `cov = L L^T` each row `x` is mapped to `L^-1 x`, found by
+    /// forward substitution rather than inverting `L`. If `cov` is the
+    /// covariance of the rows then the result has identity covariance,
+    /// and the squared norm of a whitened row is its squared
+    /// Mahalanobis length. The mean is not subtracted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let cov = Matrix::new(2, 2, vec![4.0, 2.0, 2.0, 2.0]);
+    /// let x = Matrix::new(1, 2, vec![2.0, 3.0]);
+    ///
+    /// // L = [[2, 0], [1, 1]]
+    /// let z = x.whiten_with_cov(&cov);
+    /// assert!(z.approx_eq(&Matrix::new(1, 2, vec![1.0, 2.0]), 1e-12));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `cov` is not square with as many rows as the matrix has columns.
+    /// - `cov` is not positive definite.
+    fn whiten_with_cov(&self, cov: &Matrix<T>) -> Matrix<T> where T: Any + Float;
+
+    /// Computes the log-determinant of the information matrix `X^T X`.
+    ///
+    /// Treating the rows as the design points of an experiment, this is
+    /// the D-optimality criterion: rows which add information in new
+    /// directions increase it the most. It is computed from the Cholesky
+    /// factor `L` of `X^T X` as `2 sum(ln L_ii)`.
+    ///
+    /// If the information matrix is singular, for example when there
+    /// are fewer rows than columns, the result is negative infinity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let x = Matrix::new(2, 2, vec![2.0f64, 0.0, 0.0, 3.0]);
+    ///
+    /// // det(X^T X) = 36
+    /// assert!((x.log_det_information() - 36f64.ln()).abs() < 1e-12);
+    /// ```
+    fn log_det_information(&self) -> T where T: Any + Float;
+
+    /// Computes the Frobenius inner product with another matrix.
+    ///
+    /// This is the sum of the products of corresponding entries, which
+    /// equals `trace(A^T B)` without forming the product. The Frobenius
+    /// inner product of a matrix with itself is its squared Frobenius norm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+    /// let b = Matrix::new(2, 2, vec![0.0, 1.0, -1.0, 2.0]);
+    ///
+    /// assert_eq!(a.frobenius_dot(&b), 7.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrices have different dimensions.
+    fn frobenius_dot(&self, other: &Matrix<T>) -> T
+        where T: Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>;
+
+    /// Computes an orthonormal basis for the column space.
+    ///
+    /// The basis is made up of the left singular vectors, from
+    /// `svd_jacobi`, whose singular values are larger than `tol`. The
+    /// number of columns returned is therefore the numerical rank, and
+    /// a matrix with no singular values above `tol` gives a basis with
+    /// no columns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, BaseMatrix, MatrixExt};
+    ///
+    /// // The second column is twice the first
+    /// let a = Matrix::new(3, 2, vec![1.0, 2.0, 0.0, 0.0, 1.0, 2.0]);
+    /// let basis = a.column_space(1e-10);
+    ///
+    /// assert_eq!(basis.cols(), 1);
+    /// ```
+    fn column_space(&self, tol: T) -> Matrix<T> where T: Float;
+
+    /// Computes an orthonormal basis for the null space.
+    ///
+    /// The basis is made up of the right singular vectors whose singular
+    /// values are at most `tol`, so every column `x` satisfies `A x = 0`
+    /// up to `tol`. Together with `column_space` the dimensions sum to
+    /// the number of columns. A matrix with full column rank gives a
+    /// basis with no columns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, BaseMatrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(1, 2, vec![1.0f64, -1.0]);
+    /// let basis = a.null_space(1e-10);
+    ///
+    /// assert_eq!(basis.cols(), 1);
+    /// assert!((basis[[0, 0]] - basis[[1, 0]]).abs() < 1e-10);
+    /// ```
+    fn null_space(&self, tol: T) -> Matrix<T> where T: Float;
+
+    /// Solves `AX = B` for a matrix `B` with several right-hand sides.
+    ///
+    /// The matrix is factored once with `lup_decomp` and each column of
+    /// `B` is then solved by forward and back substitution. This avoids
+    /// repeating the `O(n^3)` factorization which calling `solve` for
+    /// each column would do.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let a = Matrix::new(2, 2, vec![2.0f64, 1.0, 1.0, 3.0]);
+    /// let b = Matrix::new(2, 2, vec![3.0, 1.0, 4.0, -2.0]);
+    ///
+    /// let x = a.solve_multiple(&b).unwrap();
+    /// let y = &a * &x;
+    /// assert!(y.data().iter().zip(b.data()).all(|(p, q)| (p - q).abs() < 1e-10));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    /// - The rows of `b` do not match the rows of the matrix.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix is singular.
+    fn solve_multiple(&self, b: &Matrix<T>) -> Result<Matrix<T>, Error> where T: Any + Float;
+
+    /// Solves `LX = B` for a lower triangular matrix `L` and several right-hand sides.
+    ///
+    /// Each column of `B` is solved by forward substitution. Only the
+    /// lower triangle of the matrix is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let l = Matrix::new(2, 2, vec![2.0f64, 0.0, 1.0, 4.0]);
+    /// let b = Matrix::new(2, 2, vec![2.0, 4.0, 5.0, 6.0]);
+    ///
+    /// let x = l.solve_l_triangular_multiple(&b).unwrap();
+    /// assert_eq!(*x.data(), vec![1.0, 2.0, 1.0, 1.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    /// - The rows of `b` do not match the rows of the matrix.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix has a zero on its diagonal.
+    fn solve_l_triangular_multiple(&self, b: &Matrix<T>) -> Result<Matrix<T>, Error>
+        where T: Any + Float;
+
+    /// Solves `UX = B` for an upper triangular matrix `U` and several right-hand sides.
+    ///
+    /// Each column of `B` is solved by back substitution. Only the
+    /// upper triangle of the matrix is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// let u = Matrix::new(2, 2, vec![2.0f64, 1.0, 0.0, 4.0]);
+    /// let b = Matrix::new(2, 2, vec![3.0, 5.0, 4.0, 4.0]);
+    ///
+    /// let x = u.solve_u_triangular_multiple(&b).unwrap();
+    /// assert_eq!(*x.data(), vec![1.0, 2.0, 1.0, 1.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    /// - The rows of `b` do not match the rows of the matrix.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix has a zero on its diagonal.
+    fn solve_u_triangular_multiple(&self, b: &Matrix<T>) -> Result<Matrix<T>, Error>
+        where T: Any + Float;
+
+    /// Repairs a symmetric matrix which is not positive definite.
+    ///
+    /// The matrix is symmetrized and diagonalized with Jacobi rotations.
+    /// Eigenvalues below a floor of `sqrt(epsilon)` times the largest
+    /// eigenvalue magnitude are raised to the floor and the matrix is
+    /// reconstructed. This is the nearest positive definite matrix, in
+    /// the Frobenius norm, with eigenvalues at least the floor.
+    ///
+    /// This is useful for covariance estimates which have drifted
+    /// indefinite through rounding error so that `cholesky` fails.
+    /// Matrices which are already well conditioned and positive
+    /// definite are returned unchanged, up to rounding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, MatrixExt};
+    ///
+    /// // Eigenvalues of 2.001 and -0.001
+    /// let a = Matrix::new(2, 2, vec![1.0f64, 1.001, 1.001, 1.0]);
+    /// let b = a.nearest_pd();
+    ///
+    /// assert!(b.cholesky().is_ok());
+    /// assert!(b.data().iter().zip(a.data()).all(|(x, y)| (x - y).abs() < 1e-3));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    fn nearest_pd(&self) -> Matrix<T> where T: Float;
+}
+
+impl<T> MatrixExt<T> for Matrix<T> {
+    fn permute_rows(&self, perm: &[usize]) -> Matrix<T>
+        where T: Copy
+    {
+        assert_permutation(perm, self.rows());
+
+        let mut data = Vec::with_capacity(self.rows() * self.cols());
+        for &p in perm {
+            data.extend_from_slice(self.get_row(p).unwrap());
+        }
+
+        Matrix::new(self.rows(), self.cols(), data)
+    }
+
+    fn permute_cols(&self, perm: &[usize]) -> Matrix<T>
+        where T: Copy
+    {
+        assert_permutation(perm, self.cols());
+
+        let mut data = Vec::with_capacity(self.rows() * self.cols());
+        for row in self.iter_rows() {
+            data.extend(perm.iter().map(|&p| row[p]));
+        }
+
+        Matrix::new(self.rows(), self.cols(), data)
+    }
+
+    fn qr_decomp_givens(self) -> (Matrix<T>, Matrix<T>)
+        where T: Float
+    {
+        let m = self.rows();
+        let n = self.cols();
+
+        let mut q = Matrix::<T>::identity(m);
+        let mut r = self;
+
+        for j in 0..n {
+            for i in (j + 1..m).rev() {
+                let b = r[[i, j]];
+                if b == T::zero() {
+                    continue;
+                }
+
+                let a = r[[i - 1, j]];
+                let h = a.hypot(b);
+                let (c, s) = (a / h, b / h);
+
+                // Rotate rows i - 1 and i of R
+                for k in j..n {
+                    let (x, y) = (r[[i - 1, k]], r[[i, k]]);
+                    r[[i - 1, k]] = c * x + s * y;
+                    r[[i, k]] = c * y - s * x;
+                }
+                r[[i, j]] = T::zero();
+
+                // Accumulate the transposed rotation into Q
+                for row in q.iter_rows_mut() {
+                    let (x, y) = (row[i - 1], row[i]);
+                    row[i - 1] = c * x + s * y;
+                    row[i] = c * y - s * x;
+                }
+            }
+        }
+
+        for j in 0..m {
+            if largest_is_negative(&q, j) {
+                negate_col(&mut q, j);
+                for k in 0..n {
+                    r[[j, k]] = -r[[j, k]];
+                }
+            }
+        }
+
+        (q, r)
+    }
+
+    fn svd_jacobi(&self) -> (Matrix<T>, Matrix<T>, Matrix<T>)
+        where T: Float
+    {
+        if self.rows() < self.cols() {
+            let (s, mut v, mut u) = self.transpose().svd_jacobi();
+            fix_singular_vector_signs(&mut u, &mut v);
+            return (s, u, v);
+        }
+
+        let m = self.rows();
+        let n = self.cols();
+
+        let mut a = self.clone();
+        let mut v = Matrix::<T>::identity(n);
+
+        // Orthogonalize pairs of columns until all are mutually orthogonal
+        for _ in 0..100 {
+            let mut rotated = false;
+
+            for p in 0..n {
+                for q in p + 1..n {
+                    let (mut alpha, mut beta, mut gamma) = (T::zero(), T::zero(), T::zero());
+                    for i in 0..m {
+                        alpha = alpha + a[[i, p]] * a[[i, p]];
+                        beta = beta + a[[i, q]] * a[[i, q]];
+                        gamma = gamma + a[[i, p]] * a[[i, q]];
+                    }
+
+                    if gamma.abs() <= T::epsilon() * (alpha * beta).sqrt() {
+                        continue;
+                    }
+                    rotated = true;
+
+                    let zeta = (beta - alpha) / (gamma + gamma);
+                    let t = zeta.signum() / (zeta.abs() + (T::one() + zeta * zeta).sqrt());
+                    let c = T::one() / (T::one() + t * t).sqrt();
+                    let s = c * t;
+
+                    for mat in &mut [&mut a, &mut v] {
+                        for row in mat.iter_rows_mut() {
+                            let (x, y) = (row[p], row[q]);
+                            row[p] = c * x - s * y;
+                            row[q] = s * x + c * y;
+                        }
+                    }
+                }
+            }
+
+            if !rotated {
+                break;
+            }
+        }
+
+        // The singular values are the norms of the rotated columns
+        let norms = (0..n)
+            .map(|j| (0..m).fold(T::zero(), |acc, i| acc + a[[i, j]] * a[[i, j]]).sqrt())
+            .collect::<Vec<_>>();
+        let mut order = (0..n).collect::<Vec<_>>();
+        order.sort_by(|&i, &j| descending_nan_last(norms[i], norms[j]));
+
+        let mut sigma = Matrix::<T>::zeros(n, n);
+        let mut u = Matrix::<T>::zeros(m, n);
+        for (k, &j) in order.iter().enumerate() {
+            sigma[[k, k]] = norms[j];
+            if norms[j] > T::zero() {
+                for i in 0..m {
+                    u[[i, k]] = a[[i, j]] / norms[j];
+                }
+            }
+        }
+
+        let mut v = v.select_cols(&order);
+        fix_singular_vector_signs(&mut u, &mut v);
+
+        (sigma, u, v)
+    }
+
+    fn symmetrize(&self) -> Matrix<T>
+        where T: Float
+    {
+        assert!(self.rows() == self.cols(), "Matrix must be square to symmetrize.");
+
+        let n = self.rows();
+        let two = T::one() + T::one();
+
+        let mut data = Vec::with_capacity(n * n);
+        for i in 0..n {
+            for j in 0..n {
+                data.push((self[[i, j]] + self[[j, i]]) / two);
+            }
+        }
+
+        Matrix::new(n, n, data)
+    }
+
+    fn gram(&self) -> Matrix<T>
+        where T: Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>
+    {
+        let n = self.cols();
+        let mut g = Matrix::zeros(n, n);
+
+        // Accumulate the outer product of each row into the upper triangle
+        for row in self.iter_rows() {
+            for i in 0..n {
+                let x_i = row[i];
+                for j in i..n {
+                    g[[i, j]] = g[[i, j]] + x_i * row[j];
+                }
+            }
+        }
+
+        mirror_upper(&mut g);
+        g
+    }
+
+    fn gram_rows(&self) -> Matrix<T>
+        where T: Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>
+    {
+        let n = self.rows();
+        let mut g = Matrix::zeros(n, n);
+
+        for i in 0..n {
+            let row_i = self.get_row(i).unwrap();
+            for j in i..n {
+                let row_j = self.get_row(j).unwrap();
+                g[[i, j]] = row_i.iter()
+                    .zip(row_j.iter())
+                    .fold(T::zero(), |acc, (&x, &y)| acc + x * y);
+            }
+        }
+
+        mirror_upper(&mut g);
+        g
+    }
+
+    fn trace(&self) -> T
+        where T: Copy + Zero + Add<T, Output = T>
+    {
+        assert!(self.rows() == self.cols(), "Matrix must be square to compute the trace.");
+        (0..self.rows()).fold(T::zero(), |acc, i| acc + self[[i, i]])
+    }
+
+    fn mean_variance_cols(&self) -> (Vector<T>, Vector<T>)
+        where T: Float + FromPrimitive
+    {
+        assert!(self.rows() > 1, "Matrix must have at least two rows to compute the variance.");
+
+        let mut stats = RunningStats::default();
+        for row in self.iter_rows() {
+            stats.update(&Vector::new(row.to_vec()));
+        }
+
+        let mean = stats.mean().unwrap().clone();
+        (mean, stats.variance().unwrap())
+    }
+
+    fn variance_cols(&self) -> Vector<T>
+        where T: Float + FromPrimitive
+    {
+        self.mean_variance_cols().1
+    }
+
+    fn triu(&self, k: isize) -> Matrix<T>
+        where T: Copy + Zero
+    {
+        mask_diagonals(self, |i, j| j as isize - i as isize >= k)
+    }
+
+    fn tril(&self, k: isize) -> Matrix<T>
+        where T: Copy + Zero
+    {
+        mask_diagonals(self, |i, j| j as isize - i as isize <= k)
+    }
+
+    fn scale_rows(&self, factors: &Vector<T>) -> Matrix<T>
+        where T: Copy + Mul<T, Output = T>
+    {
+        assert!(factors.size() == self.rows(),
+                "The number of factors must match the number of rows.");
+
+        let mut data = Vec::with_capacity(self.rows() * self.cols());
+        for (row, &f) in self.iter_rows().zip(factors.data().iter()) {
+            data.extend(row.iter().map(|&x| x * f));
+        }
+
+        Matrix::new(self.rows(), self.cols(), data)
+    }
+
+    fn scale_cols(&self, factors: &Vector<T>) -> Matrix<T>
+        where T: Copy + Mul<T, Output = T>
+    {
+        assert!(factors.size() == self.cols(),
+                "The number of factors must match the number of columns.");
+
+        let mut data = Vec::with_capacity(self.rows() * self.cols());
+        for row in self.iter_rows() {
+            data.extend(row.iter().zip(factors.data().iter()).map(|(&x, &f)| x * f));
+        }
+
+        Matrix::new(self.rows(), self.cols(), data)
+    }
+
+    fn nnz(&self) -> usize
+        where T: Zero
+    {
+        self.data().iter().filter(|x| !x.is_zero()).count()
+    }
+
+    fn sparsity(&self) -> f64
+        where T: Zero
+    {
+        let size = self.rows() * self.cols();
+        if size == 0 {
+            return 0f64;
+        }
+
+        (size - self.nnz()) as f64 / size as f64
+    }
+
+    fn solve_regularized(&self, b: &Vector<T>, lambda: T) -> Result<Vector<T>, Error>
+        where T: Any + Float
+    {
+        assert!(b.size() == self.rows(),
+                "The size of the vector must match the number of rows.");
+
+        let mut system = self.gram();
+        for i in 0..system.rows() {
+            system[[i, i]] = system[[i, i]] + lambda;
+        }
+
+        system.solve(self.transpose() * b)
+    }
+
+    fn block_diag(blocks: &[Matrix<T>]) -> Matrix<T>
+        where T: Copy + Zero
+    {
+        let rows = blocks.iter().map(|b| b.rows()).sum();
+        let cols = blocks.iter().map(|b| b.cols()).sum();
+
+        let mut data = Vec::with_capacity(rows * cols);
+        let mut offset = 0;
+        for block in blocks {
+            for row in block.iter_rows() {
+                data.extend(iter::repeat_n(T::zero(), offset));
+                data.extend_from_slice(row);
+                data.extend(iter::repeat_n(T::zero(), cols - offset - block.cols()));
+            }
+            offset += block.cols();
+        }
+
+        Matrix::new(rows, cols, data)
+    }
+
+    fn unique_rows(&self) -> (Matrix<T>, Vec<usize>)
+        where T: Copy + PartialEq
+    {
+        let rows: Vec<&[T]> = self.iter_rows().collect();
+        let mut kept: Vec<usize> = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            if !kept.iter().any(|&k| rows[k] == *row) {
+                kept.push(i);
+            }
+        }
+
+        (self.select_rows(&kept), kept)
+    }
+
+    fn to_bytes(&self) -> Vec<u8>
+        where T: ByteElement
+    {
+        let mut out = Vec::with_capacity(bytes::HEADER_SIZE + self.data().len() * T::SIZE);
+        (self.rows() as u64).write_le(&mut out);
+        (self.cols() as u64).write_le(&mut out);
+        for x in self.data() {
+            x.write_le(&mut out);
+        }
+        out
+    }
+
+    fn from_bytes(data: &[u8]) -> Result<Matrix<T>, ParseError>
+        where T: ByteElement
+    {
+        if data.len() < bytes::HEADER_SIZE {
+            return Err(ParseError::Truncated {
+                expected: bytes::HEADER_SIZE,
+                found: data.len(),
+            });
+        }
+
+        let rows = bytes::read_dim(&data[..8])?;
+        let cols = bytes::read_dim(&data[8..16])?;
+        let expected = rows.checked_mul(cols)
+            .and_then(|n| n.checked_mul(T::SIZE))
+            .and_then(|n| n.checked_add(bytes::HEADER_SIZE))
+            .ok_or(ParseError::InvalidShape)?;
+
+        if data.len() < expected {
+            return Err(ParseError::Truncated { expected, found: data.len() });
+        } else if data.len() > expected {
+            return Err(ParseError::TrailingBytes { expected, found: data.len() });
+        }
+
+        let elements: Vec<T> = data[bytes::HEADER_SIZE..]
+            .chunks(T::SIZE)
+            .map(T::read_le)
+            .collect();
+        Ok(Matrix::new(rows, cols, elements))
+    }
+
+    fn rank_k_update(&mut self, u: &Matrix<T>, v: &Matrix<T>, alpha: T)
+        where T: Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>
+    {
+        assert!(u.rows() == self.rows(),
+                "The rows of u must match the rows of the matrix.");
+        assert!(v.rows() == self.cols(),
+                "The rows of v must match the columns of the matrix.");
+        assert!(u.cols() == v.cols(), "u and v must have the same number of columns.");
+
+        for (row, u_row) in self.iter_rows_mut().zip(u.iter_rows()) {
+            for (x, v_row) in row.iter_mut().zip(v.iter_rows()) {
+                let dot = u_row.iter()
+                    .zip(v_row.iter())
+                    .fold(T::zero(), |acc, (&a, &b)| acc + a * b);
+                *x = *x + alpha * dot;
+            }
+        }
+    }
+
+    fn cond_estimate(&self) -> T
+        where T: Any + Float
+    {
+        assert!(self.rows() == self.cols(), "Matrix must be square.");
+        let n = self.rows();
+        if n == 0 {
+            return T::zero();
+        }
+
+        let norm_1 = |v: &Vector<T>| v.iter().fold(T::zero(), |acc, x| acc + x.abs());
+        let a_norm = (0..n)
+            .map(|j| self.iter_rows().fold(T::zero(), |acc, row| acc + row[j].abs()))
+            .fold(T::zero(), T::max);
+
+        let (l, u, p) = match self.lup_decomp() {
+            Ok(lup) => lup,
+            Err(_) => return T::infinity(),
+        };
+        let (l_t, u_t, p_t) = (l.transpose(), u.transpose(), p.transpose());
+
+        // Solves with A and its transpose using PA = LU
+        let solve = |b: Vector<T>| {
+            l.solve_l_triangular(&p * b).and_then(|y| u.solve_u_triangular(y))
+        };
+        let solve_t = |b: Vector<T>| {
+            u_t.solve_l_triangular(b).and_then(|y| l_t.solve_u_triangular(y)).map(|y| &p_t * y)
+        };
+
+        let n_t = T::from(n).unwrap();
+        let mut x = Vector::new(vec![T::one() / n_t; n]);
+        let mut inv_norm = T::zero();
+
+        for _ in 0..5 {
+            let y = match solve(x.clone()) {
+                Ok(y) => y,
+                Err(_) => return T::infinity(),
+            };
+            inv_norm = norm_1(&y);
+
+            let signs = y.apply(&|v| if v < T::zero() { -T::one() } else { T::one() });
+            let z = match solve_t(signs) {
+                Ok(z) => z,
+                Err(_) => return T::infinity(),
+            };
+
+            let (j, z_max) = z.iter()
+                .enumerate()
+                .fold((0, T::zero()),
+                      |(j, m), (i, v)| if v.abs() > m { (i, v.abs()) } else { (j, m) });
+            if z_max <= z.dot(&x) {
+                break;
+            }
+
+            x = Vector::zeros(n);
+            x[j] = T::one();
+        }
+
+        // Higham's alternating vector guards against the cases
+        // where the iteration underestimates badly
+        if n > 1 {
+            let alt = (0..n)
+                .map(|i| {
+                    let sign = if i % 2 == 0 { T::one() } else { -T::one() };
+                    sign * (T::one() + T::from(i).unwrap() / T::from(n - 1).unwrap())
+                })
+                .collect::<Vec<_>>();
+            if let Ok(y) = solve(Vector::new(alt)) {
+                let two = T::one() + T::one();
+                let three = two + T::one();
+                inv_norm = inv_norm.max(two * norm_1(&y) / (three * n_t));
+            }
+        }
+
+        a_norm * inv_norm
+    }
+
+    fn from_qr(q: &Matrix<T>, r: &Matrix<T>) -> Matrix<T>
+        where T: Any + Float
+    {
+        assert!(q.cols() == r.rows(),
+                "The columns of q must match the rows of r.");
+        q * r
+    }
+
+    fn get(&self, i: usize, j: usize) -> Option<&T> {
+        if i < self.rows() && j < self.cols() {
+            Some(&self[[i, j]])
+        } else {
+            None
+        }
+    }
+
+    fn get_mut(&mut self, i: usize, j: usize) -> Option<&mut T> {
+        if i < self.rows() && j < self.cols() {
+            Some(&mut self[[i, j]])
+        } else {
+            None
+        }
+    }
+
+    fn sum_axis(&self, axis: Axes) -> Vector<T>
+        where T: Copy + Zero + Add<T, Output = T>
+    {
+        match axis {
+            Axes::Row => {
+                let mut sums = vec![T::zero(); self.cols()];
+                for row in self.iter_rows() {
+                    for (s, &x) in sums.iter_mut().zip(row.iter()) {
+                        *s = *s + x;
+                    }
+                }
+                Vector::new(sums)
+            }
+            Axes::Col => {
+                self.iter_rows()
+                    .map(|row| row.iter().fold(T::zero(), |acc, &x| acc + x))
+                    .collect::<Vec<_>>()
+                    .into()
+            }
+        }
+    }
+
+    fn sum_all(&self) -> T
+        where T: Copy + Zero + Add<T, Output = T>
+    {
+        self.data().iter().fold(T::zero(), |acc, &x| acc + x)
+    }
+
+    fn approx_eq(&self, other: &Matrix<T>, tol: T) -> bool
+        where T: Float
+    {
+        self.rows() == other.rows() && self.cols() == other.cols() &&
+        self.data().iter().zip(other.data().iter()).all(|(&a, &b)| (a - b).abs() <= tol)
+    }
+
+    fn row_norms(&self) -> Vector<T>
+        where T: Float
+    {
+        self.iter_rows()
+            .map(|row| row.iter().fold(T::zero(), |acc, &x| acc + x * x).sqrt())
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn col_norms(&self) -> Vector<T>
+        where T: Float
+    {
+        let mut sums = vec![T::zero(); self.cols()];
+        for row in self.iter_rows() {
+            for (s, &x) in sums.iter_mut().zip(row.iter()) {
+                *s = *s + x * x;
+            }
+        }
+        sums.into_iter().map(|s| s.sqrt()).collect::<Vec<_>>().into()
+    }
+
+    fn orthonormal_basis(&self) -> Matrix<T>
+        where T: Float
+    {
+        let m = self.rows();
+        let tol = T::epsilon().sqrt();
+        let mut basis: Vec<Vec<T>> = Vec::new();
+
+        for j in 0..self.cols() {
+            let mut v = (0..m).map(|i| self[[i, j]]).collect::<Vec<_>>();
+            let norm = v.iter().fold(T::zero(), |acc, &x| acc + x * x).sqrt();
+            if norm == T::zero() {
+                continue;
+            }
+
+            // Two passes of orthogonalization keep the basis orthonormal to
+            // working precision even when the columns are nearly dependent.
+            for _ in 0..2 {
+                for q in &basis {
+                    let proj = q.iter().zip(v.iter()).fold(T::zero(), |acc, (&a, &b)| acc + a * b);
+                    for (x, &y) in v.iter_mut().zip(q.iter()) {
+                        *x = *x - proj * y;
+                    }
+                }
+            }
+
+            let residual = v.iter().fold(T::zero(), |acc, &x| acc + x * x).sqrt();
+            if residual > tol * norm {
+                basis.push(v.into_iter().map(|x| x / residual).collect());
+            }
+        }
+
+        let mut data = Vec::with_capacity(m * basis.len());
+        for i in 0..m {
+            data.extend(basis.iter().map(|q| q[i]));
+        }
+        Matrix::new(m, basis.len(), data)
+    }
+
+    fn project_onto(&self, basis: &Matrix<T>) -> Matrix<T>
+        where T: Any + Float
+    {
+        assert!(basis.rows() == self.cols(),
+                "The basis must have as many rows as the matrix has columns.");
+
+        let lambda = T::epsilon() * basis.gram().trace();
+        let mut data = Vec::with_capacity(self.rows() * self.cols());
+
+        for row in self.iter_rows() {
+            if lambda == T::zero() {
+                // The basis is zero and spans only the origin
+                let len = data.len() + self.cols();
+                data.resize(len, T::zero());
+                continue;
+            }
+
+            let x = Vector::new(row.to_vec());
+            let coefs = basis.solve_regularized(&x, lambda)
+                .expect("The regularized normal equations are positive definite.");
+            data.extend((basis * coefs).into_vec());
+        }
+
+        Matrix::new(self.rows(), self.cols(), data)
+    }
+
+    fn pseudo_det(&self, tol: T) -> T
+        where T: Float
+    {
+        assert!(self.rows() == self.cols(),
+                "The pseudo-determinant requires a square matrix.");
+
+        let (s, _, _) = self.svd_jacobi();
+        (0..s.rows())
+            .map(|i| s[[i, i]])
+            .filter(|&x| x > tol)
+            .fold(T::one(), |acc, x| acc * x)
+    }
+
+    fn whiten_with_cov(&self, cov: &Matrix<T>) -> Matrix<T>
+        where T: Any + Float
+    {
+        let d = self.cols();
+        assert!(cov.rows() == d && cov.cols() == d,
+                "The covariance must be square with as many rows as the matrix has columns.");
+
+        let l = cov.cholesky().ok()
+            .and_then(|l| {
+                if (0..d).all(|i| l[[i, i]] > T::zero() && l[[i, i]].is_finite()) {
+                    Some(l)
+                } else {
+                    None
+                }
+            })
+            .expect("The covariance must be positive definite.");
+
+        let mut data = Vec::with_capacity(self.rows() * d);
+        for row in self.iter_rows() {
+            let start = data.len();
+            for i in 0..d {
+                let mut x = row[i];
+                for j in 0..i {
+                    x = x - l[[i, j]] * data[start + j];
+                }
+                data.push(x / l[[i, i]]);
+            }
+        }
+
+        Matrix::new(self.rows(), d, data)
+    }
+
+    fn log_det_information(&self) -> T
+        where T: Any + Float
+    {
+        let info = self.gram();
+        let l = match info.cholesky() {
+            Ok(l) => l,
+            Err(_) => return T::neg_infinity(),
+        };
+
+        (0..l.rows()).fold(T::zero(), |acc, i| {
+            let d = l[[i, i]];
+            if d > T::zero() {
+                acc + d.ln() + d.ln()
+            } else {
+                T::neg_infinity()
+            }
+        })
+    }
+
+    fn frobenius_dot(&self, other: &Matrix<T>) -> T
+        where T: Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>
+    {
+        assert!(self.rows() == other.rows() && self.cols() == other.cols(),
+                "The matrices must have the same dimensions.");
+
+        self.data()
+            .iter()
+            .zip(other.data().iter())
+            .fold(T::zero(), |acc, (&a, &b)| acc + a * b)
+    }
+
+    fn column_space(&self, tol: T) -> Matrix<T>
+        where T: Float
+    {
+        let (s, u, _) = self.svd_jacobi();
+        let rank = (0..s.rows()).filter(|&i| s[[i, i]] > tol).count();
+        u.select_cols(&(0..rank).collect::<Vec<_>>())
+    }
+
+    fn null_space(&self, tol: T) -> Matrix<T>
+        where T: Float
+    {
+        let n = self.cols();
+
+        // Pad with zero rows so that V holds a full basis of the domain
+        let (s, _, v) = if self.rows() < n {
+            let mut data = self.data().clone();
+            data.resize(n * n, T::zero());
+            Matrix::new(n, n, data).svd_jacobi()
+        } else {
+            self.svd_jacobi()
+        };
+        let rank = (0..s.rows()).filter(|&i| s[[i, i]] > tol).count();
+        v.select_cols(&(rank..n).collect::<Vec<_>>())
+    }
+
+    fn solve_multiple(&self, b: &Matrix<T>) -> Result<Matrix<T>, Error>
+        where T: Any + Float
+    {
+        assert!(self.rows() == self.cols(), "Matrix must be square to solve.");
+        assert!(b.rows() == self.rows(),
+                "The right-hand sides must have as many rows as the matrix.");
+
+        let (l, u, p) = self.lup_decomp()?;
+        let y = l.solve_l_triangular_multiple(&(p * b))?;
+        u.solve_u_triangular_multiple(&y)
+    }
+
+    fn solve_l_triangular_multiple(&self, b: &Matrix<T>) -> Result<Matrix<T>, Error>
+        where T: Any + Float
+    {
+        assert!(self.rows() == self.cols(), "Matrix must be square to solve.");
+        assert!(b.rows() == self.rows(),
+                "The right-hand sides must have as many rows as the matrix.");
+
+        solve_columns(b, |col| self.solve_l_triangular(col))
+    }
+
+    fn solve_u_triangular_multiple(&self, b: &Matrix<T>) -> Result<Matrix<T>, Error>
+        where T: Any + Float
+    {
+        assert!(self.rows() == self.cols(), "Matrix must be square to solve.");
+        assert!(b.rows() == self.rows(),
+                "The right-hand sides must have as many rows as the matrix.");
+
+        solve_columns(b, |col| self.solve_u_triangular(col))
+    }
+
+    fn nearest_pd(&self) -> Matrix<T>
+        where T: Float
+    {
+        assert!(self.rows() == self.cols(), "Matrix must be square to repair.");
+
+        let n = self.rows();
+        let (eigenvalues, v) = symmetric_eigen(&self.symmetrize());
+
+        let largest = eigenvalues.iter().fold(T::zero(), |acc, x| acc.max(x.abs()));
+        let scale = if largest > T::zero() { largest } else { T::one() };
+        let floor = T::epsilon().sqrt() * scale;
+
+        // V diag(max(lambda, floor)) V^T
+        let mut repaired = Matrix::<T>::zeros(n, n);
+        for (k, &lambda) in eigenvalues.iter().enumerate() {
+            let lambda = lambda.max(floor);
+            for i in 0..n {
+                for j in 0..n {
+                    repaired[[i, j]] = repaired[[i, j]] + lambda * v[[i, k]] * v[[j, k]];
+                }
+            }
+        }
+
+        repaired.symmetrize()
+    }
+}
+
+/// Orders numbers by decreasing value, with NaN after all numbers.
+fn descending_nan_last<T: Float>(a: T, b: T) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => b.partial_cmp(&a).unwrap(),
+    }
+}
+
+/// Whether the largest magnitude entry of column `j` is negative.
+///
+/// Ties are resolved in favour of the first entry.
+fn largest_is_negative<T: Float>(mat: &Matrix<T>, j: usize) -> bool {
+    let mut largest = T::zero();
+    for i in 0..mat.rows() {
+        if mat[[i, j]].abs() > largest.abs() {
+            largest = mat[[i, j]];
+        }
+    }
+    largest < T::zero()
+}
+
+/// Negates column `j` in place.
+fn negate_col<T: Float>(mat: &mut Matrix<T>, j: usize) {
+    for i in 0..mat.rows() {
+        mat[[i, j]] = -mat[[i, j]];
+    }
+}
+
+/// Fixes the signs of paired singular vectors in `u` and `v`.
+///
+/// Each pair is negated so the largest magnitude entry of the column
+/// of `u`, or of `v` when the column of `u` is zero, is positive.
+fn fix_singular_vector_signs<T: Float>(u: &mut Matrix<T>, v: &mut Matrix<T>) {
+    for j in 0..u.cols() {
+        let is_zero = (0..u.rows()).all(|i| u[[i, j]] == T::zero());
+        let negative = if is_zero {
+            largest_is_negative(v, j)
+        } else {
+            largest_is_negative(u, j)
+        };
+
+        if negative {
+            negate_col(u, j);
+            negate_col(v, j);
+        }
+    }
+}
+
+/// Copies the entries at `(i, j)` for which `keep(i, j)` holds, zeroing the rest.
+fn mask_diagonals<T, F>(mat: &Matrix<T>, keep: F) -> Matrix<T>
+    where T: Copy + Zero,
+          F: Fn(usize, usize) -> bool
+{
+    let mut data = Vec::with_capacity(mat.rows() * mat.cols());
+    for (i, row) in mat.iter_rows().enumerate() {
+        data.extend(row.iter().enumerate().map(|(j, &x)| if keep(i, j) { x } else { T::zero() }));
+    }
+
+    Matrix::new(mat.rows(), mat.cols(), data)
+}
+
+/// Copies the upper triangle of a square matrix into its lower triangle.
+fn mirror_upper<T: Copy>(mat: &mut Matrix<T>) {
+    let n = mat.rows();
+    for i in 0..n {
+        for j in 0..i {
+            mat[[i, j]] = mat[[j, i]];
+        }
+    }
+}
+
+/// Solves for each column of `b` with `solve`, collecting the solutions as columns.
+fn solve_columns<T, F>(b: &Matrix<T>, solve: F) -> Result<Matrix<T>, Error>
+    where T: Copy,
+          F: Fn(Vector<T>) -> Result<Vector<T>, Error>
+{
+    // The columns of B are the rows of its transpose
+    let mut data = Vec::with_capacity(b.rows() * b.cols());
+    for col in b.transpose().iter_rows() {
+        data.extend(solve(Vector::new(col))?.into_vec());
+    }
+    Ok(Matrix::new(b.cols(), b.rows(), data).transpose())
+}
+
+/// Checks that `perm` is a permutation of `0..n`.
+///
+/// Shared with `linalg::utils`. The `matrix` module is private, so this
+/// is not part of the public API.
+pub fn assert_permutation(perm: &[usize], n: usize) {
+    assert!(perm.len() == n,
+            "Permutation length must match the permuted dimension.");
+
+    let mut seen = vec![false; n];
+    for &p in perm {
+        assert!(p < n, "Permutation index exceeds the permuted dimension.");
+        assert!(!seen[p], "Permutation indices must be distinct.");
+        seen[p] = true;
+    }
+}
+
+/// Diagonalizes a symmetric matrix using cyclic Jacobi rotations.
+///
+/// Returns the eigenvalues and a matrix with the corresponding
+/// orthonormal eigenvectors as its columns, in no particular order.
+fn symmetric_eigen<T: Float>(mat: &Matrix<T>) -> (Vec<T>, Matrix<T>) {
+    let n = mat.rows();
+    let mut a = mat.clone();
+    let mut v = Matrix::<T>::identity(n);
+    let two = T::one() + T::one();
+
+    for _ in 0..100 {
+        let mut rotated = false;
+
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = a[[p, q]];
+                if apq.abs() <= T::epsilon() * (a[[p, p]] * a[[q, q]]).abs().sqrt() {
+                    continue;
+                }
+                rotated = true;
+
+                let theta = (a[[q, q]] - a[[p, p]]) / (two * apq);
+                let t = theta.signum() / (theta.abs() + (theta * theta + T::one()).sqrt());
+                let c = T::one() / (t * t + T::one()).sqrt();
+                let s = c * t;
+
+                // A <- J^T A J and V <- V J, rotating columns and then rows
+                for mat in &mut [&mut a, &mut v] {
+                    for row in mat.iter_rows_mut() {
+                        let (x, y) = (row[p], row[q]);
+                        row[p] = c * x - s * y;
+                        row[q] = s * x + c * y;
+                    }
+                }
+                for k in 0..n {
+                    let (x, y) = (a[[p, k]], a[[q, k]]);
+                    a[[p, k]] = c * x - s * y;
+                    a[[q, k]] = s * x + c * y;
+                }
+            }
+        }
+
+        if !rotated {
+            break;
+        }
+    }
+
+    ((0..n).map(|i| a[[i, i]]).collect(), v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MatrixExt;
+    use linalg::{Matrix, BaseMatrix, Axes, Vector, ParseError};
+
+    fn inverse_permutation(perm: &[usize]) -> Vec<usize> {
+        let mut inv = vec![0; perm.len()];
+        for (i, &p) in perm.iter().enumerate() {
+            inv[p] = i;
+        }
+        inv
+    }
+
+    #[test]
+    fn test_permute_rows() {
+        let a = Matrix::new(3, 2, vec![1, 2, 3, 4, 5, 6]);
+        let b = a.permute_rows(&[1, 2, 0]);
+
+        assert_eq!(b, Matrix::new(3, 2, vec![3, 4, 5, 6, 1, 2]));
+    }
+
+    #[test]
+    fn test_permute_cols() {
+        let a = Matrix::new(2, 3, vec![1, 2, 3, 4, 5, 6]);
+        let b = a.permute_cols(&[1, 2, 0]);
+
+        assert_eq!(b, Matrix::new(2, 3, vec![2, 3, 1, 5, 6, 4]));
+    }
+
+    #[test]
+    fn test_permute_rows_inverse_restores() {
+        let a = Matrix::new(4, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        let perm = [3, 0, 2, 1];
+
+        let b = a.permute_rows(&perm).permute_rows(&inverse_permutation(&perm));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_permute_cols_inverse_restores() {
+        let a = Matrix::new(2, 4, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        let perm = [2, 3, 1, 0];
+
+        let b = a.permute_cols(&perm).permute_cols(&inverse_permutation(&perm));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_permute_rows_repeated_index() {
+        let a = Matrix::new(3, 1, vec![1.0, 2.0, 3.0]);
+        let _ = a.permute_rows(&[0, 0, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_permute_cols_wrong_length() {
+        let a = Matrix::new(1, 3, vec![1.0, 2.0, 3.0]);
+        let _ = a.permute_cols(&[0, 1]);
+    }
+
+    #[test]
+    fn test_qr_decomp_givens_hessenberg() {
+        let a = Matrix::new(4, 4, vec![4.0f64, 1.0, -2.0, 2.0,
+                                       3.0, 2.0, 0.0, 1.0,
+                                       0.0, -1.5, 3.0, -2.0,
+                                       0.0, 0.0, 2.5, 1.0]);
+
+        let (q_g, r_g) = a.clone().qr_decomp_givens();
+        let (q_h, r_h) = a.clone().qr_decomp().unwrap();
+
+        // Q is orthogonal and R is upper triangular
+        let qtq = q_g.transpose() * &q_g;
+        let id = Matrix::<f64>::identity(4);
+        for (x, y) in qtq.data().iter().zip(id.data().iter()) {
+            assert!((x - y).abs() < 1e-10);
+        }
+        for i in 0..4 {
+            for j in 0..i {
+                assert_eq!(r_g[[i, j]], 0.0);
+            }
+        }
+
+        // QR recovers the input
+        let prod = &q_g * &r_g;
+        for (x, y) in prod.data().iter().zip(a.data().iter()) {
+            assert!((x - y).abs() < 1e-10);
+        }
+
+        // The factors agree with Householder QR up to sign
+        for i in 0..4 {
+            let sign = r_g[[i, i]].signum() * r_h[[i, i]].signum();
+            for j in 0..4 {
+                assert!((r_g[[i, j]] - sign * r_h[[i, j]]).abs() < 1e-10);
+                assert!((q_g[[j, i]] - sign * q_h[[j, i]]).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_qr_decomp_givens_rectangular() {
+        let a = Matrix::new(3, 2, vec![1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let (q, r) = a.clone().qr_decomp_givens();
+
+        assert_eq!(q.rows(), 3);
+        assert_eq!(r.rows(), 3);
+        assert_eq!(r.cols(), 2);
+
+        let prod = q * r;
+        for (x, y) in prod.data().iter().zip(a.data().iter()) {
+            assert!((x - y).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_symmetrize() {
+        let a = Matrix::new(3, 3, vec![1.0, 2.0, 3.0, 0.0, 5.0, 6.0, 1.0, -2.0, 9.0]);
+        let b = a.symmetrize();
+
+        assert_eq!(b, b.transpose());
+        assert_eq!(b, Matrix::new(3, 3, vec![1.0, 1.0, 2.0, 1.0, 5.0, 2.0, 2.0, 2.0, 9.0]));
+    }
+
+    #[test]
+    fn test_symmetrize_symmetric_unchanged() {
+        let a = Matrix::new(3, 3, vec![2.0, -1.0, 0.5, -1.0, 3.0, 4.0, 0.5, 4.0, 1.0]);
+        assert_eq!(a.symmetrize(), a);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_symmetrize_non_square() {
+        let a = Matrix::new(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let _ = a.symmetrize();
+    }
+
+    #[test]
+    fn test_gram() {
+        let a = Matrix::new(4, 3, vec![1.0f64, -2.0, 0.5,
+                                       3.0, 0.1, 2.0,
+                                       -1.5, 4.0, 1.0,
+                                       0.3, 0.7, -2.2]);
+        let g = a.gram();
+
+        assert_eq!(g, g.transpose());
+        let expected = a.transpose() * &a;
+        for (x, y) in g.data().iter().zip(expected.data().iter()) {
+            assert!((x - y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_gram_rows() {
+        let a = Matrix::new(3, 4, vec![1.0f64, -2.0, 0.5, 3.0,
+                                       0.1, 2.0, -1.5, 4.0,
+                                       1.0, 0.3, 0.7, -2.2]);
+        let g = a.gram_rows();
+
+        assert_eq!(g, g.transpose());
+        let expected = &a * a.transpose();
+        for (x, y) in g.data().iter().zip(expected.data().iter()) {
+            assert!((x - y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_trace() {
+        let a = Matrix::new(3, 3, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(a.trace(), 15);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_trace_non_square() {
+        let a = Matrix::new(2, 3, vec![1, 2, 3, 4, 5, 6]);
+        let _ = a.trace();
+    }
+
+    fn assert_orthonormal_cols(a: &Matrix<f64>) {
+        let g = a.transpose() * a;
+        let id = Matrix::<f64>::identity(a.cols());
+        for (x, y) in g.data().iter().zip(id.data().iter()) {
+            assert!((x - y).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_svd_jacobi_reconstructs() {
+        let a = Matrix::new(4, 3, vec![2.0f64, -1.0, 0.5,
+                                       1.0, 3.0, -2.0,
+                                       0.0, 1.0, 4.0,
+                                       -1.5, 2.0, 1.0]);
+
+        for mat in &[a.clone(), a.transpose()] {
+            let (s, u, v) = mat.svd_jacobi();
+            assert_eq!(s.rows(), 3);
+            assert_orthonormal_cols(&u);
+            assert_orthonormal_cols(&v);
+
+            let b = &u * &s * v.transpose();
+            for (x, y) in mat.data().iter().zip(b.data().iter()) {
+                assert!((x - y).abs() < 1e-10);
+            }
+
+            // Agrees with the Golub-Kahan SVD
+            let (s_gk, _, _) = mat.clone().svd().unwrap();
+            for i in 0..3 {
+                assert!(s[[i, i]] >= 0.0);
+                assert!((s[[i, i]] - s_gk[[i, i]]).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_svd_jacobi_zero_matrix() {
+        let (s, u, v) = Matrix::<f64>::zeros(3, 2).svd_jacobi();
+
+        assert_eq!(s, Matrix::zeros(2, 2));
+        assert_eq!(u, Matrix::zeros(3, 2));
+        assert_eq!(v, Matrix::identity(2));
+    }
+
+    #[test]
+    fn test_svd_jacobi_nan_does_not_panic() {
+        let a = Matrix::new(3, 2, vec![1.0, ::std::f64::NAN, 2.0, 0.5, 0.0, 1.0]);
+        let (s, u, v) = a.svd_jacobi();
+
+        assert_eq!((s.rows(), u.rows(), u.cols(), v.rows()), (2, 3, 2, 2));
+        assert!(s[[1, 1]].is_nan());
+    }
+
+    #[test]
+    fn test_variance_cols_matches_two_pass() {
+        let a = Matrix::new(4, 3, vec![2.0f64, -1.0, 0.5,
+                                       1.0, 3.0, -2.0,
+                                       0.0, 1.0, 4.0,
+                                       -1.5, 2.0, 1.0]);
+        let (mean, var) = a.mean_variance_cols();
+
+        for (x, y) in mean.data().iter().zip(a.mean(Axes::Row).data().iter()) {
+            assert!((x - y).abs() < 1e-12);
+        }
+        for (x, y) in var.data().iter().zip(a.variance(Axes::Row).unwrap().data().iter()) {
+            assert!((x - y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_variance_cols_large_offset() {
+        let data = (0..1000).map(|i| 1e8 + (i % 10) as f64 * 0.1).collect::<Vec<_>>();
+        let a = Matrix::new(1000, 1, data);
+
+        // The spread 0, 0.1, ..., 0.9 repeated has population variance 0.0825
+        let true_var = 0.0825f64 * 1000.0 / 999.0;
+
+        let (sum, sum_sq) = a.data().iter().fold((0f64, 0f64), |(s, s2), x| (s + x, s2 + x * x));
+        let naive_var = (sum_sq - sum * sum / 1000.0) / 999.0;
+        assert!((naive_var - true_var).abs() > 1.0);
+
+        let var = a.variance_cols()[0];
+        assert!((var - true_var).abs() < 1e-6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_variance_cols_single_row() {
+        let _ = Matrix::new(1, 2, vec![1.0, 2.0]).variance_cols();
+    }
+
+    #[test]
+    fn test_triu() {
+        let a = Matrix::new(3, 3, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        assert_eq!(*a.triu(0).data(), vec![1, 2, 3, 0, 5, 6, 0, 0, 9]);
+        assert_eq!(*a.triu(1).data(), vec![0, 2, 3, 0, 0, 6, 0, 0, 0]);
+        assert_eq!(*a.triu(-1).data(), vec![1, 2, 3, 4, 5, 6, 0, 8, 9]);
+        assert_eq!(a.triu(3), Matrix::zeros(3, 3));
+        assert_eq!(a.triu(-2), a);
+    }
+
+    #[test]
+    fn test_tril() {
+        let a = Matrix::new(3, 3, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        assert_eq!(*a.tril(0).data(), vec![1, 0, 0, 4, 5, 0, 7, 8, 9]);
+        assert_eq!(*a.tril(1).data(), vec![1, 2, 0, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(*a.tril(-1).data(), vec![0, 0, 0, 4, 0, 0, 7, 8, 0]);
+        assert_eq!(a.tril(-3), Matrix::zeros(3, 3));
+        assert_eq!(a.tril(2), a);
+    }
+
+    #[test]
+    fn test_triu_tril_partition() {
+        let a = Matrix::new(3, 3, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(a.triu(1) + a.tril(0), a);
+    }
+
+    #[test]
+    fn test_scale_rows_matches_diag_product() {
+        let a = Matrix::new(3, 2, vec![1.0, -2.0, 3.5, 0.5, -1.0, 4.0]);
+        let factors = Vector::new(vec![2.0, 0.5, -3.0]);
+
+        let expected = Matrix::from_diag(factors.data()) * &a;
+        assert_eq!(a.scale_rows(&factors), expected);
+    }
+
+    #[test]
+    fn test_scale_cols_matches_diag_product() {
+        let a = Matrix::new(3, 2, vec![1.0, -2.0, 3.5, 0.5, -1.0, 4.0]);
+        let factors = Vector::new(vec![-1.5, 4.0]);
+
+        let expected = &a * Matrix::from_diag(factors.data());
+        assert_eq!(a.scale_cols(&factors), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_scale_rows_wrong_size() {
+        let a = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+        let _ = a.scale_rows(&Vector::new(vec![1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_scale_cols_wrong_size() {
+        let a = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+        let _ = a.scale_cols(&Vector::new(vec![1.0]));
+    }
+
+    #[test]
+    fn test_nnz_and_sparsity() {
+        let a = Matrix::new(3, 4, vec![1.0, 0.0, 0.0, 2.0,
+                                       0.0, 0.0, -3.0, 0.0,
+                                       0.0, 4.0, 0.0, 0.0]);
+
+        assert_eq!(a.nnz(), 4);
+        assert!((a.sparsity() - 8.0 / 12.0).abs() < 1e-12);
+
+        assert_eq!(Matrix::<f64>::zeros(2, 3).nnz(), 0);
+        assert_eq!(Matrix::<f64>::zeros(2, 3).sparsity(), 1.0);
+        assert_eq!(Matrix::<f64>::ones(2, 3).sparsity(), 0.0);
+    }
+
+    #[test]
+    fn test_sparsity_empty() {
+        let a = Matrix::<f64>::new(0, 3, vec![]);
+        assert_eq!(a.nnz(), 0);
+        assert_eq!(a.sparsity(), 0.0);
+    }
+
+    #[test]
+    fn test_solve_regularized_matches_explicit_system() {
+        let a = Matrix::new(4, 3, vec![2.0f64, -1.0, 0.5,
+                                       1.0, 3.0, -2.0,
+                                       0.0, 1.0, 4.0,
+                                       -1.5, 2.0, 1.0]);
+        let b = Vector::new(vec![1.0, -2.0, 0.5, 3.0]);
+        let lambda = 0.7;
+
+        let x = a.solve_regularized(&b, lambda).unwrap();
+
+        let system = a.transpose() * &a + Matrix::identity(3) * lambda;
+        let expected = system.inverse().unwrap() * (a.transpose() * &b);
+        for (x, y) in x.data().iter().zip(expected.data().iter()) {
+            assert!((x - y).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_solve_regularized_zero_lambda_is_least_squares() {
+        let a = Matrix::new(4, 2, vec![1.0f64, 0.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0]);
+        let b = Vector::new(vec![1.0, 3.0, 5.0, 7.0]);
+
+        // The points lie exactly on y = 1 + 2x
+        let x = a.solve_regularized(&b, 0.0).unwrap();
+        assert!((x[0] - 1.0).abs() < 1e-10);
+        assert!((x[1] - 2.0).abs() < 1e-10);
+
+        // Regularizing shrinks the solution
+        let x_reg = a.solve_regularized(&b, 10.0).unwrap();
+        assert!(x_reg.dot(&x_reg) < x.dot(&x));
+    }
+
+    #[test]
+    fn test_solve_regularized_singular() {
+        let a = Matrix::new(3, 2, vec![1.0, 2.0, 2.0, 4.0, 3.0, 6.0]);
+        let b = Vector::new(vec![1.0, 2.0, 3.0]);
+
+        assert!(a.solve_regularized(&b, 0.0).is_err());
+        assert!(a.solve_regularized(&b, 1e-3).is_ok());
+    }
+
+    #[test]
+    fn test_block_diag_two_blocks() {
+        let a = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+        let b = Matrix::new(2, 2, vec![5.0, 6.0, 7.0, 8.0]);
+
+        let c = Matrix::block_diag(&[a.clone(), b.clone()]);
+        assert_eq!((c.rows(), c.cols()), (4, 4));
+
+        assert_eq!(c.select_rows(&[0, 1]).select_cols(&[0, 1]), a);
+        assert_eq!(c.select_rows(&[2, 3]).select_cols(&[2, 3]), b);
+        assert_eq!(c.select_rows(&[0, 1]).select_cols(&[2, 3]), Matrix::zeros(2, 2));
+        assert_eq!(c.select_rows(&[2, 3]).select_cols(&[0, 1]), Matrix::zeros(2, 2));
+    }
+
+    #[test]
+    fn test_block_diag_mixed_sizes() {
+        let a = Matrix::new(1, 2, vec![1, 2]);
+        let b = Matrix::new(3, 1, vec![3, 4, 5]);
+        let c = Matrix::new(1, 1, vec![6]);
+
+        let d = Matrix::block_diag(&[a, b, c]);
+        assert_eq!((d.rows(), d.cols()), (5, 4));
+        assert_eq!(*d.data(), vec![1, 2, 0, 0,
+                                   0, 0, 3, 0,
+                                   0, 0, 4, 0,
+                                   0, 0, 5, 0,
+                                   0, 0, 0, 6]);
+    }
+
+    #[test]
+    fn test_block_diag_empty() {
+        let d = Matrix::<f64>::block_diag(&[]);
+        assert_eq!((d.rows(), d.cols()), (0, 0));
+    }
+
+    #[test]
+    fn test_unique_rows() {
+        let a = Matrix::new(4, 2, vec![1.0, 2.0,
+                                       3.0, 4.0,
+                                       3.0, 4.0,
+                                       5.0, 6.0]);
+        let (unique, kept) = a.unique_rows();
+
+        assert_eq!(kept, vec![0, 1, 3]);
+        assert_eq!(unique, Matrix::new(3, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]));
+    }
+
+    #[test]
+    fn test_unique_rows_exact_comparison() {
+        let a = Matrix::new(3, 1, vec![1.0, 1.0 + 1e-12, ::std::f64::NAN]);
+        let b = Matrix::new(2, 1, vec![::std::f64::NAN, ::std::f64::NAN]);
+
+        assert_eq!(a.unique_rows().1, vec![0, 1, 2]);
+        assert_eq!(b.unique_rows().1, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let a = Matrix::new(2, 3, vec![1.5f64, -2.0, 0.0, 1e-300, ::std::f64::MAX, -0.25]);
+        assert_eq!(Matrix::<f64>::from_bytes(&a.to_bytes()).unwrap(), a);
+
+        let b = Matrix::new(3, 1, vec![-7i32, 0, 12]);
+        let bytes = b.to_bytes();
+        assert_eq!(&bytes[..16], &[3, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(&bytes[16..20], &[0xf9, 0xff, 0xff, 0xff]);
+        assert_eq!(Matrix::<i32>::from_bytes(&bytes).unwrap(), b);
+
+        let empty = Matrix::<f32>::new(0, 4, vec![]);
+        let decoded = Matrix::<f32>::from_bytes(&empty.to_bytes()).unwrap();
+        assert_eq!((decoded.rows(), decoded.cols()), (0, 4));
+    }
+
+    #[test]
+    fn test_bytes_truncated() {
+        let a = Matrix::new(2, 2, vec![1f64, 2f64, 3f64, 4f64]);
+        let bytes = a.to_bytes();
+
+        assert_eq!(Matrix::<f64>::from_bytes(&bytes[..10]),
+                   Err(ParseError::Truncated { expected: 16, found: 10 }));
+        assert_eq!(Matrix::<f64>::from_bytes(&bytes[..40]),
+                   Err(ParseError::Truncated { expected: 48, found: 40 }));
+
+        let mut extra = bytes.clone();
+        extra.push(0);
+        assert_eq!(Matrix::<f64>::from_bytes(&extra),
+                   Err(ParseError::TrailingBytes { expected: 48, found: 49 }));
+
+        let err = Matrix::<f64>::from_bytes(&bytes[..40]).unwrap_err();
+        assert_eq!(err.to_string(), "Truncated matrix bytes: expected 48 bytes, found 40.");
+    }
+
+    #[test]
+    fn test_bytes_invalid_shape() {
+        let mut bytes = vec![0xff; 16];
+        bytes.extend_from_slice(&[0; 8]);
+        assert_eq!(Matrix::<f64>::from_bytes(&bytes), Err(ParseError::InvalidShape));
+    }
+
+    #[test]
+    fn test_rank_k_update() {
+        let mut a = Matrix::new(3, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let u = Matrix::new(3, 2, vec![1.0f64, -1.0, 0.5, 2.0, 0.0, 3.0]);
+        let v = Matrix::new(2, 2, vec![2.0, 1.0, -1.0, 4.0]);
+
+        let expected = &a + (&u * v.transpose()) * 1.5;
+        a.rank_k_update(&u, &v, 1.5);
+
+        for (x, y) in a.data().iter().zip(expected.data().iter()) {
+            assert!((x - y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rank_k_update_mismatched() {
+        let mut a = Matrix::<f64>::zeros(3, 2);
+        let u = Matrix::zeros(3, 1);
+        let v = Matrix::zeros(3, 1);
+        a.rank_k_update(&u, &v, 1.0);
+    }
+
+    #[test]
+    fn test_cond_estimate() {
+        let hilbert = Matrix::from_fn(4, 4, |i, j| 1f64 / (i + j + 1) as f64);
+        let general = Matrix::new(3, 3, vec![4.0, -2.0, 1.0,
+                                             3.0, 6.0, -4.0,
+                                             2.0, 1.0, 8.0]);
+        let scaled = Matrix::new(3, 3, vec![1e-3, 2.0, 0.0,
+                                            0.0, 1.0, 5.0,
+                                            1.0, 0.0, 1e3]);
+
+        let norm_1 = |m: &Matrix<f64>| {
+            (0..m.cols())
+                .map(|j| m.iter_rows().map(|r| r[j].abs()).sum::<f64>())
+                .fold(0f64, f64::max)
+        };
+
+        for a in &[hilbert, general, scaled] {
+            let cond = norm_1(a) * norm_1(&a.clone().inverse().unwrap());
+            let estimate = a.cond_estimate();
+
+            assert!(estimate <= cond * (1f64 + 1e-8));
+            assert!(estimate >= cond / 10f64);
+        }
+    }
+
+    #[test]
+    fn test_cond_estimate_singular() {
+        let a = Matrix::new(2, 2, vec![1.0f64, 2.0, 2.0, 4.0]);
+        assert!(a.cond_estimate().is_infinite());
+    }
+
+    #[test]
+    fn test_from_qr_round_trip() {
+        let a = Matrix::new(4, 3, vec![2.0f64, -1.0, 0.5,
+                                       1.0, 3.0, -2.0,
+                                       0.0, 1.0, 4.0,
+                                       5.0, -2.0, 1.0]);
+        let (q, r) = a.clone().qr_decomp().unwrap();
+        let b = Matrix::from_qr(&q, &r);
+
+        assert_eq!((b.rows(), b.cols()), (4, 3));
+        for (x, y) in a.data().iter().zip(b.data().iter()) {
+            assert!((x - y).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_lup_reconstruction() {
+        let a = Matrix::new(3, 3, vec![1.0f64, 2.0, 0.0,
+                                       0.0, 3.0, 4.0,
+                                       5.0, 1.0, 2.0]);
+        let (l, u, p) = a.lup_decomp().unwrap();
+        let b = p.transpose() * l * u;
+
+        for (x, y) in a.data().iter().zip(b.data().iter()) {
+            assert!((x - y).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_qr_mismatched() {
+        let q = Matrix::<f64>::identity(3);
+        let r = Matrix::zeros(2, 2);
+        let _ = Matrix::from_qr(&q, &r);
+    }
+
+    #[test]
+    fn test_get() {
+        let a = Matrix::new(2, 3, vec![1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(a.get(0, 0), Some(&1));
+        assert_eq!(a.get(1, 2), Some(&6));
+        assert_eq!(a.get(2, 0), None);
+        assert_eq!(a.get(0, 3), None);
+        assert_eq!(a.get(usize::MAX, usize::MAX), None);
+
+        let empty = Matrix::<f64>::new(0, 0, vec![]);
+        assert_eq!(empty.get(0, 0), None);
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut a = Matrix::new(2, 3, vec![1, 2, 3, 4, 5, 6]);
+
+        *a.get_mut(1, 1).unwrap() = 10;
+        assert_eq!(a[[1, 1]], 10);
+
+        assert!(a.get_mut(1, 3).is_none());
+        assert!(a.get_mut(2, 1).is_none());
+    }
+
+    #[test]
+    fn test_sum_axis() {
+        let a = Matrix::new(2, 3, vec![1, 2, 3,
+                                       4, 5, 6]);
+
+        assert_eq!(a.sum_axis(Axes::Row), Vector::new(vec![5, 7, 9]));
+        assert_eq!(a.sum_axis(Axes::Col), Vector::new(vec![6, 15]));
+        assert_eq!(a.sum_all(), 21);
+    }
+
+    #[test]
+    fn test_sum_axis_empty() {
+        let a = Matrix::<f64>::new(0, 3, vec![]);
+
+        assert_eq!(a.sum_axis(Axes::Row), Vector::zeros(3));
+        assert_eq!(a.sum_axis(Axes::Col).size(), 0);
+        assert_eq!(a.sum_all(), 0.0);
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let a = Matrix::new(2, 3, vec![1.0, -2.0, 3.0, 0.0, 5.5, -6.0]);
+        let close = &a + Matrix::new(2, 3, vec![1e-7, -1e-7, 0.0, 5e-8, 0.0, -9e-8]);
+        let far = &a + Matrix::new(2, 3, vec![0.0, 0.0, 0.0, 0.0, 1e-5, 0.0]);
+
+        assert!(a.approx_eq(&a, 0.0));
+        assert!(a.approx_eq(&close, 1e-6));
+        assert!(close.approx_eq(&a, 1e-6));
+        assert!(!a.approx_eq(&far, 1e-6));
+        assert!(a.approx_eq(&far, 1e-4));
+
+        // Dimensions must match even when the data does
+        let b = Matrix::new(3, 2, a.data().clone());
+        assert!(!a.approx_eq(&b, 1.0));
+
+        let nan = Matrix::new(1, 1, vec![f64::NAN]);
+        assert!(!nan.approx_eq(&nan, 1.0));
+    }
+
+    #[test]
+    fn test_row_col_norms() {
+        use linalg::Metric;
+
+        let a = Matrix::new(3, 4, vec![1.0f64, -2.0, 0.5, 3.0,
+                                          0.0, 0.0, 0.0, 0.0,
+                                          -4.0, 1.5, 2.0, -1.0]);
+        let row_norms = a.row_norms();
+        let col_norms = a.col_norms();
+
+        assert_eq!(row_norms.size(), 3);
+        assert_eq!(col_norms.size(), 4);
+
+        for i in 0..3 {
+            let expected = a.select_rows(&[i]).norm();
+            assert!((row_norms[i] - expected).abs() < 1e-12);
+        }
+        for j in 0..4 {
+            let expected = a.select_cols(&[j]).norm();
+            assert!((col_norms[j] - expected).abs() < 1e-12);
+        }
+
+        assert_eq!(Matrix::<f64>::zeros(0, 2).col_norms().size(), 2);
+    }
+
+    #[test]
+    fn test_orthonormal_basis_rank_deficient() {
+        // The fourth column is the first minus twice the second
+        let a = Matrix::new(5, 4, vec![1.0f64, 2.0, 0.5, 1.0 - 4.0,
+                                       0.0, 1.0, -1.0, -2.0,
+                                       3.0, -1.0, 2.0, 3.0 + 2.0,
+                                       2.0, 0.5, 0.0, 2.0 - 1.0,
+                                       -1.0, 1.5, 1.0, -1.0 - 3.0]);
+        let q = a.orthonormal_basis();
+
+        assert_eq!(q.rows(), 5);
+        assert_eq!(q.cols(), 3);
+        assert_orthonormal_cols(&q);
+
+        // Every column of the input lies in the span of the basis
+        let residual = &a - &q * (q.transpose() * &a);
+        assert!(residual.data().iter().all(|x| x.abs() < 1e-10));
+    }
+
+    #[test]
+    fn test_orthonormal_basis_full_rank_and_zero() {
+        let a = Matrix::new(3, 2, vec![1.0f64, 1.0, 1.0, 1.0 + 1e-4, 1.0, 1.0]);
+        let q = a.orthonormal_basis();
+        assert_eq!(q.cols(), 2);
+        assert_orthonormal_cols(&q);
+
+        let zeros = Matrix::<f64>::zeros(3, 2);
+        let q = zeros.orthonormal_basis();
+        assert_eq!(q.rows(), 3);
+        assert_eq!(q.cols(), 0);
+    }
+
+    #[test]
+    fn test_project_onto_subspace() {
+        let basis = Matrix::new(4, 2, vec![1.0f64, 2.0,
+                                           0.0, 1.0,
+                                           1.0, -1.0,
+                                           2.0, 0.0]);
+
+        // Rows in the column space of the basis are unchanged
+        let inside = Matrix::new(2, 4, vec![3.0, 1.0, 0.0, 2.0,
+                                            1.0, 0.0, 1.0, 2.0]);
+        assert!(inside.project_onto(&basis).approx_eq(&inside, 1e-10));
+
+        // Rows orthogonal to the basis project to zero
+        let outside = Matrix::new(1, 4, vec![1.0, -1.0, 1.0, -1.0]);
+        assert!((basis.transpose() * outside.transpose()).data().iter().all(|x| x.abs() < 1e-12));
+        let p = outside.project_onto(&basis);
+        assert!(p.data().iter().all(|x| x.abs() < 1e-10));
+
+        // Projections are idempotent
+        let x = Matrix::new(1, 4, vec![1.0, 2.0, 3.0, 4.0]);
+        let p = x.project_onto(&basis);
+        assert!(p.project_onto(&basis).approx_eq(&p, 1e-10));
+    }
+
+    #[test]
+    fn test_project_onto_dependent_basis() {
+        let basis = Matrix::new(3, 2, vec![1.0f64, 2.0, 1.0, 2.0, 0.0, 0.0]);
+        let x = Matrix::new(1, 3, vec![3.0, 1.0, 4.0]);
+
+        let p = x.project_onto(&basis);
+        assert!(p.approx_eq(&Matrix::new(1, 3, vec![2.0, 2.0, 0.0]), 1e-8));
+
+        let zero = Matrix::<f64>::zeros(3, 1);
+        assert_eq!(x.project_onto(&zero), Matrix::zeros(1, 3));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_project_onto_wrong_size() {
+        let basis = Matrix::new(2, 1, vec![1.0f64, 0.0]);
+        let _ = Matrix::new(1, 3, vec![1.0, 2.0, 3.0]).project_onto(&basis);
+    }
+
+    #[test]
+    fn test_pseudo_det_rank_two() {
+        // Eigenvalues 3 and 2 on orthonormal vectors, and 0
+        let u = Matrix::new(3, 1, vec![1.0f64, 1.0, 1.0]) / 3f64.sqrt();
+        let v = Matrix::new(3, 1, vec![1.0f64, -1.0, 0.0]) / 2f64.sqrt();
+        let a = &u * u.transpose() * 3.0 + &v * v.transpose() * 2.0;
+
+        assert!((a.pseudo_det(1e-10) - 6.0).abs() < 1e-10);
+        assert!(a.det().abs() < 1e-10);
+
+        // The full determinant for non-singular matrices
+        let b = Matrix::new(2, 2, vec![2.0f64, 1.0, 1.0, 3.0]);
+        assert!((b.pseudo_det(1e-10) - 5.0).abs() < 1e-10);
+
+        assert_eq!(Matrix::<f64>::zeros(2, 2).pseudo_det(1e-10), 1.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_pseudo_det_non_square() {
+        let _ = Matrix::new(2, 3, vec![1.0f64; 6]).pseudo_det(1e-10);
+    }
+
+    #[test]
+    fn test_whiten_with_cov_identity_covariance() {
+        use data::stats::covariance;
+
+        // Correlated features with a non-zero mean
+        let a = Matrix::from_fn(50, 3, |j, i| {
+            let t = i as f64;
+            match j {
+                0 => (0.7 * t).sin() + 2.0,
+                1 => 2.0 * (0.7 * t).sin() + (1.3 * t).cos(),
+                _ => (0.7 * t).sin() - (1.3 * t).cos() + 0.5 * (2.9 * t).sin() - 1.0,
+            }
+        });
+
+        let whitened = a.whiten_with_cov(&covariance(&a));
+        assert!(covariance(&whitened).approx_eq(&Matrix::identity(3), 1e-10));
+    }
+
+    #[test]
+    fn test_whiten_with_cov_mahalanobis() {
+        let cov = Matrix::new(2, 2, vec![2.0f64, 0.5, 0.5, 1.0]);
+        let x = Matrix::new(2, 2, vec![1.0, -1.0, 0.5, 2.0]);
+        let z = x.whiten_with_cov(&cov);
+
+        let inv = cov.clone().inverse().unwrap();
+        for i in 0..2 {
+            let row = x.select_rows(&[i]);
+            let expected = (&row * &inv * row.transpose())[[0, 0]];
+            let norm = z[[i, 0]] * z[[i, 0]] + z[[i, 1]] * z[[i, 1]];
+            assert!((norm - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "positive definite")]
+    fn test_whiten_with_cov_singular() {
+        let cov = Matrix::new(2, 2, vec![1.0f64, 1.0, 1.0, 1.0]);
+        let _ = Matrix::new(1, 2, vec![1.0, 2.0]).whiten_with_cov(&cov);
+    }
+
+    fn assert_largest_positive(a: &Matrix<f64>) {
+        for j in 0..a.cols() {
+            let col = a.select_cols(&[j]);
+            let largest = col.data().iter().fold(0f64, |acc, &x| if x.abs() > acc.abs() { x } else { acc });
+            assert!(largest >= 0.0);
+        }
+    }
+
+    #[test]
+    fn test_qr_decomp_givens_sign_convention() {
+        let a = Matrix::new(3, 3, vec![-4.0f64, 1.0, 2.0,
+                                       3.0, -5.0, 1.0,
+                                       0.0, 2.0, -6.0]);
+        let (q, r) = a.clone().qr_decomp_givens();
+        assert_largest_positive(&q);
+        assert_orthonormal_cols(&q);
+        assert!((&q * &r).approx_eq(&a, 1e-10));
+
+        // Negating the matrix negates R but leaves Q unchanged
+        let (q_neg, r_neg) = (-a.clone()).qr_decomp_givens();
+        assert!(q_neg.approx_eq(&q, 1e-10));
+        assert!(r_neg.approx_eq(&-r, 1e-10));
+    }
+
+    #[test]
+    fn test_svd_jacobi_sign_convention() {
+        let a = Matrix::new(4, 3, vec![-2.0f64, 1.0, 0.5,
+                                       1.0, -3.0, 2.0,
+                                       0.0, 1.0, -1.0,
+                                       -1.5, 0.5, 2.0]);
+
+        for mat in &[a.clone(), a.transpose()] {
+            let (s, u, v) = mat.svd_jacobi();
+            assert_largest_positive(&u);
+            assert!((&u * &s * v.transpose()).approx_eq(mat, 1e-10));
+
+            // Negating the matrix only negates V
+            let (s_neg, u_neg, v_neg) = (-mat.clone()).svd_jacobi();
+            assert!(s_neg.approx_eq(&s, 1e-10));
+            assert!(u_neg.approx_eq(&u, 1e-10));
+            assert!(v_neg.approx_eq(&-v, 1e-10));
+        }
+    }
+
+    #[test]
+    fn test_svd_jacobi_sign_convention_rank_deficient() {
+        // The zero column of U falls back to V
+        let a = Matrix::new(3, 2, vec![-1.0f64, -2.0, -2.0, -4.0, -3.0, -6.0]);
+        let (s, u, v) = a.svd_jacobi();
+
+        assert_largest_positive(&u);
+        assert_largest_positive(&v.select_cols(&[1]));
+        assert!((&u * &s * v.transpose()).approx_eq(&a, 1e-10));
+    }
+
+    #[test]
+    fn test_log_det_information_informative_row() {
+        // The design covers the first direction well and the second poorly
+        let x = Matrix::new(3, 2, vec![1.0f64, 0.0,
+                                       1.0, 0.1,
+                                       0.0, 0.1]);
+        let base = x.log_det_information();
+        assert!((base.exp() - x.gram().det()).abs() < 1e-10);
+
+        let informative = x.vcat(&Matrix::new(1, 2, vec![0.0, 1.0]));
+        let redundant = x.vcat(&Matrix::new(1, 2, vec![1.0, 0.0]));
+
+        let informative_gain = informative.log_det_information() - base;
+        let redundant_gain = redundant.log_det_information() - base;
+        assert!(redundant_gain > 0.0);
+        assert!(informative_gain > redundant_gain);
+    }
+
+    #[test]
+    fn test_log_det_information_singular() {
+        let wide = Matrix::new(1, 2, vec![1.0f64, 2.0]);
+        assert_eq!(wide.log_det_information(), f64::NEG_INFINITY);
+
+        let collinear = Matrix::new(3, 2, vec![1.0f64, 2.0, 2.0, 4.0, -1.0, -2.0]);
+        assert_eq!(collinear.log_det_information(), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_frobenius_dot_is_trace() {
+        let a = Matrix::new(3, 2, vec![1.0, -2.0, 0.5, 3.0, 4.0, -1.5]);
+        let b = Matrix::new(3, 2, vec![2.0, 1.0, -1.0, 0.25, 3.0, 2.0]);
+
+        assert_eq!(a.frobenius_dot(&b), (a.transpose() * &b).trace());
+        assert_eq!(a.frobenius_dot(&b), b.frobenius_dot(&a));
+        assert_eq!(a.frobenius_dot(&a), a.data().iter().map(|x| x * x).sum::<f64>());
+
+        let c = Matrix::new(2, 2, vec![1, 2, 3, 4]);
+        assert_eq!(c.frobenius_dot(&c.transpose()), 29);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_frobenius_dot_mismatched() {
+        let a = Matrix::new(2, 3, vec![1.0; 6]);
+        let _ = a.frobenius_dot(&a.transpose());
+    }
+
+    #[test]
+    fn test_column_and_null_space() {
+        // Rank two, with the third column the sum of the first two
+        let a = Matrix::new(4, 3, vec![1.0, 0.0, 1.0,
+                                       2.0, 1.0, 3.0,
+                                       0.0, 1.0, 1.0,
+                                       -1.0, 2.0, 1.0]);
+        let wide = a.transpose();
+
+        for mat in &[a, wide] {
+            let col = mat.column_space(1e-10);
+            let null = mat.null_space(1e-10);
+
+            assert_eq!(col.rows(), mat.rows());
+            assert_eq!(null.rows(), mat.cols());
+            assert_eq!(col.cols(), 2);
+            assert_eq!(col.cols() + null.cols(), mat.cols());
+
+            // Both bases are orthonormal
+            for basis in &[&col, &null] {
+                let gram = basis.transpose() * *basis;
+                let eye = Matrix::<f64>::identity(basis.cols());
+                assert!(gram.iter().zip(eye.iter()).all(|(x, y)| (x - y).abs() < 1e-10));
+            }
+
+            // The null space maps to zero
+            assert!((mat * &null).iter().all(|x| x.abs() < 1e-10));
+
+            // Projecting onto the column space leaves the columns unchanged
+            let projected = &col * (col.transpose() * mat);
+            assert!(projected.iter().zip(mat.iter()).all(|(x, y)| (x - y).abs() < 1e-10));
+        }
+    }
+
+    #[test]
+    fn test_column_and_null_space_extremes() {
+        let full = Matrix::new(2, 2, vec![2.0, 1.0, 1.0, 3.0]);
+        assert_eq!(full.column_space(1e-10).cols(), 2);
+        assert_eq!(full.null_space(1e-10).cols(), 0);
+
+        let zero = Matrix::<f64>::zeros(2, 3);
+        assert_eq!(zero.column_space(1e-10).cols(), 0);
+        assert_eq!(zero.null_space(1e-10).cols(), 3);
+    }
+
+    #[test]
+    fn test_solve_multiple_matches_solve() {
+        let a = Matrix::new(3, 3, vec![0.0f64, 2.0, 1.0, 4.0, -1.0, 0.5, 1.0, 1.0, 3.0]);
+        let b = Matrix::new(3, 4, vec![1.0, 0.0, -2.0, 3.0,
+                                       2.0, 1.0, 0.5, 0.0,
+                                       -1.0, 4.0, 1.0, 2.0]);
+
+        let x = a.solve_multiple(&b).unwrap();
+        assert_eq!(x.rows(), 3);
+        assert_eq!(x.cols(), 4);
+        for j in 0..4 {
+            let col = Vector::new(b.iter_rows().map(|row| row[j]).collect::<Vec<_>>());
+            let expected = a.solve(col).unwrap();
+            for i in 0..3 {
+                assert!((x[[i, j]] - expected[i]).abs() < 1e-12);
+            }
+        }
+
+        // Solving against the identity gives the inverse
+        let inv = a.solve_multiple(&Matrix::identity(3)).unwrap();
+        let expected = a.inverse().unwrap();
+        assert!(inv.iter().zip(expected.iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+
+        let empty = a.solve_multiple(&Matrix::new(3, 0, Vec::new())).unwrap();
+        assert_eq!(empty.rows(), 3);
+        assert_eq!(empty.cols(), 0);
+    }
+
+    #[test]
+    fn test_triangular_solve_multiple() {
+        let l = Matrix::new(3, 3, vec![2.0f64, 0.0, 0.0, -1.0, 3.0, 0.0, 0.5, 1.0, 4.0]);
+        let b = Matrix::new(3, 2, vec![1.0, 0.0, 2.0, -2.0, 0.5, 3.0]);
+
+        let x = l.solve_l_triangular_multiple(&b).unwrap();
+        let y = &l * &x;
+        assert!(y.iter().zip(b.iter()).all(|(p, q)| (p - q).abs() < 1e-12));
+
+        let u = l.transpose();
+        let x = u.solve_u_triangular_multiple(&b).unwrap();
+        let y = &u * &x;
+        assert!(y.iter().zip(b.iter()).all(|(p, q)| (p - q).abs() < 1e-12));
+
+        // A zero on the diagonal cannot be solved
+        let singular = Matrix::new(2, 2, vec![1.0, 0.0, 1.0, 0.0]);
+        assert!(singular.solve_l_triangular_multiple(&Matrix::identity(2)).is_err());
+    }
+
+    #[test]
+    fn test_solve_multiple_singular() {
+        let a = Matrix::new(2, 2, vec![1.0, 2.0, 2.0, 4.0]);
+        assert!(a.solve_multiple(&Matrix::identity(2)).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_solve_multiple_mismatched_rows() {
+        let a = Matrix::new(2, 2, vec![2.0, 1.0, 1.0, 3.0]);
+        let _ = a.solve_multiple(&Matrix::new(3, 1, vec![1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn test_nearest_pd_repairs_indefinite() {
+        // Eigenvalues of about 2.001, 1.5 and -0.001 after mixing the axes
+        let a = Matrix::new(3, 3, vec![1.0f64, 1.001, 0.0,
+                                       1.001, 1.0, 0.0,
+                                       0.0, 0.0, 1.5]);
+        let r = Matrix::new(3, 3, vec![0.6, 0.0, -0.8, 0.0, 1.0, 0.0, 0.8, 0.0, 0.6]);
+        let a = &r * a * r.transpose();
+
+        let b = a.nearest_pd();
+        assert_eq!(b, b.transpose());
+
+        let l = b.cholesky().unwrap();
+        assert!((0..3).all(|i| l[[i, i]] > 0.0 && l[[i, i]].is_finite()));
+
+        // Only the negative eigenvalue moves, by about 0.001
+        let diff = &b - &a;
+        assert!(diff.frobenius_dot(&diff).sqrt() < 1.1e-3);
+        for i in 0..3 {
+            let x = Vector::new(r.iter_rows().map(|row| row[i]).collect::<Vec<_>>());
+            assert!((&b * &x).dot(&x) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_nearest_pd_keeps_pd() {
+        let a = Matrix::new(3, 3, vec![4.0f64, 1.0, -0.5, 1.0, 3.0, 0.2, -0.5, 0.2, 2.0]);
+        let b = a.nearest_pd();
+        assert!(a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+
+        // Singular and zero matrices are raised to a small positive floor
+        let singular = Matrix::new(2, 2, vec![1.0f64, 1.0, 1.0, 1.0]).nearest_pd();
+        assert!(singular.cholesky().is_ok());
+        assert!(singular[[1, 1]] - singular[[0, 1]] > 0.0);
+
+        let zero = Matrix::<f64>::zeros(2, 2).nearest_pd();
+        assert!(zero[[0, 0]] > 0.0 && zero[[0, 0]] < 1e-6);
+    }
+}
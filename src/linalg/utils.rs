@@ -0,0 +1,79 @@
+//! Linear algebra utility functions
+//!
+//! This module contains helper functions which act on the building
+//! blocks of linear algebra routines, such as permutations stored as
+//! index vectors.
+
+use super::matrix::assert_permutation;
+
+/// Computes the parity of a permutation of `0..n`.
+///
+/// The permutation maps `i` to `perm[i]`. Returns `1` if it has an
+/// even number of inversions and `-1` if it has an odd number. This is
+/// the sign of the permutation, which is the determinant of its
+/// permutation matrix.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_machine::linalg::utils::permutation_parity;
+///
+/// assert_eq!(permutation_parity(&[0, 1, 2]), 1);
+/// assert_eq!(permutation_parity(&[1, 0, 2]), -1);
+/// assert_eq!(permutation_parity(&[1, 2, 0]), 1);
+/// ```
+///
+/// # Panics
+///
+/// - `perm` is not a permutation of `0..perm.len()`.
+pub fn permutation_parity(perm: &[usize]) -> i8 {
+    let n = perm.len();
+    assert_permutation(perm, n);
+
+    let mut inversions = 0usize;
+    for i in 0..n {
+        inversions += perm[i + 1..].iter().filter(|&&p| p < perm[i]).count();
+    }
+
+    if inversions.is_multiple_of(2) { 1 } else { -1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::permutation_parity;
+
+    #[test]
+    fn test_permutation_parity_even() {
+        assert_eq!(permutation_parity(&[]), 1);
+        assert_eq!(permutation_parity(&[0]), 1);
+        assert_eq!(permutation_parity(&[0, 1, 2, 3]), 1);
+        // Two transpositions
+        assert_eq!(permutation_parity(&[1, 0, 3, 2]), 1);
+        // A 3-cycle
+        assert_eq!(permutation_parity(&[2, 0, 1]), 1);
+        // Reversing 4 elements uses 6 inversions
+        assert_eq!(permutation_parity(&[3, 2, 1, 0]), 1);
+    }
+
+    #[test]
+    fn test_permutation_parity_odd() {
+        assert_eq!(permutation_parity(&[1, 0]), -1);
+        assert_eq!(permutation_parity(&[0, 3, 2, 1]), -1);
+        // Reversing 6 elements uses 15 inversions
+        assert_eq!(permutation_parity(&[5, 4, 3, 2, 1, 0]), -1);
+        // A 4-cycle
+        assert_eq!(permutation_parity(&[1, 2, 3, 0]), -1);
+    }
+
+    #[test]
+    #[should_panic(expected = "distinct")]
+    fn test_permutation_parity_repeated() {
+        let _ = permutation_parity(&[0, 1, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds")]
+    fn test_permutation_parity_out_of_range() {
+        let _ = permutation_parity(&[0, 3, 1]);
+    }
+}
@@ -0,0 +1,475 @@
+//! Vector extension methods
+//!
+//! This module contains the `VectorExt` trait which extends the
+//! rulinalg `Vector` with additional functionality used throughout
+//! rusty-machine.
+
+use linalg::{Matrix, Vector};
+
+use libnum::{Float, FromPrimitive, Zero};
+
+use std::ops::{Add, Mul, Sub};
+
+/// Extension methods for the `Vector` struct.
+///
+/// The trait is implemented for `Vector<T>` and can be accessed
+/// by importing `rusty_machine::linalg::VectorExt` (or the prelude).
+pub trait VectorExt<T> {
+    /// Computes the inner product of two vectors of the same size.
+    ///
+    /// Unlike `Vector::dot`, which silently truncates to the shorter
+    /// of the two vectors, this checks that the sizes match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Vector, VectorExt};
+    ///
+    /// let a = Vector::new(vec![1.0, 2.0, 3.0]);
+    /// let b = Vector::new(vec![4.0, 5.0, 6.0]);
+    ///
+    /// assert_eq!(a.inner_product(&b), 32.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The vectors have different sizes.
+    fn inner_product(&self, other: &Vector<T>) -> T
+        where T: Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>;
+
+    /// Computes the weighted inner product `sum(a_i b_i w_i)`.
+    ///
+    /// This is the inner product used by weighted least squares.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Vector, VectorExt};
+    ///
+    /// let a = Vector::new(vec![1.0, 2.0, 3.0]);
+    /// let b = Vector::new(vec![4.0, 5.0, 6.0]);
+    /// let w = Vector::new(vec![1.0, 0.0, 0.5]);
+    ///
+    /// assert_eq!(a.weighted_dot(&b, &w), 13.0);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The vectors and weights have different sizes.
+    fn weighted_dot(&self, other: &Vector<T>, weights: &Vector<T>) -> T
+        where T: Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>;
+
+    /// Checks whether two vectors are equal within an absolute tolerance.
+    ///
+    /// The vectors are equal if they have the same size and every pair
+    /// of elements differs by at most `tol`. Elements which are NaN are
+    /// never equal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Vector, VectorExt};
+    ///
+    /// let a = Vector::new(vec![1.0, 2.0, 3.0]);
+    /// let b = Vector::new(vec![1.0, 2.0, 3.0 - 1e-10]);
+    ///
+    /// assert!(a.approx_eq(&b, 1e-8));
+    /// assert!(!a.approx_eq(&b, 1e-12));
+    /// ```
+    fn approx_eq(&self, other: &Vector<T>, tol: T) -> bool where T: Float;
+
+    /// Computes the simple moving average over windows of the given size.
+    ///
+    /// Only full windows are averaged, so the output has
+    /// `size - window + 1` elements. The `i`-th element is the mean
+    /// of the elements `i` to `i + window - 1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Vector, VectorExt};
+    ///
+    /// let a = Vector::new(vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+    ///
+    /// assert_eq!(*a.moving_average(3).data(), vec![2.0, 3.0, 4.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `window` is zero or larger than the vector.
+    fn moving_average(&self, window: usize) -> Vector<T> where T: Float + FromPrimitive;
+
+    /// Computes the first differences `a[i + 1] - a[i]`.
+    ///
+    /// The output has one element fewer than the vector, and is
+    /// empty if the vector has fewer than two elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Vector, VectorExt};
+    ///
+    /// let a = Vector::new(vec![1, 4, 9, 16]);
+    ///
+    /// assert_eq!(*a.diff().data(), vec![3, 5, 7]);
+    /// ```
+    fn diff(&self) -> Vector<T> where T: Copy + Sub<T, Output = T>;
+
+    /// Computes the exponentially weighted moving average.
+    ///
+    /// The output has the same size as the vector, with `s[0] = a[0]`
+    /// and `s[i] = alpha a[i] + (1 - alpha) s[i - 1]`. Larger values
+    /// of `alpha` discount older elements more quickly, and `alpha = 1`
+    /// returns the vector unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Vector, VectorExt};
+    ///
+    /// let a = Vector::new(vec![0.0, 4.0, 4.0]);
+    ///
+    /// assert_eq!(*a.ewma(0.5).data(), vec![0.0, 2.0, 3.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `alpha` is not in `(0, 1]`.
+    fn ewma(&self, alpha: T) -> Vector<T> where T: Float;
+
+    /// Computes the exponentially weighted moving variance.
+    ///
+    /// Uses the incremental update matching `ewma`, with `v[0] = 0` and
+    /// `v[i] = (1 - alpha) (v[i - 1] + alpha d^2)` where `d = a[i] - s[i - 1]`
+    /// is the deviation from the previous average.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Vector, VectorExt};
+    ///
+    /// let a = Vector::new(vec![0.0, 4.0]);
+    ///
+    /// assert_eq!(*a.ewm_variance(0.5).data(), vec![0.0, 4.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `alpha` is not in `(0, 1]`.
+    fn ewm_variance(&self, alpha: T) -> Vector<T> where T: Float;
+
+    /// Computes the Jacobian of the softmax at a probability vector.
+    ///
+    /// For `s = softmax(z)` this is `diag(s) - s s^T`, so entry `(i, j)`
+    /// is the derivative of `s[i]` with respect to `z[j]`. It is used to
+    /// backpropagate through a softmax output. The vector should be the
+    /// softmax output, not the inputs `z`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::linalg::{Matrix, Vector, VectorExt};
+    ///
+    /// let s = Vector::new(vec![0.5, 0.25, 0.25]);
+    /// let j = s.softmax_jacobian();
+    ///
+    /// assert_eq!(j, Matrix::new(3, 3, vec![0.25, -0.125, -0.125,
+    ///                                      -0.125, 0.1875, -0.0625,
+    ///                                      -0.125, -0.0625, 0.1875]));
+    /// ```
+    fn softmax_jacobian(&self) -> Matrix<T>
+        where T: Copy + Zero + Sub<T, Output = T> + Mul<T, Output = T>;
+}
+
+impl<T> VectorExt<T> for Vector<T> {
+    fn inner_product(&self, other: &Vector<T>) -> T
+        where T: Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>
+    {
+        assert!(self.size() == other.size(),
+                "Vector sizes must match for the inner product, found {} and {}.",
+                self.size(),
+                other.size());
+
+        self.dot(other)
+    }
+
+    fn weighted_dot(&self, other: &Vector<T>, weights: &Vector<T>) -> T
+        where T: Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>
+    {
+        assert!(self.size() == other.size() && self.size() == weights.size(),
+                "Vector and weight sizes must match for the weighted dot product, found {}, {} \
+                 and {}.",
+                self.size(),
+                other.size(),
+                weights.size());
+
+        self.data()
+            .iter()
+            .zip(other.data().iter())
+            .zip(weights.data().iter())
+            .fold(T::zero(), |acc, ((&a, &b), &w)| acc + a * b * w)
+    }
+
+    fn approx_eq(&self, other: &Vector<T>, tol: T) -> bool
+        where T: Float
+    {
+        self.size() == other.size() &&
+        self.data().iter().zip(other.data().iter()).all(|(&a, &b)| (a - b).abs() <= tol)
+    }
+
+    fn moving_average(&self, window: usize) -> Vector<T>
+        where T: Float + FromPrimitive
+    {
+        assert!(window > 0 && window <= self.size(),
+                "The window must be positive and no larger than the vector, found {} for size {}.",
+                window,
+                self.size());
+
+        let n = T::from_usize(window).unwrap();
+        self.data()
+            .windows(window)
+            .map(|w| w.iter().fold(T::zero(), |acc, &x| acc + x) / n)
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn diff(&self) -> Vector<T>
+        where T: Copy + Sub<T, Output = T>
+    {
+        self.data()
+            .windows(2)
+            .map(|w| w[1] - w[0])
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn ewma(&self, alpha: T) -> Vector<T>
+        where T: Float
+    {
+        assert_smoothing_factor(alpha);
+
+        let mut avg = match self.data().first() {
+            Some(&x) => x,
+            None => return Vector::new(vec![]),
+        };
+        self.data()
+            .iter()
+            .map(|&x| {
+                avg = avg + alpha * (x - avg);
+                avg
+            })
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn ewm_variance(&self, alpha: T) -> Vector<T>
+        where T: Float
+    {
+        assert_smoothing_factor(alpha);
+
+        let mut avg = match self.data().first() {
+            Some(&x) => x,
+            None => return Vector::new(vec![]),
+        };
+        let mut var = T::zero();
+        self.data()
+            .iter()
+            .map(|&x| {
+                let delta = x - avg;
+                avg = avg + alpha * delta;
+                var = (T::one() - alpha) * (var + alpha * delta * delta);
+                var
+            })
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn softmax_jacobian(&self) -> Matrix<T>
+        where T: Copy + Zero + Sub<T, Output = T> + Mul<T, Output = T>
+    {
+        let n = self.size();
+        let mut data = Vec::with_capacity(n * n);
+        for i in 0..n {
+            for j in 0..n {
+                let diag = if i == j { self[i] } else { T::zero() };
+                data.push(diag - self[i] * self[j]);
+            }
+        }
+        Matrix::new(n, n, data)
+    }
+}
+
+/// Checks that the smoothing factor lies in `(0, 1]`.
+fn assert_smoothing_factor<T: Float>(alpha: T) {
+    assert!(alpha > T::zero() && alpha <= T::one(),
+            "The smoothing factor must be in (0, 1].");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VectorExt;
+    use learning::optim::numerical_gradient;
+    use linalg::{BaseMatrix, Vector};
+
+    #[test]
+    fn test_inner_product() {
+        let a = Vector::new(vec![1, -2, 3, 4]);
+        let b = Vector::new(vec![2, 5, -1, 0]);
+
+        assert_eq!(a.inner_product(&b), -11);
+        assert_eq!(a.inner_product(&b), a.dot(&b));
+    }
+
+    #[test]
+    #[should_panic(expected = "Vector sizes must match")]
+    fn test_inner_product_mismatch() {
+        let a = Vector::new(vec![1.0, 2.0, 3.0]);
+        let b = Vector::new(vec![1.0, 2.0]);
+
+        let _ = a.inner_product(&b);
+    }
+
+    #[test]
+    fn test_weighted_dot() {
+        let a = Vector::new(vec![1.0, 2.0, -1.0, 0.5]);
+        let b = Vector::new(vec![3.0, -1.0, 2.0, 4.0]);
+        let w = Vector::new(vec![0.5, 2.0, 1.0, 0.25]);
+
+        // 1.5 - 4 - 2 + 0.5
+        assert_eq!(a.weighted_dot(&b, &w), -4.0);
+
+        // Unit weights give the usual dot product
+        assert_eq!(a.weighted_dot(&b, &Vector::ones(4)), a.dot(&b));
+    }
+
+    #[test]
+    #[should_panic(expected = "weight sizes must match")]
+    fn test_weighted_dot_mismatch() {
+        let a = Vector::new(vec![1.0, 2.0]);
+        let w = Vector::new(vec![1.0, 2.0, 3.0]);
+
+        let _ = a.weighted_dot(&a, &w);
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let a = Vector::new(vec![1.0, -2.0, 3.0]);
+        let close = Vector::new(vec![1.0 + 1e-7, -2.0 - 1e-7, 3.0]);
+        let far = Vector::new(vec![1.0, -2.0, 3.0 + 1e-5]);
+
+        assert!(a.approx_eq(&close, 1e-6));
+        assert!(!a.approx_eq(&far, 1e-6));
+        assert!(a.approx_eq(&far, 1e-4));
+        assert!(!a.approx_eq(&Vector::new(vec![1.0, -2.0]), 1.0));
+    }
+
+    #[test]
+    fn test_moving_average() {
+        let constant = Vector::new(vec![2.5; 10]);
+        for window in 1..11 {
+            let avg = constant.moving_average(window);
+            assert_eq!(avg.size(), 11 - window);
+            assert!(avg.data().iter().all(|&x| x == 2.5));
+        }
+
+        let a = Vector::new(vec![1.0, 3.0, -2.0, 6.0]);
+        assert_eq!(*a.moving_average(2).data(), vec![2.0, 0.5, 2.0]);
+        assert_eq!(*a.moving_average(4).data(), vec![2.0]);
+        assert_eq!(a.moving_average(1), a);
+    }
+
+    #[test]
+    #[should_panic(expected = "The window must be positive")]
+    fn test_moving_average_window_too_large() {
+        let _ = Vector::new(vec![1.0, 2.0]).moving_average(3);
+    }
+
+    #[test]
+    fn test_diff() {
+        let ramp = Vector::new((0..8).map(|i| 0.5 * i as f64 - 1.0).collect::<Vec<_>>());
+        let d = ramp.diff();
+        assert_eq!(d.size(), 7);
+        assert!(d.data().iter().all(|&x| x == 0.5));
+
+        assert_eq!(Vector::new(vec![3]).diff().size(), 0);
+        assert_eq!(Vector::<f64>::new(vec![]).diff().size(), 0);
+    }
+
+    #[test]
+    fn test_ewma_converges_to_constant() {
+        // Starting away from the constant level
+        let mut data = vec![0.0f64];
+        data.extend(vec![10.0; 50]);
+        let a = Vector::new(data);
+
+        let avg = a.ewma(0.2);
+        assert_eq!(avg.size(), a.size());
+        assert_eq!(avg[0], 0.0);
+
+        // The remaining gap shrinks by the factor 1 - alpha each step
+        for i in 1..avg.size() {
+            let expected = 10.0 * (1.0 - 0.8f64.powi(i as i32));
+            assert!((avg[i] - expected).abs() < 1e-10);
+        }
+        assert!((avg[50] - 10.0).abs() < 1e-3);
+
+        // A smaller smoothing factor converges more slowly
+        let slow = a.ewma(0.05);
+        assert!(slow[10] < avg[10]);
+
+        assert_eq!(a.ewma(1.0), a);
+    }
+
+    #[test]
+    fn test_ewm_variance() {
+        let constant = Vector::new(vec![3.0; 10]);
+        assert!(constant.ewm_variance(0.3).data().iter().all(|&v| v == 0.0));
+
+        // An alternating series settles at variance 1
+        let alternating = Vector::new((0..200).map(|i| if i % 2 == 0 { 1.0f64 } else { -1.0 })
+            .collect::<Vec<_>>());
+        let var = alternating.ewm_variance(0.1);
+        assert!(var.data().iter().all(|&v| v >= 0.0));
+        assert!((var[199] - 1.0).abs() < 0.1);
+
+        assert_eq!(Vector::<f64>::new(vec![]).ewm_variance(0.5).size(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "The smoothing factor must be in (0, 1].")]
+    fn test_ewma_zero_alpha() {
+        let _ = Vector::new(vec![1.0, 2.0]).ewma(0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "The smoothing factor must be in (0, 1].")]
+    fn test_ewm_variance_large_alpha() {
+        let _ = Vector::new(vec![1.0, 2.0]).ewm_variance(1.5);
+    }
+
+    fn softmax(z: &Vector<f64>) -> Vector<f64> {
+        let max = z.iter().fold(f64::NEG_INFINITY, |acc, &x| acc.max(x));
+        let exp = z.clone().apply(&|x| (x - max).exp());
+        let total = exp.sum();
+        exp / total
+    }
+
+    #[test]
+    fn test_softmax_jacobian_matches_numerical() {
+        let z = Vector::new(vec![0.3, -1.2, 2.0, 0.0]);
+        let jac = softmax(&z).softmax_jacobian();
+
+        for i in 0..4 {
+            let grad = numerical_gradient(|z| softmax(z)[i], &z, 1e-5);
+            for j in 0..4 {
+                assert!((jac[[i, j]] - grad[j]).abs() < 1e-9);
+            }
+        }
+
+        // Shifting the inputs does not change the softmax, so the rows sum to zero
+        for row in jac.iter_rows() {
+            assert!(row.iter().sum::<f64>().abs() < 1e-12);
+        }
+        assert_eq!(jac, jac.transpose());
+    }
+}
@@ -0,0 +1,105 @@
+//! Euclidean distances and nearest neighbour search.
+//!
+//! Shared by the neighbour based models and the clustering metrics.
+
+use linalg::{Matrix, BaseMatrix};
+
+use libnum::Float;
+
+use std::cmp::Ordering;
+
+/// The squared Euclidean distance between two points.
+pub fn squared_distance<T: Float>(x: &[T], y: &[T]) -> T {
+    x.iter().zip(y).fold(T::zero(), |acc, (&a, &b)| acc + (a - b) * (a - b))
+}
+
+/// The Euclidean distance between two points.
+pub fn distance<T: Float>(x: &[T], y: &[T]) -> T {
+    squared_distance(x, y).sqrt()
+}
+
+/// The Euclidean distance between every pair of rows.
+pub fn pairwise_distances<T: Float>(data: &Matrix<T>) -> Matrix<T> {
+    let rows = data.iter_rows().collect::<Vec<_>>();
+    let n = rows.len();
+    let mut distances = Matrix::zeros(n, n);
+    for i in 0..n {
+        for j in i + 1..n {
+            let d = distance(rows[i], rows[j]);
+            distances[[i, j]] = d;
+            distances[[j, i]] = d;
+        }
+    }
+    distances
+}
+
+/// Find the `k` nearest rows of `inputs` to `point`, optionally excluding one row.
+///
+/// Returns `(index, distance)` pairs sorted by increasing distance,
+/// with equal distances in order of index. Rows at a NaN distance
+/// are ranked after all others.
+pub fn k_nearest<T: Float>(point: &[T],
+                           inputs: &Matrix<T>,
+                           k: usize,
+                           exclude: Option<usize>)
+                           -> Vec<(usize, T)> {
+    let mut distances = inputs.iter_rows()
+        .enumerate()
+        .filter(|&(idx, _)| Some(idx) != exclude)
+        .map(|(idx, row)| (idx, distance(row, point)))
+        .collect::<Vec<_>>();
+
+    // The sort is stable so equal distances keep their index order
+    distances.sort_by(|a, b| ascending_nan_last(a.1, b.1));
+    distances.truncate(k);
+    distances
+}
+
+/// Orders numbers by increasing value, with NaN after all numbers.
+pub fn ascending_nan_last<T: Float>(a: T, b: T) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{k_nearest, pairwise_distances};
+
+    use linalg::Matrix;
+
+    #[test]
+    fn test_k_nearest_ties_and_exclude() {
+        let inputs = Matrix::new(4, 1, vec![1.0, -1.0, 0.0, 3.0]);
+
+        let nearest = k_nearest(&[0.0], &inputs, 3, None);
+        assert_eq!(nearest, vec![(2, 0.0), (0, 1.0), (1, 1.0)]);
+
+        let nearest = k_nearest(&[0.0], &inputs, 2, Some(2));
+        assert_eq!(nearest, vec![(0, 1.0), (1, 1.0)]);
+    }
+
+    #[test]
+    fn test_k_nearest_nan_last() {
+        let inputs = Matrix::new(3, 1, vec![::std::f64::NAN, 2.0, 1.0]);
+
+        let nearest = k_nearest(&[0.0], &inputs, 3, None);
+        assert_eq!(nearest[0], (2, 1.0));
+        assert_eq!(nearest[1], (1, 2.0));
+        assert_eq!(nearest[2].0, 0);
+        assert!(nearest[2].1.is_nan());
+    }
+
+    #[test]
+    fn test_pairwise_distances() {
+        let data = Matrix::new(3, 2, vec![0.0, 0.0, 3.0, 4.0, 0.0, 1.0]);
+        let distances = pairwise_distances(&data);
+
+        assert_eq!(distances.into_vec(), vec![0.0, 5.0, 1.0,
+                                              5.0, 0.0, 4.242640687119285,
+                                              1.0, 4.242640687119285, 0.0]);
+    }
+}
@@ -5,6 +5,8 @@
 pub use linalg::{Matrix, MatrixSlice, MatrixSliceMut, BaseMatrix, BaseMatrixMut};
 pub use linalg::Vector;
 pub use linalg::Axes;
+pub use linalg::MatrixExt;
+pub use linalg::VectorExt;
 
 pub use learning::SupModel;
 pub use learning::UnSupModel;
@@ -1,8 +1,10 @@
 pub mod exponential;
 pub mod gaussian;
+pub mod multivariate_normal;
 
 pub use self::gaussian::Gaussian;
 pub use self::exponential::Exponential;
+pub use self::multivariate_normal::MultivariateNormal;
 
 /// Statistical constants
 ///
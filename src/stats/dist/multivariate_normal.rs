@@ -0,0 +1,265 @@
+//! Multivariate normal distribution module.
+//!
+//! Contains a multivariate Gaussian random variable with a full
+//! covariance matrix. The covariance is factored once on construction
+//! and the factor is used for sampling and for the density.
+
+use linalg::{Matrix, BaseMatrix, Vector};
+use rulinalg::error::{Error, ErrorKind};
+use rand::Rng;
+use rand::distributions::{Sample, IndependentSample};
+use rand::distributions::normal::StandardNormal;
+use super::consts as stat_consts;
+
+/// A multivariate Gaussian random variable.
+///
+/// This struct stores the lower triangular Cholesky factor `L` of the
+/// covariance, with `LL^T = cov`. Samples are drawn as `mean + Lz`
+/// where `z` is a vector of independent standard normals.
+#[derive(Debug, Clone)]
+pub struct MultivariateNormal {
+    mean: Vector<f64>,
+    cov: Matrix<f64>,
+    chol: Matrix<f64>,
+}
+
+impl MultivariateNormal {
+    /// Creates a new multivariate Gaussian random variable from
+    /// a given mean and covariance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::stats::dist::MultivariateNormal;
+    /// use rusty_machine::linalg::{Matrix, Vector};
+    ///
+    /// let mean = Vector::new(vec![1.0, -1.0]);
+    /// let cov = Matrix::new(2, 2, vec![2.0, 0.5, 0.5, 1.0]);
+    ///
+    /// let mvn = MultivariateNormal::new(mean, cov).unwrap();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The covariance is not square with the size of the mean.
+    ///
+    /// # Failures
+    ///
+    /// - The covariance is not positive definite.
+    pub fn new(mean: Vector<f64>, cov: Matrix<f64>) -> Result<MultivariateNormal, Error> {
+        assert!(cov.rows() == mean.size() && cov.cols() == mean.size(),
+                "The covariance must be square with the size of the mean.");
+
+        let chol = cov.cholesky()?;
+        if (0..mean.size()).any(|i| !(chol[[i, i]] > 0f64 && chol[[i, i]].is_finite())) {
+            return Err(Error::new(ErrorKind::DecompFailure,
+                                  "Covariance is not positive definite."));
+        }
+
+        Ok(MultivariateNormal {
+            mean,
+            cov,
+            chol,
+        })
+    }
+
+    /// Fits a multivariate Gaussian random variable to the rows
+    /// of the inputs.
+    ///
+    /// Uses the sample mean and the unbiased sample covariance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_machine::stats::dist::MultivariateNormal;
+    /// use rusty_machine::linalg::Matrix;
+    ///
+    /// let inputs = Matrix::new(4, 2, vec![1.0, 2.0, 2.0, 2.5, 0.0, 1.0, 1.0, 0.5]);
+    /// let mvn = MultivariateNormal::fit(&inputs).unwrap();
+    ///
+    /// assert_eq!(*mvn.mean().data(), vec![1.0, 1.5]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - There are fewer than two rows.
+    ///
+    /// # Failures
+    ///
+    /// - The sample covariance is not positive definite.
+    pub fn fit(inputs: &Matrix<f64>) -> Result<MultivariateNormal, Error> {
+        let n = inputs.rows();
+        assert!(n > 1, "At least two rows are needed to fit a covariance.");
+
+        let d = inputs.cols();
+        let mut mean = vec![0f64; d];
+        for row in inputs.iter_rows() {
+            for (m, x) in mean.iter_mut().zip(row.iter()) {
+                *m += x;
+            }
+        }
+        for m in &mut mean {
+            *m /= n as f64;
+        }
+
+        let mut cov = Matrix::zeros(d, d);
+        for row in inputs.iter_rows() {
+            for j in 0..d {
+                for k in 0..d {
+                    cov[[j, k]] += (row[j] - mean[j]) * (row[k] - mean[k]);
+                }
+            }
+        }
+
+        MultivariateNormal::new(Vector::new(mean), cov / (n - 1) as f64)
+    }
+
+    /// The mean of the distribution.
+    pub fn mean(&self) -> &Vector<f64> {
+        &self.mean
+    }
+
+    /// The covariance of the distribution.
+    pub fn cov(&self) -> &Matrix<f64> {
+        &self.cov
+    }
+
+    /// The lower triangular Cholesky factor of the covariance.
+    pub fn cholesky_factor(&self) -> &Matrix<f64> {
+        &self.chol
+    }
+
+    /// Draws a sample from the distribution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate rusty_machine;
+    /// use rusty_machine::stats::dist::MultivariateNormal;
+    /// use rusty_machine::linalg::{Matrix, Vector};
+    /// use rand::thread_rng;
+    ///
+    /// # fn main() {
+    ///
+    /// let cov = Matrix::new(2, 2, vec![2.0, 0.5, 0.5, 1.0]);
+    /// let mvn = MultivariateNormal::new(Vector::zeros(2), cov).unwrap();
+    ///
+    /// let x = mvn.sample(&mut thread_rng());
+    /// assert_eq!(x.size(), 2);
+    /// # }
+    /// ```
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Vector<f64> {
+        let d = self.mean.size();
+        let z = (0..d)
+            .map(|_| {
+                let StandardNormal(n) = rng.gen::<StandardNormal>();
+                n
+            })
+            .collect::<Vec<_>>();
+
+        let mut x = self.mean.clone();
+        for i in 0..d {
+            for (j, zj) in z.iter().enumerate().take(i + 1) {
+                x[i] += self.chol[[i, j]] * zj;
+            }
+        }
+        x
+    }
+
+    /// The log pdf of the distribution.
+    ///
+    /// # Panics
+    ///
+    /// - The point does not have the size of the mean.
+    pub fn logpdf(&self, x: &Vector<f64>) -> f64 {
+        assert!(x.size() == self.mean.size(),
+                "The point must have the size of the mean.");
+
+        let d = self.mean.size();
+        let z = self.chol
+            .solve_l_triangular(x - &self.mean)
+            .expect("The Cholesky factor has a positive diagonal.");
+        let log_sqrt_det = (0..d).map(|i| self.chol[[i, i]].ln()).sum::<f64>();
+
+        -0.5 * d as f64 * stat_consts::LN_2_PI - log_sqrt_det - 0.5 * z.dot(&z)
+    }
+
+    /// The pdf of the distribution.
+    pub fn pdf(&self, x: &Vector<f64>) -> f64 {
+        self.logpdf(x).exp()
+    }
+}
+
+impl Sample<Vector<f64>> for MultivariateNormal {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> Vector<f64> {
+        self.ind_sample(rng)
+    }
+}
+
+impl IndependentSample<Vector<f64>> for MultivariateNormal {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> Vector<f64> {
+        MultivariateNormal::sample(self, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultivariateNormal;
+    use linalg::{Matrix, BaseMatrix, Vector};
+    use rand::{SeedableRng, StdRng};
+
+    #[test]
+    fn test_empirical_covariance() {
+        let mean = Vector::new(vec![1.0, -2.0, 0.5]);
+        let cov = Matrix::new(3, 3, vec![2.0, 0.8, -0.3, 0.8, 1.0, 0.2, -0.3, 0.2, 0.5]);
+        let mvn = MultivariateNormal::new(mean.clone(), cov.clone()).unwrap();
+
+        let seed: &[usize] = &[1, 2, 3];
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let n = 20000;
+        let mut samples = Vec::with_capacity(3 * n);
+        for _ in 0..n {
+            samples.extend(mvn.sample(&mut rng).into_vec());
+        }
+
+        let fitted = MultivariateNormal::fit(&Matrix::new(n, 3, samples)).unwrap();
+
+        for i in 0..3 {
+            assert!((fitted.mean()[i] - mean[i]).abs() < 0.05);
+            for j in 0..3 {
+                assert!((fitted.cov()[[i, j]] - cov[[i, j]]).abs() < 0.05);
+            }
+        }
+    }
+
+    #[test]
+    fn test_logpdf() {
+        let cov = Matrix::new(2, 2, vec![4.0, 0.0, 0.0, 1.0]);
+        let mvn = MultivariateNormal::new(Vector::new(vec![1.0, 0.0]), cov).unwrap();
+
+        // Independent components give the sum of univariate log pdfs
+        let x = Vector::new(vec![3.0, -1.0]);
+        let expected = -(2f64 * ::std::f64::consts::PI).ln() - 2f64.ln() - 0.5 - 0.5;
+        assert!((mvn.logpdf(&x) - expected).abs() < 1e-12);
+        assert!((mvn.pdf(&x) - expected.exp()).abs() < 1e-12);
+
+        let l = mvn.cholesky_factor();
+        assert_eq!(*l.data(), vec![2.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_not_positive_definite() {
+        let cov = Matrix::new(2, 2, vec![1.0, 2.0, 2.0, 1.0]);
+        assert!(MultivariateNormal::new(Vector::zeros(2), cov).is_err());
+
+        let singular = Matrix::new(2, 2, vec![1.0, 1.0, 1.0, 1.0]);
+        assert!(MultivariateNormal::new(Vector::zeros(2), singular).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_dimension_mismatch() {
+        let _ = MultivariateNormal::new(Vector::zeros(3), Matrix::identity(2));
+    }
+}
@@ -1,7 +1,7 @@
-use rm::linalg::Matrix;
-use rm::learning::UnSupModel;
+use rm::linalg::{Matrix, BaseMatrix, Vector};
+use rm::learning::{LearningResult, UnSupModel};
 use rm::learning::k_means::KMeansClassifier;
-use rm::learning::k_means::{Forgy, RandomPartition, KPlusPlus};
+use rm::learning::k_means::{Forgy, RandomPartition, KPlusPlus, Initializer};
 
 #[test]
 fn test_model_default() {
@@ -99,3 +99,191 @@ fn test_two_centroids() {
     assert!(classes.data().iter().take(3).all(|x| *x == class_a));
     assert!(classes.data().iter().skip(3).all(|x| *x == class_b));
 }
+
+#[test]
+fn test_model_seeded() {
+    let inputs = Matrix::new(30, 1, (0..30).map(|x| x as f64).collect::<Vec<_>>());
+
+    let mut a = KMeansClassifier::new_specified(3, 0, Forgy).with_seed(7);
+    let mut b = KMeansClassifier::new_specified(3, 0, Forgy).with_seed(7);
+    let mut c = KMeansClassifier::new_specified(3, 0, Forgy).with_seed(8);
+    a.train(&inputs).unwrap();
+    b.train(&inputs).unwrap();
+    c.train(&inputs).unwrap();
+
+    assert_eq!(a.centroids(), b.centroids());
+    assert!(a.centroids() != c.centroids());
+
+    let mut a = KMeansClassifier::new(3).with_seed(1);
+    let mut b = KMeansClassifier::new(3).with_seed(1);
+    a.train(&inputs).unwrap();
+    b.train(&inputs).unwrap();
+
+    assert_eq!(a.centroids(), b.centroids());
+}
+
+#[test]
+fn test_partial_fit_matches_batch() {
+    let centres = [(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)];
+    let mut data = Vec::new();
+    for i in 0..90 {
+        let (x, y) = centres[i % 3];
+        let offset = ((i * 7) % 11) as f64 / 10.0 - 0.5;
+        data.push(x + offset);
+        data.push(y - offset / 2.0);
+    }
+    let inputs = Matrix::new(90, 2, data);
+
+    let mut full = KMeansClassifier::new(3).with_seed(3);
+    full.train(&inputs).unwrap();
+
+    let mut online = KMeansClassifier::new(3).with_seed(3);
+    for b in 0..9 {
+        let rows = (b * 10..b * 10 + 10).collect::<Vec<_>>();
+        online.partial_fit(&inputs.select_rows(&rows)).unwrap();
+    }
+
+    let sorted = |m: &Matrix<f64>| {
+        let mut rows = m.iter_rows().map(|r| r.to_vec()).collect::<Vec<_>>();
+        rows.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        rows
+    };
+    let full_centroids = sorted(full.centroids().as_ref().unwrap());
+    let online_centroids = sorted(online.centroids().as_ref().unwrap());
+    for (f, o) in full_centroids.iter().zip(online_centroids.iter()) {
+        assert!(f.iter().zip(o.iter()).all(|(a, b)| (a - b).abs() < 0.1));
+    }
+
+    // Both models agree on every point
+    let full_labels = full.predict(&inputs).unwrap();
+    let online_labels = online.predict(&inputs).unwrap();
+    for i in 0..90 {
+        for j in 0..90 {
+            assert_eq!(full_labels[i] == full_labels[j], online_labels[i] == online_labels[j]);
+        }
+    }
+}
+
+#[test]
+fn test_partial_fit_invalid_batch() {
+    let mut model = KMeansClassifier::new(3);
+    assert!(model.partial_fit(&Matrix::new(2, 1, vec![0.0, 1.0])).is_err());
+
+    model.partial_fit(&Matrix::new(3, 1, vec![0.0, 1.0, 2.0])).unwrap();
+    assert!(model.partial_fit(&Matrix::new(1, 2, vec![0.0, 1.0])).is_err());
+}
+
+/// Starts from the given centroids.
+#[derive(Debug)]
+struct FixedCentroids(Matrix<f64>);
+
+impl Initializer for FixedCentroids {
+    fn init_centroids(&self, _: usize, _: &Matrix<f64>) -> LearningResult<Matrix<f64>> {
+        Ok(self.0.clone())
+    }
+}
+
+#[test]
+fn test_initializer_default_rng_method() {
+    let start = FixedCentroids(Matrix::new(2, 1, vec![0.0, 10.0]));
+
+    // Seeded training goes through the default `init_centroids_with_rng`
+    let inputs = Matrix::new(4, 1, vec![0.0, 1.0, 10.0, 11.0]);
+    let mut model = KMeansClassifier::new_specified(2, 100, start).with_seed(3);
+    model.train(&inputs).unwrap();
+    assert_eq!(*model.centroids().as_ref().unwrap().data(), vec![0.5, 10.5]);
+
+    // The trait can still be used as a trait object
+    let initializers: Vec<Box<dyn Initializer>> = vec![Box::new(Forgy), Box::new(KPlusPlus)];
+    assert_eq!(initializers.len(), 2);
+}
+
+#[test]
+fn test_weight_equals_duplicate() {
+    let inputs = Matrix::new(6, 2, vec![0.0, 0.0, 1.0, 0.5, 0.2, 1.4,
+                                        4.0, 4.0, 5.5, 3.0, 2.5, 2.5]);
+    let start = || FixedCentroids(Matrix::new(2, 2, vec![0.0, 1.0, 3.0, 3.0]));
+
+    // Duplicate the last point, which is between the clusters
+    let duplicated = inputs.select_rows(&[0, 1, 2, 3, 4, 5, 5]);
+    let mut copies = KMeansClassifier::new_specified(2, 100, start());
+    copies.train(&duplicated).unwrap();
+
+    let mut weighted = KMeansClassifier::new_specified(2, 100, start());
+    weighted.set_weights(Some(Vector::new(vec![1.0, 1.0, 1.0, 1.0, 1.0, 2.0])));
+    weighted.train(&inputs).unwrap();
+
+    let copies = copies.centroids().as_ref().unwrap();
+    let weighted = weighted.centroids().as_ref().unwrap();
+    for (a, b) in copies.iter().zip(weighted.iter()) {
+        assert!((a - b).abs() < 1e-12);
+    }
+
+    // Unweighted training gives different centroids
+    let mut unweighted = KMeansClassifier::new_specified(2, 100, start());
+    unweighted.train(&inputs).unwrap();
+    assert!(unweighted.centroids().as_ref().unwrap() != weighted);
+}
+
+#[test]
+fn test_invalid_weights() {
+    let inputs = Matrix::new(3, 1, vec![0.0, 1.0, 2.0]);
+    let mut model = KMeansClassifier::new(2);
+
+    model.set_weights(Some(Vector::new(vec![1.0, 1.0])));
+    assert!(model.train(&inputs).is_err());
+
+    model.set_weights(Some(Vector::new(vec![1.0, -1.0, 1.0])));
+    assert!(model.train(&inputs).is_err());
+}
+
+#[test]
+fn test_partial_fit_after_weighted_train() {
+    let inputs = Matrix::new(4, 1, vec![0.0, 1.0, 10.0, 11.0]);
+    let start = FixedCentroids(Matrix::new(2, 1, vec![0.0, 10.0]));
+
+    let mut model = KMeansClassifier::new_specified(2, 100, start);
+    model.set_weights(Some(Vector::new(vec![3.0, 1.0, 1.0, 1.0])));
+    model.train(&inputs).unwrap();
+    assert_eq!(*model.centroids().as_ref().unwrap().data(), vec![0.25, 10.5]);
+
+    // The batch has no weights, so they must be cleared first
+    let batch = Matrix::new(1, 1, vec![2.25]);
+    assert!(model.partial_fit(&batch).is_err());
+
+    // The first centroid has a total weight of four
+    model.set_weights(None);
+    model.partial_fit(&batch).unwrap();
+    let centroids = model.centroids().as_ref().unwrap();
+    assert!((centroids[[0, 0]] - 0.65).abs() < 1e-12);
+    assert_eq!(centroids[[1, 0]], 10.5);
+}
+
+#[test]
+fn test_partial_fit_from_set_centroids() {
+    let mut model = KMeansClassifier::new(2);
+    model.set_centroids(Matrix::new(2, 1, vec![0.0, 10.0])).unwrap();
+
+    // The given centroids have no points, so the first becomes the mean of the batch
+    model.partial_fit(&Matrix::new(2, 1, vec![1.0, 2.0])).unwrap();
+    assert_eq!(*model.centroids().as_ref().unwrap().data(), vec![1.5, 10.0]);
+}
+
+#[test]
+fn test_warm_start_matches_fixed_initializer() {
+    let inputs = Matrix::new(6, 2, vec![0.0, 0.0, 1.0, 0.5, 0.2, 1.4,
+                                        4.0, 4.0, 5.5, 3.0, 2.5, 2.5]);
+    let start = Matrix::new(2, 2, vec![0.0, 1.0, 3.0, 3.0]);
+
+    let mut fixed = KMeansClassifier::new_specified(2, 100, FixedCentroids(start.clone()));
+    fixed.train(&inputs).unwrap();
+
+    let mut warm = KMeansClassifier::new(2);
+    assert!(!warm.warm_start());
+    warm.set_warm_start(true);
+    warm.set_centroids(start).unwrap();
+    warm.train(&inputs).unwrap();
+
+    assert_eq!(fixed.centroids(), warm.centroids());
+    assert!(warm.set_centroids(Matrix::zeros(3, 2)).is_err());
+}
@@ -1,9 +1,11 @@
-use rm::linalg::Matrix;
+use rm::linalg::{Matrix, BaseMatrix};
 use rm::linalg::Vector;
 use rm::learning::SupModel;
 use rm::learning::lin_reg::LinRegressor;
 use libnum::abs;
 
+use std::f64;
+
 #[test]
 fn test_optimized_regression() {
     let mut lin_mod = LinRegressor::default();
@@ -32,6 +34,137 @@ fn test_regression() {
     assert!(err_2 < 1e-8);
 }
 
+#[test]
+fn test_regression_without_intercept() {
+    let inputs = Matrix::new(3, 1, vec![1.0, 2.0, 3.0]);
+    let targets = Vector::new(vec![3.0, 5.0, 7.0]);
+
+    let mut lin_mod = LinRegressor::default();
+    lin_mod.set_center(false);
+    lin_mod.train(&inputs, &targets).unwrap();
+
+    // The slope through the origin is sum(xy) / sum(x^2)
+    let parameters = lin_mod.parameters().unwrap();
+    assert_eq!(parameters[0], 0.0);
+    assert!(abs(parameters[1] - 34.0 / 14.0) < 1e-8);
+
+    let std_errors = lin_mod.coef_std_errors(&inputs, &targets).unwrap();
+    assert_eq!(std_errors.size(), 1);
+    assert_eq!(lin_mod.leverage(&inputs).unwrap().sum().round(), 1.0);
+}
+
+#[test]
+fn test_scaled_regression_matches_unscaled() {
+    let inputs = Matrix::new(5, 2, vec![1.0, 200.0, 2.0, 50.0, 3.0, 150.0, 4.0, 300.0, 5.0, 250.0]);
+    let targets = Vector::new(vec![3.1, 2.4, 5.2, 7.9, 8.3]);
+
+    let mut unscaled = LinRegressor::default();
+    unscaled.train(&inputs, &targets).unwrap();
+
+    let mut scaled = LinRegressor::default();
+    scaled.set_scale(true);
+    scaled.train(&inputs, &targets).unwrap();
+
+    let a = unscaled.parameters().unwrap();
+    let b = scaled.parameters().unwrap();
+    for (x, y) in a.data().iter().zip(b.data().iter()) {
+        assert!(abs(x - y) < 1e-8);
+    }
+    assert_eq!(scaled.predict(&inputs).unwrap().size(), 5);
+}
+
+#[test]
+fn test_equal_weights_match_ols() {
+    let inputs = Matrix::new(5, 2, vec![1.0, 2.0, 2.0, 0.5, 3.0, 1.5, 4.0, 3.0, 5.0, 2.5]);
+    let targets = Vector::new(vec![3.1, 2.4, 5.2, 7.9, 8.3]);
+
+    let mut ols = LinRegressor::default();
+    ols.train(&inputs, &targets).unwrap();
+
+    let mut wls = LinRegressor::default();
+    wls.set_weights(Some(Vector::new(vec![2.5; 5])));
+    wls.train(&inputs, &targets).unwrap();
+
+    let ols_params = ols.parameters().unwrap();
+    let wls_params = wls.parameters().unwrap();
+    for (x, y) in ols_params.data().iter().zip(wls_params.data().iter()) {
+        assert!(abs(x - y) < 1e-8);
+    }
+}
+
+#[test]
+fn test_down_weighted_outlier() {
+    // Points on the line y = 2x + 1, with an outlier at x = 5
+    let inputs = Matrix::new(5, 1, vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+    let targets = Vector::new(vec![3.0, 5.0, 7.0, 9.0, 30.0]);
+
+    let mut ols = LinRegressor::default();
+    ols.train(&inputs, &targets).unwrap();
+
+    let mut wls = LinRegressor::default();
+    wls.set_weights(Some(Vector::new(vec![1.0, 1.0, 1.0, 1.0, 1e-6])));
+    wls.train(&inputs, &targets).unwrap();
+
+    let ols_params = ols.parameters().unwrap();
+    let wls_params = wls.parameters().unwrap();
+
+    // The outlier pulls the unweighted slope upwards
+    assert!(ols_params[1] > 4.0);
+
+    // Down-weighting the outlier recovers the true line
+    assert!(abs(wls_params[0] - 1.0) < 1e-3);
+    assert!(abs(wls_params[1] - 2.0) < 1e-3);
+}
+
+#[test]
+fn test_optimized_regression_uses_weights() {
+    // Points on the line y = 2x + 1, with an outlier at x = 1
+    let inputs = Matrix::new(5, 1, vec![-1.0, -0.5, 0.0, 0.5, 1.0]);
+    let targets = Vector::new(vec![-1.0, 0.0, 1.0, 2.0, 6.0]);
+    let weights = Vector::new(vec![1.0, 1.0, 1.0, 1.0, 1e-6]);
+
+    let mut exact = LinRegressor::default();
+    exact.set_weights(Some(weights.clone()));
+    exact.train(&inputs, &targets).unwrap();
+
+    let mut ols = LinRegressor::default();
+    ols.train_with_optimization(&inputs, &targets);
+
+    let mut wls = LinRegressor::default();
+    wls.set_weights(Some(weights));
+    wls.train_with_optimization(&inputs, &targets);
+
+    // Gradient descent finds the weighted solution, not the unweighted one
+    let exact = exact.parameters().unwrap();
+    let ols = ols.parameters().unwrap();
+    let wls = wls.parameters().unwrap();
+    let dist = |p: &Vector<f64>| abs(p[0] - exact[0]) + abs(p[1] - exact[1]);
+    assert!(dist(wls) < 1e-2);
+    assert!(dist(ols) > 0.5);
+}
+
+#[test]
+#[should_panic]
+fn test_optimized_regression_wrong_weights() {
+    let inputs = Matrix::new(3, 1, vec![2.0, 3.0, 4.0]);
+    let targets = Vector::new(vec![5.0, 6.0, 7.0]);
+
+    let mut lin_mod = LinRegressor::default();
+    lin_mod.set_weights(Some(Vector::new(vec![1.0, 1.0])));
+    lin_mod.train_with_optimization(&inputs, &targets);
+}
+
+#[test]
+fn test_weights_wrong_length() {
+    let inputs = Matrix::new(3, 1, vec![2.0, 3.0, 4.0]);
+    let targets = Vector::new(vec![5.0, 6.0, 7.0]);
+
+    let mut lin_mod = LinRegressor::default();
+    lin_mod.set_weights(Some(Vector::new(vec![1.0, 1.0])));
+
+    assert!(lin_mod.train(&inputs, &targets).is_err());
+}
+
 #[test]
 #[should_panic]
 fn test_no_train_params() {
@@ -47,4 +180,243 @@ fn test_no_train_predict() {
     let inputs = Matrix::new(3, 2, vec![1.0, 2.0, 1.0, 3.0, 1.0, 4.0]);
 
     let _ = lin_mod.predict(&inputs).unwrap();
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_perfect_fit_score() {
+    let mut lin_mod = LinRegressor::default();
+    let inputs = Matrix::new(4, 1, vec![1.0, 3.0, 5.0, 7.0]);
+    let targets = Vector::new(vec![1.0, 5.0, 9.0, 13.0]);
+
+    lin_mod.train(&inputs, &targets).unwrap();
+
+    let score = lin_mod.score(&inputs, &targets).unwrap();
+    assert!(abs(score - 1.0) < 1e-8);
+}
+
+#[test]
+fn test_score_untrained() {
+    let lin_mod = LinRegressor::default();
+    let inputs = Matrix::new(2, 1, vec![1.0, 2.0]);
+    let targets = Vector::new(vec![1.0, 2.0]);
+
+    assert!(lin_mod.score(&inputs, &targets).is_err());
+}
+
+#[test]
+fn test_residuals_exact_fit() {
+    // Targets are exactly y = 1 + 2 x_0 + x_1
+    let inputs = Matrix::new(4, 2, vec![1.0, 2.0, 2.0, -1.0, 3.0, 0.5, 4.0, 3.0]);
+    let targets = Vector::new(vec![5.0, 4.0, 7.5, 12.0]);
+
+    let mut lin_mod = LinRegressor::default();
+    lin_mod.train(&inputs, &targets).unwrap();
+
+    let residuals = lin_mod.residuals(&inputs, &targets).unwrap();
+    assert_eq!(residuals.size(), 4);
+    for r in residuals.data() {
+        assert!(abs(*r) < 1e-8);
+    }
+    assert!(lin_mod.residual_sum_of_squares(&inputs, &targets).unwrap() < 1e-12);
+}
+
+#[test]
+fn test_residual_sum_of_squares() {
+    let inputs = Matrix::new(4, 1, vec![0.0, 1.0, 2.0, 3.0]);
+    let targets = Vector::new(vec![1.0, 0.0, 1.0, 0.0]);
+
+    let mut lin_mod = LinRegressor::default();
+    lin_mod.train(&inputs, &targets).unwrap();
+
+    // The fit is 0.8 - 0.2x leaving residuals 0.2, -0.6, 0.6, -0.2
+    let residuals = lin_mod.residuals(&inputs, &targets).unwrap();
+    for (r, e) in residuals.data().iter().zip([0.2, -0.6, 0.6, -0.2].iter()) {
+        assert!(abs(r - e) < 1e-8);
+    }
+
+    let rss = lin_mod.residual_sum_of_squares(&inputs, &targets).unwrap();
+    assert!(abs(rss - 0.8) < 1e-8);
+}
+
+#[test]
+fn test_residuals_untrained() {
+    let lin_mod = LinRegressor::default();
+    let inputs = Matrix::new(2, 1, vec![0.0, 1.0]);
+    let targets = Vector::new(vec![1.0, 0.0]);
+
+    assert!(lin_mod.residuals(&inputs, &targets).is_err());
+}
+
+/// Samples where the targets depend strongly on the first input
+/// and not at all on the second.
+fn strong_and_irrelevant() -> (Matrix<f64>, Vector<f64>) {
+    let n = 40;
+    let mut inputs = Vec::with_capacity(2 * n);
+    let mut targets = Vec::with_capacity(n);
+
+    for i in 0..n {
+        let t = i as f64;
+        let x_0 = (0.37 * t).sin();
+        let x_1 = (1.13 * t).cos();
+        inputs.push(x_0);
+        inputs.push(x_1);
+        targets.push(2.0 + 3.0 * x_0 + 0.3 * (2.71 * t).sin());
+    }
+
+    (Matrix::new(n, 2, inputs), Vector::new(targets))
+}
+
+#[test]
+fn test_t_statistics_strong_and_irrelevant() {
+    let (inputs, targets) = strong_and_irrelevant();
+
+    let mut lin_mod = LinRegressor::default();
+    lin_mod.train(&inputs, &targets).unwrap();
+
+    let std_errors = lin_mod.coef_std_errors(&inputs, &targets).unwrap();
+    assert!(std_errors.data().iter().all(|&s| s > 0.0));
+
+    let t_stats = lin_mod.t_statistics(&inputs, &targets).unwrap();
+    assert!(abs(t_stats[1]) > 20.0);
+    assert!(abs(t_stats[2]) < 3.0);
+
+    let p_values = lin_mod.p_values(&inputs, &targets).unwrap();
+    assert!(p_values[1] < 1e-3);
+    assert!(p_values[2] > 0.01);
+}
+
+#[test]
+fn test_p_values_follow_student_t() {
+    // With one or two residual degrees of freedom the two-sided
+    // Student's t p-values have closed forms.
+    let inputs = Matrix::new(3, 1, vec![0.0, 1.0, 2.0]);
+    let targets = Vector::new(vec![1.0, 3.5, 4.5]);
+
+    let mut lin_mod = LinRegressor::default();
+    lin_mod.train(&inputs, &targets).unwrap();
+
+    let t_stats = lin_mod.t_statistics(&inputs, &targets).unwrap();
+    let p_values = lin_mod.p_values(&inputs, &targets).unwrap();
+    for (&t, &p) in t_stats.data().iter().zip(p_values.data().iter()) {
+        let expected = 1.0 - 2.0 * t.abs().atan() / f64::consts::PI;
+        assert!(abs(p - expected) < 1e-10);
+    }
+
+    let inputs = Matrix::new(4, 1, vec![0.0, 1.0, 2.0, 3.0]);
+    let targets = Vector::new(vec![1.1, 2.9, 5.2, 6.8]);
+
+    lin_mod.train(&inputs, &targets).unwrap();
+
+    let t_stats = lin_mod.t_statistics(&inputs, &targets).unwrap();
+    let p_values = lin_mod.p_values(&inputs, &targets).unwrap();
+    for (&t, &p) in t_stats.data().iter().zip(p_values.data().iter()) {
+        let expected = 1.0 - t.abs() / (2.0 + t * t).sqrt();
+        assert!(abs(p - expected) < 1e-10);
+    }
+}
+
+#[test]
+fn test_coef_std_errors_too_few_samples() {
+    let inputs = Matrix::new(2, 1, vec![0.0, 1.0]);
+    let targets = Vector::new(vec![1.0, 3.0]);
+
+    let mut lin_mod = LinRegressor::default();
+    lin_mod.train(&inputs, &targets).unwrap();
+
+    assert!(lin_mod.coef_std_errors(&inputs, &targets).is_err());
+}
+
+#[test]
+fn test_leverage_bounds_and_sum() {
+    let (inputs, _) = strong_and_irrelevant();
+    let lin_mod = LinRegressor::default();
+
+    let leverage = lin_mod.leverage(&inputs).unwrap();
+    assert_eq!(leverage.size(), 40);
+    assert!(leverage.data().iter().all(|&h| h >= 0.0 && h <= 1.0));
+
+    // Two features and the intercept
+    assert!(abs(leverage.sum() - 3.0) < 1e-10);
+}
+
+#[test]
+fn test_leverage_matches_hat_matrix() {
+    let inputs = Matrix::new(5, 1, vec![1.0, 2.0, 3.0, 4.0, 8.0]);
+    let mut lin_mod = LinRegressor::default();
+    lin_mod.set_weights(Some(Vector::new(vec![1.0, 2.0, 1.0, 0.5, 1.0])));
+
+    let ones = Matrix::<f64>::ones(5, 1);
+    let x = ones.hcat(&inputs);
+    let w = Matrix::from_diag(&[1.0, 2.0, 1.0, 0.5, 1.0]);
+    let hat = &x * (x.transpose() * &w * &x).inverse().unwrap() * x.transpose() * &w;
+
+    let leverage = lin_mod.leverage(&inputs).unwrap();
+    for i in 0..5 {
+        assert!(abs(leverage[i] - hat[[i, i]]) < 1e-10);
+    }
+    assert!(abs(leverage.sum() - 2.0) < 1e-10);
+}
+
+#[test]
+fn test_leverage_rank_deficient() {
+    let inputs = Matrix::new(3, 1, vec![2.0, 2.0, 2.0]);
+    let lin_mod = LinRegressor::default();
+
+    assert!(lin_mod.leverage(&inputs).is_err());
+}
+
+#[test]
+fn test_cooks_distance_influential_point() {
+    let (inputs, targets) = strong_and_irrelevant();
+
+    // Add a distant sample far from the trend of the others
+    let inputs = inputs.vcat(&Matrix::new(1, 2, vec![4.0, 0.0]));
+    let mut targets = targets.into_vec();
+    targets.push(-10.0);
+    let targets = Vector::new(targets);
+
+    let mut lin_mod = LinRegressor::default();
+    lin_mod.train(&inputs, &targets).unwrap();
+
+    let distances = lin_mod.cooks_distance(&inputs, &targets).unwrap();
+    assert_eq!(distances.size(), 41);
+    assert!(distances.data().iter().all(|&d| d >= 0.0));
+
+    let others = distances.data()[..40].iter().cloned().fold(0.0, f64::max);
+    assert!(distances[40] > 1.0);
+    assert!(distances[40] > 50.0 * others);
+}
+
+#[test]
+fn test_cooks_distance_matches_refit() {
+    let inputs = Matrix::new(6, 1, vec![0.0, 1.0, 2.0, 3.0, 4.0, 6.0]);
+    let targets = Vector::new(vec![0.5, 1.1, 2.4, 2.8, 4.3, 4.9]);
+
+    let mut lin_mod = LinRegressor::default();
+    lin_mod.train(&inputs, &targets).unwrap();
+    let distances = lin_mod.cooks_distance(&inputs, &targets).unwrap();
+
+    let rss = lin_mod.residual_sum_of_squares(&inputs, &targets).unwrap();
+    let s2 = rss / 4.0;
+    let fitted = lin_mod.predict(&inputs).unwrap();
+
+    // Compare with the change in fitted values when leaving out each sample
+    for i in 0..6 {
+        let keep = (0..6).filter(|&j| j != i).collect::<Vec<_>>();
+        let mut loo = LinRegressor::default();
+        loo.train(&inputs.select_rows(&keep),
+                   &Vector::new(keep.iter().map(|&j| targets[j]).collect::<Vec<_>>()))
+            .unwrap();
+        let diff = loo.predict(&inputs).unwrap() - &fitted;
+        let expected = diff.dot(&diff) / (2.0 * s2);
+        assert!(abs(distances[i] - expected) < 1e-8);
+    }
+}
+
+#[test]
+fn test_cooks_distance_untrained() {
+    let (inputs, targets) = strong_and_irrelevant();
+    let lin_mod = LinRegressor::default();
+
+    assert!(lin_mod.cooks_distance(&inputs, &targets).is_err());
+}
@@ -0,0 +1,64 @@
+use rm::learning::optim::coordinate_descent::{CoordinateDescent, soft_threshold};
+
+use rm::linalg::{Matrix, Vector};
+
+/// Inputs with 5 features where only the first and fourth
+/// carry signal, with targets y = 3 x_0 - 2 x_3.
+fn sparse_problem() -> (Matrix<f64>, Vector<f64>) {
+    let n = 50;
+    let mut data = Vec::with_capacity(n * 5);
+    let mut targets = Vec::with_capacity(n);
+
+    for i in 0..n {
+        let t = i as f64;
+        let row = [(0.3 * t).sin(), (0.7 * t).cos(), (1.1 * t).sin(), (1.7 * t).cos(), (2.3 * t).sin()];
+        targets.push(3.0 * row[0] - 2.0 * row[3]);
+        data.extend_from_slice(&row);
+    }
+
+    (Matrix::new(n, 5, data), Vector::new(targets))
+}
+
+#[test]
+fn coordinate_descent_least_squares() {
+    let (inputs, targets) = sparse_problem();
+
+    let cd = CoordinateDescent::default();
+    let (params, iters) = cd.minimize(&inputs, &targets, &[0.0; 5], |rho, z| rho / z);
+
+    assert!(iters < cd.iters());
+    let expected = [3.0, 0.0, 0.0, -2.0, 0.0];
+    for (x, y) in params.iter().zip(expected.iter()) {
+        assert!((x - y).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn coordinate_descent_lasso_recovers_sparse_solution() {
+    let (inputs, targets) = sparse_problem();
+
+    let cd = CoordinateDescent::default();
+    let (params, _) = cd.minimize(&inputs, &targets, &[0.0; 5],
+                                  |rho, z| soft_threshold(rho, 0.05) / z);
+
+    // The irrelevant features are exactly zero
+    for &j in &[1, 2, 4] {
+        assert_eq!(params[j], 0.0);
+    }
+
+    // The relevant features are shrunk towards zero but kept
+    assert!(params[0] > 2.5 && params[0] < 3.0);
+    assert!(params[3] < -1.5 && params[3] > -2.0);
+}
+
+#[test]
+fn coordinate_descent_large_penalty_gives_zero() {
+    let (inputs, targets) = sparse_problem();
+
+    let cd = CoordinateDescent::default();
+    let (params, iters) = cd.minimize(&inputs, &targets, &[0.0; 5],
+                                      |rho, z| soft_threshold(rho, 100.0) / z);
+
+    assert_eq!(params, vec![0.0; 5]);
+    assert_eq!(iters, 1);
+}
@@ -2,8 +2,9 @@ use rm::learning::optim::Optimizable;
 use rm::learning::optim::fmincg::ConjugateGD;
 use rm::learning::optim::grad_desc::{GradientDesc, StochasticGD, AdaGrad, RMSProp};
 use rm::learning::optim::OptimAlgorithm;
+use rm::learning::optim::line_search::Backtracking;
 
-use rm::linalg::Matrix;
+use rm::linalg::{BaseMatrix, Matrix};
 
 /// A model which uses the cost function
 /// y = (x - c)^2
@@ -97,4 +98,79 @@ fn convex_rmsprop_training() {
 
   assert!(params[0] - 20f64 < 1e-10);
   assert!(x_sq.compute_grad(&params, &Matrix::zeros(1, 1), &Matrix::zeros(1, 1)).0 < 1e-10);
-}
\ No newline at end of file
+}
+
+/// A least squares model `y = w x` fit one sample at a time,
+/// so that the result depends on the order of the samples.
+struct LineModel;
+
+impl Optimizable for LineModel {
+    type Inputs = Matrix<f64>;
+    type Targets = Matrix<f64>;
+
+    fn compute_grad(&self, params: &[f64], inputs: &Matrix<f64>, targets: &Matrix<f64>) -> (f64, Vec<f64>) {
+        let mut cost = 0f64;
+        let mut grad = 0f64;
+        for (x, y) in inputs.data().iter().zip(targets.data().iter()) {
+            let r = params[0] * x - y;
+            cost += r * r;
+            grad += 2f64 * r * x;
+        }
+        (cost, vec![grad])
+    }
+}
+
+fn line_data() -> (Matrix<f64>, Matrix<f64>) {
+    let inputs = Matrix::new(10, 1, (0..10).map(|x| x as f64 / 10f64).collect::<Vec<_>>());
+    let targets = Matrix::new(10, 1, vec![0.1, -0.2, 0.5, 0.1, 0.9, 0.3, 1.2, 0.4, 1.5, 0.7]);
+    (inputs, targets)
+}
+
+#[test]
+fn stochastic_algorithms_seeded() {
+    let (inputs, targets) = line_data();
+    let start = vec![0f64];
+
+    let sgd = |seed| StochasticGD::new(0.1, 0.5, 2).with_seed(seed)
+        .optimize(&LineModel, &start, &inputs, &targets);
+    assert_eq!(sgd(3), sgd(3));
+    assert!(sgd(3) != sgd(4));
+
+    let ada = |seed| AdaGrad::new(0.5, 1f64, 2).with_seed(seed)
+        .optimize(&LineModel, &start, &inputs, &targets);
+    assert_eq!(ada(3), ada(3));
+    assert!(ada(3) != ada(4));
+
+    let rms = |seed| RMSProp::new(0.1, 0.9, 1e-5, 2).with_seed(seed)
+        .optimize(&LineModel, &start, &inputs, &targets);
+    assert_eq!(rms(3), rms(3));
+    assert!(rms(3) != rms(4));
+}
+
+/// The badly scaled convex function `0.5 (x^2 + 100 y^2)`.
+struct BadlyScaled;
+
+impl Optimizable for BadlyScaled {
+    type Inputs = Matrix<f64>;
+    type Targets = Matrix<f64>;
+
+    fn compute_grad(&self, params: &[f64], _: &Matrix<f64>, _: &Matrix<f64>) -> (f64, Vec<f64>) {
+        (0.5 * (params[0] * params[0] + 100f64 * params[1] * params[1]),
+         vec![params[0], 100f64 * params[1]])
+    }
+}
+
+#[test]
+fn gd_line_search_badly_scaled() {
+    let start = vec![1f64, 1f64];
+    let zeros = Matrix::zeros(1, 1);
+
+    // The step is too large for the steep direction
+    let params = GradientDesc::new(0.5, 50).optimize(&BadlyScaled, &start, &zeros, &zeros);
+    assert!(params[1].abs() > 1e10);
+
+    let gd = GradientDesc::new(0.5, 2000).with_line_search(Backtracking::default());
+    let params = gd.optimize(&BadlyScaled, &start, &zeros, &zeros);
+    assert!(params[0].abs() < 1e-6);
+    assert!(params[1].abs() < 1e-6);
+}
@@ -0,0 +1,171 @@
+use rm::learning::optim::Optimizable;
+use rm::learning::optim::OptimAlgorithm;
+use rm::learning::optim::grad_desc::GradientDesc;
+use rm::learning::optim::lbfgs::LBFGS;
+use rm::learning::optim::line_search::Backtracking;
+
+use rm::linalg::{BaseMatrix, Matrix, Metric, Vector};
+
+use std::cell::Cell;
+
+/// The convex quadratic `0.5 x^T A x - b^T x`.
+///
+/// Counts the number of gradient evaluations.
+struct Quadratic {
+    a: Matrix<f64>,
+    b: Vector<f64>,
+    evals: Cell<usize>,
+}
+
+impl Quadratic {
+    fn new() -> Quadratic {
+        Quadratic {
+            a: Matrix::new(3, 3, vec![4.0, 1.0, 0.0,
+                                      1.0, 3.0, 0.5,
+                                      0.0, 0.5, 2.0]),
+            b: Vector::new(vec![1.0, -2.0, 3.0]),
+            evals: Cell::new(0),
+        }
+    }
+
+    fn solution(&self) -> Vector<f64> {
+        self.a.solve(self.b.clone()).unwrap()
+    }
+}
+
+impl Optimizable for Quadratic {
+    type Inputs = Matrix<f64>;
+    type Targets = Matrix<f64>;
+
+    fn compute_grad(&self, params: &[f64], _: &Matrix<f64>, _: &Matrix<f64>) -> (f64, Vec<f64>) {
+        self.evals.set(self.evals.get() + 1);
+        let x = Vector::new(params.to_vec());
+        let ax = &self.a * &x;
+        let cost = 0.5 * x.dot(&ax) - self.b.dot(&x);
+        (cost, (ax - &self.b).into_vec())
+    }
+}
+
+/// The mean logistic loss of a linear model.
+///
+/// Counts the number of gradient evaluations.
+struct LogisticLoss {
+    evals: Cell<usize>,
+}
+
+impl Optimizable for LogisticLoss {
+    type Inputs = Matrix<f64>;
+    type Targets = Vector<f64>;
+
+    fn compute_grad(&self, params: &[f64], inputs: &Matrix<f64>, targets: &Vector<f64>) -> (f64, Vec<f64>) {
+        self.evals.set(self.evals.get() + 1);
+        let n = inputs.rows() as f64;
+        let mut cost = 0f64;
+        let mut grad = vec![0f64; params.len()];
+
+        for (row, &y) in inputs.iter_rows().zip(targets.iter()) {
+            let z = row.iter().zip(params.iter()).map(|(x, w)| x * w).sum::<f64>();
+            let p = 1f64 / (1f64 + (-z).exp());
+            cost -= y * p.ln() + (1f64 - y) * (1f64 - p).ln();
+            for (g, x) in grad.iter_mut().zip(row.iter()) {
+                *g += (p - y) * x / n;
+            }
+        }
+
+        (cost / n, grad)
+    }
+}
+
+fn logistic_data() -> (Matrix<f64>, Vector<f64>) {
+    let mut inputs = Vec::new();
+    let mut targets = Vec::new();
+    for i in 0..50 {
+        let t = i as f64 / 10f64 - 2.5;
+        // An intercept, the feature and an overlapping class boundary
+        inputs.extend_from_slice(&[1f64, t, (3f64 * t).sin()]);
+        targets.push(if t + 0.8 * (7f64 * t).cos() > 0f64 { 1f64 } else { 0f64 });
+    }
+    (Matrix::new(50, 3, inputs), Vector::new(targets))
+}
+
+#[test]
+fn lbfgs_quadratic_convergence() {
+    let model = Quadratic::new();
+    let lbfgs = LBFGS::new(5, 100, 1e-10);
+    let params = lbfgs.optimize(&model, &[0f64; 3], &Matrix::zeros(1, 1), &Matrix::zeros(1, 1));
+
+    let evals = model.evals.get();
+    let error = (Vector::new(params) - model.solution()).norm();
+    assert!(error < 1e-8);
+    assert!(evals <= 20);
+
+    // Gradient descent with a stable step is far from converged
+    // after the same number of iterations
+    let model = Quadratic::new();
+    let gd = GradientDesc::new(0.2, evals);
+    let params = gd.optimize(&model, &[0f64; 3], &Matrix::zeros(1, 1), &Matrix::zeros(1, 1));
+    let error = (Vector::new(params) - model.solution()).norm();
+    assert!(error > 1e-4);
+}
+
+#[test]
+fn lbfgs_logistic_convergence() {
+    let (inputs, targets) = logistic_data();
+    let grad_norm = |params: &[f64]| {
+        let model = LogisticLoss { evals: Cell::new(0) };
+        Vector::new(model.compute_grad(params, &inputs, &targets).1).norm()
+    };
+
+    let model = LogisticLoss { evals: Cell::new(0) };
+    let params = LBFGS::default().optimize(&model, &[0f64; 3], &inputs, &targets);
+    let evals = model.evals.get();
+    assert!(grad_norm(&params) < 1e-8);
+    assert!(evals <= 50);
+
+    let model = LogisticLoss { evals: Cell::new(0) };
+    let params = GradientDesc::new(1f64, evals).optimize(&model, &[0f64; 3], &inputs, &targets);
+    assert!(grad_norm(&params) > 1e-4);
+}
+
+/// The convex function `sum_i sqrt(1 + (s_i x_i)^2)` whose curvature
+/// vanishes away from the minimum, so full quasi-Newton steps overshoot.
+struct SoftAbs {
+    scales: Vec<f64>,
+}
+
+impl Optimizable for SoftAbs {
+    type Inputs = Matrix<f64>;
+    type Targets = Matrix<f64>;
+
+    fn compute_grad(&self, params: &[f64], _: &Matrix<f64>, _: &Matrix<f64>) -> (f64, Vec<f64>) {
+        let mut cost = 0f64;
+        let mut grad = Vec::with_capacity(params.len());
+        for (x, s) in params.iter().zip(self.scales.iter()) {
+            let r = (1f64 + s * s * x * x).sqrt();
+            cost += r;
+            grad.push(s * s * x / r);
+        }
+        (cost, grad)
+    }
+}
+
+#[test]
+fn lbfgs_line_search_prevents_divergence() {
+    let model = SoftAbs { scales: vec![1f64, 10f64] };
+    let start = vec![3f64, 0.5];
+    let zeros = Matrix::zeros(1, 1);
+
+    // Without a line search the iterates diverge
+    let params = LBFGS::new(5, 50, 1e-10).optimize(&model, &start, &zeros, &zeros);
+    assert!(params.iter().any(|x| x.abs() > 1e3));
+
+    let lbfgs = LBFGS::new(5, 100, 1e-10).with_line_search(Backtracking::default());
+    let params = lbfgs.optimize(&model, &start, &zeros, &zeros);
+    assert!(params[0].abs() < 1e-8);
+    assert!(params[1].abs() < 1e-8);
+
+    // The default algorithm uses a line search
+    let params = LBFGS::default().optimize(&model, &start, &zeros, &zeros);
+    assert!(params[0].abs() < 1e-6);
+    assert!(params[1].abs() < 1e-6);
+}
@@ -1,5 +1,6 @@
 extern crate rusty_machine as rm;
 extern crate num as libnum;
+extern crate rand;
 
 pub mod learning {
     mod dbscan;
@@ -9,5 +10,7 @@ pub mod learning {
 
     pub mod optim {
     	mod grad_desc;
+    	mod coordinate_descent;
+    	mod lbfgs;
     }
 }
\ No newline at end of file